@@ -1,7 +1,10 @@
 use itertools::Itertools;
 use syn::parse::{Parse, ParseStream};
 use syn::spanned::Spanned;
-use syn::{Attribute, Error, Expr, ExprArray, ExprLit, ExprPath, Lit, LitInt, LitStr, Token};
+use syn::{
+    Attribute, Error, Expr, ExprArray, ExprLit, ExprPath, ExprUnary, Ident, Lit, LitInt, LitStr,
+    Token, Type, UnOp,
+};
 
 pub enum DefaultDefinition {
     Default,
@@ -11,7 +14,10 @@ pub enum DefaultDefinition {
 pub struct FieldAttributes {
     pub default: Option<DefaultDefinition>,
     pub name: Option<LitStr>,
+    pub qualifier: Option<Type>,
     pub ignore: bool,
+    pub late: bool,
+    pub owned: bool,
 }
 
 impl TryFrom<&Attribute> for FieldAttributes {
@@ -20,11 +26,21 @@ impl TryFrom<&Attribute> for FieldAttributes {
     fn try_from(value: &Attribute) -> Result<Self, Self::Error> {
         let mut default = None;
         let mut name = None;
+        let mut qualifier = None;
         let mut ignore = false;
+        let mut late = false;
+        let mut owned = false;
 
         value.parse_nested_meta(|meta| {
             if meta.path.is_ident("default") {
-                if name.is_some() {
+                if late {
+                    return Err(Error::new(
+                        value.span(),
+                        "Cannot use default value for a late dependency!",
+                    ));
+                }
+
+                if name.is_some() || qualifier.is_some() {
                     return Err(Error::new(
                         value.span(),
                         "Cannot use default value when injecting a named instance!",
@@ -46,19 +62,63 @@ impl TryFrom<&Attribute> for FieldAttributes {
                     ));
                 }
 
+                if qualifier.is_some() {
+                    return Err(Error::new(
+                        value.span(),
+                        "Cannot use both a name and a qualifier!",
+                    ));
+                }
+
                 let value = meta.value()?;
                 name = Some(value.parse()?);
+            } else if meta.path.is_ident("qualifier") {
+                if default.is_some() {
+                    return Err(Error::new(
+                        value.span(),
+                        "Cannot inject a qualified instance if using the default value!",
+                    ));
+                }
+
+                if name.is_some() {
+                    return Err(Error::new(
+                        value.span(),
+                        "Cannot use both a name and a qualifier!",
+                    ));
+                }
+
+                qualifier = Some(meta.value()?.parse()?);
             } else if meta.path.is_ident("ignore") {
                 ignore = true;
+            } else if meta.path.is_ident("late") {
+                if default.is_some() {
+                    return Err(Error::new(
+                        value.span(),
+                        "Cannot use default value for a late dependency!",
+                    ));
+                }
+
+                late = true;
+            } else if meta.path.is_ident("owned") {
+                owned = true;
             }
 
             Ok(())
         })?;
 
+        if owned && (late || default.is_some()) {
+            return Err(Error::new(
+                value.span(),
+                "Owned dependencies cannot be late or use the default value!",
+            ));
+        }
+
         Ok(Self {
             default,
             name,
+            qualifier,
             ignore,
+            late,
+            owned,
         })
     }
 }
@@ -87,11 +147,20 @@ impl From<&str> for ConstructorParameter {
 #[derive(Default)]
 pub struct ComponentAttributes {
     pub names: Option<ExprArray>,
-    pub condition: Option<ExprPath>,
+    pub names_from: Option<ExprPath>,
+    pub naming: Option<Ident>,
+    pub qualifier: Option<Type>,
+    pub condition: Option<Expr>,
+    pub profile: Option<LitStr>,
     pub priority: i8,
+    pub order: i32,
     pub scope: Option<LitStr>,
     pub constructor: Option<ExprPath>,
     pub constructor_parameters: Vec<ConstructorParameter>,
+    pub lifecycle: bool,
+    pub disposable: bool,
+    pub concurrent: bool,
+    pub is_override: bool,
 }
 
 impl ComponentAttributes {
@@ -108,13 +177,57 @@ impl TryFrom<&Attribute> for ComponentAttributes {
         let mut result = Self::default();
         value.parse_nested_meta(|meta| {
             if meta.path.is_ident("names") {
-                if result.names.is_some() {
+                if result.names.is_some() || result.names_from.is_some() {
                     return Err(Error::new(value.span(), "Names are already defined!"));
                 }
 
                 if let Expr::Array(array) = meta.value()?.parse::<Expr>()? {
                     result.names = Some(array);
                 }
+            } else if meta.path.is_ident("names_from") {
+                if result.names.is_some() || result.names_from.is_some() {
+                    return Err(Error::new(value.span(), "Names are already defined!"));
+                }
+
+                if let Expr::Lit(ExprLit {
+                    lit: Lit::Str(path),
+                    ..
+                }) = meta.value()?.parse::<Expr>()?
+                {
+                    result.names_from = Some(path.parse()?);
+                }
+            } else if meta.path.is_ident("naming") {
+                if result.naming.is_some() {
+                    return Err(Error::new(value.span(), "Naming is already defined!"));
+                }
+
+                let naming = match meta.value()?.parse::<Expr>()? {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(naming),
+                        ..
+                    }) => naming,
+                    naming => {
+                        return Err(Error::new(
+                            naming.span(),
+                            "Naming strategy must be a string literal!",
+                        ))
+                    }
+                };
+
+                let variant = match naming.value().as_str() {
+                    "type_name" => "TypeName",
+                    "crate_prefix" => "CratePrefix",
+                    "module_path" => "ModulePath",
+                    _ => return Err(Error::new(naming.span(), "Unknown naming strategy!")),
+                };
+
+                result.naming = Some(Ident::new(variant, naming.span()));
+            } else if meta.path.is_ident("qualifier") {
+                if result.qualifier.is_some() {
+                    return Err(Error::new(value.span(), "Qualifier is already defined!"));
+                }
+
+                result.qualifier = Some(meta.value()?.parse()?);
             } else if meta.path.is_ident("condition") {
                 if result.condition.is_some() {
                     return Err(Error::new(value.span(), "Condition is already defined!"));
@@ -127,6 +240,18 @@ impl TryFrom<&Attribute> for ComponentAttributes {
                 {
                     result.condition = Some(path.parse()?);
                 }
+            } else if meta.path.is_ident("profile") {
+                if result.profile.is_some() {
+                    return Err(Error::new(value.span(), "Profile is already defined!"));
+                }
+
+                if let Expr::Lit(ExprLit {
+                    lit: Lit::Str(profile),
+                    ..
+                }) = meta.value()?.parse::<Expr>()?
+                {
+                    result.profile = Some(profile);
+                }
             } else if meta.path.is_ident("priority") {
                 if let Expr::Lit(ExprLit {
                     lit: Lit::Int(priority),
@@ -135,6 +260,25 @@ impl TryFrom<&Attribute> for ComponentAttributes {
                 {
                     result.priority = priority.base10_parse()?;
                 }
+            } else if meta.path.is_ident("order") {
+                result.order = match meta.value()?.parse::<Expr>()? {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Int(order),
+                        ..
+                    }) => order.base10_parse()?,
+                    Expr::Unary(ExprUnary {
+                        op: UnOp::Neg(_),
+                        expr,
+                        ..
+                    }) => match *expr {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Int(order),
+                            ..
+                        }) => -order.base10_parse::<i32>()?,
+                        _ => return Err(Error::new(value.span(), "Invalid order!")),
+                    },
+                    _ => return Err(Error::new(value.span(), "Invalid order!")),
+                };
             } else if meta.path.is_ident("constructor") {
                 if result.constructor.is_some() {
                     return Err(Error::new(value.span(), "Constructor is already defined!"));
@@ -174,6 +318,14 @@ impl TryFrom<&Attribute> for ComponentAttributes {
                 {
                     result.constructor_parameters = Self::parse_constructor_parameters(&string);
                 }
+            } else if meta.path.is_ident("lifecycle") {
+                result.lifecycle = true;
+            } else if meta.path.is_ident("disposable") {
+                result.disposable = true;
+            } else if meta.path.is_ident("concurrent") {
+                result.concurrent = true;
+            } else if meta.path.is_ident("override") {
+                result.is_override = true;
             }
 
             Ok(())
@@ -186,7 +338,7 @@ impl TryFrom<&Attribute> for ComponentAttributes {
 #[derive(Default)]
 pub struct ComponentAliasAttributes {
     pub is_primary: bool,
-    pub condition: Option<ExprPath>,
+    pub condition: Option<Expr>,
     pub priority: i8,
     pub scope: Option<LitStr>,
 }
@@ -259,3 +411,36 @@ mod kw {
     custom_keyword!(priority);
     custom_keyword!(scope);
 }
+
+pub struct GenericComponentRegistration {
+    pub component_type: Type,
+    pub names: Option<ExprArray>,
+}
+
+impl Parse for GenericComponentRegistration {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let component_type = input.parse()?;
+        let mut names = None;
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+
+            if key == "names" {
+                names = Some(input.parse()?);
+            } else {
+                return Err(Error::new(key.span(), "Unknown argument"));
+            }
+        }
+
+        Ok(Self {
+            component_type,
+            names,
+        })
+    }
+}
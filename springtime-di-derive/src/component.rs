@@ -1,17 +1,18 @@
 use crate::attributes::{
     ComponentAliasAttributes, ComponentAttributes, ConstructorParameter, DefaultDefinition,
-    FieldAttributes,
+    FieldAttributes, GenericComponentRegistration,
 };
 use convert_case::{Case, Casing};
 use itertools::Itertools;
-use proc_macro2::{Ident, TokenStream};
-use quote::quote;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote};
 use std::ops::Deref;
 use syn::spanned::Spanned;
 use syn::{
-    parse_str, Attribute, Data, DataStruct, DeriveInput, Error, Expr, ExprArray, ExprLit, ExprPath,
-    Field, Fields, FieldsNamed, FieldsUnnamed, GenericArgument, Item, Lit, LitStr, PathArguments,
-    Result, Type, TypePath, TypeTraitObject,
+    parse_quote, parse_str, Attribute, Data, DataStruct, DeriveInput, Error, Expr, ExprLit,
+    ExprPath, Field, FieldMutability, Fields, FieldsNamed, FieldsUnnamed, FnArg, GenericArgument,
+    ImplItem, ImplItemFn, Index, Item, Lit, PathArguments, Result, ReturnType, Type, TypePath,
+    TypeTraitObject, Visibility,
 };
 
 const COMPONENT_ATTR: &str = "component";
@@ -55,7 +56,11 @@ fn get_wrapped_type(ty: &Type, expected_wrapper: &str, require_ptr: bool) -> Opt
         return Some(quote!(#ty));
     }
 
-    if let GenericArgument::Type(Type::Path(TypePath { path, .. })) = &args[0] {
+    get_ptr_type(&args[0])
+}
+
+fn get_ptr_type(arg: &GenericArgument) -> Option<TokenStream> {
+    if let GenericArgument::Type(Type::Path(TypePath { path, .. })) = arg {
         if let Some(last_segment) = path.segments.last() {
             if last_segment.ident == "ComponentInstancePtr" {
                 if let PathArguments::AngleBracketed(args) = &last_segment.arguments {
@@ -70,6 +75,27 @@ fn get_wrapped_type(ty: &Type, expected_wrapper: &str, require_ptr: bool) -> Opt
     None
 }
 
+fn get_injected_map_type(ty: &Type) -> Option<TokenStream> {
+    let path = match ungroup(ty) {
+        Type::Path(ty) => &ty.path,
+        _ => {
+            return None;
+        }
+    };
+
+    let seg = path.segments.last()?;
+    if seg.ident != "FxHashMap" && seg.ident != "HashMap" {
+        return None;
+    }
+
+    match &seg.arguments {
+        PathArguments::AngleBracketed(bracketed) if bracketed.args.len() == 2 => {
+            get_ptr_type(&bracketed.args[1])
+        }
+        _ => None,
+    }
+}
+
 fn get_injected_option_type(ty: &Type) -> Option<TokenStream> {
     get_wrapped_type(ty, "Option", true)
 }
@@ -97,7 +123,7 @@ fn get_injected_type(ty: &Type) -> TokenStream {
         }
     }
 
-    quote!(<#ty as Deref>::Target)
+    quote!(<#ty as std::ops::Deref>::Target)
 }
 
 fn get_injected_vec_type(ty: &Type) -> Option<TokenStream> {
@@ -109,6 +135,19 @@ fn get_constructor_vec_type(ty: &Type) -> Option<TokenStream> {
 }
 
 fn get_unnamed_instance(ty: &Type) -> TokenStream {
+    // maps are keyed by component names and can use any hasher
+    if let Some(ty) = get_injected_map_type(ty) {
+        #[cfg(not(feature = "async"))]
+        return quote! {
+            instance_provider.named_instances_typed::<#ty>()?.into_iter().collect()
+        };
+
+        #[cfg(feature = "async")]
+        return quote! {
+            instance_provider.named_instances_typed::<#ty>().await?.into_iter().collect()
+        };
+    }
+
     let (getter, ty) = get_injected_option_type(ty)
         .map(|ty| (quote!(primary_instance_option), ty))
         .or_else(|| get_injected_vec_type(ty).map(|ty| (quote!(instances_typed), ty)))
@@ -125,7 +164,7 @@ fn get_unnamed_instance(ty: &Type) -> TokenStream {
     }
 }
 
-fn get_named_instance(ty: &Type, name: &LitStr) -> TokenStream {
+fn get_named_instance(ty: &Type, name: &TokenStream) -> TokenStream {
     let (getter, ty) = get_injected_option_type(ty)
         .map(|ty| (quote!(instance_by_name_option), ty))
         .or_else(|| get_injected_vec_type(ty).map(|ty| (quote!(instances_typed), ty)))
@@ -142,73 +181,252 @@ fn get_named_instance(ty: &Type, name: &LitStr) -> TokenStream {
     }
 }
 
-fn get_instance(ty: &Type, name: Option<&LitStr>) -> TokenStream {
+fn is_factory_handle(ty: &Type) -> bool {
+    if let Type::Path(TypePath { path, .. }) = ungroup(ty) {
+        if let Some(last_segment) = path.segments.last() {
+            return last_segment.ident == "FactoryHandle" && last_segment.arguments.is_empty();
+        }
+    }
+
+    false
+}
+
+// ScopedProxy is an alias of ComponentProvider
+fn get_provider_type(ty: &Type) -> Option<TokenStream> {
+    get_wrapped_type(ty, "ComponentProvider", false)
+        .or_else(|| get_wrapped_type(ty, "ScopedProxy", false))
+}
+
+fn get_instance(ty: &Type, name: Option<&TokenStream>) -> TokenStream {
+    if is_factory_handle(ty) {
+        return quote! {
+            springtime_di::instance_provider::FactoryHandle::new(instance_provider)?
+        };
+    }
+
+    if let Some(ty) = get_provider_type(ty) {
+        return name
+            .map(|name| {
+                quote! {
+                    springtime_di::instance_provider::ComponentProvider::<#ty>::named(instance_provider, #name)?
+                }
+            })
+            .unwrap_or_else(|| {
+                quote! {
+                    springtime_di::instance_provider::ComponentProvider::<#ty>::new(instance_provider)?
+                }
+            });
+    }
+
     name.map(|name| get_named_instance(ty, name))
         .unwrap_or_else(|| get_unnamed_instance(ty))
 }
 
-fn generate_field_construction(field: &Field) -> Result<TokenStream> {
+fn get_owned_instance(ty: &Type, name: Option<&TokenStream>) -> TokenStream {
+    let getter = name
+        .map(|name| quote!(instance_by_name_owned::<#ty>(#name)))
+        .unwrap_or_else(|| quote!(primary_instance_owned::<#ty>()));
+
+    #[cfg(not(feature = "async"))]
+    quote! {
+        instance_provider.#getter?
+    }
+
+    #[cfg(feature = "async")]
+    quote! {
+        instance_provider.#getter.await?
+    }
+}
+
+fn get_field_instance_name(attributes: &FieldAttributes) -> Option<TokenStream> {
+    attributes
+        .qualifier
+        .as_ref()
+        .map(|qualifier| quote!(springtime_di::component::qualifier_name::<#qualifier>()))
+        .or_else(|| attributes.name.as_ref().map(|name| quote!(#name)))
+}
+
+// returns the construction expression and whether it injects an instance from the provider
+fn generate_field_construction(field: &Field) -> Result<(TokenStream, bool)> {
     for attr in &field.attrs {
         if attr.path().is_ident(COMPONENT_ATTR) {
             let attributes = FieldAttributes::try_from(attr)?;
+            if attributes.late {
+                return Ok((
+                    quote!(springtime_di::instance_provider::Late::default()),
+                    false,
+                ));
+            }
+
+            if attributes.owned {
+                return Ok((
+                    get_owned_instance(&field.ty, get_field_instance_name(&attributes).as_ref()),
+                    true,
+                ));
+            }
+
             return match &attributes.default {
-                Some(DefaultDefinition::Expr(path)) => Ok(quote!(#path())),
-                Some(DefaultDefinition::Default) => Ok(quote!(std::default::Default::default())),
-                _ => Ok(get_instance(&field.ty, attributes.name.as_ref())),
+                Some(DefaultDefinition::Expr(path)) => Ok((quote!(#path()), false)),
+                Some(DefaultDefinition::Default) => {
+                    Ok((quote!(std::default::Default::default()), false))
+                }
+                _ => Ok((
+                    get_instance(&field.ty, get_field_instance_name(&attributes).as_ref()),
+                    !is_factory_handle(&field.ty),
+                )),
             };
         }
     }
 
-    Ok(get_instance(&field.ty, None))
+    Ok((get_instance(&field.ty, None), !is_factory_handle(&field.ty)))
 }
 
-fn make_named_struct(fields: &FieldsNamed) -> Result<TokenStream> {
-    let fields: Vec<_> = fields
+// injected instances are resolved concurrently, each with its own dependency handle, before
+// constructing the component; returns the resolution code and expressions to use in place of the
+// given ones
+fn resolve_concurrently(instances: Vec<(TokenStream, bool)>) -> (TokenStream, Vec<TokenStream>) {
+    if instances.iter().filter(|(_, injected)| *injected).count() < 2 {
+        return (
+            quote!(),
+            instances
+                .into_iter()
+                .map(|(instance, _)| instance)
+                .collect(),
+        );
+    }
+
+    let mut handles = vec![];
+    let mut resolutions = vec![];
+    let mut values = vec![];
+    for (index, (instance, injected)) in instances.into_iter().enumerate() {
+        if !injected {
+            values.push(instance);
+            continue;
+        }
+
+        let handle = format_ident!("__handle_{}", index);
+        let value = format_ident!("__dependency_{}", index);
+
+        handles.push(quote! {
+            let mut #handle = instance_provider
+                .dependency_handle()
+                .ok_or(ComponentInstanceProviderError::SharedHandleUnsupported)?;
+        });
+        resolutions.push((
+            quote!(#value),
+            quote! {
+                async {
+                    let instance_provider = &mut *#handle;
+                    let instance = #instance;
+                    Ok::<_, ComponentInstanceProviderError>(instance)
+                }
+            },
+        ));
+        values.push(quote!(#value));
+    }
+
+    let (value, resolution) = resolutions.pop().unwrap();
+    let (pattern, resolution) = resolutions.into_iter().rev().fold(
+        (value, resolution),
+        |(pattern, resolution), (value, next_resolution)| {
+            (
+                quote!((#value, #pattern)),
+                quote!(springtime_di::future::try_join(#next_resolution, #resolution)),
+            )
+        },
+    );
+
+    (
+        quote! {
+            #(#handles)*
+            let #pattern = #resolution.await?;
+        },
+        values,
+    )
+}
+
+fn with_resolution(resolution: TokenStream, expr: TokenStream) -> TokenStream {
+    if resolution.is_empty() {
+        expr
+    } else {
+        quote! {
+            {
+                #resolution
+                #expr
+            }
+        }
+    }
+}
+
+fn make_named_struct(fields: &FieldsNamed, concurrent: bool) -> Result<TokenStream> {
+    let instances: Vec<_> = fields
         .named
         .iter()
-        .map(|field| -> Result<TokenStream> {
-            let ident = field.ident.as_ref().unwrap();
-            let instance = generate_field_construction(field)?;
-            Ok(quote! {
-                #ident: #instance
-            })
-        })
+        .map(generate_field_construction)
         .try_collect()?;
+    let (resolution, instances) = if concurrent {
+        resolve_concurrently(instances)
+    } else {
+        (
+            quote!(),
+            instances
+                .into_iter()
+                .map(|(instance, _)| instance)
+                .collect(),
+        )
+    };
 
-    Ok(quote! {
-        Self {
-            #(#fields),*
-        }
-    })
+    let idents = fields
+        .named
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap());
+    Ok(with_resolution(
+        resolution,
+        quote! {
+            Self {
+                #(#idents: #instances),*
+            }
+        },
+    ))
 }
 
-fn make_unnamed_struct(fields: &FieldsUnnamed) -> Result<TokenStream> {
-    let fields: Vec<_> = fields
+fn make_unnamed_struct(fields: &FieldsUnnamed, concurrent: bool) -> Result<TokenStream> {
+    let instances: Vec<_> = fields
         .unnamed
         .iter()
-        .map(|field| -> Result<TokenStream> {
-            let instance = generate_field_construction(field)?;
-            Ok(quote! {
-                #instance
-            })
-        })
+        .map(generate_field_construction)
         .try_collect()?;
+    let (resolution, instances) = if concurrent {
+        resolve_concurrently(instances)
+    } else {
+        (
+            quote!(),
+            instances
+                .into_iter()
+                .map(|(instance, _)| instance)
+                .collect(),
+        )
+    };
 
-    Ok(quote! {
-        Self(#(#fields),*)
-    })
+    Ok(with_resolution(
+        resolution,
+        quote! {
+            Self(#(#instances),*)
+        },
+    ))
 }
 
 fn generate_constructor_call_arguments<'a>(
     fields: impl Iterator<Item = &'a Field>,
     constructor_parameters: &[ConstructorParameter],
-) -> Result<TokenStream> {
+) -> Result<Vec<(TokenStream, bool)>> {
     let fields: Vec<_> = fields
         .map(|field| {
             for attr in &field.attrs {
                 if attr.path().is_ident(COMPONENT_ATTR) {
                     let attributes = FieldAttributes::try_from(attr)?;
-                    return Ok((attributes.ignore, field));
+                    // late dependencies are injected after construction
+                    return Ok((attributes.ignore || attributes.late, field));
                 }
             }
 
@@ -220,18 +438,19 @@ fn generate_constructor_call_arguments<'a>(
 
     let constructor_parameters = generate_constructor_parameters(constructor_parameters)?;
 
-    Ok(if fields.is_empty() {
-        quote!(#constructor_parameters)
-    } else {
-        quote! {
-            #(#fields),*, #constructor_parameters
-        }
-    })
+    Ok(fields
+        .into_iter()
+        .chain(
+            constructor_parameters
+                .into_iter()
+                .map(|parameter| (parameter, true)),
+        )
+        .collect())
 }
 
 fn generate_constructor_parameters(
     constructor_parameters: &[ConstructorParameter],
-) -> Result<TokenStream> {
+) -> Result<Vec<TokenStream>> {
     constructor_parameters
         .iter()
         .map(|param| {
@@ -298,37 +517,200 @@ fn generate_constructor_parameters(
                     })
             })
         })
-        .fold_ok(quote!(), |tokens, param| quote!(#tokens #param,))
+        .try_collect()
+}
+
+fn generate_late_injection(fields: &Fields) -> Result<Vec<TokenStream>> {
+    let mut injections = vec![];
+    for (index, field) in fields.iter().enumerate() {
+        for attr in &field.attrs {
+            if !attr.path().is_ident(COMPONENT_ATTR) {
+                continue;
+            }
+
+            let attributes = FieldAttributes::try_from(attr)?;
+            if !attributes.late {
+                continue;
+            }
+
+            let ty = get_wrapped_type(&field.ty, "Late", false).ok_or_else(|| {
+                Error::new(
+                    field.span(),
+                    "Late dependencies must be of the Late<T> type!",
+                )
+            })?;
+            let member = field
+                .ident
+                .as_ref()
+                .map(|ident| quote!(#ident))
+                .unwrap_or_else(|| {
+                    let index = Index::from(index);
+                    quote!(#index)
+                });
+            let (getter, name) = get_field_instance_name(&attributes)
+                .map(|name| (quote!(instance_by_name_typed), name))
+                .unwrap_or_else(|| (quote!(primary_instance_typed), quote!()));
+
+            #[cfg(not(feature = "async"))]
+            injections.push(quote! {
+                component.#member.set(instance_provider.#getter::<#ty>(#name)?);
+            });
+
+            #[cfg(feature = "async")]
+            injections.push(quote! {
+                component.#member.set(instance_provider.#getter::<#ty>(#name).await?);
+            });
+        }
+    }
+
+    Ok(injections)
+}
+
+fn make_dependency(ty: &TokenStream, name: Option<&TokenStream>, kind: &str) -> TokenStream {
+    let name = name
+        .map(|name| quote!(Some(#name)))
+        .unwrap_or_else(|| quote!(None));
+    let kind = Ident::new(kind, Span::call_site());
+
+    quote! {
+        springtime_di::component_registry::ComponentDependency::new::<#ty>(
+            #name,
+            springtime_di::component_registry::DependencyKind::#kind,
+        )
+    }
+}
+
+// mirrors get_instance()
+fn get_dependency(ty: &Type, name: Option<&TokenStream>) -> TokenStream {
+    if let Some(ty) = get_provider_type(ty) {
+        return make_dependency(&ty, name, "Provider");
+    }
+
+    if let Some(ty) = get_injected_map_type(ty).or_else(|| get_injected_vec_type(ty)) {
+        return make_dependency(&ty, None, "All");
+    }
+
+    if let Some(ty) = get_injected_option_type(ty) {
+        return make_dependency(&ty, name, "Optional");
+    }
+
+    make_dependency(&get_injected_type(ty), name, "Required")
+}
+
+fn generate_field_dependency(field: &Field, has_constructor: bool) -> Result<Option<TokenStream>> {
+    // the factory itself is always available
+    if is_factory_handle(&field.ty) {
+        return Ok(None);
+    }
+
+    for attr in &field.attrs {
+        if attr.path().is_ident(COMPONENT_ATTR) {
+            let attributes = FieldAttributes::try_from(attr)?;
+            let name = get_field_instance_name(&attributes);
+            if attributes.late {
+                return Ok(get_wrapped_type(&field.ty, "Late", false)
+                    .map(|ty| make_dependency(&ty, name.as_ref(), "Late")));
+            }
+
+            if attributes.default.is_some() || (has_constructor && attributes.ignore) {
+                return Ok(None);
+            }
+
+            if attributes.owned {
+                let ty = &field.ty;
+                return Ok(Some(make_dependency(
+                    &quote!(#ty),
+                    name.as_ref(),
+                    "Required",
+                )));
+            }
+
+            return Ok(Some(get_dependency(&field.ty, name.as_ref())));
+        }
+    }
+
+    Ok(Some(get_dependency(&field.ty, None)))
+}
+
+fn generate_dependencies(
+    fields: &Fields,
+    constructor_parameters: Option<&[ConstructorParameter]>,
+) -> Result<TokenStream> {
+    let mut dependencies = vec![];
+    for field in fields {
+        if let Some(dependency) =
+            generate_field_dependency(field, constructor_parameters.is_some())?
+        {
+            dependencies.push(dependency);
+        }
+    }
+
+    for param in constructor_parameters.unwrap_or_default() {
+        let component_type = parse_str::<Type>(&param.component_type)?;
+        let name = param.name.as_ref().map(|name| quote!(#name));
+
+        // mirrors generate_constructor_parameters()
+        let dependency = if let Some(ty) = get_constructor_option_type(&component_type) {
+            make_dependency(&ty, name.as_ref(), "Optional")
+        } else if let Some(ty) = name
+            .is_none()
+            .then(|| get_constructor_vec_type(&component_type))
+            .flatten()
+        {
+            make_dependency(&ty, None, "All")
+        } else {
+            make_dependency(&quote!(#component_type), name.as_ref(), "Required")
+        };
+
+        dependencies.push(dependency);
+    }
+
+    Ok(quote!(vec![#(#dependencies),*]))
 }
 
 fn make_constructor_call(
     fields: &Fields,
     constructor: &ExprPath,
     constructor_parameters: &[ConstructorParameter],
+    concurrent: bool,
 ) -> Result<TokenStream> {
-    let fields = match fields {
+    let arguments = match fields {
         Fields::Named(FieldsNamed { named, .. }) => {
             generate_constructor_call_arguments(named.iter(), constructor_parameters)?
         }
         Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
             generate_constructor_call_arguments(unnamed.iter(), constructor_parameters)?
         }
-        Fields::Unit => generate_constructor_parameters(constructor_parameters)?,
+        Fields::Unit => generate_constructor_parameters(constructor_parameters)?
+            .into_iter()
+            .map(|parameter| (parameter, true))
+            .collect(),
+    };
+    let (resolution, arguments) = if concurrent {
+        resolve_concurrently(arguments)
+    } else {
+        (
+            quote!(),
+            arguments
+                .into_iter()
+                .map(|(argument, _)| argument)
+                .collect(),
+        )
     };
 
     #[cfg(not(feature = "async"))]
     let call = quote! {
-        #constructor(#fields)
+        #constructor(#(#arguments),*)
             .map_err(|error| ComponentInstanceProviderError::ConstructorError(error))
     };
     #[cfg(feature = "async")]
     let call = quote! {
-        #constructor(#fields)
+        #constructor(#(#arguments),*)
             .await
             .map_err(|error| ComponentInstanceProviderError::ConstructorError(error))
     };
 
-    Ok(call)
+    Ok(with_resolution(resolution, call))
 }
 
 fn extract_component_attributes(attributes: &[Attribute]) -> Result<Option<ComponentAttributes>> {
@@ -346,26 +728,93 @@ fn extract_component_attributes(attributes: &[Attribute]) -> Result<Option<Compo
 }
 
 //noinspection DuplicatedCode
-fn generate_names(attribute_names: Option<ExprArray>, ident: &Ident) -> Vec<String> {
-    attribute_names
-        .map(|names| {
+fn qualify_names(names: TokenStream, attributes: Option<&ComponentAttributes>) -> TokenStream {
+    if let Some(qualifier) = attributes.and_then(|attributes| attributes.qualifier.as_ref()) {
+        quote!({
+            let mut names: Vec<String> = #names.into_iter().map(Into::into).collect();
+            names.push(springtime_di::component::qualifier_name::<#qualifier>().to_string());
             names
-                .elems
-                .iter()
-                .filter_map(|elem| {
-                    if let Expr::Lit(ExprLit {
-                        lit: Lit::Str(string),
-                        ..
-                    }) = elem
-                    {
-                        Some(string.value())
-                    } else {
-                        None
-                    }
-                })
-                .collect()
         })
-        .unwrap_or_else(|| vec![ident.to_string().to_case(Case::Snake)])
+    } else {
+        names
+    }
+}
+
+fn generate_condition(attributes: Option<&ComponentAttributes>) -> TokenStream {
+    let condition = attributes.and_then(|attributes| attributes.condition.clone());
+    let profile = attributes.and_then(|attributes| attributes.profile.clone());
+
+    // profiles are checked by an additional condition, which needs to pass along the user one
+    let condition = match (profile, condition) {
+        (Some(profile), Some(condition)) => quote!(springtime_di::all_of!(
+            springtime_di::component_registry::conditional::ActiveProfile::new(#profile),
+            #condition
+        )),
+        (Some(profile), None) => {
+            quote!(springtime_di::component_registry::conditional::ActiveProfile::new(#profile))
+        }
+        (None, Some(condition)) => quote!(#condition),
+        (None, None) => return quote!(),
+    };
+
+    quote!(.with_condition(
+        springtime_di::component_registry::conditional::component_condition(#condition)
+    ))
+}
+
+fn generate_names(attributes: Option<&ComponentAttributes>, ident: &Ident) -> TokenStream {
+    if let Some(names) = attributes.and_then(|attributes| attributes.names.as_ref()) {
+        let names = names
+            .elems
+            .iter()
+            .filter_map(|elem| {
+                if let Expr::Lit(ExprLit {
+                    lit: Lit::Str(string),
+                    ..
+                }) = elem
+                {
+                    Some(string.value())
+                } else {
+                    None
+                }
+            })
+            .collect_vec();
+        let count = names.len();
+        return quote!({
+            let names: [&str; #count] = [#(#names),*];
+            names
+        });
+    }
+
+    let name = ident.to_string().to_case(Case::Snake);
+    if let Some(naming) = attributes.and_then(|attributes| attributes.naming.as_ref()) {
+        return quote!([springtime_di::component::ComponentNamingStrategy::#naming.component_name(
+            #name,
+            module_path!()
+        )]);
+    }
+
+    quote!({
+        let names: [&str; 1] = [#name];
+        names
+    })
+}
+
+// names generated without an explicit strategy follow the one configured for the registry
+fn generate_default_name(attributes: Option<&ComponentAttributes>, ident: &Ident) -> TokenStream {
+    let is_named = attributes
+        .map(|attributes| {
+            attributes.names.is_some()
+                || attributes.names_from.is_some()
+                || attributes.naming.is_some()
+        })
+        .unwrap_or(false);
+    if is_named {
+        return quote!();
+    }
+
+    let name = ident.to_string().to_case(Case::Snake);
+    quote!(.with_default_name(#name, module_path!()))
 }
 
 pub fn generate_injectable(item: &Item) -> Result<TokenStream> {
@@ -399,22 +848,58 @@ pub fn generate_injectable(item: &Item) -> Result<TokenStream> {
 pub fn expand_component(input: &DeriveInput) -> Result<TokenStream> {
     if let Data::Struct(DataStruct { fields, .. }) = &input.data {
         let ident = &input.ident;
+        if let Some(lifetime) = input.generics.lifetimes().next() {
+            return Err(Error::new(
+                lifetime.span(),
+                "Components cannot have lifetime parameters!",
+            ));
+        }
+
+        // generic components are registered for concrete types with register_generic_component!
+        let is_generic = !input.generics.params.is_empty();
+        let mut generics = input.generics.clone();
+        if is_generic {
+            let (_, ty_generics, _) = input.generics.split_for_impl();
+            generics.make_where_clause().predicates.push(parse_quote! {
+                #ident #ty_generics: 'static + springtime_di::component_registry::internal::ComponentInstanceBounds
+            });
+        }
+
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let turbofish = ty_generics.as_turbofish();
+        let component_type = quote!(#ident #ty_generics);
+
         let attributes = extract_component_attributes(&input.attrs)?;
+        let dependencies = generate_dependencies(
+            fields,
+            attributes.as_ref().and_then(|attributes| {
+                attributes
+                    .constructor
+                    .as_ref()
+                    .map(|_| attributes.constructor_parameters.as_slice())
+            }),
+        )?;
+        // concurrent resolution needs futures, so it's only supported in async mode
+        let concurrent = cfg!(feature = "async")
+            && attributes
+                .as_ref()
+                .map(|attributes| attributes.concurrent)
+                .unwrap_or(false);
         let generation = if let Some(ComponentAttributes {
             constructor: Some(constructor),
             constructor_parameters,
             ..
         }) = &attributes
         {
-            make_constructor_call(fields, constructor, constructor_parameters)?
+            make_constructor_call(fields, constructor, constructor_parameters, concurrent)?
         } else {
             match fields {
                 Fields::Named(fields) => {
-                    let component = make_named_struct(fields)?;
+                    let component = make_named_struct(fields, concurrent)?;
                     quote!(Ok(#component))
                 }
                 Fields::Unnamed(fields) => {
-                    let component = make_unnamed_struct(fields)?;
+                    let component = make_unnamed_struct(fields, concurrent)?;
                     quote!(Ok(#component))
                 }
                 Fields::Unit => quote! { Ok(Self) },
@@ -423,42 +908,107 @@ pub fn expand_component(input: &DeriveInput) -> Result<TokenStream> {
         let names = attributes
             .as_ref()
             .and_then(|attributes| attributes.names.clone());
-        let names = generate_names(names, &input.ident);
-        let condition = attributes
+        let names_from = attributes
             .as_ref()
-            .and_then(|attributes| attributes.condition.clone())
-            .map(|condition| quote!(Some(#condition)))
-            .unwrap_or_else(|| quote!(None));
+            .and_then(|attributes| attributes.names_from.clone());
+        let names = if let Some(names_from) = names_from {
+            quote!(#names_from())
+        } else if is_generic && names.is_none() {
+            // the same generic type can be registered multiple times, so the name needs to be
+            // unique for each concrete type
+            quote!([std::any::type_name::<#component_type>()])
+        } else {
+            generate_names(attributes.as_ref(), &input.ident)
+        };
+        let default_name =
+            (!is_generic).then(|| generate_default_name(attributes.as_ref(), &input.ident));
+        let names = qualify_names(names, attributes.as_ref());
+        let condition = generate_condition(attributes.as_ref());
         let priority = attributes
             .as_ref()
             .map(|attributes| attributes.priority)
             .unwrap_or(0);
-        let scope = attributes
+        let is_override = attributes
             .as_ref()
-            .and_then(|attributes| attributes.scope.clone())
-            .map(|scope| quote!(#scope))
-            .unwrap_or_else(|| quote!(springtime_di::scope::SINGLETON));
+            .map(|attributes| attributes.is_override)
+            .unwrap_or(false);
+        let order = attributes
+            .as_ref()
+            .map(|attributes| attributes.order)
+            .unwrap_or(0);
+        let late_injections = generate_late_injection(fields)?;
+        let late_injection = (!late_injections.is_empty())
+            .then(|| quote!(.with_late_injection(inject_late #turbofish)));
 
         #[cfg(not(feature = "async"))]
-        let constructor = quote! {
-            fn constructor(
-                instance_provider: &mut dyn ComponentInstanceProvider,
-            ) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
-                #ident::create(instance_provider).map(|p| ComponentInstancePtr::new(p) as ComponentInstanceAnyPtr)
+        let inject_late = (!late_injections.is_empty()).then(|| {
+            quote! {
+                fn inject_late #impl_generics (
+                    instance: springtime_di::instance_provider::ComponentInstanceAnyPtr,
+                    instance_provider: &mut dyn springtime_di::instance_provider::ComponentInstanceProvider,
+                ) -> Result<(), springtime_di::instance_provider::ComponentInstanceProviderError>
+                #where_clause
+                {
+                    use springtime_di::instance_provider::TypedComponentInstanceProvider;
+                    if let Ok(component) = instance.downcast::<#component_type>() {
+                        #(#late_injections)*
+                    }
+
+                    Ok(())
+                }
             }
-        };
+        });
 
         #[cfg(feature = "async")]
-        let constructor = quote! {
-            fn constructor(
-                instance_provider: &mut (dyn ComponentInstanceProvider + Sync + Send),
-            ) -> springtime_di::future::BoxFuture<Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError>> {
-                use springtime_di::future::FutureExt;
-                async move {
-                    #ident::create(instance_provider).await.map(|p| ComponentInstancePtr::new(p) as ComponentInstanceAnyPtr)
-                }.boxed()
+        let inject_late = (!late_injections.is_empty()).then(|| {
+            quote! {
+                fn inject_late #impl_generics (
+                    instance: springtime_di::instance_provider::ComponentInstanceAnyPtr,
+                    instance_provider: &mut (dyn springtime_di::instance_provider::ComponentInstanceProvider + Sync + Send),
+                ) -> springtime_di::future::BoxFuture<'_, Result<(), springtime_di::instance_provider::ComponentInstanceProviderError>>
+                #where_clause
+                {
+                    use springtime_di::future::FutureExt;
+                    use springtime_di::instance_provider::TypedComponentInstanceProvider;
+                    async move {
+                        if let Ok(component) = instance.downcast::<#component_type>() {
+                            #(#late_injections)*
+                        }
+
+                        Ok(())
+                    }
+                    .boxed()
+                }
             }
+        });
+
+        let post_construct = if attributes
+            .as_ref()
+            .map(|attributes| attributes.lifecycle)
+            .unwrap_or(false)
+        {
+            Some(quote!(.with_post_construct(
+                springtime_di::component_registry::internal::post_construct_component::<#component_type>
+            )))
+        } else {
+            None
+        };
+        let pre_destroy = if attributes
+            .as_ref()
+            .map(|attributes| attributes.disposable)
+            .unwrap_or(false)
+        {
+            Some(quote!(.with_pre_destroy(
+                springtime_di::component_registry::internal::pre_destroy_component::<#component_type>
+            )))
+        } else {
+            None
         };
+        let scope = attributes
+            .as_ref()
+            .and_then(|attributes| attributes.scope.clone())
+            .map(|scope| quote!(#scope))
+            .unwrap_or_else(|| quote!(springtime_di::scope::SINGLETON));
 
         #[cfg(not(feature = "async"))]
         let create = quote! {
@@ -466,7 +1016,6 @@ pub fn expand_component(input: &DeriveInput) -> Result<TokenStream> {
                 instance_provider: &mut dyn springtime_di::instance_provider::ComponentInstanceProvider,
             ) -> Result<Self, springtime_di::instance_provider::ComponentInstanceProviderError> {
                 use springtime_di::instance_provider::{ComponentInstanceProviderError, TypedComponentInstanceProvider};
-                use std::ops::Deref;
                 #generation
             }
         };
@@ -478,17 +1027,32 @@ pub fn expand_component(input: &DeriveInput) -> Result<TokenStream> {
             ) -> springtime_di::future::BoxFuture<Result<Self, springtime_di::instance_provider::ComponentInstanceProviderError>> {
                 use springtime_di::future::FutureExt;
                 use springtime_di::instance_provider::{ComponentInstanceProviderError, TypedComponentInstanceProvider};
-                use std::ops::Deref;
                 async move { #generation }.boxed()
             }
         };
 
+        let registration = (!is_generic && !cfg!(feature = "explicit-registration")).then(|| {
+            quote! {
+                use springtime_di::component_registry::internal::{ComponentDefinitionRegisterer, submit};
+
+                fn register() -> TypedComponentDefinition {
+                    <#ident as ComponentRegistration>::definition()
+                }
+
+                submit! {
+                    ComponentDefinitionRegisterer {
+                        register,
+                    }
+                };
+            }
+        });
+
         Ok(quote! {
             #[automatically_derived]
-            impl springtime_di::component::Injectable for #ident {}
+            impl #impl_generics springtime_di::component::Injectable for #component_type #where_clause {}
 
             #[automatically_derived]
-            impl springtime_di::component::ComponentDowncast<#ident> for #ident {
+            impl #impl_generics springtime_di::component::ComponentDowncast<#component_type> for #component_type #where_clause {
                 fn downcast(
                     source: springtime_di::instance_provider::ComponentInstanceAnyPtr,
                 ) -> Result<springtime_di::instance_provider::ComponentInstancePtr<Self>, springtime_di::instance_provider::ComponentInstanceAnyPtr> {
@@ -497,43 +1061,32 @@ pub fn expand_component(input: &DeriveInput) -> Result<TokenStream> {
             }
 
             #[automatically_derived]
-            impl springtime_di::component::Component for #ident {
+            impl #impl_generics springtime_di::component::Component for #component_type #where_clause {
                 #create
             }
 
             const _: () = {
-                use springtime_di::component::{Component, ComponentDowncast};
-                use springtime_di::component_registry::ComponentMetadata;
-                use springtime_di::component_registry::internal::{ComponentDefinitionRegisterer, submit, TypedComponentDefinition};
-                use springtime_di::instance_provider::{ComponentInstanceAnyPtr, ComponentInstanceProvider, ComponentInstanceProviderError, ComponentInstancePtr};
-                use std::any::{Any, TypeId, type_name};
-
-                #constructor
-
-                fn cast(instance: ComponentInstanceAnyPtr) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr> {
-                    #ident::downcast(instance).map(|p| Box::new(p) as Box<dyn Any>)
-                }
-
-                fn register() -> TypedComponentDefinition {
-                    TypedComponentDefinition {
-                        target: TypeId::of::<#ident>(),
-                        target_name: type_name::<#ident>(),
-                        condition: #condition,
-                        priority: #priority,
-                        metadata: ComponentMetadata {
-                            names: [#(#names.to_string()),*].into_iter().collect(),
-                            scope: #scope.to_string(),
-                            constructor,
-                            cast,
-                        },
+                use springtime_di::component_registry::internal::{component_definition, ComponentRegistration, TypedComponentDefinition};
+
+                #inject_late
+
+                #[automatically_derived]
+                impl #impl_generics ComponentRegistration for #component_type #where_clause {
+                    fn definition() -> TypedComponentDefinition {
+                        component_definition::<#component_type>(#names, #scope)
+                            #default_name
+                            .with_order(#order)
+                            .with_priority(#priority)
+                            .with_override(#is_override)
+                            .with_dependencies(#dependencies)
+                            #condition
+                            #late_injection
+                            #post_construct
+                            #pre_destroy
                     }
                 }
 
-                submit! {
-                    ComponentDefinitionRegisterer {
-                        register,
-                    }
-                };
+                #registration
             };
         })
     } else {
@@ -544,6 +1097,66 @@ pub fn expand_component(input: &DeriveInput) -> Result<TokenStream> {
     }
 }
 
+pub fn expand_generic_registration(input: &GenericComponentRegistration) -> TokenStream {
+    // generic components are collected explicitly like all others
+    if cfg!(feature = "explicit-registration") {
+        return quote!();
+    }
+
+    let component_type = &input.component_type;
+    let names = input.names.as_ref().map(|names| {
+        let count = names.elems.len();
+        let names = &names.elems;
+        quote! {
+            let names: [&str; #count] = [#names];
+            definition.metadata.names = names.into_iter().map(|name| name.to_string()).collect();
+        }
+    });
+
+    quote! {
+        const _: () = {
+            use springtime_di::component_registry::internal::{ComponentDefinitionRegisterer, ComponentRegistration, submit, TypedComponentDefinition};
+
+            fn register() -> TypedComponentDefinition {
+                #[allow(unused_mut)]
+                let mut definition = <#component_type as ComponentRegistration>::definition();
+                #names
+                definition
+            }
+
+            submit! {
+                ComponentDefinitionRegisterer {
+                    register,
+                }
+            };
+        };
+    }
+}
+
+pub fn register_event_listener(
+    item: &Item,
+    args: &ComponentAliasAttributes,
+) -> Result<TokenStream> {
+    let is_listener = match item {
+        Item::Impl(item_impl) => item_impl
+            .trait_
+            .as_ref()
+            .and_then(|(_, path, ..)| path.segments.last())
+            .map(|segment| segment.ident == "EventListener")
+            .unwrap_or(false),
+        _ => false,
+    };
+
+    if !is_listener {
+        return Err(Error::new(
+            item.span(),
+            "Event listeners need to implement the EventListener trait!",
+        ));
+    }
+
+    register_component_alias(item, args)
+}
+
 pub fn register_component_alias(
     item: &Item,
     args: &ComponentAliasAttributes,
@@ -568,13 +1181,17 @@ pub fn register_component_alias(
         let condition = args
             .condition
             .as_ref()
-            .map(|condition| quote!(Some(#condition)))
+            .map(|condition| {
+                quote!(Some(
+                    springtime_di::component_registry::conditional::component_condition(#condition)
+                ))
+            })
             .unwrap_or_else(|| quote!(None));
         let priority = args.priority;
         let scope = args
             .scope
             .as_ref()
-            .map(|scope| quote!(Some(#scope.to_string())))
+            .map(|scope| quote!(Some(#scope)))
             .unwrap_or_else(|| quote!(None));
 
         #[cfg(feature = "threadsafe")]
@@ -582,6 +1199,22 @@ pub fn register_component_alias(
         #[cfg(not(feature = "threadsafe"))]
         let trait_bounds = quote!();
 
+        let registration = (!cfg!(feature = "explicit-registration")).then(|| {
+            quote! {
+                use springtime_di::component_registry::internal::{ComponentAliasRegisterer, submit};
+
+                fn register() -> ComponentAliasDefinition {
+                    <#target_type as ComponentAliasRegistration<dyn #trait_type #trait_bounds>>::alias_definition()
+                }
+
+                submit! {
+                    ComponentAliasRegisterer {
+                        register,
+                    }
+                };
+            }
+        });
+
         Ok(quote! {
             #[automatically_derived]
             impl springtime_di::component::ComponentDowncast<#target_type> for dyn #trait_type #trait_bounds {
@@ -593,38 +1226,16 @@ pub fn register_component_alias(
             }
 
             const _: () = {
-                use springtime_di::component::ComponentDowncast;
-                use springtime_di::component_registry::ComponentAliasMetadata;
-                use springtime_di::component_registry::internal::{ComponentAliasDefinition, ComponentAliasRegisterer, submit};
-                use springtime_di::instance_provider::ComponentInstanceAnyPtr;
-                use std::any::{Any, TypeId, type_name};
-
-                fn cast(instance: ComponentInstanceAnyPtr) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr> {
-                    <dyn #trait_type #trait_bounds as ComponentDowncast<#target_type>>::downcast(instance)
-                        .map(|p| Box::new(p) as Box<dyn Any>)
-                }
+                use springtime_di::component_registry::internal::{alias_definition, ComponentAliasDefinition, ComponentAliasRegistration};
 
-                fn register() -> ComponentAliasDefinition {
-                    ComponentAliasDefinition {
-                        alias_type: TypeId::of::<dyn #trait_type #trait_bounds>(),
-                        target_type: TypeId::of::<#target_type>(),
-                        alias_name: type_name::<dyn #trait_type #trait_bounds>(),
-                        target_name: type_name::<#target_type>(),
-                        condition: #condition,
-                        priority: #priority,
-                        metadata: ComponentAliasMetadata {
-                            is_primary: #is_primary,
-                            scope: #scope,
-                            cast,
-                        }
+                #[automatically_derived]
+                impl ComponentAliasRegistration<dyn #trait_type #trait_bounds> for #target_type {
+                    fn alias_definition() -> ComponentAliasDefinition {
+                        alias_definition::<#target_type, dyn #trait_type #trait_bounds>(#is_primary, #scope, #condition, #priority)
                     }
                 }
 
-                submit! {
-                    ComponentAliasRegisterer {
-                        register,
-                    }
-                };
+                #registration
             };
         })
     } else {
@@ -634,3 +1245,273 @@ pub fn register_component_alias(
         ))
     }
 }
+
+// factory methods can return the component directly or wrapped in a Result with ErrorPtr
+fn get_factory_component_type(ty: &Type) -> (&Type, bool) {
+    if let Type::Path(TypePath { path, .. }) = ungroup(ty) {
+        if let Some(last_segment) = path.segments.last() {
+            if last_segment.ident == "Result" {
+                if let PathArguments::AngleBracketed(args) = &last_segment.arguments {
+                    if let Some(GenericArgument::Type(ty)) = args.args.first() {
+                        return (ty, true);
+                    }
+                }
+            }
+        }
+    }
+
+    (ty, false)
+}
+
+fn expand_factory_method(self_ty: &Type, method: &ImplItemFn) -> Result<TokenStream> {
+    let signature = &method.sig;
+    let ident = &signature.ident;
+    if !signature.generics.params.is_empty() {
+        return Err(Error::new(
+            signature.generics.span(),
+            "Factory methods cannot have generic parameters!",
+        ));
+    }
+
+    #[cfg(not(feature = "async"))]
+    if let Some(asyncness) = &signature.asyncness {
+        return Err(Error::new(
+            asyncness.span(),
+            "Async factory methods require the async feature!",
+        ));
+    }
+
+    let return_type = match &signature.output {
+        ReturnType::Type(_, ty) => ty.as_ref(),
+        ReturnType::Default => {
+            return Err(Error::new(
+                signature.span(),
+                "Factory methods need to return a component!",
+            ));
+        }
+    };
+    let (component_type, is_fallible) = get_factory_component_type(return_type);
+
+    let mut arguments = vec![];
+    let mut dependencies = vec![];
+    for input in &signature.inputs {
+        let input = match input {
+            FnArg::Typed(input) => input,
+            FnArg::Receiver(receiver) => {
+                return Err(Error::new(
+                    receiver.span(),
+                    "Factory methods cannot take self!",
+                ));
+            }
+        };
+
+        // parameters are injected the same way as struct fields
+        let field = Field {
+            attrs: input.attrs.clone(),
+            vis: Visibility::Inherited,
+            mutability: FieldMutability::None,
+            ident: None,
+            colon_token: None,
+            ty: input.ty.as_ref().clone(),
+        };
+
+        for attr in &field.attrs {
+            if attr.path().is_ident(COMPONENT_ATTR) {
+                let attributes = FieldAttributes::try_from(attr)?;
+                if attributes.late || attributes.ignore {
+                    return Err(Error::new(
+                        attr.span(),
+                        "Factory method parameters cannot be late or ignored!",
+                    ));
+                }
+            }
+        }
+
+        arguments.push(generate_field_construction(&field)?.0);
+        if let Some(dependency) = generate_field_dependency(&field, false)? {
+            dependencies.push(dependency);
+        }
+    }
+
+    let attributes = extract_component_attributes(&method.attrs)?;
+    if let Some(attributes) = &attributes {
+        if attributes.constructor.is_some()
+            || !attributes.constructor_parameters.is_empty()
+            || attributes.lifecycle
+            || attributes.disposable
+            || attributes.concurrent
+        {
+            return Err(Error::new(
+                method.span(),
+                "Factory methods only support registration arguments!",
+            ));
+        }
+    }
+
+    let names = attributes
+        .as_ref()
+        .and_then(|attributes| attributes.names_from.clone())
+        .map(|names_from| quote!(#names_from()))
+        .unwrap_or_else(|| generate_names(attributes.as_ref(), ident));
+    let default_name = generate_default_name(attributes.as_ref(), ident);
+    // multiple methods can produce the same type, so qualified products get separate definitions,
+    // which are also named after the qualifier
+    let qualifier = attributes
+        .as_ref()
+        .and_then(|attributes| attributes.qualifier.as_ref())
+        .map(|qualifier| {
+            quote!(.with_qualifier(springtime_di::component::qualifier_name::<#qualifier>()))
+        });
+    let condition = generate_condition(attributes.as_ref());
+    let priority = attributes
+        .as_ref()
+        .map(|attributes| attributes.priority)
+        .unwrap_or(0);
+    let is_override = attributes
+        .as_ref()
+        .map(|attributes| attributes.is_override)
+        .unwrap_or(false);
+    let order = attributes
+        .as_ref()
+        .map(|attributes| attributes.order)
+        .unwrap_or(0);
+    let scope = attributes
+        .as_ref()
+        .and_then(|attributes| attributes.scope.clone())
+        .map(|scope| quote!(#scope))
+        .unwrap_or_else(|| quote!(springtime_di::scope::SINGLETON));
+
+    let call = if signature.asyncness.is_some() {
+        quote!(<#self_ty>::#ident(#(#arguments),*).await)
+    } else {
+        quote!(<#self_ty>::#ident(#(#arguments),*))
+    };
+    let call = if is_fallible {
+        quote!(#call.map_err(|error| ComponentInstanceProviderError::ConstructorError(error))?)
+    } else {
+        call
+    };
+
+    let construct = format_ident!("construct_{}", ident);
+    let definition = format_ident!("{}_definition", ident);
+
+    #[cfg(not(feature = "async"))]
+    let constructor = quote! {
+        fn #construct(
+            instance_provider: &mut dyn springtime_di::instance_provider::ComponentInstanceProvider,
+        ) -> Result<springtime_di::instance_provider::ComponentInstanceAnyPtr, springtime_di::instance_provider::ComponentInstanceProviderError> {
+            use springtime_di::instance_provider::{ComponentInstanceProviderError, TypedComponentInstanceProvider};
+            let component: #component_type = #call;
+            Ok(springtime_di::instance_provider::ComponentInstancePtr::new(component) as springtime_di::instance_provider::ComponentInstanceAnyPtr)
+        }
+    };
+
+    #[cfg(feature = "async")]
+    let constructor = quote! {
+        fn #construct(
+            instance_provider: &mut (dyn springtime_di::instance_provider::ComponentInstanceProvider + Sync + Send),
+        ) -> springtime_di::future::BoxFuture<'_, Result<springtime_di::instance_provider::ComponentInstanceAnyPtr, springtime_di::instance_provider::ComponentInstanceProviderError>> {
+            use springtime_di::future::FutureExt;
+            use springtime_di::instance_provider::{ComponentInstanceProviderError, TypedComponentInstanceProvider};
+            async move {
+                let component: #component_type = #call;
+                Ok(springtime_di::instance_provider::ComponentInstancePtr::new(component) as springtime_di::instance_provider::ComponentInstanceAnyPtr)
+            }
+            .boxed()
+        }
+    };
+
+    let registration = (!cfg!(feature = "explicit-registration")).then(|| {
+        quote! {
+            submit! {
+                ComponentDefinitionRegisterer {
+                    register: #definition,
+                }
+            };
+        }
+    });
+
+    Ok(quote! {
+        #constructor
+
+        fn #definition() -> TypedComponentDefinition {
+            factory_component_definition::<#component_type>(#names, #scope, #construct)
+                #default_name
+                .with_order(#order)
+                .with_priority(#priority)
+                .with_override(#is_override)
+                .with_dependencies(vec![#(#dependencies),*])
+                #condition
+                #qualifier
+        }
+
+        #registration
+    })
+}
+
+pub fn expand_component_factory(item: &Item) -> Result<TokenStream> {
+    let item_impl = match item {
+        Item::Impl(item_impl) if item_impl.trait_.is_none() => item_impl,
+        _ => {
+            return Err(Error::new(
+                item.span(),
+                "Component factories are possible only on inherent impl blocks!",
+            ));
+        }
+    };
+
+    if !item_impl.generics.params.is_empty() {
+        return Err(Error::new(
+            item_impl.generics.span(),
+            "Component factories cannot have generic parameters!",
+        ));
+    }
+
+    let self_ty = item_impl.self_ty.as_ref();
+    let mut item_impl = item_impl.clone();
+    let mut methods = vec![];
+    let mut definitions = vec![];
+
+    for impl_item in &mut item_impl.items {
+        if let ImplItem::Fn(method) = impl_item {
+            methods.push(expand_factory_method(self_ty, method)?);
+            definitions.push(format_ident!("{}_definition", method.sig.ident));
+
+            // helper attributes are only meaningful for the factory
+            method
+                .attrs
+                .retain(|attr| !attr.path().is_ident(COMPONENT_ATTR));
+            for input in &mut method.sig.inputs {
+                if let FnArg::Typed(input) = input {
+                    input
+                        .attrs
+                        .retain(|attr| !attr.path().is_ident(COMPONENT_ATTR));
+                }
+            }
+        }
+    }
+
+    let submit = (!cfg!(feature = "explicit-registration")).then(|| {
+        quote! {
+            use springtime_di::component_registry::internal::{ComponentDefinitionRegisterer, submit};
+        }
+    });
+
+    Ok(quote! {
+        #item_impl
+
+        const _: () = {
+            use springtime_di::component_registry::internal::{factory_component_definition, ComponentFactoryRegistration, TypedComponentDefinition};
+            #submit
+
+            #(#methods)*
+
+            #[automatically_derived]
+            impl ComponentFactoryRegistration for #self_ty {
+                fn definitions() -> Vec<TypedComponentDefinition> {
+                    vec![#(#definitions()),*]
+                }
+            }
+        };
+    })
+}
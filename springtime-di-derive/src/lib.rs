@@ -1,5 +1,8 @@
-use crate::attributes::ComponentAliasAttributes;
-use crate::component::{expand_component, generate_injectable, register_component_alias};
+use crate::attributes::{ComponentAliasAttributes, GenericComponentRegistration};
+use crate::component::{
+    expand_component, expand_component_factory, expand_generic_registration, generate_injectable,
+    register_component_alias, register_event_listener,
+};
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, DeriveInput, Error, Item};
@@ -40,3 +43,31 @@ pub fn component_alias(args: TokenStream, input: TokenStream) -> TokenStream {
     })
     .into()
 }
+
+#[proc_macro_attribute]
+pub fn event_listener(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as ComponentAliasAttributes);
+    let item = parse_macro_input!(input as Item);
+    let registration =
+        register_event_listener(&item, &args).unwrap_or_else(Error::into_compile_error);
+
+    (quote! {
+        #item
+        #registration
+    })
+    .into()
+}
+
+#[proc_macro_attribute]
+pub fn component_factory(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as Item);
+    expand_component_factory(&item)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn register_generic_component(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as GenericComponentRegistration);
+    expand_generic_registration(&input).into()
+}
@@ -0,0 +1,88 @@
+// note: this example assumes you've analyzed the previous ones
+
+use springtime_di::component::{ComponentLifecycle, DisposableComponent};
+use springtime_di::factory::ComponentFactoryBuilder;
+use springtime_di::future::{BoxFuture, FutureExt};
+use springtime_di::instance_provider::{
+    ComponentInstancePtr, ErrorPtr, TypedComponentInstanceProvider,
+};
+use springtime_di::Component;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// "lifecycle" enables the post-construct callback, while "disposable" enables the destroy callback
+#[derive(Component)]
+#[component(lifecycle, disposable)]
+struct ConnectionPool {
+    #[component(default)]
+    pending_writes: Mutex<Vec<String>>,
+}
+
+impl ConnectionPool {
+    fn write(&self, value: &str) {
+        self.pending_writes.lock().unwrap().push(value.to_string());
+    }
+}
+
+impl ComponentLifecycle for ConnectionPool {
+    fn post_construct(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+        async {
+            println!("Connection pool ready.");
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+impl DisposableComponent for ConnectionPool {
+    // with the "async" feature, components can release their resources asynchronously
+    fn destroy(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+        async {
+            let pending_writes = self.pending_writes.lock().unwrap().split_off(0);
+
+            // simulate flushing data over the network
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            println!("Flushed pending writes: {pending_writes:?}");
+
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+#[derive(Component)]
+#[component(disposable)]
+struct Repository {
+    pool: ComponentInstancePtr<ConnectionPool>,
+}
+
+impl DisposableComponent for Repository {
+    fn destroy(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+        async {
+            // the pool is still alive, since dependents are destroyed before their dependencies
+            self.pool.write("last write");
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let mut component_factory = ComponentFactoryBuilder::new()
+        .expect("error initializing ComponentFactoryBuilder")
+        .build();
+
+    let repository = component_factory
+        .primary_instance_typed::<Repository>()
+        .await
+        .expect("error creating Repository");
+
+    repository.pool.write("first write");
+
+    // prints "Flushed pending writes: ["first write", "last write"]"
+    component_factory
+        .shutdown()
+        .await
+        .expect("error shutting down ComponentFactory");
+}
@@ -8,8 +8,9 @@
 //! feature is enabled:
 //!
 //! ```
+//! use fxhash::FxHashMap;
 //! use springtime_di::component::Component;
-//! use springtime_di::instance_provider::ComponentInstancePtr;
+//! use springtime_di::instance_provider::{ComponentInstancePtr, ComponentProvider, FactoryHandle};
 //! use springtime_di::{Component, component_alias, injectable};
 //!
 //! #[injectable]
@@ -32,6 +33,13 @@
 //!     optional_dependency: Option<ComponentInstancePtr<TestDependency>>,
 //!     // all registered dependencies of given type
 //!     all_dependencies: Vec<ComponentInstancePtr<dyn TestTrait + Sync + Send>>,
+//!     // all registered dependencies of given type keyed by their names; any map type which can be
+//!     // collected from (String, ComponentInstancePtr) pairs works
+//!     named_dependencies: FxHashMap<String, ComponentInstancePtr<dyn TestTrait + Sync + Send>>,
+//!     // provider for requesting instances on demand, e.g. new prototypes
+//!     provider: ComponentProvider<TestDependency>,
+//!     // handle for looking up instances at runtime, e.g. by dynamically chosen names
+//!     factory: FactoryHandle,
 //!     #[component(default)]
 //!     default: i8,
 //!     #[component(default = "dummy_expr")]
@@ -48,29 +56,72 @@
 //! ### Supported `#[component]` struct configuration
 //!
 //! * `names = ["name"]` - use given name list as the component names, instead of the auto-generated
-//! one
-//! * `condition = "expr"` - call `expr()` and evaluate if given component should be registered; see
-//! [crate::component_registry::conditional]
+//!   one
+//! * `naming = "strategy"` - generate the default name with given [ComponentNamingStrategy]:
+//!   `type_name`, `crate_prefix` or `module_path`, instead of the one configured for the
+//!   [registry](crate::component_registry::StaticComponentDefinitionRegistry::new_with_naming_strategy);
+//!   useful for avoiding collisions between components with the same type name from different
+//!   crates
+//! * `names_from = "expr"` - call `expr()` at registration time to get the component names, instead
+//!   of the auto-generated one; useful for incorporating runtime data, e.g. hostname or shard id,
+//!   in names; the function should return an `IntoIterator` of `Into<String>`, e.g. `Vec<String>`
+//! * `condition = "expr"` - evaluate the `expr`
+//!   [Condition](crate::component_registry::conditional::Condition), e.g. a function or a
+//!   parameterized condition object, to check if given component should be registered; see
+//!   [crate::component_registry::conditional]
+//! * `profile = "name"` - register the component only if given profile is active, or inactive if
+//!   prefixed with `!`; can be combined with `condition`, in which case both need to pass; see
+//!   [crate::component_registry::conditional]
 //! * `priority = number` - if a condition is present, use the given numerical priority to establish
-//! the order of registration in relation to other components with a condition (i8; higher is first;
-//! default is 0)
+//!   the order of registration in relation to other components with a condition (i8; higher is
+//!   first; default is 0); also used to choose between overrides with the same name - see
+//!   `override`
+//! * `override` - take precedence over components with the same name, which are not marked as
+//!   overrides, e.g. to replace a default component from a library crate; if both are marked, the
+//!   one with the higher `priority` wins; the losing component is not registered, but can be
+//!   inspected via
+//!   [shadowed_definitions](crate::component_registry::ComponentDefinitionRegistry::shadowed_definitions);
+//!   components with the same name and precedence, e.g. when neither is an override, are handled
+//!   according to the registry settings
 //! * `constructor = "expr"` - call `expr(dependencies...)` to construct the component, instead of
-//! using standard struct construction; parameters must be in the same order as fields in the struct
-//! while non-injected fields can be ignored with the `#[component(ignore)]` attribute
+//!   using standard struct construction; parameters must be in the same order as fields in the
+//!   struct while non-injected fields can be ignored with the `#[component(ignore)]` attribute
 //! * `constructor_parameters = "params"` - additional injectable parameters for the above
-//! constructor; the "params" string consists of comma separated definitions in format:
-//! `(Type | Type/name | Option<Type> | Option<Type>/name | Vec<Type>)`, which means (in order):
-//! primary instance of `Type`, `name`d instance of `Type`, optional primary instance of `Type`,
-//! optional `name`d instance of `Type`, all instances of `Type`
+//!   constructor; the "params" string consists of comma separated definitions in format:
+//!   `(Type | Type/name | Option<Type> | Option<Type>/name | Vec<Type>)`, which means (in order):
+//!   primary instance of `Type`, `name`d instance of `Type`, optional primary instance of `Type`,
+//!   optional `name`d instance of `Type`, all instances of `Type`
+//! * `qualifier = Type` - additionally name the component after the `Type` marker, which can be
+//!   used instead of a string name for type-safe injection; see [qualifier_name]
 //! * `scope = "name"` - use the [scope](crate::scope) named `name` or
-//! [SINGLETON](crate::scope::SINGLETON) as default
+//!   [SINGLETON](crate::scope::SINGLETON) as default
+//! * `order = number` - position of the component when injecting all instances of a type, e.g.
+//!   `Vec<ComponentInstancePtr<dyn Trait>>` (i32; lower is first; default is 0); components with
+//!   the same order are sorted by type name
+//! * `lifecycle` - call [ComponentLifecycle::post_construct] after the component is constructed and
+//!   stored in its scope; the component must implement [ComponentLifecycle]
+//! * `disposable` - call [DisposableComponent::destroy] when the
+//!   [ComponentFactory](crate::factory::ComponentFactory) is shut down; the component must
+//!   implement [DisposableComponent]
+//! * `concurrent` - resolve injected dependencies concurrently, instead of one after another, which
+//!   can reduce startup time if they are slow to construct, e.g. connection pools; only has an
+//!   effect with the `async` feature; instances shared by the dependencies are still constructed
+//!   only once
 //!
 //! ### Supported `#[component]` field configuration
 //!
 //! * `default` - use `Default::default()` initialization
 //! * `default = "expr"` - call `expr()` for initialization
 //! * `name = "name"` - inject instance named as `name`
+//! * `qualifier = Type` - inject instance qualified with the `Type` marker; can be used instead of
+//! `name`
 //! * `ignore` - ignore the field when using custom constructor
+//! * `late` - inject the [Late](crate::instance_provider::Late) dependency after the component is
+//! constructed and stored in its scope, which allows circular dependencies; can be combined with
+//! `name`
+//! * `owned` - inject the concrete component by value, instead of a [ComponentInstancePtr], which
+//! transfers ownership to the owning component; only possible for instances not shared with anyone
+//! else, e.g. [prototypes](crate::scope::PROTOTYPE); can be combined with `name`
 //!
 //! ## Registering component aliases
 //!
@@ -105,20 +156,142 @@
 //! * `primary` - mark the concrete component, for which we're implementing the trait, as selected
 //! (primary) when requesting a single instance of `ComponentInstancePtr<dyn Trait>` and multiple
 //! components are available
-//! * `condition = "expr"` - call `expr()` and evaluate if given component should be registered; see
-//! [crate::component_registry::conditional]
+//! * `condition = "expr"` - evaluate the `expr`
+//!   [Condition](crate::component_registry::conditional::Condition),
+//! e.g. a function or a parameterized condition object, to check if given component should be
+//! registered; see [crate::component_registry::conditional]
 //! * `priority = number` - if a condition is present, use the given numerical priority to establish
 //! the order of registration in relation to other components with a condition (i8; higher is first;
 //! default is 0)
 //! * `scope = "name"` - use the [scope](crate::scope) named `name` to override the concrete
 //! component scope
+//!
+//! ## Qualifiers
+//!
+//! String names are not refactor-safe and typos are only detected at runtime. Alternatively,
+//! components can be qualified with marker types, which are checked by the compiler:
+//!
+//! ```
+//! use springtime_di::instance_provider::ComponentInstancePtr;
+//! use springtime_di::{component_alias, injectable, Component};
+//!
+//! struct Primary;
+//!
+//! #[injectable]
+//! trait Storage {}
+//!
+//! #[derive(Component)]
+//! #[component(qualifier = Primary)]
+//! struct PrimaryStorage;
+//!
+//! #[component_alias]
+//! impl Storage for PrimaryStorage {}
+//!
+//! #[derive(Component)]
+//! struct StorageUser {
+//!     #[component(qualifier = Primary)]
+//!     storage: ComponentInstancePtr<dyn Storage + Send + Sync>,
+//! }
+//! ```
+//!
+//! ## Registering generic components
+//!
+//! Generic structs can also derive `Component`, but they are not registered automatically, since
+//! the DI system needs concrete types. Instead, each instantiation should be registered with the
+//! `register_generic_component!` macro:
+//!
+//! ```
+//! use springtime_di::{component_alias, injectable, register_generic_component, Component};
+//!
+//! trait Entity {}
+//!
+//! struct User;
+//!
+//! impl Entity for User {}
+//!
+//! #[injectable]
+//! trait UserRepository {}
+//!
+//! #[derive(Component)]
+//! struct Repository<T: Entity + 'static> {
+//!     #[component(default)]
+//!     entities: Vec<T>,
+//! }
+//!
+//! register_generic_component!(Repository<User>);
+//!
+//! #[component_alias]
+//! impl UserRepository for Repository<User> {}
+//! ```
+//!
+//! Unless explicitly named, each instantiation is named after its full type name. Names can be
+//! overridden for a given instantiation with the `names` argument, e.g.
+//! `register_generic_component!(Repository<User>, names = ["userRepository"])`. Lifetime parameters
+//! are not supported.
+//!
+//! ## Factory methods
+//!
+//! Types which cannot derive `Component`, e.g. ones coming from third-party crates, can still be
+//! registered by producing them in factory methods. Every associated function in an impl block
+//! marked with `#[component_factory]` registers its return type as a component named after the
+//! function. Parameters are injected the same way as struct fields and support the same field
+//! configuration, apart from late injection:
+//!
+//! ```
+//! use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
+//! use springtime_di::{component_factory, Component};
+//!
+//! // a type from a third-party crate
+//! struct HttpClient {
+//!     base_url: String,
+//! }
+//!
+//! #[derive(Component)]
+//! struct Settings {
+//!     #[component(default)]
+//!     base_url: String,
+//! }
+//!
+//! struct ClientFactory;
+//!
+//! #[component_factory]
+//! impl ClientFactory {
+//!     #[component(names = ["client"])]
+//!     fn http_client(settings: ComponentInstancePtr<Settings>) -> Result<HttpClient, ErrorPtr> {
+//!         Ok(HttpClient {
+//!             base_url: settings.base_url.clone(),
+//!         })
+//!     }
+//! }
+//! ```
+//!
+//! Factory methods can return the component directly or `Result<T, ErrorPtr>`. With the `async`
+//! feature, they can also be `async`. The `#[component]` method attribute accepts registration
+//! arguments: `names`, `names_from`, `qualifier`, `condition`, `profile`, `priority`, `override`,
+//! `scope` and `order`. Since produced types are not components, they cannot have aliases or
+//! lifecycle callbacks. Multiple methods can produce the same type, as long as each of them has a
+//! distinct `qualifier` - every qualified product gets a separate definition, which can be injected
+//! with `#[component(qualifier = Type)]`, while the unqualified one (if any) stays primary.
+//!
+//! ## Explicit registration
+//!
+//! Automatic discovery relies on link-time collection, which isn't supported on all targets, e.g.
+//! `wasm32-unknown-unknown`. With the `explicit-registration` feature, components and aliases are
+//! not registered automatically. Instead, they should be gathered with the
+//! [collect_components](crate::collect_components) macro and passed to
+//! [StaticComponentDefinitionRegistry::new_with_definitions](crate::component_registry::StaticComponentDefinitionRegistry::new_with_definitions).
+//! The `inventory` dependency can then be dropped by disabling the default `automatic-registration`
+//! feature. Generic components are listed there like any other, since `register_generic_component!`
+//! has no effect in this mode. Components produced by factory methods are available through
+//! `<Factory as ComponentFactoryRegistration>::definitions()`.
 
 #[cfg(feature = "async")]
 use crate::future::BoxFuture;
 use crate::instance_provider::ComponentInstanceProviderError;
 use crate::instance_provider::{
-    ComponentInstanceAnyPtr, ComponentInstanceProvider, ComponentInstancePtr,
+    ComponentInstanceAnyPtr, ComponentInstanceProvider, ComponentInstancePtr, ErrorPtr,
 };
+use std::any::type_name;
 
 /// Base trait for components for dependency injection.
 ///
@@ -139,6 +312,87 @@ pub trait Component: ComponentDowncast<Self> + Sized {
     ) -> BoxFuture<Result<Self, ComponentInstanceProviderError>>;
 }
 
+/// Optional lifecycle callbacks for components. Implement this trait and mark the component with
+/// `#[component(lifecycle)]` to have [post_construct](Self::post_construct) called by the
+/// [ComponentFactory](crate::factory::ComponentFactory) after the component is fully constructed
+/// and stored in its scope. This allows running initialization logic which needs injected
+/// dependencies, without resorting to custom constructors.
+pub trait ComponentLifecycle {
+    #[cfg(not(feature = "async"))]
+    /// Called after the component is constructed and stored in its scope. An error is propagated
+    /// to the code requesting the instance.
+    fn post_construct(&self) -> Result<(), ErrorPtr>;
+
+    #[cfg(feature = "async")]
+    /// Called after the component is constructed and stored in its scope. An error is propagated
+    /// to the code requesting the instance.
+    fn post_construct(&self) -> BoxFuture<'_, Result<(), ErrorPtr>>;
+}
+
+/// Optional disposal callback for components. Implement this trait and mark the component with
+/// `#[component(disposable)]` to have [destroy](Self::destroy) called when the
+/// [ComponentFactory](crate::factory::ComponentFactory) is
+/// [shut down](crate::factory::ComponentFactory::shutdown). Only singleton instances are
+/// destroyed, in reverse creation order, so dependents are destroyed before their dependencies.
+/// This allows releasing resources like connection pools in a predictable way.
+pub trait DisposableComponent {
+    #[cfg(not(feature = "async"))]
+    /// Called when the component is destroyed.
+    fn destroy(&self) -> Result<(), ErrorPtr>;
+
+    #[cfg(feature = "async")]
+    /// Called when the component is destroyed.
+    fn destroy(&self) -> BoxFuture<'_, Result<(), ErrorPtr>>;
+}
+
+/// Returns the component name corresponding to given qualifier type. Qualified components are
+/// registered with this name, so it can be used to request them from an
+/// [instance provider](crate::instance_provider::ComponentInstanceProvider).
+pub fn qualifier_name<Q: ?Sized + 'static>() -> &'static str {
+    type_name::<Q>()
+}
+
+/// Function generating a component name from a snake case type name and the path of the module
+/// containing the type.
+pub type ComponentNamingFn = fn(&str, &str) -> String;
+
+/// Strategy for generating default component names, when no names are given explicitly. Names based
+/// only on type names can collide when multiple crates define components with the same type name,
+/// e.g. `Config`, which can be avoided by including crate names or module paths. The strategy for
+/// all components is passed to
+/// [StaticComponentDefinitionRegistry::new_with_naming_strategy](crate::component_registry::StaticComponentDefinitionRegistry::new_with_naming_strategy)
+/// or
+/// [ComponentFactoryBuilder::new_with_naming_strategy](crate::factory::ComponentFactoryBuilder::new_with_naming_strategy).
+#[derive(Clone, Copy, Debug, Default)]
+pub enum ComponentNamingStrategy {
+    /// Snake case type name, e.g. `app_config`.
+    #[default]
+    TypeName,
+    /// Snake case type name prefixed with the crate name, e.g. `my_crate::app_config`.
+    CratePrefix,
+    /// Snake case type name prefixed with the full module path, e.g.
+    /// `my_crate::config::app_config`.
+    ModulePath,
+    /// Name generated by a user function.
+    Custom(ComponentNamingFn),
+}
+
+impl ComponentNamingStrategy {
+    /// Generates a component name from given snake case type name and the path of the module
+    /// containing the type.
+    pub fn component_name(self, name: &str, module_path: &str) -> String {
+        match self {
+            Self::TypeName => name.to_string(),
+            Self::CratePrefix => {
+                let crate_name = module_path.split("::").next().unwrap_or(module_path);
+                format!("{crate_name}::{name}")
+            }
+            Self::ModulePath => format!("{module_path}::{name}"),
+            Self::Custom(naming) => naming(name, module_path),
+        }
+    }
+}
+
 /// Helper trait for traits implemented by components, thus allowing injection of components based
 /// on `dyn Trait` types. The type `C` refers to a concrete component type. Typically automatically
 /// derived when using the `#[component_alias]` attribute.
@@ -148,5 +402,6 @@ pub trait ComponentDowncast<C: Component>: Injectable {
     ) -> Result<ComponentInstancePtr<Self>, ComponentInstanceAnyPtr>;
 }
 
-/// Marker trait for injectable types - components and aliases.
+/// Marker trait for injectable types - components and aliases. Injection itself only requires
+/// `'static` types, so factory method products don't need to implement it.
 pub trait Injectable: 'static {}
@@ -3,28 +3,46 @@
 //! automatically or manually.
 
 pub mod conditional;
+pub mod export;
+pub mod validation;
 
-use crate::component::{Component, ComponentDowncast, Injectable};
+use crate::component::{Component, ComponentDowncast, ComponentNamingStrategy};
 use crate::component_registry::conditional::{
     ComponentDefinitionRegistryFacade, ConditionMetadata, ContextFactory,
 };
+use crate::component_registry::export::{
+    ComponentRegistryExport, ExportedCondition, ExportedDefinition, ExportedType,
+};
+use crate::component_registry::internal::{
+    cast_component, construct_component, ComponentAliasDefinition, ComponentInstanceBounds,
+    DefaultComponentName, TypedComponentDefinition,
+};
+#[cfg(not(feature = "explicit-registration"))]
 use crate::component_registry::internal::{
-    ComponentAliasDefinition, ComponentAliasRegisterer, ComponentDefinitionRegisterer,
-    TypedComponentDefinition,
+    ComponentAliasRegisterer, ComponentDefinitionRegisterer,
 };
 use crate::component_registry::registry::NamedComponentDefinitionMap;
+use crate::factory::ComponentDefinitionRegistryPtr;
 use crate::instance_provider::{
     CastFunction, ComponentInstanceAnyPtr, ComponentInstanceProvider,
-    ComponentInstanceProviderError,
+    ComponentInstanceProviderError, ErrorPtr,
 };
 use derivative::Derivative;
 #[cfg(feature = "async")]
 use futures::future::BoxFuture;
 use fxhash::{FxHashMap, FxHashSet};
 use itertools::Itertools;
-#[cfg(test)]
+#[cfg(any(test, feature = "test-util"))]
 use mockall::automock;
+#[cfg(feature = "serde")]
+use serde::Serialize;
 use std::any::{type_name, TypeId};
+use std::cell::RefCell;
+use std::mem;
+#[cfg(not(feature = "threadsafe"))]
+use std::rc::Rc;
+#[cfg(feature = "threadsafe")]
+use std::sync::Arc;
 use thiserror::Error;
 
 #[cfg(not(feature = "async"))]
@@ -38,7 +56,49 @@ pub type Constructor =
         instance_provider: &mut (dyn ComponentInstanceProvider + Sync + Send),
     ) -> BoxFuture<'_, Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError>>;
 
-/// Error related to component registries.
+/// Function injecting late dependencies into a component instance after it is constructed and
+/// stored in its scope, which allows circular dependencies.
+#[cfg(not(feature = "async"))]
+pub type LateInjection = fn(
+    instance: ComponentInstanceAnyPtr,
+    instance_provider: &mut dyn ComponentInstanceProvider,
+) -> Result<(), ComponentInstanceProviderError>;
+
+/// Function injecting late dependencies into a component instance after it is constructed and
+/// stored in its scope, which allows circular dependencies.
+#[cfg(feature = "async")]
+pub type LateInjection = fn(
+    instance: ComponentInstanceAnyPtr,
+    instance_provider: &mut (dyn ComponentInstanceProvider + Sync + Send),
+) -> BoxFuture<'_, Result<(), ComponentInstanceProviderError>>;
+
+/// Function called after a component instance is constructed and stored in its scope. Please see
+/// [ComponentLifecycle](crate::component::ComponentLifecycle) for details.
+#[cfg(not(feature = "async"))]
+pub type PostConstruct = fn(instance: ComponentInstanceAnyPtr) -> Result<(), ErrorPtr>;
+
+/// Function called after a component instance is constructed and stored in its scope. Please see
+/// [ComponentLifecycle](crate::component::ComponentLifecycle) for details.
+#[cfg(feature = "async")]
+pub type PostConstruct =
+    fn(instance: ComponentInstanceAnyPtr) -> BoxFuture<'static, Result<(), ErrorPtr>>;
+
+/// Function called when a component instance is destroyed during
+/// [shutdown](crate::factory::ComponentFactory::shutdown). Please see
+/// [DisposableComponent](crate::component::DisposableComponent) for details.
+#[cfg(not(feature = "async"))]
+pub type PreDestroy = fn(instance: ComponentInstanceAnyPtr) -> Result<(), ErrorPtr>;
+
+/// Function called when a component instance is destroyed during
+/// [shutdown](crate::factory::ComponentFactory::shutdown). Please see
+/// [DisposableComponent](crate::component::DisposableComponent) for details.
+#[cfg(feature = "async")]
+pub type PreDestroy =
+    fn(instance: ComponentInstanceAnyPtr) -> BoxFuture<'static, Result<(), ErrorPtr>>;
+
+/// Error related to component registries. New kinds of errors can be added in minor releases, so
+/// matching needs a wildcard arm.
+#[non_exhaustive]
 #[derive(Error, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
 pub enum ComponentDefinitionRegistryError {
     #[error("Attempted to register a duplicated component with name: {0}")]
@@ -57,11 +117,94 @@ pub enum ComponentDefinitionRegistryError {
         alias_type: String,
         target_type: String,
     },
+    #[error("Cannot find component named {name} for type: {type_name}")]
+    MissingNamedComponent { type_name: String, name: String },
+    #[error("Cannot find component type: {0}")]
+    MissingComponentType(String),
+    #[error("Type name {type_name} is ambiguous: {}", .candidates.join(", "))]
+    AmbiguousComponentType {
+        type_name: String,
+        candidates: Vec<String>,
+    },
+    #[error("Registry doesn't support changing primary components")]
+    PrimarySelectionUnsupported,
+    #[error("Registry doesn't support creating independent copies")]
+    CloningUnsupported,
+    #[error("Registry doesn't support removing aliases")]
+    AliasRemovalUnsupported,
+    #[error("Cannot provide an instance of {type_name} in a non-singleton scope: {scope}")]
+    NonSingletonInstance { type_name: String, scope: String },
+    #[error("Cannot register alias {alias_type} for another alias: {target_type}")]
+    AliasChain {
+        alias_type: String,
+        target_type: String,
+    },
+}
+
+/// The way a component depends on another type.
+#[derive(Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum DependencyKind {
+    /// Single instance, which needs to be present.
+    Required,
+    /// Single instance, which can be missing.
+    Optional,
+    /// All instances of a type, e.g. `Vec<ComponentInstancePtr<T>>`, which can be empty.
+    All,
+    /// Instances requested on demand with a
+    /// [ComponentProvider](crate::instance_provider::ComponentProvider) or a
+    /// [ScopedProxy](crate::instance_provider::ScopedProxy), which can be missing at construction
+    /// time.
+    Provider,
+    /// Single instance injected after construction, which allows circular dependencies.
+    Late,
+}
+
+/// Dependency declared by a component, which allows inspecting the component graph without
+/// creating any instances.
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+pub struct ComponentDependency {
+    /// Type of the dependency - either a concrete component type or an alias.
+    pub type_id: TypeId,
+
+    /// Human-readable type name for reporting purposes.
+    pub type_name: String,
+
+    /// Name of the requested instance, if requested by name.
+    pub name: Option<String>,
+
+    pub kind: DependencyKind,
+}
+
+impl ComponentDependency {
+    /// Creates a new dependency on given type.
+    pub fn new<T: ?Sized + 'static>(name: Option<&str>, kind: DependencyKind) -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            type_name: type_name::<T>().to_string(),
+            name: name.map(|name| name.to_string()),
+            kind,
+        }
+    }
 }
 
+/// Shared pointer to a [ComponentDefinition]. Registries hand out definitions through such
+/// pointers, so looking them up doesn't copy names and other data.
+#[cfg(not(feature = "threadsafe"))]
+pub type ComponentDefinitionPtr = Rc<ComponentDefinition>;
+/// Shared pointer to a [ComponentDefinition]. Registries hand out definitions through such
+/// pointers, so looking them up doesn't copy names and other data.
+#[cfg(feature = "threadsafe")]
+pub type ComponentDefinitionPtr = Arc<ComponentDefinition>;
+
+/// Visitor called with registered types and their definitions by
+/// [ComponentDefinitionRegistry::visit_definitions].
+pub type DefinitionVisitor<'a> = dyn FnMut(TypeId, &[ComponentDefinitionPtr]) + 'a;
+
 /// Definition for a [Component] registered in a definition registry.
 #[derive(Derivative, Clone)]
 #[derivative(Debug)]
+#[non_exhaustive]
 pub struct ComponentDefinition {
     /// Each component has at least one name, which can be used to request a specific instance.
     /// Derive-based components have their name generated from type name by converting it to snake
@@ -80,33 +223,205 @@ pub struct ComponentDefinition {
     /// can be a need to find out what is the leaf type.
     pub resolved_type_id: TypeId,
 
+    /// Distinguishes additional definitions of the same concrete type, registered with
+    /// [ComponentDefinitionRegistry::register_qualified_component]. The default definition of a
+    /// type has no qualifier.
+    pub qualifier: Option<String>,
+
     /// Human-readable type name for reporting purposes.
     pub resolved_type_name: String,
 
+    /// Position of the component when requesting all instances of a given type (lower is first;
+    /// default is 0). Definitions with the same order are sorted by
+    /// [resolved_type_name](Self::resolved_type_name) and then by [qualifier](Self::qualifier), so
+    /// the resulting order never depends on the order of registration or replacement. Set with
+    /// `#[component(order = N)]`.
+    pub order: i32,
+
     /// Constructor method for type-erased instances.
     #[derivative(Debug = "ignore")]
     pub constructor: Constructor,
 
+    /// Optional function injecting late dependencies after the instance is constructed and stored
+    /// in its scope.
+    #[derivative(Debug = "ignore")]
+    pub late_injection: Option<LateInjection>,
+
+    /// Optional function called after the instance is constructed and stored in its scope.
+    #[derivative(Debug = "ignore")]
+    pub post_construct: Option<PostConstruct>,
+
+    /// Optional function called when a singleton instance is destroyed during factory shutdown.
+    #[derivative(Debug = "ignore")]
+    pub pre_destroy: Option<PreDestroy>,
+
     /// Cast function associated for given type. Please see the documentation for [CastFunction] for
     /// details on usage.
     #[derivative(Debug = "ignore")]
     pub cast: CastFunction,
+
+    /// Dependencies declared by the component. Derive-based components declare all injected
+    /// dependencies, while manually registered ones might not declare any.
+    pub dependencies: Vec<ComponentDependency>,
+}
+
+impl ComponentDefinition {
+    /// Creates a non-primary, unqualified definition of given concrete type from its metadata.
+    pub fn new(target: TypeId, target_name: &str, metadata: &ComponentMetadata) -> Self {
+        Self {
+            names: metadata.names.clone(),
+            is_primary: false,
+            scope: metadata.scope.clone(),
+            resolved_type_id: target,
+            qualifier: None,
+            resolved_type_name: target_name.to_string(),
+            order: metadata.order,
+            constructor: metadata.constructor,
+            late_injection: metadata.late_injection,
+            post_construct: metadata.post_construct,
+            pre_destroy: metadata.pre_destroy,
+            cast: metadata.cast,
+            dependencies: metadata.dependencies.clone(),
+        }
+    }
+
+    /// Returns the key identifying the component instance created from this definition, which
+    /// stays the same for copies of the definition shared with aliases.
+    #[inline]
+    pub fn key(&self) -> ComponentDefinitionKey {
+        ComponentDefinitionKey {
+            type_id: self.resolved_type_id,
+            qualifier: self.qualifier.clone(),
+        }
+    }
+}
+
+/// Identifies a single definition of a concrete component type. Useful for [scopes](crate::scope)
+/// which need to store instances of different definitions of the same type separately.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ComponentDefinitionKey {
+    /// Concrete component type id.
+    pub type_id: TypeId,
+
+    /// Qualifier of the definition, if it's not the default one.
+    pub qualifier: Option<String>,
+}
+
+/// Definition which lost to another definition with the same name, when registering components
+/// with different precedence. Please see [component](crate::component) for details on precedence.
+#[derive(Clone, Debug)]
+pub struct ShadowedDefinition {
+    /// The definition which was left out of the registry.
+    pub definition: ComponentDefinitionPtr,
+
+    /// Name shared by both definitions.
+    pub name: String,
+
+    /// Type name of the component which took precedence.
+    pub shadowed_by: String,
 }
 
 /// Registration information for a [Component]. Please see [ComponentDefinition] for information
 /// about the meaning of the fields.
 #[derive(Derivative, Clone)]
 #[derivative(Debug)]
+#[non_exhaustive]
 pub struct ComponentMetadata {
     pub names: FxHashSet<String>,
 
     pub scope: String,
 
+    pub order: i32,
+
     #[derivative(Debug = "ignore")]
     pub constructor: Constructor,
 
+    #[derivative(Debug = "ignore")]
+    pub late_injection: Option<LateInjection>,
+
+    #[derivative(Debug = "ignore")]
+    pub post_construct: Option<PostConstruct>,
+
+    #[derivative(Debug = "ignore")]
+    pub pre_destroy: Option<PreDestroy>,
+
     #[derivative(Debug = "ignore")]
     pub cast: CastFunction,
+
+    pub dependencies: Vec<ComponentDependency>,
+}
+
+impl ComponentMetadata {
+    /// Creates metadata with the default order, no lifecycle functions and no declared
+    /// dependencies. These can be set with the `with_*` methods.
+    pub fn new(
+        names: impl IntoIterator<Item = impl Into<String>>,
+        scope: impl Into<String>,
+        constructor: Constructor,
+        cast: CastFunction,
+    ) -> Self {
+        Self {
+            names: names.into_iter().map(Into::into).collect(),
+            scope: scope.into(),
+            order: 0,
+            constructor,
+            late_injection: None,
+            post_construct: None,
+            pre_destroy: None,
+            cast,
+            dependencies: vec![],
+        }
+    }
+
+    /// Creates metadata for a [Component], which is constructed with [Component::create]. This is
+    /// the simplest way to register components manually, e.g. at runtime with
+    /// [ComponentFactory](crate::factory::ComponentFactory). Note: lifecycle callbacks and late
+    /// injection declared with the derive macro are not included and need to be set with the
+    /// `with_*` methods, if needed.
+    pub fn for_component<C: Component + ComponentInstanceBounds>(
+        names: impl IntoIterator<Item = impl Into<String>>,
+        scope: impl Into<String>,
+    ) -> Self {
+        Self::new(
+            names,
+            scope,
+            construct_component::<C>,
+            cast_component::<C, C>,
+        )
+    }
+
+    /// Sets the position of the component when requesting all instances of a given type.
+    pub fn with_order(mut self, order: i32) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets the function injecting late dependencies.
+    pub fn with_late_injection(mut self, late_injection: LateInjection) -> Self {
+        self.late_injection = Some(late_injection);
+        self
+    }
+
+    /// Sets the function called after the instance is constructed.
+    pub fn with_post_construct(mut self, post_construct: PostConstruct) -> Self {
+        self.post_construct = Some(post_construct);
+        self
+    }
+
+    /// Sets the function called when a singleton instance is destroyed.
+    pub fn with_pre_destroy(mut self, pre_destroy: PreDestroy) -> Self {
+        self.pre_destroy = Some(pre_destroy);
+        self
+    }
+
+    /// Sets the dependencies declared by the component.
+    pub fn with_dependencies(
+        mut self,
+        dependencies: impl IntoIterator<Item = ComponentDependency>,
+    ) -> Self {
+        self.dependencies = dependencies.into_iter().collect();
+        self
+    }
 }
 
 /// Registration information for an  alias for a [Component] registered in a definition registry.
@@ -124,7 +439,7 @@ pub struct ComponentAliasMetadata {
 
 /// A registry of component definitions which can be used when requesting instances via a
 /// [ComponentInstanceProvider].
-#[cfg_attr(test, automock)]
+#[cfg_attr(any(test, feature = "test-util"), automock)]
 pub trait ComponentDefinitionRegistry {
     /// Adds a new definition for a given type. Note: handling of duplicate component names is
     /// registry-dependent. Name is used for reporting purposes.
@@ -135,9 +450,32 @@ pub trait ComponentDefinitionRegistry {
         metadata: &ComponentMetadata,
     ) -> Result<(), ComponentDefinitionRegistryError>;
 
+    /// Adds an additional definition for a given concrete type, distinguished by the qualifier,
+    /// e.g. to have two differently-configured clients without newtype wrappers. The qualifier is
+    /// also registered as a component name, so the instance can be requested by it. Aliases of the
+    /// type receive the new definition as well. The default implementation returns
+    /// [ComponentDefinitionRegistryError::DuplicateComponentType], since registries don't need to
+    /// support multiple definitions per type.
+    fn register_qualified_component(
+        &mut self,
+        _target: TypeId,
+        target_name: &str,
+        _qualifier: &str,
+        _metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        Err(ComponentDefinitionRegistryError::DuplicateComponentType(
+            target_name.to_string(),
+        ))
+    }
+
     /// Adds an alias for a component of target type. This is useful when registering
     /// `dyn Trait` as an alias for a given concrete type. If alias cannot by cast to target,
     /// component creation will fail. Names are used for reporting purposes.
+    ///
+    /// The target needs to be a concrete component type, since the
+    /// [cast](ComponentAliasMetadata::cast) function receives concrete component instances -
+    /// aliases of aliases are rejected with [ComponentDefinitionRegistryError::AliasChain].
+    /// Registering the same alias for a given component more than once has no effect.
     fn register_alias(
         &mut self,
         alias_type: TypeId,
@@ -147,14 +485,86 @@ pub trait ComponentDefinitionRegistry {
         metadata: &ComponentAliasMetadata,
     ) -> Result<(), ComponentDefinitionRegistryError>;
 
-    /// Returns all registered definitions for a given type.
-    fn components_by_type(&self, type_id: TypeId) -> Vec<ComponentDefinition>;
+    /// Removes the definition of a concrete component type, along with its names and all alias
+    /// entries referring to it. Returns the removed definition, if present.
+    fn remove_component(&mut self, target: TypeId) -> Option<ComponentDefinitionPtr>;
+
+    /// Removes all entries of given alias type, e.g. a `dyn Trait`, while keeping the components
+    /// they refer to, along with their other aliases. Returns the removed entries. Returns
+    /// [AliasRemovalUnsupported](ComponentDefinitionRegistryError::AliasRemovalUnsupported) by
+    /// default.
+    fn remove_alias(
+        &mut self,
+        _alias_type: TypeId,
+    ) -> Result<Vec<ComponentDefinitionPtr>, ComponentDefinitionRegistryError> {
+        Err(ComponentDefinitionRegistryError::AliasRemovalUnsupported)
+    }
+
+    /// Replaces the definition of a concrete component type, updating all alias entries referring
+    /// to it, while keeping their alias-specific configuration. Registers a new definition, if none
+    /// is present. Name is used for reporting purposes.
+    fn replace_component(
+        &mut self,
+        target: TypeId,
+        target_name: &str,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentDefinitionRegistryError>;
+
+    /// Returns all registered definitions for a given type, sorted by
+    /// [order](ComponentDefinition::order).
+    fn components_by_type(&self, type_id: TypeId) -> Vec<ComponentDefinitionPtr>;
 
     /// Returns a definition with given name.
-    fn component_by_name(&self, name: &str, type_id: TypeId) -> Option<ComponentDefinition>;
+    fn component_by_name(&self, name: &str, type_id: TypeId) -> Option<ComponentDefinitionPtr>;
+
+    /// Returns a definition with given name, regardless of its type. Since names are unique, this
+    /// is the definition registered for the concrete component type, which can be useful when only
+    /// a string identifier is known, e.g. in tooling or plugin systems. The default implementation
+    /// visits all definitions.
+    fn definition_by_name(&self, name: &str) -> Option<ComponentDefinitionPtr> {
+        let mut result = None;
+        self.visit_definitions(&mut |type_id, definitions| {
+            if result.is_none() {
+                result = definitions
+                    .iter()
+                    .find(|definition| {
+                        definition.resolved_type_id == type_id && definition.names.contains(name)
+                    })
+                    .cloned();
+            }
+        });
+
+        result
+    }
 
     /// Returns primary component for a given type.
-    fn primary_component(&self, type_id: TypeId) -> Option<ComponentDefinition>;
+    fn primary_component(&self, type_id: TypeId) -> Option<ComponentDefinitionPtr>;
+
+    /// Marks the component with given name as the primary one for given type, e.g. a `dyn Trait`,
+    /// replacing the primary marker set at registration time. Allows choosing implementations at
+    /// startup, e.g. from configuration. Instances which have already been injected are not
+    /// affected. Returns
+    /// [PrimarySelectionUnsupported](ComponentDefinitionRegistryError::PrimarySelectionUnsupported)
+    /// by default.
+    fn set_primary_component(
+        &mut self,
+        _type_id: TypeId,
+        _name: &str,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        Err(ComponentDefinitionRegistryError::PrimarySelectionUnsupported)
+    }
+
+    /// Returns the registered type with given name, which can be either the full name, as reported
+    /// by [type_name](std::any::type_name), or a short one without module paths and `Send`/`Sync`
+    /// bounds, e.g. `dyn Storage`. Full names take precedence, while short names matching multiple
+    /// types result in
+    /// [AmbiguousComponentType](ComponentDefinitionRegistryError::AmbiguousComponentType). The
+    /// default implementation doesn't know any type names.
+    fn type_id_by_name(&self, type_name: &str) -> Result<TypeId, ComponentDefinitionRegistryError> {
+        Err(ComponentDefinitionRegistryError::MissingComponentType(
+            type_name.to_string(),
+        ))
+    }
 
     /// Checks if given type is present in this registry.
     fn is_registered(&self, type_id: TypeId) -> bool;
@@ -163,7 +573,62 @@ pub trait ComponentDefinitionRegistry {
     fn is_name_registered(&self, name: &str) -> bool;
 
     /// Returns a copy of the whole registry as a map.
-    fn all_definitions(&self) -> FxHashMap<TypeId, Vec<ComponentDefinition>>;
+    fn all_definitions(&self) -> FxHashMap<TypeId, Vec<ComponentDefinitionPtr>>;
+
+    /// Calls given visitor with every registered type and its definitions, without copying the
+    /// registry. The order of types is unspecified. The default implementation visits the result of
+    /// [all_definitions](Self::all_definitions).
+    ///
+    /// Note: the visitor should not modify the registry, since it might be borrowed for the
+    /// duration of the visit.
+    fn visit_definitions<'a>(&self, visitor: &mut DefinitionVisitor<'a>) {
+        for (type_id, definitions) in self.all_definitions() {
+            visitor(type_id, &definitions);
+        }
+    }
+
+    /// Returns definitions which were left out, because other definitions with the same name took
+    /// precedence during registration. The default implementation returns nothing.
+    fn shadowed_definitions(&self) -> Vec<ShadowedDefinition> {
+        vec![]
+    }
+
+    /// Creates an independent copy of this registry, e.g. for a
+    /// [forked](crate::factory::ComponentFactory::fork) factory, so changes made to one copy are
+    /// not visible in the other. Returns `None` if not supported, which is the default.
+    fn clone_registry(&self) -> Option<ComponentDefinitionRegistryPtr> {
+        None
+    }
+
+    /// Exports the registry contents for external tooling. The default implementation only knows
+    /// the names of concrete component types and doesn't report condition outcomes.
+    fn export(&self) -> ComponentRegistryExport {
+        let types = self
+            .all_definitions()
+            .into_iter()
+            .map(|(type_id, definitions)| {
+                let type_name = definitions
+                    .iter()
+                    .find(|definition| definition.resolved_type_id == type_id)
+                    .map(|definition| definition.resolved_type_name.clone());
+
+                ExportedType {
+                    is_alias: type_name.is_none(),
+                    type_name: type_name.unwrap_or_else(|| format!("{type_id:?}")),
+                    definitions: definitions
+                        .iter()
+                        .map(|definition| ExportedDefinition::from(&**definition))
+                        .collect(),
+                }
+            })
+            .sorted_by(|first, second| first.type_name.cmp(&second.type_name))
+            .collect();
+
+        ComponentRegistryExport {
+            types,
+            conditions: vec![],
+        }
+    }
 }
 
 /// Helper trait for [ComponentDefinitionRegistry] providing strongly-typed access.
@@ -174,20 +639,42 @@ pub trait TypedComponentDefinitionRegistry {
         metadata: &ComponentMetadata,
     ) -> Result<(), ComponentDefinitionRegistryError>;
 
+    /// Typesafe version of [ComponentDefinitionRegistry::register_qualified_component].
+    fn register_qualified_component_typed<T: Component>(
+        &mut self,
+        qualifier: &str,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentDefinitionRegistryError>;
+
     /// Typesafe version of [ComponentDefinitionRegistry::register_alias].
     fn register_alias_typed<Source: ComponentDowncast<Target> + ?Sized, Target: Component>(
         &mut self,
         metadata: &ComponentAliasMetadata,
     ) -> Result<(), ComponentDefinitionRegistryError>;
 
+    /// Typesafe version of [ComponentDefinitionRegistry::remove_component].
+    fn remove_component_typed<T: Component>(&mut self) -> Option<ComponentDefinitionPtr>;
+
+    /// Typesafe version of [ComponentDefinitionRegistry::replace_component].
+    fn replace_component_typed<T: Component>(
+        &mut self,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentDefinitionRegistryError>;
+
+    /// Typesafe version of [ComponentDefinitionRegistry::set_primary_component].
+    fn set_primary_component_typed<T: ?Sized + 'static>(
+        &mut self,
+        name: &str,
+    ) -> Result<(), ComponentDefinitionRegistryError>;
+
     /// Typesafe version of [ComponentDefinitionRegistry::components_by_type].
-    fn components_by_type_typed<T: Injectable + ?Sized>(&self) -> Vec<ComponentDefinition>;
+    fn components_by_type_typed<T: ?Sized + 'static>(&self) -> Vec<ComponentDefinitionPtr>;
 
     /// Typesafe version of [ComponentDefinitionRegistry::primary_component].
-    fn primary_component_typed<T: Injectable + ?Sized>(&self) -> Option<ComponentDefinition>;
+    fn primary_component_typed<T: ?Sized + 'static>(&self) -> Option<ComponentDefinitionPtr>;
 
     /// Typesafe version of [ComponentDefinitionRegistry::is_registered].
-    fn is_registered_typed<T: Injectable>(&self) -> bool;
+    fn is_registered_typed<T: 'static>(&self) -> bool;
 }
 
 impl<CDR: ComponentDefinitionRegistry + ?Sized> TypedComponentDefinitionRegistry for CDR {
@@ -199,6 +686,15 @@ impl<CDR: ComponentDefinitionRegistry + ?Sized> TypedComponentDefinitionRegistry
         self.register_component(TypeId::of::<T>(), type_name::<T>(), metadata)
     }
 
+    #[inline]
+    fn register_qualified_component_typed<T: Component>(
+        &mut self,
+        qualifier: &str,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        self.register_qualified_component(TypeId::of::<T>(), type_name::<T>(), qualifier, metadata)
+    }
+
     #[inline]
     fn register_alias_typed<Source: ComponentDowncast<Target> + ?Sized, Target: Component>(
         &mut self,
@@ -214,26 +710,166 @@ impl<CDR: ComponentDefinitionRegistry + ?Sized> TypedComponentDefinitionRegistry
     }
 
     #[inline]
-    fn components_by_type_typed<T: Injectable + ?Sized>(&self) -> Vec<ComponentDefinition> {
+    fn remove_component_typed<T: Component>(&mut self) -> Option<ComponentDefinitionPtr> {
+        self.remove_component(TypeId::of::<T>())
+    }
+
+    #[inline]
+    fn replace_component_typed<T: Component>(
+        &mut self,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        self.replace_component(TypeId::of::<T>(), type_name::<T>(), metadata)
+    }
+
+    #[inline]
+    fn set_primary_component_typed<T: ?Sized + 'static>(
+        &mut self,
+        name: &str,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        self.set_primary_component(TypeId::of::<T>(), name)
+    }
+
+    #[inline]
+    fn components_by_type_typed<T: ?Sized + 'static>(&self) -> Vec<ComponentDefinitionPtr> {
         self.components_by_type(TypeId::of::<T>())
     }
 
     #[inline]
-    fn primary_component_typed<T: Injectable + ?Sized>(&self) -> Option<ComponentDefinition> {
+    fn primary_component_typed<T: ?Sized + 'static>(&self) -> Option<ComponentDefinitionPtr> {
         self.primary_component(TypeId::of::<T>())
     }
 
     #[inline]
-    fn is_registered_typed<T: Injectable>(&self) -> bool {
+    fn is_registered_typed<T: 'static>(&self) -> bool {
         self.is_registered(TypeId::of::<T>())
     }
 }
 
+/// Component and alias definitions collected explicitly, instead of being discovered
+/// automatically. Usually created with the [collect_components](crate::collect_components) macro
+/// and passed to [StaticComponentDefinitionRegistry::new_with_definitions].
+#[derive(Clone, Default)]
+pub struct ComponentDefinitions {
+    pub components: Vec<TypedComponentDefinition>,
+    pub aliases: Vec<ComponentAliasDefinition>,
+}
+
+/// Collects [ComponentDefinitions] for given component types, along with their aliases for given
+/// trait objects. This allows using components without automatic discovery, e.g. with the
+/// `explicit-registration` feature on targets where it's not supported:
+///
+/// ```
+/// use springtime_di::component_registry::conditional::SimpleContextFactory;
+/// use springtime_di::component_registry::{
+///     StaticComponentDefinitionRegistry, TypedComponentDefinitionRegistry,
+/// };
+/// use springtime_di::{collect_components, component_alias, injectable, Component};
+///
+/// #[injectable]
+/// trait Storage {}
+///
+/// #[derive(Component)]
+/// struct MemoryStorage;
+///
+/// #[component_alias]
+/// impl Storage for MemoryStorage {}
+///
+/// #[derive(Component)]
+/// struct Unused;
+///
+/// let definitions = collect_components!(MemoryStorage => [dyn Storage + Send + Sync]);
+/// let registry = StaticComponentDefinitionRegistry::new_with_definitions(
+///     false,
+///     &SimpleContextFactory,
+///     definitions,
+/// )
+/// .unwrap();
+///
+/// assert!(registry.is_registered_typed::<MemoryStorage>());
+/// assert!(!registry.is_registered_typed::<Unused>());
+/// ```
+#[macro_export]
+macro_rules! collect_components {
+    ($($component:ty $(=> [$($alias:ty),* $(,)?])?),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut definitions = $crate::component_registry::ComponentDefinitions::default();
+        $(
+            definitions.components.push(
+                <$component as $crate::component_registry::internal::ComponentRegistration>::definition()
+            );
+            $($(
+                definitions.aliases.push(
+                    <$component as $crate::component_registry::internal::ComponentAliasRegistration<$alias>>::alias_definition()
+                );
+            )*)?
+        )*
+        definitions
+    }};
+}
+
+/// Registers given component types, along with their aliases for given trait objects, in a
+/// [StaticComponentDefinitionRegistry], without relying on automatic discovery. Accepts a reference
+/// to the [ContextFactory] used for evaluating conditions, followed by the same component list as
+/// [collect_components](crate::collect_components). This allows activating only a subset of
+/// components, e.g. from a dependency crate:
+///
+/// ```
+/// use springtime_di::component_registry::conditional::SimpleContextFactory;
+/// use springtime_di::component_registry::{
+///     ComponentDefinitions, StaticComponentDefinitionRegistry, TypedComponentDefinitionRegistry,
+/// };
+/// use springtime_di::{component_alias, injectable, register_components, Component};
+///
+/// #[injectable]
+/// trait Storage {}
+///
+/// #[derive(Component)]
+/// struct MemoryStorage;
+///
+/// #[component_alias]
+/// impl Storage for MemoryStorage {}
+///
+/// #[derive(Component)]
+/// struct DiskStorage;
+///
+/// #[component_alias]
+/// impl Storage for DiskStorage {}
+///
+/// let mut registry = StaticComponentDefinitionRegistry::new_with_definitions(
+///     false,
+///     &SimpleContextFactory,
+///     ComponentDefinitions::default(),
+/// )
+/// .unwrap();
+///
+/// register_components!(
+///     registry,
+///     &SimpleContextFactory,
+///     MemoryStorage => [dyn Storage + Send + Sync]
+/// )
+/// .unwrap();
+///
+/// assert!(registry.is_registered_typed::<MemoryStorage>());
+/// assert!(!registry.is_registered_typed::<DiskStorage>());
+/// ```
+#[macro_export]
+macro_rules! register_components {
+    ($registry:expr, $context_factory:expr, $($components:tt)*) => {
+        $registry.register_definitions(
+            $crate::collect_components!($($components)*),
+            $context_factory,
+        )
+    };
+}
+
 /// Registry of component definitions initialized from statically registered definitions.
 #[derive(Clone, Debug)]
 pub struct StaticComponentDefinitionRegistry {
     definition_map: NamedComponentDefinitionMap,
     allow_definition_overriding: bool,
+    condition_outcomes: Vec<ExportedCondition>,
+    naming_strategy: ComponentNamingStrategy,
 }
 
 impl StaticComponentDefinitionRegistry {
@@ -241,17 +877,93 @@ impl StaticComponentDefinitionRegistry {
         allow_definition_overriding: bool,
         context_factory: &CF,
     ) -> Result<Self, ComponentDefinitionRegistryError> {
-        let component_definitions: Vec<TypedComponentDefinition> =
-            inventory::iter::<ComponentDefinitionRegisterer>
-                .into_iter()
-                .map(|registerer| (registerer.register)())
-                .collect_vec();
+        Self::new_with_naming_strategy(
+            allow_definition_overriding,
+            context_factory,
+            ComponentNamingStrategy::default(),
+        )
+    }
 
-        let alias_definitions: Vec<ComponentAliasDefinition> =
-            inventory::iter::<ComponentAliasRegisterer>
-                .into_iter()
-                .map(|registerer| (registerer.register)())
-                .collect_vec();
+    /// Creates a registry generating default names of components, which don't specify names or a
+    /// naming strategy explicitly, with given [ComponentNamingStrategy].
+    pub fn new_with_naming_strategy<CF: ContextFactory>(
+        allow_definition_overriding: bool,
+        context_factory: &CF,
+        naming_strategy: ComponentNamingStrategy,
+    ) -> Result<Self, ComponentDefinitionRegistryError> {
+        let mut registry = Self::empty(allow_definition_overriding, naming_strategy);
+        registry.register_definitions(Self::registered_definitions(), context_factory)?;
+        Ok(registry)
+    }
+
+    /// Creates a registry containing only given definitions, without looking at automatically
+    /// registered ones.
+    pub fn new_with_definitions<CF: ContextFactory>(
+        allow_definition_overriding: bool,
+        context_factory: &CF,
+        definitions: ComponentDefinitions,
+    ) -> Result<Self, ComponentDefinitionRegistryError> {
+        let mut registry = Self::empty(
+            allow_definition_overriding,
+            ComponentNamingStrategy::default(),
+        );
+        registry.register_definitions(definitions, context_factory)?;
+        Ok(registry)
+    }
+
+    /// Sets the [ComponentNamingStrategy] for definitions registered afterwards with
+    /// [register_definitions](Self::register_definitions). Already registered definitions keep
+    /// their names.
+    pub fn with_naming_strategy(mut self, naming_strategy: ComponentNamingStrategy) -> Self {
+        self.naming_strategy = naming_strategy;
+        self
+    }
+
+    fn empty(allow_definition_overriding: bool, naming_strategy: ComponentNamingStrategy) -> Self {
+        Self {
+            definition_map: Default::default(),
+            allow_definition_overriding,
+            condition_outcomes: vec![],
+            naming_strategy,
+        }
+    }
+
+    /// Registers given definitions, along with their aliases, evaluating conditions with contexts
+    /// from given factory. Useful for activating only a subset of components, e.g. from a
+    /// dependency crate, when the registry is created with
+    /// [new_with_definitions](Self::new_with_definitions). Components registered earlier can be
+    /// targets of given aliases. Nothing is registered if any registration fails. Please see also
+    /// [register_components](crate::register_components).
+    pub fn register_definitions<CF: ContextFactory>(
+        &mut self,
+        definitions: ComponentDefinitions,
+        context_factory: &CF,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        let definition_map = self.definition_map.clone();
+        let condition_outcomes = self.condition_outcomes.len();
+
+        let result = self.try_register_definitions(definitions, context_factory);
+        if result.is_err() {
+            self.definition_map = definition_map;
+            self.condition_outcomes.truncate(condition_outcomes);
+        }
+
+        result
+    }
+
+    fn try_register_definitions<CF: ContextFactory>(
+        &mut self,
+        definitions: ComponentDefinitions,
+        context_factory: &CF,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        let ComponentDefinitions {
+            components: mut component_definitions,
+            aliases: alias_definitions,
+        } = definitions;
+
+        for definition in &mut component_definitions {
+            Self::apply_naming_strategy(definition, self.naming_strategy);
+        }
 
         // components need to be registered in appropriate order to ensure dependencies are met:
         // 1. unconditional components - they depend on nothing, so can go first
@@ -260,40 +972,110 @@ impl StaticComponentDefinitionRegistry {
         // 4. unconditional aliases for conditional components - they might depend on the above only
         // 5. conditional aliases - they might depend on anything
 
-        let (definition_map, enabled_types) = Self::register_unconditional_components(
+        // components registered by previous calls can have aliases registered now
+        let mut enabled_types = self.definition_map.component_types();
+        enabled_types.extend(Self::register_unconditional_components(
+            &mut self.definition_map,
             &component_definitions,
-            allow_definition_overriding,
-        )?;
-
-        let mut registry = Self {
-            definition_map,
-            allow_definition_overriding,
-        };
+            self.allow_definition_overriding,
+        )?);
 
         // register aliases for unconditionally registered components
-        registry.register_unconditional_aliases(&alias_definitions, &enabled_types)?;
+        Self::register_unconditional_aliases(
+            &mut self.definition_map,
+            &alias_definitions,
+            &enabled_types,
+        )?;
 
-        registry.register_conditional_components_with_dependents(
+        self.register_conditional_components_with_dependents(
             component_definitions,
-            alias_definitions.clone(),
+            alias_definitions,
             enabled_types,
             context_factory,
-        )?;
+        )
+    }
 
-        Ok(registry)
+    fn apply_naming_strategy(
+        definition: &mut TypedComponentDefinition,
+        naming_strategy: ComponentNamingStrategy,
+    ) {
+        if let Some(DefaultComponentName { name, module_path }) = definition.default_name {
+            if definition.metadata.names.remove(name) {
+                definition
+                    .metadata
+                    .names
+                    .insert(naming_strategy.component_name(name, module_path));
+            }
+        }
+    }
+
+    #[cfg(not(feature = "explicit-registration"))]
+    fn registered_definitions() -> ComponentDefinitions {
+        ComponentDefinitions {
+            components: inventory::iter::<ComponentDefinitionRegisterer>
+                .into_iter()
+                .map(|registerer| (registerer.register)())
+                .collect_vec(),
+            aliases: inventory::iter::<ComponentAliasRegisterer>
+                .into_iter()
+                .map(|registerer| (registerer.register)())
+                .collect_vec(),
+        }
+    }
+
+    // nothing is registered automatically, since components are collected explicitly
+    #[cfg(feature = "explicit-registration")]
+    fn registered_definitions() -> ComponentDefinitions {
+        ComponentDefinitions::default()
+    }
+
+    /// Returns an iterator over all registered types and their definitions, borrowing them from
+    /// the registry. The order of types is unspecified.
+    #[inline]
+    pub fn definitions_iter(&self) -> impl Iterator<Item = (TypeId, &[ComponentDefinitionPtr])> {
+        self.definition_map.definitions_iter()
     }
 
     fn register_conditional_components_with_dependents<CF: ContextFactory>(
         &mut self,
         component_definitions: Vec<TypedComponentDefinition>,
         alias_definitions: Vec<ComponentAliasDefinition>,
-        mut enabled_types: FxHashSet<TypeId>,
+        enabled_types: FxHashSet<TypeId>,
         context_factory: &CF,
     ) -> Result<(), ComponentDefinitionRegistryError> {
         if component_definitions.is_empty() && alias_definitions.is_empty() {
             return Ok(());
         }
 
+        // conditions need to see registrations made during the pass, while sharing a single
+        // context, so the definitions are temporarily moved behind a RefCell
+        let definition_map = RefCell::new(mem::take(&mut self.definition_map));
+        let result = Self::register_conditional_definitions(
+            &definition_map,
+            &component_definitions,
+            &alias_definitions,
+            enabled_types,
+            self.allow_definition_overriding,
+            context_factory,
+            &mut self.condition_outcomes,
+        );
+
+        self.definition_map = definition_map.into_inner();
+        result
+    }
+
+    fn register_conditional_definitions<CF: ContextFactory>(
+        definition_map: &RefCell<NamedComponentDefinitionMap>,
+        component_definitions: &[TypedComponentDefinition],
+        alias_definitions: &[ComponentAliasDefinition],
+        mut enabled_types: FxHashSet<TypeId>,
+        allow_definition_overriding: bool,
+        context_factory: &CF,
+        condition_outcomes: &mut Vec<ExportedCondition>,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        let facade = DefinitionMapFacade(definition_map);
+        let context = context_factory.create_context(&facade);
+
         let mut new_enabled_types = FxHashSet::default();
 
         for (definition, condition) in component_definitions
@@ -301,31 +1083,46 @@ impl StaticComponentDefinitionRegistry {
             .filter_map(|definition| {
                 definition
                     .condition
+                    .as_ref()
                     .map(|condition| (definition, condition))
             })
             .sorted_by_key(|(definition, _)| -definition.priority)
         {
-            if (condition)(
-                context_factory.create_context(self).as_ref(),
+            let passed = condition.evaluate(
+                context.as_ref(),
                 ConditionMetadata::Component {
                     type_id: definition.target,
                     metadata: &definition.metadata,
                 },
-            ) {
-                self.definition_map.try_register_component(
-                    definition.target,
-                    definition.target_name,
-                    &definition.metadata,
-                    self.allow_definition_overriding,
-                )?;
+            );
 
-                new_enabled_types.insert(definition.target);
+            condition_outcomes.push(ExportedCondition {
+                type_name: definition.target_name.to_string(),
+                target_type_name: None,
+                priority: definition.priority,
+                passed,
+            });
+
+            if passed {
+                definition_map
+                    .borrow_mut()
+                    .try_register_typed_definition(definition, allow_definition_overriding)?;
+
+                new_enabled_types.insert(definition.target);
             }
         }
 
-        self.register_unconditional_aliases(&alias_definitions, &new_enabled_types)?;
+        // overridden components shouldn't get any aliases
+        new_enabled_types.retain(|type_id| definition_map.borrow().is_registered(*type_id));
+
+        Self::register_unconditional_aliases(
+            &mut definition_map.borrow_mut(),
+            alias_definitions,
+            &new_enabled_types,
+        )?;
 
         enabled_types.extend(new_enabled_types);
+        enabled_types.retain(|type_id| definition_map.borrow().is_registered(*type_id));
 
         for (definition, condition) in alias_definitions
             .iter()
@@ -333,19 +1130,29 @@ impl StaticComponentDefinitionRegistry {
             .filter_map(|definition| {
                 definition
                     .condition
+                    .as_ref()
                     .map(|condition| (definition, condition))
             })
             .sorted_by_key(|(definition, _)| -definition.priority)
         {
-            if (condition)(
-                context_factory.create_context(self).as_ref(),
+            let passed = condition.evaluate(
+                context.as_ref(),
                 ConditionMetadata::Alias {
                     alias_type: definition.alias_type,
                     target_type: definition.target_type,
                     metadata: &definition.metadata,
                 },
-            ) {
-                self.definition_map.try_register_alias(
+            );
+
+            condition_outcomes.push(ExportedCondition {
+                type_name: definition.alias_name.to_string(),
+                target_type_name: Some(definition.target_name.to_string()),
+                priority: definition.priority,
+                passed,
+            });
+
+            if passed {
+                definition_map.borrow_mut().try_register_alias(
                     definition.alias_type,
                     definition.target_type,
                     definition.alias_name,
@@ -359,39 +1166,36 @@ impl StaticComponentDefinitionRegistry {
     }
 
     fn register_unconditional_components(
+        definition_map: &mut NamedComponentDefinitionMap,
         component_definitions: &[TypedComponentDefinition],
         allow_definition_overriding: bool,
-    ) -> Result<(NamedComponentDefinitionMap, FxHashSet<TypeId>), ComponentDefinitionRegistryError>
-    {
-        let mut definition_map = NamedComponentDefinitionMap::default();
+    ) -> Result<FxHashSet<TypeId>, ComponentDefinitionRegistryError> {
         let mut enabled_types = FxHashSet::default();
 
         for definition in component_definitions
             .iter()
             .filter(|definition| definition.condition.is_none())
         {
-            definition_map.try_register_component(
-                definition.target,
-                definition.target_name,
-                &definition.metadata,
-                allow_definition_overriding,
-            )?;
+            definition_map
+                .try_register_typed_definition(definition, allow_definition_overriding)?;
 
             enabled_types.insert(definition.target);
         }
 
-        Ok((definition_map, enabled_types))
+        enabled_types.retain(|type_id| definition_map.is_registered(*type_id));
+
+        Ok(enabled_types)
     }
 
     fn register_unconditional_aliases(
-        &mut self,
+        definition_map: &mut NamedComponentDefinitionMap,
         alias_definitions: &[ComponentAliasDefinition],
         enabled_types: &FxHashSet<TypeId>,
     ) -> Result<(), ComponentDefinitionRegistryError> {
         for definition in alias_definitions.iter().filter(|definition| {
             definition.condition.is_none() && enabled_types.contains(&definition.target_type)
         }) {
-            self.definition_map.try_register_alias(
+            definition_map.try_register_alias(
                 definition.alias_type,
                 definition.target_type,
                 definition.alias_name,
@@ -420,6 +1224,23 @@ impl ComponentDefinitionRegistry for StaticComponentDefinitionRegistry {
         )
     }
 
+    #[inline]
+    fn register_qualified_component(
+        &mut self,
+        target: TypeId,
+        target_name: &str,
+        qualifier: &str,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        self.definition_map.try_register_qualified_component(
+            target,
+            target_name,
+            qualifier,
+            metadata,
+            self.allow_definition_overriding,
+        )
+    }
+
     #[inline]
     fn register_alias(
         &mut self,
@@ -439,20 +1260,67 @@ impl ComponentDefinitionRegistry for StaticComponentDefinitionRegistry {
     }
 
     #[inline]
-    fn components_by_type(&self, type_id: TypeId) -> Vec<ComponentDefinition> {
+    fn remove_component(&mut self, target: TypeId) -> Option<ComponentDefinitionPtr> {
+        self.definition_map.remove_component(target)
+    }
+
+    #[inline]
+    fn remove_alias(
+        &mut self,
+        alias_type: TypeId,
+    ) -> Result<Vec<ComponentDefinitionPtr>, ComponentDefinitionRegistryError> {
+        Ok(self.definition_map.remove_alias(alias_type))
+    }
+
+    #[inline]
+    fn replace_component(
+        &mut self,
+        target: TypeId,
+        target_name: &str,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        self.definition_map.replace_component(
+            target,
+            target_name,
+            metadata,
+            self.allow_definition_overriding,
+        )
+    }
+
+    #[inline]
+    fn components_by_type(&self, type_id: TypeId) -> Vec<ComponentDefinitionPtr> {
         self.definition_map.components_by_type(type_id)
     }
 
     #[inline]
-    fn component_by_name(&self, name: &str, type_id: TypeId) -> Option<ComponentDefinition> {
+    fn component_by_name(&self, name: &str, type_id: TypeId) -> Option<ComponentDefinitionPtr> {
         self.definition_map.component_by_name(name, type_id)
     }
 
     #[inline]
-    fn primary_component(&self, type_id: TypeId) -> Option<ComponentDefinition> {
+    fn definition_by_name(&self, name: &str) -> Option<ComponentDefinitionPtr> {
+        self.definition_map.definition_by_name(name)
+    }
+
+    #[inline]
+    fn primary_component(&self, type_id: TypeId) -> Option<ComponentDefinitionPtr> {
         self.definition_map.primary_component(type_id)
     }
 
+    #[inline]
+    fn set_primary_component(
+        &mut self,
+        type_id: TypeId,
+        name: &str,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        self.definition_map.set_primary_component(type_id, name)
+    }
+
+    #[inline]
+    fn type_id_by_name(&self, type_name: &str) -> Result<TypeId, ComponentDefinitionRegistryError> {
+        self.definition_map.type_id_by_name(type_name)
+    }
+
     #[inline]
     fn is_registered(&self, type_id: TypeId) -> bool {
         <Self as ComponentDefinitionRegistryFacade>::is_registered(self, type_id)
@@ -464,9 +1332,63 @@ impl ComponentDefinitionRegistry for StaticComponentDefinitionRegistry {
     }
 
     #[inline]
-    fn all_definitions(&self) -> FxHashMap<TypeId, Vec<ComponentDefinition>> {
+    fn all_definitions(&self) -> FxHashMap<TypeId, Vec<ComponentDefinitionPtr>> {
         self.definition_map.all_definitions()
     }
+
+    fn visit_definitions<'a>(&self, visitor: &mut DefinitionVisitor<'a>) {
+        for (type_id, definitions) in self.definitions_iter() {
+            visitor(type_id, definitions);
+        }
+    }
+
+    #[inline]
+    fn shadowed_definitions(&self) -> Vec<ShadowedDefinition> {
+        self.definition_map.shadowed_definitions().to_vec()
+    }
+
+    #[inline]
+    fn clone_registry(&self) -> Option<ComponentDefinitionRegistryPtr> {
+        Some(Box::new(self.clone()))
+    }
+
+    fn export(&self) -> ComponentRegistryExport {
+        let mut types = self.definition_map.export_types();
+        for exported in &mut types {
+            for definition in &mut exported.definitions {
+                definition.has_condition = self.condition_outcomes.iter().any(|condition| {
+                    condition.passed
+                        && if exported.is_alias {
+                            condition.type_name == exported.type_name
+                                && condition.target_type_name.as_ref()
+                                    == Some(&definition.resolved_type_name)
+                        } else {
+                            condition.type_name == exported.type_name
+                                && condition.target_type_name.is_none()
+                        }
+                });
+            }
+        }
+
+        ComponentRegistryExport {
+            types,
+            conditions: self.condition_outcomes.clone(),
+        }
+    }
+}
+
+struct DefinitionMapFacade<'a>(&'a RefCell<NamedComponentDefinitionMap>);
+
+impl ComponentDefinitionRegistryFacade for DefinitionMapFacade<'_> {
+    #[inline]
+    fn is_registered(&self, target: TypeId) -> bool {
+        self.0.borrow().is_registered(target)
+    }
+
+    #[inline]
+    fn is_name_registered(&self, name: &str) -> bool {
+        self.0.borrow().is_name_registered(name)
+    }
 }
 
 impl ComponentDefinitionRegistryFacade for StaticComponentDefinitionRegistry {
@@ -481,19 +1403,71 @@ impl ComponentDefinitionRegistryFacade for StaticComponentDefinitionRegistry {
     }
 }
 
+// strips module paths and marker bounds, e.g. "dyn app::Storage + core::marker::Send" becomes
+// "dyn Storage"
+fn short_type_name(type_name: &str) -> String {
+    let mut short = String::with_capacity(type_name.len());
+    let mut path_start = 0;
+    let mut chars = type_name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            short.truncate(path_start);
+        } else {
+            short.push(c);
+            if !(c.is_alphanumeric() || c == '_') {
+                path_start = short.len();
+            }
+        }
+    }
+
+    short.replace(" + Send", "").replace(" + Sync", "")
+}
+
 mod registry {
-    use crate::component_registry::ComponentDefinitionRegistryError;
+    use crate::component_registry::export::{ExportedDefinition, ExportedType};
+    use crate::component_registry::internal::TypedComponentDefinition;
+    use crate::component_registry::{short_type_name, ComponentDefinitionRegistryError};
     use crate::component_registry::{
-        ComponentAliasMetadata, ComponentDefinition, ComponentMetadata,
+        ComponentAliasMetadata, ComponentDefinition, ComponentDefinitionPtr, ComponentMetadata,
+        ShadowedDefinition,
     };
     use fxhash::{FxHashMap, FxHashSet};
+    use itertools::Itertools;
     use std::any::TypeId;
     use tracing::debug;
 
+    // precedence of statically registered components when resolving duplicate names - explicit
+    // overrides win, followed by higher priority among overrides
+    #[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    pub(super) struct RegistrationPrecedence {
+        is_override: bool,
+        priority: i8,
+    }
+
+    impl From<&TypedComponentDefinition> for RegistrationPrecedence {
+        fn from(definition: &TypedComponentDefinition) -> Self {
+            // priority of regular components only orders conditional registration, so their
+            // duplicates are still reported
+            Self {
+                is_override: definition.is_override,
+                priority: if definition.is_override {
+                    definition.priority
+                } else {
+                    0
+                },
+            }
+        }
+    }
+
     #[derive(Default, Clone, Debug)]
     pub(super) struct NamedComponentDefinitionMap {
-        definitions: FxHashMap<TypeId, Vec<ComponentDefinition>>,
-        names: FxHashSet<String>,
+        definitions: FxHashMap<TypeId, Vec<ComponentDefinitionPtr>>,
+        // names mapped to types of components having them
+        names: FxHashMap<String, TypeId>,
+        type_names: FxHashMap<TypeId, String>,
+        precedences: FxHashMap<TypeId, RegistrationPrecedence>,
+        shadowed: Vec<ShadowedDefinition>,
     }
 
     impl NamedComponentDefinitionMap {
@@ -501,7 +1475,7 @@ mod registry {
             &self,
             name: &str,
             type_id: TypeId,
-        ) -> Option<ComponentDefinition> {
+        ) -> Option<ComponentDefinitionPtr> {
             self.definitions
                 .get(&type_id)
                 .and_then(|definitions| {
@@ -512,21 +1486,116 @@ mod registry {
                 .cloned()
         }
 
-        pub(super) fn components_by_type(&self, type_id: TypeId) -> Vec<ComponentDefinition> {
+        pub(super) fn definition_by_name(&self, name: &str) -> Option<ComponentDefinitionPtr> {
+            let type_id = self.names.get(name)?;
+            self.definitions
+                .get(type_id)
+                .and_then(|definitions| {
+                    definitions.iter().find(|definition| {
+                        definition.resolved_type_id == *type_id && definition.names.contains(name)
+                    })
+                })
+                .cloned()
+        }
+
+        pub(super) fn components_by_type(&self, type_id: TypeId) -> Vec<ComponentDefinitionPtr> {
             self.definitions.get(&type_id).cloned().unwrap_or_default()
         }
 
-        pub(super) fn primary_component(&self, type_id: TypeId) -> Option<ComponentDefinition> {
+        pub(super) fn primary_component(&self, type_id: TypeId) -> Option<ComponentDefinitionPtr> {
             self.definitions.get(&type_id).and_then(|definitions| {
                 (if definitions.len() == 1 {
                     definitions.first()
                 } else {
-                    definitions.iter().find(|definition| definition.is_primary)
+                    definitions
+                        .iter()
+                        .find(|definition| definition.is_primary)
+                        .or_else(|| Self::default_definition(definitions))
                 })
                 .cloned()
             })
         }
 
+        // qualified definitions don't compete with the default definition of the same type
+        fn default_definition(
+            definitions: &[ComponentDefinitionPtr],
+        ) -> Option<&ComponentDefinitionPtr> {
+            let first = definitions.first()?;
+            if definitions
+                .iter()
+                .any(|definition| definition.resolved_type_id != first.resolved_type_id)
+            {
+                return None;
+            }
+
+            definitions
+                .iter()
+                .find(|definition| definition.qualifier.is_none())
+        }
+
+        pub(super) fn set_primary_component(
+            &mut self,
+            type_id: TypeId,
+            name: &str,
+        ) -> Result<(), ComponentDefinitionRegistryError> {
+            let definitions = self
+                .definitions
+                .get_mut(&type_id)
+                .filter(|definitions| {
+                    definitions
+                        .iter()
+                        .any(|definition| definition.names.contains(name))
+                })
+                .ok_or_else(|| ComponentDefinitionRegistryError::MissingNamedComponent {
+                    type_name: self
+                        .type_names
+                        .get(&type_id)
+                        .cloned()
+                        .unwrap_or_else(|| format!("{type_id:?}")),
+                    name: name.to_string(),
+                })?;
+
+            for definition in definitions {
+                let is_primary = definition.names.contains(name);
+                if definition.is_primary != is_primary {
+                    ComponentDefinitionPtr::make_mut(definition).is_primary = is_primary;
+                }
+            }
+
+            Ok(())
+        }
+
+        pub(super) fn type_id_by_name(
+            &self,
+            type_name: &str,
+        ) -> Result<TypeId, ComponentDefinitionRegistryError> {
+            if let Some((type_id, _)) = self.type_names.iter().find(|(_, name)| *name == type_name)
+            {
+                return Ok(*type_id);
+            }
+
+            let matching = self
+                .type_names
+                .iter()
+                .filter(|(_, name)| short_type_name(name) == type_name)
+                .collect_vec();
+
+            match matching.as_slice() {
+                [] => Err(ComponentDefinitionRegistryError::MissingComponentType(
+                    type_name.to_string(),
+                )),
+                [(type_id, _)] => Ok(**type_id),
+                _ => Err(ComponentDefinitionRegistryError::AmbiguousComponentType {
+                    type_name: type_name.to_string(),
+                    candidates: matching
+                        .into_iter()
+                        .map(|(_, name)| name.clone())
+                        .sorted()
+                        .collect(),
+                }),
+            }
+        }
+
         pub(super) fn try_register_alias(
             &mut self,
             alias_type: TypeId,
@@ -543,6 +1612,15 @@ mod registry {
                 "Registering new alias."
             );
 
+            // casts can't be composed, so alias definitions always need to target concrete
+            // components directly
+            if self.is_alias(target_type) {
+                return Err(ComponentDefinitionRegistryError::AliasChain {
+                    alias_type: alias_name.to_string(),
+                    target_type: target_name.to_string(),
+                });
+            }
+
             let mut target_definitions = self
                 .definitions
                 .get(&target_type)
@@ -552,8 +1630,7 @@ mod registry {
                 })
                 .cloned()?;
 
-            // this should not be possible, since we don't allow removing definitions, but better
-            // be safe
+            // removing definitions also removes empty entries, but better be safe
             if target_definitions.is_empty() {
                 return Err(ComponentDefinitionRegistryError::MissingBaseComponent {
                     alias_type: alias_name.to_string(),
@@ -561,8 +1638,20 @@ mod registry {
                 });
             }
 
+            // qualified definitions follow the default one and are never primary themselves
+            let single_target = target_definitions.len() == 1;
+            let is_primary_target = |definition: &ComponentDefinitionPtr| {
+                single_target || definition.qualifier.is_none()
+            };
+
             // if we're registering a primary alias, there needs to be a single target
-            if metadata.is_primary && target_definitions.len() > 1 {
+            if metadata.is_primary
+                && target_definitions
+                    .iter()
+                    .filter(|definition| is_primary_target(definition))
+                    .count()
+                    != 1
+            {
                 return Err(
                     ComponentDefinitionRegistryError::DuplicatePrimaryComponent {
                         alias_type: alias_name.to_string(),
@@ -571,17 +1660,7 @@ mod registry {
                 );
             }
 
-            // should run once due to above anyway
-            for definition in &mut target_definitions {
-                definition.is_primary = metadata.is_primary;
-                definition.cast = metadata.cast;
-
-                if let Some(scope) = &metadata.scope {
-                    definition.scope.clone_from(scope);
-                }
-            }
-
-            if let Some(alias_definitions) = self.definitions.get_mut(&alias_type) {
+            if let Some(alias_definitions) = self.definitions.get(&alias_type) {
                 if metadata.is_primary
                     && alias_definitions
                         .iter()
@@ -595,14 +1674,54 @@ mod registry {
                     );
                 }
 
+                // the same component can be reachable by multiple aliases
+                target_definitions.retain(|target_definition| {
+                    !alias_definitions.iter().any(|definition| {
+                        definition.resolved_type_id == target_definition.resolved_type_id
+                            && definition.qualifier == target_definition.qualifier
+                    })
+                });
+
+                if target_definitions.is_empty() {
+                    return Ok(());
+                }
+            }
+
+            // should run once due to above anyway
+            for definition in &mut target_definitions {
+                let is_primary = metadata.is_primary && is_primary_target(definition);
+                let definition = ComponentDefinitionPtr::make_mut(definition);
+                definition.is_primary = is_primary;
+                definition.cast = metadata.cast;
+
+                if let Some(scope) = &metadata.scope {
+                    definition.scope.clone_from(scope);
+                }
+            }
+
+            if let Some(alias_definitions) = self.definitions.get_mut(&alias_type) {
                 alias_definitions.append(&mut target_definitions);
+                Self::sort_definitions(alias_definitions);
             } else {
                 self.definitions.insert(alias_type, target_definitions);
             }
 
+            self.type_names.insert(alias_type, alias_name.to_string());
+
             Ok(())
         }
 
+        fn is_alias(&self, type_id: TypeId) -> bool {
+            self.definitions
+                .get(&type_id)
+                .map(|definitions| {
+                    definitions
+                        .iter()
+                        .any(|definition| definition.resolved_type_id != type_id)
+                })
+                .unwrap_or(false)
+        }
+
         pub(super) fn try_register_component(
             &mut self,
             target: TypeId,
@@ -614,7 +1733,7 @@ mod registry {
 
             if !allow_definition_overriding {
                 if let Some(name) = metadata.names.iter().find_map(|name| {
-                    if self.names.contains(name) {
+                    if self.names.contains_key(name) {
                         Some(name.clone())
                     } else {
                         None
@@ -626,23 +1745,16 @@ mod registry {
                 }
             }
 
-            let definition = ComponentDefinition {
-                names: metadata.names.clone(),
-                is_primary: false,
-                scope: metadata.scope.clone(),
-                resolved_type_id: target,
-                resolved_type_name: target_name.to_string(),
-                constructor: metadata.constructor,
-                cast: metadata.cast,
-            };
+            let definition = Self::create_definition(target, target_name, None, metadata);
 
             let names = definition.names.clone();
 
             if let Some(entries) = self.definitions.get_mut(&target) {
-                // concrete component types should not have multiple definitions
-                debug_assert!(entries.len() <= 1);
-
-                if !allow_definition_overriding && !entries.is_empty() {
+                // concrete component types have a single default definition, while qualified ones
+                // are kept when overriding
+                if !allow_definition_overriding
+                    && entries.iter().any(|entry| entry.qualifier.is_none())
+                {
                     return Err(ComponentDefinitionRegistryError::DuplicateComponentType(
                         target_name.to_string(),
                     ));
@@ -650,129 +1762,541 @@ mod registry {
 
                 entries
                     .iter()
+                    .filter(|entry| entry.qualifier.is_none())
                     .flat_map(|entry| entry.names.iter())
                     .for_each(|name| {
                         self.names.remove(name);
                     });
 
-                *entries = vec![definition];
+                entries.retain(|entry| entry.qualifier.is_some());
+                entries.push(ComponentDefinitionPtr::new(definition));
+                Self::sort_definitions(entries);
             } else {
-                self.definitions.insert(target, vec![definition]);
+                self.definitions
+                    .insert(target, vec![ComponentDefinitionPtr::new(definition)]);
             }
 
-            self.names.extend(names);
+            self.names
+                .extend(names.into_iter().map(|name| (name, target)));
+            self.type_names.insert(target, target_name.to_string());
             Ok(())
         }
 
-        #[inline]
-        pub(super) fn is_registered(&self, target: TypeId) -> bool {
-            self.definitions
-                .get(&target)
-                .map(|definitions| !definitions.is_empty())
-                .unwrap_or(false)
-        }
+        pub(super) fn try_register_qualified_component(
+            &mut self,
+            target: TypeId,
+            target_name: &str,
+            qualifier: &str,
+            metadata: &ComponentMetadata,
+            allow_definition_overriding: bool,
+        ) -> Result<(), ComponentDefinitionRegistryError> {
+            debug!(
+                ?target,
+                target_name, qualifier, "Registering new qualified component."
+            );
 
-        #[inline]
-        pub(super) fn is_name_registered(&self, name: &str) -> bool {
-            self.names.contains(name)
-        }
+            let mut definition =
+                Self::create_definition(target, target_name, Some(qualifier), metadata);
+            definition.names.insert(qualifier.to_string());
 
-        #[inline]
-        pub(super) fn all_definitions(&self) -> FxHashMap<TypeId, Vec<ComponentDefinition>> {
-            self.definitions.clone()
-        }
-    }
+            let previous = self.definitions.get(&target).and_then(|definitions| {
+                definitions
+                    .iter()
+                    .find(|definition| definition.qualifier.as_deref() == Some(qualifier))
+                    .cloned()
+            });
 
-    #[cfg(test)]
-    mod tests {
-        #[cfg(not(feature = "async"))]
-        mod sync {
-            use crate::component_registry::registry::NamedComponentDefinitionMap;
-            use crate::component_registry::{
-                ComponentAliasMetadata, ComponentDefinitionRegistryError, ComponentMetadata,
-            };
-            use crate::instance_provider::{
-                ComponentInstanceAnyPtr, ComponentInstanceProvider, ComponentInstanceProviderError,
-                ComponentInstancePtr,
-            };
-            use std::any::{Any, TypeId};
+            if !allow_definition_overriding {
+                if previous.is_some() {
+                    return Err(ComponentDefinitionRegistryError::DuplicateComponentType(
+                        format!("{target_name}({qualifier})"),
+                    ));
+                }
 
-            fn cast(
-                instance: ComponentInstanceAnyPtr,
-            ) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr> {
-                Err(instance)
+                if let Some(name) = definition
+                    .names
+                    .iter()
+                    .find(|name| self.names.contains_key(*name))
+                {
+                    return Err(ComponentDefinitionRegistryError::DuplicateComponentName(
+                        name.clone(),
+                    ));
+                }
             }
 
-            fn constructor(
-                _instance_provider: &mut dyn ComponentInstanceProvider,
-            ) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
-                Ok(ComponentInstancePtr::new(0) as ComponentInstanceAnyPtr)
+            if let Some(previous) = &previous {
+                for name in &previous.names {
+                    self.names.remove(name);
+                }
             }
 
-            fn create_metadata() -> (ComponentMetadata, TypeId) {
-                (
-                    ComponentMetadata {
-                        names: ["name".to_string()].into_iter().collect(),
-                        scope: "".to_string(),
-                        constructor,
-                        cast,
-                    },
-                    TypeId::of::<i8>(),
-                )
+            let key = definition.key();
+            self.names
+                .extend(definition.names.iter().map(|name| (name.clone(), target)));
+            self.type_names.insert(target, target_name.to_string());
+
+            // aliases of the type get a copy of the definition, using their own cast function
+            for (type_id, definitions) in &mut self.definitions {
+                let alias_definition = if *type_id == target {
+                    Some(definition.clone())
+                } else {
+                    definitions
+                        .iter()
+                        .find(|alias_definition| {
+                            alias_definition.resolved_type_id == target
+                                && alias_definition.qualifier.is_none()
+                        })
+                        .map(|alias_definition| ComponentDefinition {
+                            cast: alias_definition.cast,
+                            ..definition.clone()
+                        })
+                };
+
+                if let Some(alias_definition) = alias_definition {
+                    definitions.retain(|definition| definition.key() != key);
+                    definitions.push(ComponentDefinitionPtr::new(alias_definition));
+                    Self::sort_definitions(definitions);
+                }
             }
 
-            #[test]
-            fn should_reject_primary_alias_for_ambiguous_target() {
+            self.definitions
+                .entry(target)
+                .or_insert_with(|| vec![ComponentDefinitionPtr::new(definition)]);
+
+            Ok(())
+        }
+
+        fn sort_definitions(definitions: &mut [ComponentDefinitionPtr]) {
+            definitions.sort_by(|first, second| {
+                first
+                    .order
+                    .cmp(&second.order)
+                    .then_with(|| first.resolved_type_name.cmp(&second.resolved_type_name))
+                    .then_with(|| first.qualifier.cmp(&second.qualifier))
+            });
+        }
+
+        // statically registered definitions can be qualified, e.g. when multiple factory methods
+        // produce the same type
+        pub(super) fn try_register_typed_definition(
+            &mut self,
+            definition: &TypedComponentDefinition,
+            allow_definition_overriding: bool,
+        ) -> Result<(), ComponentDefinitionRegistryError> {
+            if let Some(qualifier) = &definition.qualifier {
+                self.try_register_qualified_component(
+                    definition.target,
+                    definition.target_name,
+                    qualifier,
+                    &definition.metadata,
+                    allow_definition_overriding,
+                )
+            } else {
+                self.try_register_component_with_precedence(
+                    definition.target,
+                    definition.target_name,
+                    &definition.metadata,
+                    RegistrationPrecedence::from(definition),
+                    allow_definition_overriding,
+                )
+            }
+        }
+
+        // resolves name conflicts with components registered with different precedence, before
+        // registering a new component
+        pub(super) fn try_register_component_with_precedence(
+            &mut self,
+            target: TypeId,
+            target_name: &str,
+            metadata: &ComponentMetadata,
+            precedence: RegistrationPrecedence,
+            allow_definition_overriding: bool,
+        ) -> Result<(), ComponentDefinitionRegistryError> {
+            let conflicts = metadata
+                .names
+                .iter()
+                .filter_map(|name| {
+                    let type_id = *self.names.get(name)?;
+                    if type_id == target {
+                        return None;
+                    }
+
+                    self.definitions
+                        .get(&type_id)?
+                        .iter()
+                        .find(|definition| definition.resolved_type_id == type_id)
+                        .filter(|definition| definition.names.contains(name))
+                        .map(|definition| (definition.clone(), name.clone()))
+                })
+                .unique_by(|(definition, _)| definition.resolved_type_id)
+                .sorted_by(|(first, _), (second, _)| {
+                    first.resolved_type_name.cmp(&second.resolved_type_name)
+                })
+                .collect_vec();
+
+            if let Some((winner, name)) = conflicts
+                .iter()
+                .find(|(definition, _)| self.precedence(definition.resolved_type_id) > precedence)
+            {
+                debug!(
+                    ?target,
+                    target_name,
+                    winner = winner.resolved_type_name,
+                    "Component is shadowed by another one with higher precedence."
+                );
+
+                self.shadowed.push(ShadowedDefinition {
+                    definition: ComponentDefinitionPtr::new(Self::create_definition(
+                        target,
+                        target_name,
+                        None,
+                        metadata,
+                    )),
+                    name: name.clone(),
+                    shadowed_by: winner.resolved_type_name.clone(),
+                });
+
+                return Ok(());
+            }
+
+            for (definition, name) in conflicts {
+                if self.precedence(definition.resolved_type_id) < precedence {
+                    debug!(
+                        ?target,
+                        target_name,
+                        shadowed = definition.resolved_type_name,
+                        "Component shadows another one with lower precedence."
+                    );
+
+                    self.remove_definitions(definition.resolved_type_id);
+                    self.shadowed.push(ShadowedDefinition {
+                        definition,
+                        name,
+                        shadowed_by: target_name.to_string(),
+                    });
+                }
+            }
+
+            self.try_register_component(
+                target,
+                target_name,
+                metadata,
+                allow_definition_overriding,
+            )?;
+            self.precedences.insert(target, precedence);
+            Ok(())
+        }
+
+        #[inline]
+        pub(super) fn shadowed_definitions(&self) -> &[ShadowedDefinition] {
+            &self.shadowed
+        }
+
+        #[inline]
+        fn precedence(&self, type_id: TypeId) -> RegistrationPrecedence {
+            self.precedences.get(&type_id).copied().unwrap_or_default()
+        }
+
+        fn create_definition(
+            target: TypeId,
+            target_name: &str,
+            qualifier: Option<&str>,
+            metadata: &ComponentMetadata,
+        ) -> ComponentDefinition {
+            ComponentDefinition {
+                qualifier: qualifier.map(|qualifier| qualifier.to_string()),
+                ..ComponentDefinition::new(target, target_name, metadata)
+            }
+        }
+
+        pub(super) fn remove_component(
+            &mut self,
+            target: TypeId,
+        ) -> Option<ComponentDefinitionPtr> {
+            debug!(?target, "Removing component.");
+
+            self.remove_definitions(target)
+        }
+
+        pub(super) fn remove_alias(&mut self, alias_type: TypeId) -> Vec<ComponentDefinitionPtr> {
+            debug!(?alias_type, "Removing alias.");
+
+            let definitions = match self.definitions.get_mut(&alias_type) {
+                Some(definitions) => definitions,
+                None => return vec![],
+            };
+
+            // definitions of a concrete type registered under the same id are not alias entries
+            let (removed, kept): (Vec<_>, Vec<_>) = definitions
+                .drain(..)
+                .partition(|definition| definition.resolved_type_id != alias_type);
+
+            if kept.is_empty() {
+                self.definitions.remove(&alias_type);
+                self.type_names.remove(&alias_type);
+            } else {
+                *definitions = kept;
+            }
+
+            removed
+        }
+
+        fn remove_definitions(&mut self, target: TypeId) -> Option<ComponentDefinitionPtr> {
+            let definitions = self.definitions.get(&target)?;
+            let definition = definitions
+                .iter()
+                .find(|definition| {
+                    definition.resolved_type_id == target && definition.qualifier.is_none()
+                })
+                .or_else(|| definitions.first())
+                .cloned()?;
+
+            // qualified definitions of the type are removed along with the default one
+            for name in definitions
+                .iter()
+                .filter(|definition| definition.resolved_type_id == target)
+                .flat_map(|definition| definition.names.iter())
+            {
+                self.names.remove(name);
+            }
+
+            self.precedences.remove(&target);
+
+            // aliases contain copies of the target definition
+            self.definitions.retain(|type_id, definitions| {
+                definitions.retain(|definition| definition.resolved_type_id != target);
+                if definitions.is_empty() {
+                    self.type_names.remove(type_id);
+                    false
+                } else {
+                    true
+                }
+            });
+
+            Some(definition)
+        }
+
+        pub(super) fn replace_component(
+            &mut self,
+            target: TypeId,
+            target_name: &str,
+            metadata: &ComponentMetadata,
+            allow_definition_overriding: bool,
+        ) -> Result<(), ComponentDefinitionRegistryError> {
+            debug!(?target, target_name, "Replacing component.");
+
+            let previous = self.definitions.get(&target).and_then(|definitions| {
+                definitions
+                    .iter()
+                    .find(|definition| {
+                        definition.resolved_type_id == target && definition.qualifier.is_none()
+                    })
+                    .cloned()
+            });
+
+            let previous = if let Some(previous) = previous {
+                previous
+            } else {
+                return self.try_register_component(
+                    target,
+                    target_name,
+                    metadata,
+                    allow_definition_overriding,
+                );
+            };
+
+            if !allow_definition_overriding {
+                if let Some(name) = metadata
+                    .names
+                    .iter()
+                    .find(|name| self.names.contains_key(*name) && !previous.names.contains(*name))
+                {
+                    return Err(ComponentDefinitionRegistryError::DuplicateComponentName(
+                        name.clone(),
+                    ));
+                }
+            }
+
+            for name in &previous.names {
+                self.names.remove(name);
+            }
+
+            for (type_id, definitions) in &mut self.definitions {
+                let mut changed = false;
+                for definition in definitions.iter_mut().filter(|definition| {
+                    definition.resolved_type_id == target && definition.qualifier.is_none()
+                }) {
+                    let definition = ComponentDefinitionPtr::make_mut(definition);
+                    definition.names.clone_from(&metadata.names);
+                    definition.resolved_type_name = target_name.to_string();
+                    definition.order = metadata.order;
+                    definition.constructor = metadata.constructor;
+                    definition.late_injection = metadata.late_injection;
+                    definition.post_construct = metadata.post_construct;
+                    definition.pre_destroy = metadata.pre_destroy;
+
+                    // aliases can override the scope, which needs to be kept
+                    if *type_id == target || definition.scope == previous.scope {
+                        definition.scope.clone_from(&metadata.scope);
+                    }
+
+                    // aliases have their own cast functions
+                    if *type_id == target {
+                        definition.cast = metadata.cast;
+                    }
+
+                    changed = true;
+                }
+
+                if changed {
+                    Self::sort_definitions(definitions);
+                }
+            }
+
+            self.names
+                .extend(metadata.names.iter().map(|name| (name.clone(), target)));
+            self.type_names.insert(target, target_name.to_string());
+            Ok(())
+        }
+
+        pub(super) fn export_types(&self) -> Vec<ExportedType> {
+            self.definitions
+                .iter()
+                .map(|(type_id, definitions)| ExportedType {
+                    type_name: self
+                        .type_names
+                        .get(type_id)
+                        .cloned()
+                        .unwrap_or_else(|| format!("{type_id:?}")),
+                    is_alias: definitions
+                        .iter()
+                        .any(|definition| definition.resolved_type_id != *type_id),
+                    definitions: definitions
+                        .iter()
+                        .map(|definition| ExportedDefinition::from(&**definition))
+                        .collect(),
+                })
+                .sorted_by(|first, second| first.type_name.cmp(&second.type_name))
+                .collect()
+        }
+
+        #[inline]
+        pub(super) fn is_registered(&self, target: TypeId) -> bool {
+            self.definitions
+                .get(&target)
+                .map(|definitions| !definitions.is_empty())
+                .unwrap_or(false)
+        }
+
+        // concrete component types, as opposed to aliases
+        pub(super) fn component_types(&self) -> FxHashSet<TypeId> {
+            self.definitions
+                .iter()
+                .filter(|(type_id, definitions)| {
+                    definitions
+                        .iter()
+                        .any(|definition| definition.resolved_type_id == **type_id)
+                })
+                .map(|(type_id, _)| *type_id)
+                .collect()
+        }
+
+        #[inline]
+        pub(super) fn is_name_registered(&self, name: &str) -> bool {
+            self.names.contains_key(name)
+        }
+
+        #[inline]
+        pub(super) fn all_definitions(&self) -> FxHashMap<TypeId, Vec<ComponentDefinitionPtr>> {
+            self.definitions.clone()
+        }
+
+        #[inline]
+        pub(super) fn definitions_iter(
+            &self,
+        ) -> impl Iterator<Item = (TypeId, &[ComponentDefinitionPtr])> {
+            self.definitions
+                .iter()
+                .map(|(type_id, definitions)| (*type_id, definitions.as_slice()))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        #[cfg(not(feature = "async"))]
+        mod sync {
+            use crate::component_registry::internal::TypedComponentDefinition;
+            use crate::component_registry::registry::NamedComponentDefinitionMap;
+            use crate::component_registry::{
+                ComponentAliasMetadata, ComponentDefinitionPtr, ComponentDefinitionRegistryError,
+                ComponentMetadata,
+            };
+            use crate::instance_provider::{
+                ComponentInstanceAnyPtr, ComponentInstanceProvider, ComponentInstanceProviderError,
+                ComponentInstancePtr,
+            };
+            use std::any::{Any, TypeId};
+
+            fn cast(
+                instance: ComponentInstanceAnyPtr,
+                _target: &mut dyn Any,
+            ) -> Result<(), ComponentInstanceAnyPtr> {
+                Err(instance)
+            }
+
+            fn constructor(
+                _instance_provider: &mut dyn ComponentInstanceProvider,
+            ) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
+                Ok(ComponentInstancePtr::new(0) as ComponentInstanceAnyPtr)
+            }
+
+            fn create_metadata() -> (ComponentMetadata, TypeId) {
+                (
+                    ComponentMetadata {
+                        names: ["name".to_string()].into_iter().collect(),
+                        scope: "".to_string(),
+                        order: 0,
+                        constructor,
+                        late_injection: None,
+                        post_construct: None,
+                        pre_destroy: None,
+                        cast,
+                        dependencies: vec![],
+                    },
+                    TypeId::of::<i8>(),
+                )
+            }
+
+            #[test]
+            fn should_reject_primary_alias_for_ambiguous_target() {
                 let definition = ComponentMetadata {
                     names: Default::default(),
                     scope: "".to_string(),
+                    order: 0,
                     constructor,
+                    late_injection: None,
+                    post_construct: None,
+                    pre_destroy: None,
                     cast,
+                    dependencies: vec![],
                 };
-                let alias_id_1 = TypeId::of::<u8>();
-                let alias_id_2 = TypeId::of::<u16>();
-                let target_id_1 = TypeId::of::<i16>();
-                let target_id_2 = TypeId::of::<u16>();
+                let alias_id = TypeId::of::<u8>();
+                let target_id = TypeId::of::<i16>();
 
+                // qualified definitions only, so there's no default one to be primary
                 let mut registry = NamedComponentDefinitionMap::default();
                 registry
-                    .try_register_component(target_id_1, "", &definition, false)
-                    .unwrap();
-                registry
-                    .try_register_component(target_id_2, "", &definition, false)
-                    .unwrap();
-                registry
-                    .try_register_alias(
-                        alias_id_1,
-                        target_id_1,
-                        "",
-                        "",
-                        &ComponentAliasMetadata {
-                            is_primary: false,
-                            scope: None,
-                            cast,
-                        },
-                    )
+                    .try_register_qualified_component(target_id, "", "first", &definition, false)
                     .unwrap();
                 registry
-                    .try_register_alias(
-                        alias_id_1,
-                        target_id_2,
-                        "",
-                        "",
-                        &ComponentAliasMetadata {
-                            is_primary: false,
-                            scope: None,
-                            cast,
-                        },
-                    )
+                    .try_register_qualified_component(target_id, "", "second", &definition, false)
                     .unwrap();
 
                 assert!(matches!(
                     registry
                         .try_register_alias(
-                            alias_id_2,
-                            alias_id_1,
+                            alias_id,
+                            target_id,
                             "",
                             "",
                             &ComponentAliasMetadata {
@@ -786,6 +2310,39 @@ mod registry {
                 ));
             }
 
+            #[test]
+            fn should_reject_duplicate_names_regardless_of_priority() {
+                let (definition, id) = create_metadata();
+
+                let mut registry = NamedComponentDefinitionMap::default();
+                registry
+                    .try_register_typed_definition(
+                        &TypedComponentDefinition::new::<i8>(definition.clone()),
+                        false,
+                    )
+                    .unwrap();
+
+                assert!(matches!(
+                    registry.try_register_typed_definition(
+                        &TypedComponentDefinition::new::<i16>(definition.clone()).with_priority(1),
+                        false,
+                    ),
+                    Err(ComponentDefinitionRegistryError::DuplicateComponentName(_))
+                ));
+
+                registry
+                    .try_register_typed_definition(
+                        &TypedComponentDefinition::new::<i16>(definition)
+                            .with_priority(1)
+                            .with_override(true),
+                        false,
+                    )
+                    .unwrap();
+                assert!(!registry.is_registered(id));
+                assert!(registry.is_registered(TypeId::of::<i16>()));
+                assert_eq!(registry.shadowed_definitions().len(), 1);
+            }
+
             #[test]
             fn should_register_definition() {
                 let (definition, id) = create_metadata();
@@ -863,6 +2420,37 @@ mod registry {
                 );
             }
 
+            #[test]
+            fn should_reject_alias_of_alias() {
+                let (definition, id) = create_metadata();
+                let alias_id = TypeId::of::<u8>();
+                let alias_metadata = ComponentAliasMetadata {
+                    is_primary: false,
+                    scope: None,
+                    cast,
+                };
+
+                let mut registry = NamedComponentDefinitionMap::default();
+                registry
+                    .try_register_component(id, "", &definition, false)
+                    .unwrap();
+                registry
+                    .try_register_alias(alias_id, id, "", "", &alias_metadata)
+                    .unwrap();
+
+                assert!(matches!(
+                    registry.try_register_alias(
+                        TypeId::of::<u16>(),
+                        alias_id,
+                        "",
+                        "",
+                        &alias_metadata
+                    ),
+                    Err(ComponentDefinitionRegistryError::AliasChain { .. })
+                ));
+                assert!(!registry.is_registered(TypeId::of::<u16>()));
+            }
+
             #[test]
             fn should_register_alias_scope() {
                 let (definition, id) = create_metadata();
@@ -879,100 +2467,351 @@ mod registry {
                         "",
                         "",
                         &ComponentAliasMetadata {
-                            is_primary: false,
-                            scope: Some("scope".to_string()),
+                            is_primary: false,
+                            scope: Some("scope".to_string()),
+                            cast,
+                        },
+                    )
+                    .unwrap();
+
+                assert_eq!(registry.components_by_type(alias_id)[0].scope, "scope");
+            }
+
+            #[test]
+            fn should_remove_component_with_aliases() {
+                let (definition, id) = create_metadata();
+                let alias_id = TypeId::of::<u8>();
+
+                let mut registry = NamedComponentDefinitionMap::default();
+                registry
+                    .try_register_component(id, "", &definition, false)
+                    .unwrap();
+                registry
+                    .try_register_alias(
+                        alias_id,
+                        id,
+                        "",
+                        "",
+                        &ComponentAliasMetadata {
+                            is_primary: false,
+                            scope: None,
+                            cast,
+                        },
+                    )
+                    .unwrap();
+
+                assert!(registry.remove_component(id).is_some());
+                assert!(!registry.is_registered(id));
+                assert!(!registry.is_registered(alias_id));
+                assert!(!registry.is_name_registered("name"));
+                assert!(registry.remove_component(id).is_none());
+            }
+
+            #[test]
+            fn should_iterate_shared_definitions() {
+                let (definition, id) = create_metadata();
+                let alias_id = TypeId::of::<u8>();
+
+                let mut registry = NamedComponentDefinitionMap::default();
+                registry
+                    .try_register_component(id, "", &definition, false)
+                    .unwrap();
+                registry
+                    .try_register_alias(
+                        alias_id,
+                        id,
+                        "",
+                        "",
+                        &ComponentAliasMetadata {
+                            is_primary: false,
+                            scope: None,
+                            cast,
+                        },
+                    )
+                    .unwrap();
+
+                let definitions = registry.definitions_iter().collect::<Vec<_>>();
+                assert_eq!(definitions.len(), 2);
+
+                let (_, target_definitions) = definitions
+                    .iter()
+                    .find(|(type_id, _)| *type_id == id)
+                    .unwrap();
+                assert!(ComponentDefinitionPtr::ptr_eq(
+                    &target_definitions[0],
+                    &registry.components_by_type(id)[0]
+                ));
+            }
+
+            #[test]
+            fn should_replace_component_keeping_alias_scope() {
+                let (definition, id) = create_metadata();
+                let alias_id = TypeId::of::<u8>();
+
+                let mut registry = NamedComponentDefinitionMap::default();
+                registry
+                    .try_register_component(id, "", &definition, false)
+                    .unwrap();
+                registry
+                    .try_register_alias(
+                        alias_id,
+                        id,
+                        "",
+                        "",
+                        &ComponentAliasMetadata {
+                            is_primary: false,
+                            scope: Some("scope".to_string()),
+                            cast,
+                        },
+                    )
+                    .unwrap();
+
+                let replacement = ComponentMetadata {
+                    names: ["replacement".to_string()].into_iter().collect(),
+                    scope: "other".to_string(),
+                    ..definition
+                };
+                registry
+                    .replace_component(id, "", &replacement, false)
+                    .unwrap();
+
+                assert_eq!(registry.components_by_type(id)[0].scope, "other");
+                assert_eq!(
+                    registry.components_by_type(alias_id)[0].names,
+                    replacement.names
+                );
+                assert_eq!(registry.components_by_type(alias_id)[0].scope, "scope");
+                assert!(!registry.is_name_registered("name"));
+                assert!(registry.is_name_registered("replacement"));
+            }
+
+            #[test]
+            fn should_reject_duplicate_primary_alias() {
+                let (definition, id) = create_metadata();
+                let alias_id = TypeId::of::<u8>();
+
+                let mut registry = NamedComponentDefinitionMap::default();
+                registry
+                    .try_register_component(id, "", &definition, false)
+                    .unwrap();
+                registry
+                    .try_register_alias(
+                        alias_id,
+                        id,
+                        "",
+                        "",
+                        &ComponentAliasMetadata {
+                            is_primary: true,
+                            scope: None,
+                            cast,
+                        },
+                    )
+                    .unwrap();
+
+                assert!(matches!(
+                    registry
+                        .try_register_alias(
+                            alias_id,
+                            id,
+                            "",
+                            "",
+                            &ComponentAliasMetadata {
+                                is_primary: true,
+                                scope: None,
+                                cast
+                            },
+                        )
+                        .unwrap_err(),
+                    ComponentDefinitionRegistryError::DuplicatePrimaryComponent { .. }
+                ));
+            }
+
+            #[test]
+            fn should_return_primary_single_definition() {
+                let (definition, id) = create_metadata();
+                let alias_id = TypeId::of::<u8>();
+
+                let mut registry = NamedComponentDefinitionMap::default();
+                registry
+                    .try_register_component(id, "", &definition, false)
+                    .unwrap();
+                registry
+                    .try_register_alias(
+                        alias_id,
+                        id,
+                        "",
+                        "",
+                        &ComponentAliasMetadata {
+                            is_primary: false,
+                            scope: None,
+                            cast,
+                        },
+                    )
+                    .unwrap();
+
+                assert!(registry.primary_component(alias_id).is_some());
+            }
+
+            #[test]
+            fn should_return_explicit_primary_definition() {
+                let (definition, id_1) = create_metadata();
+                let id_2 = TypeId::of::<u16>();
+                let alias_id = TypeId::of::<u8>();
+
+                let mut registry = NamedComponentDefinitionMap::default();
+                registry
+                    .try_register_component(id_1, "", &definition, false)
+                    .unwrap();
+                registry
+                    .try_register_component(id_2, "", &definition, true)
+                    .unwrap();
+                registry
+                    .try_register_alias(
+                        alias_id,
+                        id_1,
+                        "",
+                        "",
+                        &ComponentAliasMetadata {
+                            is_primary: false,
+                            scope: None,
+                            cast,
+                        },
+                    )
+                    .unwrap();
+                registry
+                    .try_register_alias(
+                        alias_id,
+                        id_2,
+                        "",
+                        "",
+                        &ComponentAliasMetadata {
+                            is_primary: true,
+                            scope: None,
                             cast,
                         },
                     )
                     .unwrap();
 
-                assert_eq!(registry.components_by_type(alias_id)[0].scope, "scope");
+                assert!(registry.primary_component(alias_id).is_some());
             }
 
             #[test]
-            fn should_reject_duplicate_primary_alias() {
-                let (definition, id) = create_metadata();
+            fn should_set_primary_definition() {
+                let (mut definition, id_1) = create_metadata();
+                let id_2 = TypeId::of::<u16>();
                 let alias_id = TypeId::of::<u8>();
 
                 let mut registry = NamedComponentDefinitionMap::default();
                 registry
-                    .try_register_component(id, "", &definition, false)
+                    .try_register_component(id_1, "", &definition, false)
                     .unwrap();
+                definition.names = ["second".to_string()].into_iter().collect();
                 registry
-                    .try_register_alias(
-                        alias_id,
-                        id,
-                        "",
-                        "",
-                        &ComponentAliasMetadata {
-                            is_primary: true,
-                            scope: None,
-                            cast,
-                        },
-                    )
+                    .try_register_component(id_2, "", &definition, false)
                     .unwrap();
 
-                assert!(matches!(
+                for (target, is_primary) in [(id_1, false), (id_2, true)] {
                     registry
                         .try_register_alias(
                             alias_id,
-                            id,
-                            "",
+                            target,
+                            "dyn app::Storage + core::marker::Sync + core::marker::Send",
                             "",
                             &ComponentAliasMetadata {
-                                is_primary: true,
+                                is_primary,
                                 scope: None,
-                                cast
+                                cast,
                             },
                         )
-                        .unwrap_err(),
-                    ComponentDefinitionRegistryError::DuplicatePrimaryComponent { .. }
+                        .unwrap();
+                }
+
+                assert_eq!(
+                    registry
+                        .definition_by_name("second")
+                        .unwrap()
+                        .resolved_type_id,
+                    id_2
+                );
+                assert!(registry.definition_by_name("unknown").is_none());
+                assert_eq!(registry.type_id_by_name("dyn Storage"), Ok(alias_id));
+                assert_eq!(
+                    registry.type_id_by_name(
+                        "dyn app::Storage + core::marker::Sync + core::marker::Send"
+                    ),
+                    Ok(alias_id)
+                );
+                assert!(matches!(
+                    registry.type_id_by_name("dyn Unknown"),
+                    Err(ComponentDefinitionRegistryError::MissingComponentType(_))
+                ));
+                registry.set_primary_component(alias_id, "name").unwrap();
+                assert_eq!(
+                    registry
+                        .primary_component(alias_id)
+                        .unwrap()
+                        .resolved_type_id,
+                    id_1
+                );
+
+                assert!(matches!(
+                    registry.set_primary_component(alias_id, "unknown"),
+                    Err(ComponentDefinitionRegistryError::MissingNamedComponent { .. })
                 ));
             }
 
             #[test]
-            fn should_return_primary_single_definition() {
+            fn should_reject_ambiguous_type_names() {
                 let (definition, id) = create_metadata();
-                let alias_id = TypeId::of::<u8>();
 
                 let mut registry = NamedComponentDefinitionMap::default();
                 registry
                     .try_register_component(id, "", &definition, false)
                     .unwrap();
-                registry
-                    .try_register_alias(
-                        alias_id,
-                        id,
-                        "",
-                        "",
-                        &ComponentAliasMetadata {
-                            is_primary: false,
-                            scope: None,
-                            cast,
-                        },
-                    )
-                    .unwrap();
 
-                assert!(registry.primary_component(alias_id).is_some());
+                for (alias_id, alias_name) in [
+                    (TypeId::of::<u8>(), "dyn app::Storage"),
+                    (TypeId::of::<u16>(), "dyn other::Storage"),
+                ] {
+                    registry
+                        .try_register_alias(
+                            alias_id,
+                            id,
+                            alias_name,
+                            "",
+                            &ComponentAliasMetadata {
+                                is_primary: false,
+                                scope: None,
+                                cast,
+                            },
+                        )
+                        .unwrap();
+                }
+
+                assert!(matches!(
+                    registry.type_id_by_name("dyn Storage"),
+                    Err(ComponentDefinitionRegistryError::AmbiguousComponentType { candidates, .. })
+                        if candidates == ["dyn app::Storage", "dyn other::Storage"]
+                ));
+                assert_eq!(
+                    registry.type_id_by_name("dyn other::Storage"),
+                    Ok(TypeId::of::<u16>())
+                );
             }
 
             #[test]
-            fn should_return_explicit_primary_definition() {
-                let (definition, id_1) = create_metadata();
-                let id_2 = TypeId::of::<u16>();
+            fn should_register_qualified_definitions() {
+                let (definition, id) = create_metadata();
                 let alias_id = TypeId::of::<u8>();
 
                 let mut registry = NamedComponentDefinitionMap::default();
                 registry
-                    .try_register_component(id_1, "", &definition, false)
-                    .unwrap();
-                registry
-                    .try_register_component(id_2, "", &definition, true)
+                    .try_register_component(id, "", &definition, false)
                     .unwrap();
                 registry
                     .try_register_alias(
                         alias_id,
-                        id_1,
+                        id,
                         "",
                         "",
                         &ComponentAliasMetadata {
@@ -982,10 +2821,43 @@ mod registry {
                         },
                     )
                     .unwrap();
+                registry
+                    .try_register_qualified_component(
+                        id,
+                        "",
+                        "qualified",
+                        &ComponentMetadata {
+                            names: Default::default(),
+                            ..definition.clone()
+                        },
+                        false,
+                    )
+                    .unwrap();
+
+                assert_eq!(registry.components_by_type(id).len(), 2);
+                assert_eq!(registry.components_by_type(alias_id).len(), 2);
+                for type_id in [id, alias_id] {
+                    assert!(registry
+                        .primary_component(type_id)
+                        .unwrap()
+                        .qualifier
+                        .is_none());
+                }
+                assert_eq!(
+                    registry
+                        .component_by_name("qualified", alias_id)
+                        .unwrap()
+                        .qualifier
+                        .as_deref(),
+                    Some("qualified")
+                );
+
+                // primary aliases mark only the default definition
+                let primary_alias_id = TypeId::of::<u16>();
                 registry
                     .try_register_alias(
-                        alias_id,
-                        id_2,
+                        primary_alias_id,
+                        id,
                         "",
                         "",
                         &ComponentAliasMetadata {
@@ -995,8 +2867,89 @@ mod registry {
                         },
                     )
                     .unwrap();
+                assert_eq!(
+                    registry
+                        .components_by_type(primary_alias_id)
+                        .iter()
+                        .filter(|definition| definition.is_primary)
+                        .count(),
+                    1
+                );
+                assert!(registry
+                    .primary_component(primary_alias_id)
+                    .unwrap()
+                    .qualifier
+                    .is_none());
 
-                assert!(registry.primary_component(alias_id).is_some());
+                assert!(matches!(
+                    registry
+                        .try_register_qualified_component(id, "", "qualified", &definition, false)
+                        .unwrap_err(),
+                    ComponentDefinitionRegistryError::DuplicateComponentType(_)
+                ));
+
+                // overriding the default definition keeps qualified ones
+                registry
+                    .try_register_component(id, "", &definition, true)
+                    .unwrap();
+                assert_eq!(registry.components_by_type(id).len(), 2);
+
+                registry.remove_component(id);
+                assert!(!registry.is_name_registered("name"));
+                assert!(!registry.is_name_registered("qualified"));
+                assert!(registry.components_by_type(alias_id).is_empty());
+            }
+
+            #[test]
+            fn should_reject_primary_aliases_in_any_registration_order() {
+                let (definition, id) = create_metadata();
+                let second_id = TypeId::of::<i16>();
+                let alias_id = TypeId::of::<u8>();
+
+                for targets in [[id, second_id], [second_id, id]] {
+                    let mut registry = NamedComponentDefinitionMap::default();
+                    let results = targets
+                        .into_iter()
+                        .map(|target| {
+                            registry
+                                .try_register_component(
+                                    target,
+                                    "",
+                                    &ComponentMetadata {
+                                        names: [format!("{target:?}")].into_iter().collect(),
+                                        ..definition.clone()
+                                    },
+                                    false,
+                                )
+                                .unwrap();
+                            registry.try_register_alias(
+                                alias_id,
+                                target,
+                                "",
+                                "",
+                                &ComponentAliasMetadata {
+                                    is_primary: true,
+                                    scope: None,
+                                    cast,
+                                },
+                            )
+                        })
+                        .collect::<Vec<_>>();
+
+                    assert!(results[0].is_ok());
+                    assert!(matches!(
+                        results[1],
+                        Err(ComponentDefinitionRegistryError::DuplicatePrimaryComponent { .. })
+                    ));
+                    assert_eq!(
+                        registry
+                            .components_by_type(alias_id)
+                            .iter()
+                            .filter(|definition| definition.is_primary)
+                            .count(),
+                        1
+                    );
+                }
             }
 
             #[test]
@@ -1047,11 +3000,24 @@ mod registry {
 
 #[doc(hidden)]
 pub mod internal {
+    use crate::component::{Component, ComponentDowncast, ComponentLifecycle, DisposableComponent};
     use crate::component_registry::conditional::ComponentCondition;
-    use crate::component_registry::{ComponentAliasMetadata, ComponentMetadata};
+    use crate::component_registry::{
+        ComponentAliasMetadata, ComponentDependency, ComponentMetadata, Constructor, LateInjection,
+        PostConstruct, PreDestroy,
+    };
+    #[cfg(feature = "async")]
+    use crate::future::{BoxFuture, FutureExt};
+    use crate::instance_provider::{
+        store_cast_result, ComponentInstanceAnyPtr, ComponentInstanceProvider,
+        ComponentInstanceProviderError, ComponentInstancePtr, ErrorPtr,
+    };
+    use crate::scope::SINGLETON;
+    #[cfg(not(feature = "explicit-registration"))]
     use inventory::collect;
+    #[cfg(not(feature = "explicit-registration"))]
     pub use inventory::submit;
-    use std::any::TypeId;
+    use std::any::{type_name, Any, TypeId};
 
     #[derive(Clone)]
     pub struct TypedComponentDefinition {
@@ -1059,9 +3025,32 @@ pub mod internal {
         pub target_name: &'static str,
         pub condition: Option<ComponentCondition>,
         pub priority: i8,
+        pub is_override: bool,
+        pub qualifier: Option<String>,
+        pub default_name: Option<DefaultComponentName>,
         pub metadata: ComponentMetadata,
     }
 
+    /// Auto-generated component name, which is adjusted at registration according to the
+    /// [ComponentNamingStrategy](crate::component::ComponentNamingStrategy) of the registry.
+    #[derive(Clone, Copy, Debug)]
+    pub struct DefaultComponentName {
+        pub name: &'static str,
+        pub module_path: &'static str,
+    }
+
+    /// Registration information of a component type. Allows registering generic components for
+    /// concrete types.
+    pub trait ComponentRegistration {
+        fn definition() -> TypedComponentDefinition;
+    }
+
+    /// Registration information of components produced by factory methods in a
+    /// `#[component_factory]` impl block.
+    pub trait ComponentFactoryRegistration {
+        fn definitions() -> Vec<TypedComponentDefinition>;
+    }
+
     pub struct ComponentDefinitionRegisterer {
         pub register: fn() -> TypedComponentDefinition,
     }
@@ -1081,8 +3070,252 @@ pub mod internal {
         pub register: fn() -> ComponentAliasDefinition,
     }
 
+    /// Registration information of a component alias for the `T` trait object.
+    pub trait ComponentAliasRegistration<T: ?Sized> {
+        fn alias_definition() -> ComponentAliasDefinition;
+    }
+
+    #[cfg(not(feature = "explicit-registration"))]
     collect!(ComponentDefinitionRegisterer);
+    #[cfg(not(feature = "explicit-registration"))]
     collect!(ComponentAliasRegisterer);
+
+    /// Bounds required for component instances to be stored as [ComponentInstanceAnyPtr].
+    #[cfg(feature = "threadsafe")]
+    pub trait ComponentInstanceBounds: Send + Sync {}
+
+    #[cfg(feature = "threadsafe")]
+    impl<T: Send + Sync> ComponentInstanceBounds for T {}
+
+    /// Bounds required for component instances to be stored as [ComponentInstanceAnyPtr].
+    #[cfg(not(feature = "threadsafe"))]
+    pub trait ComponentInstanceBounds {}
+
+    #[cfg(not(feature = "threadsafe"))]
+    impl<T> ComponentInstanceBounds for T {}
+
+    #[cfg(not(feature = "async"))]
+    pub fn construct_component<C: Component + ComponentInstanceBounds>(
+        instance_provider: &mut dyn ComponentInstanceProvider,
+    ) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
+        C::create(instance_provider)
+            .map(|p| ComponentInstancePtr::new(p) as ComponentInstanceAnyPtr)
+    }
+
+    #[cfg(feature = "async")]
+    pub fn construct_component<C: Component + ComponentInstanceBounds>(
+        instance_provider: &mut (dyn ComponentInstanceProvider + Sync + Send),
+    ) -> BoxFuture<'_, Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError>> {
+        async move {
+            C::create(instance_provider)
+                .await
+                .map(|p| ComponentInstancePtr::new(p) as ComponentInstanceAnyPtr)
+        }
+        .boxed()
+    }
+
+    pub fn cast_component<C: Component, T: ComponentDowncast<C> + ?Sized>(
+        instance: ComponentInstanceAnyPtr,
+        target: &mut dyn Any,
+    ) -> Result<(), ComponentInstanceAnyPtr> {
+        T::downcast(instance).map(|p| store_cast_result(p, target))
+    }
+
+    #[cfg(not(feature = "async"))]
+    pub fn post_construct_component<C: ComponentLifecycle + ComponentInstanceBounds + 'static>(
+        instance: ComponentInstanceAnyPtr,
+    ) -> Result<(), ErrorPtr> {
+        match instance.downcast::<C>() {
+            Ok(component) => component.post_construct(),
+            Err(_) => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub fn post_construct_component<C: ComponentLifecycle + ComponentInstanceBounds + 'static>(
+        instance: ComponentInstanceAnyPtr,
+    ) -> BoxFuture<'static, Result<(), ErrorPtr>> {
+        async move {
+            match instance.downcast::<C>() {
+                Ok(component) => component.post_construct().await,
+                Err(_) => Ok(()),
+            }
+        }
+        .boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    pub fn pre_destroy_component<C: DisposableComponent + ComponentInstanceBounds + 'static>(
+        instance: ComponentInstanceAnyPtr,
+    ) -> Result<(), ErrorPtr> {
+        match instance.downcast::<C>() {
+            Ok(component) => component.destroy(),
+            Err(_) => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub fn pre_destroy_component<C: DisposableComponent + ComponentInstanceBounds + 'static>(
+        instance: ComponentInstanceAnyPtr,
+    ) -> BoxFuture<'static, Result<(), ErrorPtr>> {
+        async move {
+            match instance.downcast::<C>() {
+                Ok(component) => component.destroy().await,
+                Err(_) => Ok(()),
+            }
+        }
+        .boxed()
+    }
+
+    impl TypedComponentDefinition {
+        pub fn new<T: ?Sized + 'static>(metadata: ComponentMetadata) -> Self {
+            Self {
+                target: TypeId::of::<T>(),
+                target_name: type_name::<T>(),
+                condition: None,
+                priority: 0,
+                is_override: false,
+                qualifier: None,
+                default_name: None,
+                metadata,
+            }
+        }
+
+        pub fn with_condition(mut self, condition: ComponentCondition) -> Self {
+            self.condition = Some(condition);
+            self
+        }
+
+        pub fn with_priority(mut self, priority: i8) -> Self {
+            self.priority = priority;
+            self
+        }
+
+        pub fn with_override(mut self, is_override: bool) -> Self {
+            self.is_override = is_override;
+            self
+        }
+
+        pub fn with_qualifier(mut self, qualifier: &str) -> Self {
+            self.qualifier = Some(qualifier.to_string());
+            self
+        }
+
+        /// Marks given name as the auto-generated one, so it's adjusted according to the naming
+        /// strategy of the registry.
+        pub fn with_default_name(mut self, name: &'static str, module_path: &'static str) -> Self {
+            self.default_name = Some(DefaultComponentName { name, module_path });
+            self
+        }
+
+        pub fn with_order(mut self, order: i32) -> Self {
+            self.metadata = self.metadata.with_order(order);
+            self
+        }
+
+        pub fn with_late_injection(mut self, late_injection: LateInjection) -> Self {
+            self.metadata = self.metadata.with_late_injection(late_injection);
+            self
+        }
+
+        pub fn with_post_construct(mut self, post_construct: PostConstruct) -> Self {
+            self.metadata = self.metadata.with_post_construct(post_construct);
+            self
+        }
+
+        pub fn with_pre_destroy(mut self, pre_destroy: PreDestroy) -> Self {
+            self.metadata = self.metadata.with_pre_destroy(pre_destroy);
+            self
+        }
+
+        pub fn with_dependencies(
+            mut self,
+            dependencies: impl IntoIterator<Item = ComponentDependency>,
+        ) -> Self {
+            self.metadata = self.metadata.with_dependencies(dependencies);
+            self
+        }
+    }
+
+    /// Creates a definition of a derived component, which can be adjusted further with the
+    /// [TypedComponentDefinition] builder methods.
+    pub fn component_definition<C: Component + ComponentInstanceBounds>(
+        names: impl IntoIterator<Item = impl Into<String>>,
+        scope: &str,
+    ) -> TypedComponentDefinition {
+        TypedComponentDefinition::new::<C>(ComponentMetadata::for_component::<C>(names, scope))
+    }
+
+    #[cfg(not(feature = "async"))]
+    pub fn construct_default_component<C: Default + ComponentInstanceBounds + 'static>(
+        _instance_provider: &mut dyn ComponentInstanceProvider,
+    ) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
+        Ok(ComponentInstancePtr::new(C::default()) as ComponentInstanceAnyPtr)
+    }
+
+    #[cfg(feature = "async")]
+    pub fn construct_default_component<C: Default + ComponentInstanceBounds + 'static>(
+        _instance_provider: &mut (dyn ComponentInstanceProvider + Sync + Send),
+    ) -> BoxFuture<'_, Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError>> {
+        async { Ok(ComponentInstancePtr::new(C::default()) as ComponentInstanceAnyPtr) }.boxed()
+    }
+
+    /// Metadata of a default-valued singleton, registered on demand when no other definition
+    /// exists.
+    pub fn default_component_metadata<C: Default + ComponentInstanceBounds + 'static>(
+    ) -> ComponentMetadata {
+        ComponentMetadata::new(
+            [type_name::<C>()],
+            SINGLETON,
+            construct_default_component::<C>,
+            cast_factory_product::<C>,
+        )
+    }
+
+    pub fn cast_factory_product<T: ComponentInstanceBounds + 'static>(
+        instance: ComponentInstanceAnyPtr,
+        target: &mut dyn Any,
+    ) -> Result<(), ComponentInstanceAnyPtr> {
+        instance
+            .downcast::<T>()
+            .map(|p| store_cast_result(p, target))
+    }
+
+    /// Creates a definition of a component produced by a factory method, which can be adjusted
+    /// further with the [TypedComponentDefinition] builder methods.
+    pub fn factory_component_definition<T: ComponentInstanceBounds + 'static>(
+        names: impl IntoIterator<Item = impl Into<String>>,
+        scope: &str,
+        constructor: Constructor,
+    ) -> TypedComponentDefinition {
+        TypedComponentDefinition::new::<T>(ComponentMetadata::new(
+            names,
+            scope,
+            constructor,
+            cast_factory_product::<T>,
+        ))
+    }
+
+    pub fn alias_definition<C: Component, T: ComponentDowncast<C> + ?Sized>(
+        is_primary: bool,
+        scope: Option<&str>,
+        condition: Option<ComponentCondition>,
+        priority: i8,
+    ) -> ComponentAliasDefinition {
+        ComponentAliasDefinition {
+            alias_type: TypeId::of::<T>(),
+            target_type: TypeId::of::<C>(),
+            alias_name: type_name::<T>(),
+            target_name: type_name::<C>(),
+            condition,
+            priority,
+            metadata: ComponentAliasMetadata {
+                is_primary,
+                scope: scope.map(|scope| scope.to_string()),
+                cast: cast_component::<C, T>,
+            },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1096,12 +3329,12 @@ mod tests {
         };
         use crate::component_registry::ComponentDefinitionRegistryError;
         use crate::component_registry::{
-            ComponentDefinition, ComponentDefinitionRegistry, ComponentMetadata,
-            StaticComponentDefinitionRegistry, TypedComponentDefinitionRegistry,
+            ComponentAliasMetadata, ComponentDefinition, ComponentDefinitionRegistry,
+            ComponentMetadata, StaticComponentDefinitionRegistry, TypedComponentDefinitionRegistry,
         };
         use crate::instance_provider::{
-            ComponentInstanceAnyPtr, ComponentInstanceProvider, ComponentInstanceProviderError,
-            ComponentInstancePtr,
+            store_cast_result, ComponentInstanceAnyPtr, ComponentInstanceProvider,
+            ComponentInstanceProviderError, ComponentInstancePtr,
         };
         use std::any::{type_name, Any, TypeId};
 
@@ -1137,8 +3370,9 @@ mod tests {
 
         fn test_cast(
             instance: ComponentInstanceAnyPtr,
-        ) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr> {
-            TestComponent::downcast(instance).map(|p| Box::new(p) as Box<dyn Any>)
+            target: &mut dyn Any,
+        ) -> Result<(), ComponentInstanceAnyPtr> {
+            TestComponent::downcast(instance).map(|p| store_cast_result(p, target))
         }
 
         #[test]
@@ -1150,8 +3384,13 @@ mod tests {
                 .register_component_typed::<TestComponent>(&ComponentMetadata {
                     names: ["a".to_string()].into_iter().collect(),
                     scope: "".to_string(),
+                    order: 0,
                     constructor: test_constructor,
+                    late_injection: None,
+                    post_construct: None,
+                    pre_destroy: None,
                     cast: test_cast,
+                    dependencies: vec![],
                 })
                 .unwrap();
 
@@ -1176,10 +3415,16 @@ mod tests {
                 names: ["name".to_string()].into_iter().collect(),
                 is_primary: false,
                 scope: "".to_string(),
+                order: 0,
                 resolved_type_id: TypeId::of::<TestComponent>(),
+                qualifier: None,
                 resolved_type_name: type_name::<TestComponent>().to_string(),
                 constructor: test_constructor,
+                late_injection: None,
+                post_construct: None,
+                pre_destroy: None,
                 cast: test_cast,
+                dependencies: vec![],
             };
 
             let mut registry =
@@ -1189,8 +3434,13 @@ mod tests {
                 .register_component_typed::<TestComponent>(&ComponentMetadata {
                     names: definition.names.clone(),
                     scope: "".to_string(),
+                    order: 0,
                     constructor: test_constructor,
+                    late_injection: None,
+                    post_construct: None,
+                    pre_destroy: None,
                     cast: test_cast,
+                    dependencies: vec![],
                 })
                 .unwrap();
 
@@ -1199,8 +3449,13 @@ mod tests {
                     .register_component_typed::<TestComponent>(&ComponentMetadata {
                         names: definition.names,
                         scope: "".to_string(),
+                        order: 0,
                         constructor: test_constructor,
+                        late_injection: None,
+                        post_construct: None,
+                        pre_destroy: None,
                         cast: test_cast,
+                        dependencies: vec![],
                     })
                     .unwrap_err(),
                 ComponentDefinitionRegistryError::DuplicateComponentName("name".to_string())
@@ -1216,16 +3471,26 @@ mod tests {
                 .register_component_typed::<TestComponent>(&ComponentMetadata {
                     names: ["name".to_string()].into_iter().collect(),
                     scope: "".to_string(),
+                    order: 0,
                     constructor: test_constructor,
+                    late_injection: None,
+                    post_construct: None,
+                    pre_destroy: None,
                     cast: test_cast,
+                    dependencies: vec![],
                 })
                 .unwrap();
             registry
                 .register_component_typed::<TestComponent>(&ComponentMetadata {
                     names: ["name2".to_string()].into_iter().collect(),
                     scope: "".to_string(),
+                    order: 0,
                     constructor: test_constructor,
+                    late_injection: None,
+                    post_construct: None,
+                    pre_destroy: None,
                     cast: test_cast,
+                    dependencies: vec![],
                 })
                 .unwrap();
 
@@ -1233,5 +3498,105 @@ mod tests {
                 .component_by_name("name2", TypeId::of::<TestComponent>())
                 .unwrap();
         }
+
+        #[test]
+        fn should_sort_alias_definitions() {
+            let mut registry =
+                StaticComponentDefinitionRegistry::new(false, &SimpleContextFactory::default())
+                    .unwrap();
+
+            for (type_id, type_name, order) in [
+                (TypeId::of::<u8>(), "b", 0),
+                (TypeId::of::<u16>(), "a", 0),
+                (TypeId::of::<u32>(), "c", -1),
+            ] {
+                registry
+                    .register_component(
+                        type_id,
+                        type_name,
+                        &ComponentMetadata {
+                            names: [type_name.to_string()].into_iter().collect(),
+                            scope: "".to_string(),
+                            order,
+                            constructor: test_constructor,
+                            late_injection: None,
+                            post_construct: None,
+                            pre_destroy: None,
+                            cast: test_cast,
+                            dependencies: vec![],
+                        },
+                    )
+                    .unwrap();
+                registry
+                    .register_alias(
+                        TypeId::of::<TestComponent>(),
+                        type_id,
+                        "alias",
+                        type_name,
+                        &ComponentAliasMetadata {
+                            is_primary: false,
+                            scope: None,
+                            cast: test_cast,
+                        },
+                    )
+                    .unwrap();
+            }
+
+            let names = registry
+                .components_by_type(TypeId::of::<TestComponent>())
+                .into_iter()
+                .map(|definition| definition.resolved_type_name.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["c", "a", "b"]);
+        }
+
+        #[test]
+        fn should_keep_alias_definitions_sorted_after_replacement() {
+            let mut registry =
+                StaticComponentDefinitionRegistry::new(false, &SimpleContextFactory::default())
+                    .unwrap();
+
+            let create_metadata = |name: &str, order| ComponentMetadata {
+                names: [name.to_string()].into_iter().collect(),
+                scope: "".to_string(),
+                order,
+                constructor: test_constructor,
+                late_injection: None,
+                post_construct: None,
+                pre_destroy: None,
+                cast: test_cast,
+                dependencies: vec![],
+            };
+
+            for (type_id, type_name) in [(TypeId::of::<u8>(), "a"), (TypeId::of::<u16>(), "b")] {
+                registry
+                    .register_component(type_id, type_name, &create_metadata(type_name, 0))
+                    .unwrap();
+                registry
+                    .register_alias(
+                        TypeId::of::<TestComponent>(),
+                        type_id,
+                        "alias",
+                        type_name,
+                        &ComponentAliasMetadata {
+                            is_primary: false,
+                            scope: None,
+                            cast: test_cast,
+                        },
+                    )
+                    .unwrap();
+            }
+
+            registry
+                .replace_component(TypeId::of::<u16>(), "b", &create_metadata("b", -1))
+                .unwrap();
+
+            let names = registry
+                .components_by_type(TypeId::of::<TestComponent>())
+                .into_iter()
+                .map(|definition| definition.resolved_type_name.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["b", "a"]);
+        }
     }
 }
@@ -1,10 +1,54 @@
 //! Conditional component definition registration support.
+//!
+//! ## Conditions
+//!
+//! Any function accepting a [Context] and [ConditionMetadata] and returning a `bool` is a
+//! [Condition]. Conditions which need parameters, e.g. a variable name, can be objects implementing
+//! the trait, like the built-in [EnvVarSet] or [ActiveProfile], rather than dedicated functions.
+//!
+//! ## Profiles
+//!
+//! Profiles allow enabling groups of components at once, e.g. only in the development environment.
+//! Components can be registered only for a given profile with `#[component(profile = "name")]`,
+//! or when given profile is not active with `#[component(profile = "!name")]`. Active profiles
+//! come from the [Context] - [SimpleContextFactory] reads them from the [ACTIVE_PROFILES_ENV]
+//! environment variable, while [ProfileContextFactory] uses explicitly given ones, e.g. read from
+//! configuration. Profiles can also be checked explicitly in conditions with [profile_active] and
+//! [profile_not_active], e.g. `condition = "profile_active(\"dev\")"`.
+//!
+//! ## Combining conditions
+//!
+//! Conditions can be combined with [all_of](crate::all_of), [any_of](crate::any_of) and
+//! [not](crate::not) macros, which produce new conditions, also usable in attributes:
+//!
+//! ```
+//! use springtime_di::component_registry::conditional::{registered_component, unregistered_name};
+//! use springtime_di::{all_of, not, Component};
+//!
+//! #[derive(Component)]
+//! struct Dependency;
+//!
+//! #[derive(Component)]
+//! #[component(
+//!     condition = "all_of!(registered_component::<Dependency>, not!(unregistered_name))"
+//! )]
+//! struct ConditionalComponent;
+//! ```
 
-use crate::component::Injectable;
 use crate::component_registry::{ComponentAliasMetadata, ComponentMetadata};
+use fxhash::{FxHashMap, FxHashSet};
 #[cfg(test)]
 use mockall::automock;
 use std::any::TypeId;
+use std::cell::RefCell;
+use std::env;
+#[cfg(not(feature = "threadsafe"))]
+use std::rc::Rc;
+#[cfg(feature = "threadsafe")]
+use std::sync::Arc;
+
+/// Name of the environment variable containing comma-separated active profiles.
+pub const ACTIVE_PROFILES_ENV: &str = "SPRINGTIME_PROFILES";
 
 /// A read-only facade of a [ComponentDefinitionRegistry](super::ComponentDefinitionRegistry) safe
 /// to use in registration conditions.
@@ -21,11 +65,47 @@ pub trait ComponentDefinitionRegistryFacade {
 pub trait Context {
     /// Returns the registry for which the conditional evaluation is taking place.
     fn registry(&self) -> &dyn ComponentDefinitionRegistryFacade;
+
+    /// Returns the result of an evaluation stored under given key, or performs the evaluation and
+    /// stores its result. Since a single context is shared by all conditions evaluated in a
+    /// registration pass, this allows expensive checks (e.g. reading configuration files or the
+    /// environment) to be performed once per pass. The default implementation doesn't memoize
+    /// anything.
+    ///
+    /// *Note:* only results which don't depend on the registry state or [ConditionMetadata] should
+    /// be memoized, since both can change during a pass.
+    fn memoize(&self, _key: &str, evaluate: &dyn Fn() -> bool) -> bool {
+        evaluate()
+    }
+
+    /// Checks if given profile is active. The default implementation reports no active profiles.
+    fn is_profile_active(&self, _profile: &str) -> bool {
+        false
+    }
+
+    /// Returns all active profiles, in unspecified order. The default implementation reports no
+    /// active profiles.
+    fn active_profiles(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Returns the value of given environment variable, if set and valid unicode. The default
+    /// implementation reads the process environment.
+    fn env_var(&self, name: &str) -> Option<String> {
+        env::var(name).ok()
+    }
+
+    /// Returns a configuration property with given key, if the context has access to application
+    /// configuration. The default implementation has no properties.
+    fn property(&self, _key: &str) -> Option<String> {
+        None
+    }
 }
 
 /// Factory for contexts for conditional component registration.
 pub trait ContextFactory {
-    /// Creates a new context when starting evaluation.
+    /// Creates a new context when starting a registration pass. The context is then shared by all
+    /// conditions evaluated in the pass.
     fn create_context<'a>(
         &self,
         registry: &'a dyn ComponentDefinitionRegistryFacade,
@@ -46,20 +126,221 @@ pub enum ConditionMetadata<'a> {
     },
 }
 
-/// Registration condition which should pass to let given [ConditionMetadata] be registered.
-pub type ComponentCondition = fn(context: &dyn Context, metadata: ConditionMetadata) -> bool;
+/// Registration condition which should pass to let given [ConditionMetadata] be registered. Any
+/// function with a matching signature is a condition, while custom types can implement this trait
+/// to carry parameters, e.g. a property key, instead of requiring a dedicated function per check.
+pub trait Condition {
+    /// Evaluates if given entity should be registered.
+    fn evaluate(&self, context: &dyn Context, metadata: ConditionMetadata) -> bool;
+}
+
+impl<F: Fn(&dyn Context, ConditionMetadata) -> bool> Condition for F {
+    fn evaluate(&self, context: &dyn Context, metadata: ConditionMetadata) -> bool {
+        (self)(context, metadata)
+    }
+}
+
+/// Shared pointer to a [Condition] stored in component definitions.
+#[cfg(not(feature = "threadsafe"))]
+pub type ComponentCondition = Rc<dyn Condition>;
+/// Shared pointer to a [Condition] stored in component definitions.
+#[cfg(feature = "threadsafe")]
+pub type ComponentCondition = Arc<dyn Condition + Send + Sync>;
+
+#[cfg(not(feature = "threadsafe"))]
+/// Wraps given [Condition] in a [ComponentCondition].
+pub fn component_condition<C: Condition + 'static>(condition: C) -> ComponentCondition {
+    Rc::new(condition)
+}
+
+#[cfg(feature = "threadsafe")]
+/// Wraps given [Condition] in a [ComponentCondition].
+pub fn component_condition<C: Condition + Send + Sync + 'static>(
+    condition: C,
+) -> ComponentCondition {
+    Arc::new(condition)
+}
+
+/// Condition which passes if all inner conditions pass. Conditions are evaluated in order and the
+/// evaluation stops at the first failing one. Usually created by [all_of](crate::all_of).
+#[derive(Clone)]
+pub struct AllOf {
+    conditions: Vec<ComponentCondition>,
+}
+
+impl AllOf {
+    /// Creates a condition passing if all given conditions pass.
+    pub fn new(conditions: Vec<ComponentCondition>) -> Self {
+        Self { conditions }
+    }
+}
+
+impl Condition for AllOf {
+    fn evaluate(&self, context: &dyn Context, metadata: ConditionMetadata) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.evaluate(context, metadata))
+    }
+}
+
+/// Condition which passes if any inner condition passes. Conditions are evaluated in order and the
+/// evaluation stops at the first passing one. Usually created by [any_of](crate::any_of).
+#[derive(Clone)]
+pub struct AnyOf {
+    conditions: Vec<ComponentCondition>,
+}
+
+impl AnyOf {
+    /// Creates a condition passing if any of given conditions passes.
+    pub fn new(conditions: Vec<ComponentCondition>) -> Self {
+        Self { conditions }
+    }
+}
+
+impl Condition for AnyOf {
+    fn evaluate(&self, context: &dyn Context, metadata: ConditionMetadata) -> bool {
+        self.conditions
+            .iter()
+            .any(|condition| condition.evaluate(context, metadata))
+    }
+}
+
+/// Condition which passes if the inner condition fails. Usually created by [not](crate::not).
+#[derive(Clone)]
+pub struct Not {
+    condition: ComponentCondition,
+}
+
+impl Not {
+    /// Creates a condition negating given one.
+    pub fn new(condition: ComponentCondition) -> Self {
+        Self { condition }
+    }
+}
+
+impl Condition for Not {
+    fn evaluate(&self, context: &dyn Context, metadata: ConditionMetadata) -> bool {
+        !self.condition.evaluate(context, metadata)
+    }
+}
+
+/// Condition which passes if given profile is active, or if it's not active when prefixed with
+/// `!`. Used by `#[component(profile = "name")]`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActiveProfile {
+    profile: String,
+}
+
+impl ActiveProfile {
+    /// Creates a condition for given profile, which can be prefixed with `!` for negation.
+    pub fn new(profile: impl Into<String>) -> Self {
+        Self {
+            profile: profile.into(),
+        }
+    }
+}
+
+impl Condition for ActiveProfile {
+    fn evaluate(&self, context: &dyn Context, _metadata: ConditionMetadata) -> bool {
+        active_profile(context, &self.profile)
+    }
+}
+
+/// Condition which passes if given environment variable is set, e.g.
+/// `condition = "EnvVarSet::new(\"FEATURE_X\")"`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EnvVarSet {
+    name: String,
+}
+
+impl EnvVarSet {
+    /// Creates a condition checking given environment variable.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+impl Condition for EnvVarSet {
+    fn evaluate(&self, context: &dyn Context, _metadata: ConditionMetadata) -> bool {
+        env_var_set(context, &self.name)
+    }
+}
+
+/// Creates an [AllOf] condition which passes if all given conditions pass.
+#[macro_export]
+macro_rules! all_of {
+    ($($condition:expr),+ $(,)?) => {
+        $crate::component_registry::conditional::AllOf::new(vec![
+            $($crate::component_registry::conditional::component_condition($condition)),+
+        ])
+    };
+}
+
+/// Creates an [AnyOf] condition which passes if any given condition passes.
+#[macro_export]
+macro_rules! any_of {
+    ($($condition:expr),+ $(,)?) => {
+        $crate::component_registry::conditional::AnyOf::new(vec![
+            $($crate::component_registry::conditional::component_condition($condition)),+
+        ])
+    };
+}
+
+/// Creates a [Not] condition which passes if given condition fails.
+#[macro_export]
+macro_rules! not {
+    ($condition:expr $(,)?) => {
+        $crate::component_registry::conditional::Not::new(
+            $crate::component_registry::conditional::component_condition($condition),
+        )
+    };
+}
 
 struct SimpleContext<'a> {
     registry: &'a dyn ComponentDefinitionRegistryFacade,
+    results: RefCell<FxHashMap<String, bool>>,
+    active_profiles: FxHashSet<String>,
+}
+
+impl<'a> SimpleContext<'a> {
+    fn new(
+        registry: &'a dyn ComponentDefinitionRegistryFacade,
+        active_profiles: FxHashSet<String>,
+    ) -> Self {
+        Self {
+            registry,
+            results: Default::default(),
+            active_profiles,
+        }
+    }
 }
 
 impl Context for SimpleContext<'_> {
     fn registry(&self) -> &dyn ComponentDefinitionRegistryFacade {
         self.registry
     }
+
+    fn memoize(&self, key: &str, evaluate: &dyn Fn() -> bool) -> bool {
+        if let Some(result) = self.results.borrow().get(key) {
+            return *result;
+        }
+
+        let result = evaluate();
+        self.results.borrow_mut().insert(key.to_string(), result);
+        result
+    }
+
+    fn is_profile_active(&self, profile: &str) -> bool {
+        self.active_profiles.contains(profile)
+    }
+
+    fn active_profiles(&self) -> Vec<String> {
+        self.active_profiles.iter().cloned().collect()
+    }
 }
 
-/// Factory producing contexts containing only the necessary data and noting more.
+/// Factory producing contexts containing only the necessary data and memoized evaluation results.
+/// Active profiles are read from the [ACTIVE_PROFILES_ENV] environment variable.
 #[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
 pub struct SimpleContextFactory;
 
@@ -68,12 +349,77 @@ impl ContextFactory for SimpleContextFactory {
         &self,
         registry: &'a dyn ComponentDefinitionRegistryFacade,
     ) -> Box<dyn Context + 'a> {
-        Box::new(SimpleContext { registry })
+        let active_profiles = env::var(ACTIVE_PROFILES_ENV)
+            .map(|profiles| parse_profiles(&profiles))
+            .unwrap_or_default();
+
+        Box::new(SimpleContext::new(registry, active_profiles))
+    }
+}
+
+/// Factory producing the same contexts as [SimpleContextFactory], but with explicitly given active
+/// profiles.
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub struct ProfileContextFactory {
+    active_profiles: FxHashSet<String>,
+}
+
+impl ProfileContextFactory {
+    pub fn new(active_profiles: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            active_profiles: active_profiles.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl ContextFactory for ProfileContextFactory {
+    fn create_context<'a>(
+        &self,
+        registry: &'a dyn ComponentDefinitionRegistryFacade,
+    ) -> Box<dyn Context + 'a> {
+        Box::new(SimpleContext::new(registry, self.active_profiles.clone()))
+    }
+}
+
+/// Parses a comma-separated list of profiles, e.g. "dev, local".
+pub fn parse_profiles(profiles: &str) -> FxHashSet<String> {
+    profiles
+        .split(',')
+        .map(str::trim)
+        .filter(|profile| !profile.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Returns true if given environment variable is set, e.g. `env_var_set(context, "FEATURE_X")`.
+pub fn env_var_set(context: &dyn Context, name: &str) -> bool {
+    context.env_var(name).is_some()
+}
+
+/// Returns true if given profile is active, or if it's not active when prefixed with `!`, e.g.
+/// `!prod`. Used by `#[component(profile = "name")]`.
+pub fn active_profile(context: &dyn Context, profile: &str) -> bool {
+    if let Some(profile) = profile.strip_prefix('!') {
+        !context.is_profile_active(profile)
+    } else {
+        context.is_profile_active(profile)
     }
 }
 
+/// Creates a condition which passes if given profile is active, e.g.
+/// `condition = "profile_active(\"dev\")"`.
+pub fn profile_active(profile: impl Into<String>) -> ActiveProfile {
+    ActiveProfile::new(profile)
+}
+
+/// Creates a condition which passes if given profile is not active, e.g.
+/// `condition = "profile_not_active(\"prod\")"`.
+pub fn profile_not_active(profile: impl Into<String>) -> ActiveProfile {
+    ActiveProfile::new(format!("!{}", profile.into()))
+}
+
 /// Simple condition returning true if the given type is already registered.
-pub fn registered_component<T: Injectable + ?Sized>(
+pub fn registered_component<T: ?Sized + 'static>(
     context: &dyn Context,
     _metadata: ConditionMetadata,
 ) -> bool {
@@ -81,7 +427,7 @@ pub fn registered_component<T: Injectable + ?Sized>(
 }
 
 /// Simple condition returning true if the given type is not registered yet.
-pub fn unregistered_component<T: Injectable + ?Sized>(
+pub fn unregistered_component<T: ?Sized + 'static>(
     context: &dyn Context,
     metadata: ConditionMetadata,
 ) -> bool {
@@ -106,7 +452,9 @@ mod tests {
     mod sync {
         use crate::component::Injectable;
         use crate::component_registry::conditional::{
-            registered_component, unregistered_component, unregistered_name, ConditionMetadata,
+            active_profile, env_var_set, parse_profiles, profile_active, profile_not_active,
+            registered_component, unregistered_component, unregistered_name,
+            ComponentDefinitionRegistryFacade, Condition, ConditionMetadata, Context,
             MockComponentDefinitionRegistryFacade, SimpleContext,
         };
         use crate::component_registry::{ComponentAliasMetadata, ComponentMetadata};
@@ -115,6 +463,7 @@ mod tests {
         use mockall::predicate::*;
         use mockall::Sequence;
         use std::any::{Any, TypeId};
+        use std::cell::Cell;
 
         struct TestComponent;
 
@@ -130,7 +479,8 @@ mod tests {
 
         fn test_cast(
             instance: ComponentInstanceAnyPtr,
-        ) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr> {
+            _target: &mut dyn Any,
+        ) -> Result<(), ComponentInstanceAnyPtr> {
             Err(instance)
         }
 
@@ -152,9 +502,7 @@ mod tests {
                 .in_sequence(&mut seq)
                 .return_const(false);
 
-            let context = SimpleContext {
-                registry: &registry,
-            };
+            let context = SimpleContext::new(&registry, Default::default());
             let metadata = ComponentAliasMetadata {
                 is_primary: false,
                 scope: None,
@@ -186,15 +534,18 @@ mod tests {
                 .times(1)
                 .return_const(false);
 
-            let context = SimpleContext {
-                registry: &registry,
-            };
+            let context = SimpleContext::new(&registry, Default::default());
 
             let metadata = ComponentMetadata {
                 names: ["n2".to_string(), "n1".to_string()].into_iter().collect(),
                 scope: "".to_string(),
+                order: 0,
                 constructor: test_constructor,
+                late_injection: None,
+                post_construct: None,
+                pre_destroy: None,
                 cast: test_cast,
+                dependencies: vec![],
             };
             let metadata = ConditionMetadata::Component {
                 type_id: TypeId::of::<TestComponent>(),
@@ -216,5 +567,84 @@ mod tests {
 
             assert!(unregistered_name(&context, metadata));
         }
+
+        #[test]
+        fn should_memoize_results() {
+            let registry = MockComponentDefinitionRegistryFacade::new();
+            let context = SimpleContext::new(&registry, Default::default());
+            let evaluations = Cell::new(0);
+
+            let evaluate = || {
+                evaluations.set(evaluations.get() + 1);
+                true
+            };
+
+            assert!(context.memoize("key", &evaluate));
+            assert!(context.memoize("key", &evaluate));
+            assert!(!context.memoize("other", &|| false));
+            assert_eq!(evaluations.get(), 1);
+        }
+
+        #[test]
+        fn should_check_active_profiles() {
+            let registry = MockComponentDefinitionRegistryFacade::new();
+            let context = SimpleContext::new(&registry, parse_profiles("dev, local,"));
+
+            assert!(active_profile(&context, "dev"));
+            assert!(active_profile(&context, "local"));
+            assert!(!active_profile(&context, "prod"));
+            assert!(active_profile(&context, "!prod"));
+            assert!(!active_profile(&context, "!dev"));
+
+            let mut profiles = context.active_profiles();
+            profiles.sort();
+            assert_eq!(profiles, ["dev", "local"]);
+        }
+
+        #[test]
+        fn should_evaluate_profile_conditions() {
+            let registry = MockComponentDefinitionRegistryFacade::new();
+            let context = SimpleContext::new(&registry, parse_profiles("dev"));
+            let metadata = ComponentAliasMetadata {
+                is_primary: false,
+                scope: None,
+                cast: test_cast,
+            };
+            let metadata = ConditionMetadata::Alias {
+                alias_type: TypeId::of::<i8>(),
+                target_type: TypeId::of::<TestComponent>(),
+                metadata: &metadata,
+            };
+
+            assert!(profile_active("dev").evaluate(&context, metadata));
+            assert!(!profile_active("prod").evaluate(&context, metadata));
+            assert!(profile_not_active("prod").evaluate(&context, metadata));
+            assert!(!profile_not_active("dev").evaluate(&context, metadata));
+        }
+
+        struct TestEnvContext<'a> {
+            registry: &'a dyn ComponentDefinitionRegistryFacade,
+        }
+
+        impl Context for TestEnvContext<'_> {
+            fn registry(&self) -> &dyn ComponentDefinitionRegistryFacade {
+                self.registry
+            }
+
+            fn env_var(&self, name: &str) -> Option<String> {
+                (name == "SPRINGTIME_TEST_VARIABLE").then(|| "set".to_string())
+            }
+        }
+
+        #[test]
+        fn should_read_env_vars() {
+            let registry = MockComponentDefinitionRegistryFacade::new();
+            let context = TestEnvContext {
+                registry: &registry,
+            };
+
+            assert!(env_var_set(&context, "SPRINGTIME_TEST_VARIABLE"));
+            assert!(!env_var_set(&context, "SPRINGTIME_UNSET_TEST_VARIABLE"));
+        }
     }
 }
@@ -0,0 +1,458 @@
+//! Export of the component registry contents, for consumption by external tooling, e.g.
+//! documentation generators or architecture linters. The export is a read-only snapshot of the
+//! wiring model: registered types, their names, scopes, primary flags, conditions and dependencies.
+//! All types are serializable when the `serde` feature is enabled.
+//!
+//! The export can also be [rendered](ComponentRegistryExport::to_dot) as a
+//! [DOT](https://graphviz.org/doc/info/lang.html) graph for visualization, e.g. during architecture
+//! reviews:
+//!
+//! ```
+//! use springtime_di::factory::ComponentFactoryBuilder;
+//!
+//! let factory = ComponentFactoryBuilder::new()
+//!     .expect("error initializing ComponentFactoryBuilder")
+//!     .build();
+//!
+//! // render with e.g. "dot -Tsvg components.dot -o components.svg"
+//! let dot = factory.export_definitions().to_dot();
+//! ```
+//!
+//! For verifying what was actually assembled in a given deployment, the export can be summarized
+//! as [diagnostics](ComponentRegistryExport::diagnostics), which list concrete components, alias
+//! edges and skipped registrations, and can be dumped e.g. as JSON at startup.
+
+use crate::component_registry::{ComponentDefinition, ComponentDependency, DependencyKind};
+use fxhash::FxHashSet;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Projection of the whole wiring model of a
+/// [ComponentDefinitionRegistry](super::ComponentDefinitionRegistry).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ComponentRegistryExport {
+    /// All registered types, sorted by name.
+    pub types: Vec<ExportedType>,
+    /// Outcomes of conditions evaluated during registration, in order of evaluation.
+    pub conditions: Vec<ExportedCondition>,
+}
+
+/// Type registered in the registry - either a concrete component type or an alias.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ExportedType {
+    /// Human-readable type name.
+    pub type_name: String,
+    /// Is this an alias for other types, e.g. `dyn Trait`.
+    pub is_alias: bool,
+    /// Definitions registered for this type.
+    pub definitions: Vec<ExportedDefinition>,
+}
+
+/// Single component definition.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ExportedDefinition {
+    /// Component names, sorted.
+    pub names: Vec<String>,
+    /// Is this the primary definition for the type.
+    pub is_primary: bool,
+    /// Name of the [Scope](crate::scope::Scope) used for the component.
+    pub scope: String,
+    /// Position of the component when requesting all instances of a type.
+    pub order: i32,
+    /// Concrete component type name.
+    pub resolved_type_name: String,
+    /// Qualifier distinguishing additional definitions of the same concrete type.
+    pub qualifier: Option<String>,
+    /// Was the definition registered because a condition passed. For aliases, refers to the
+    /// condition of the alias itself, not the target component.
+    pub has_condition: bool,
+    /// Dependencies declared by the component.
+    pub dependencies: Vec<ExportedDependency>,
+}
+
+impl From<&ComponentDefinition> for ExportedDefinition {
+    fn from(value: &ComponentDefinition) -> Self {
+        let mut names = value.names.iter().cloned().collect::<Vec<_>>();
+        names.sort_unstable();
+
+        Self {
+            names,
+            is_primary: value.is_primary,
+            scope: value.scope.clone(),
+            order: value.order,
+            resolved_type_name: value.resolved_type_name.clone(),
+            qualifier: value.qualifier.clone(),
+            has_condition: false,
+            dependencies: value
+                .dependencies
+                .iter()
+                .map(ExportedDependency::from)
+                .collect(),
+        }
+    }
+}
+
+/// Dependency declared by a component.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ExportedDependency {
+    /// Human-readable name of the dependency type - either a concrete component type or an alias.
+    pub type_name: String,
+    /// Name of the requested instance, if requested by name.
+    pub name: Option<String>,
+    /// The way the component depends on the type.
+    pub kind: DependencyKind,
+}
+
+impl From<&ComponentDependency> for ExportedDependency {
+    fn from(value: &ComponentDependency) -> Self {
+        Self {
+            type_name: value.type_name.clone(),
+            name: value.name.clone(),
+            kind: value.kind,
+        }
+    }
+}
+
+/// Outcome of a registration condition.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ExportedCondition {
+    /// Name of the conditionally registered type.
+    pub type_name: String,
+    /// Name of the target type, if the condition was evaluated for an alias.
+    pub target_type_name: Option<String>,
+    /// Condition priority.
+    pub priority: i8,
+    /// Did the condition pass, thus registering the type.
+    pub passed: bool,
+}
+
+/// Flat summary of the registry contents, meant for verifying what was actually assembled in a
+/// given deployment.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ComponentRegistryDiagnostics {
+    /// Registered concrete components, sorted by type name.
+    pub components: Vec<DiagnosedComponent>,
+    /// Edges from aliases to concrete components, sorted by alias type name.
+    pub aliases: Vec<DiagnosedAlias>,
+    /// Registrations skipped because their conditions failed, in order of evaluation.
+    pub skipped: Vec<ExportedCondition>,
+}
+
+/// Registered concrete component definition.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DiagnosedComponent {
+    /// Concrete component type name.
+    pub type_name: String,
+    /// Component names, sorted.
+    pub names: Vec<String>,
+    /// Name of the [Scope](crate::scope::Scope) used for the component.
+    pub scope: String,
+    /// Qualifier distinguishing additional definitions of the same concrete type.
+    pub qualifier: Option<String>,
+    /// Was the component registered because a condition passed.
+    pub has_condition: bool,
+}
+
+/// Alias pointing at a concrete component.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DiagnosedAlias {
+    /// Alias type name, e.g. `dyn Trait`.
+    pub alias_type_name: String,
+    /// Concrete component type name.
+    pub target_type_name: String,
+    /// Is the target the primary component for the alias.
+    pub is_primary: bool,
+    /// Was the alias registered because a condition passed.
+    pub has_condition: bool,
+}
+
+impl ComponentRegistryExport {
+    /// Summarizes the export as [ComponentRegistryDiagnostics].
+    pub fn diagnostics(&self) -> ComponentRegistryDiagnostics {
+        let mut diagnostics = ComponentRegistryDiagnostics {
+            skipped: self
+                .conditions
+                .iter()
+                .filter(|condition| !condition.passed)
+                .cloned()
+                .collect(),
+            ..Default::default()
+        };
+
+        for exported in &self.types {
+            for definition in &exported.definitions {
+                if exported.is_alias {
+                    diagnostics.aliases.push(DiagnosedAlias {
+                        alias_type_name: exported.type_name.clone(),
+                        target_type_name: definition.resolved_type_name.clone(),
+                        is_primary: definition.is_primary,
+                        has_condition: definition.has_condition,
+                    });
+                } else {
+                    diagnostics.components.push(DiagnosedComponent {
+                        type_name: exported.type_name.clone(),
+                        names: definition.names.clone(),
+                        scope: definition.scope.clone(),
+                        qualifier: definition.qualifier.clone(),
+                        has_condition: definition.has_condition,
+                    });
+                }
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Renders the component graph in the [DOT](https://graphviz.org/doc/info/lang.html) format.
+    /// Concrete components are drawn as boxes with their scopes, aliases as ellipses pointing at
+    /// their target components, and dependencies as edges styled after their [DependencyKind].
+    /// Conditionally registered components are dashed, components with failed conditions are gray
+    /// and dependencies on unregistered types are red.
+    pub fn to_dot(&self) -> String {
+        let registered_types: FxHashSet<_> = self
+            .types
+            .iter()
+            .map(|exported| exported.type_name.as_str())
+            .collect();
+        let component_conditions = self
+            .conditions
+            .iter()
+            .filter(|condition| condition.target_type_name.is_none());
+        let conditional_types: FxHashSet<_> = component_conditions
+            .clone()
+            .map(|condition| condition.type_name.as_str())
+            .collect();
+        let mut failed_types = component_conditions
+            .filter(|condition| {
+                !condition.passed && !registered_types.contains(condition.type_name.as_str())
+            })
+            .map(|condition| condition.type_name.as_str())
+            .collect::<Vec<_>>();
+
+        let mut lines = vec!["digraph components {".to_string()];
+        let mut missing_types = vec![];
+
+        for exported in &self.types {
+            let id = dot_id(&exported.type_name);
+            if exported.is_alias {
+                lines.push(format!("    {id} [shape=ellipse];"));
+
+                for definition in &exported.definitions {
+                    let primary = if definition.is_primary {
+                        ", label=\"primary\""
+                    } else {
+                        ""
+                    };
+
+                    lines.push(format!(
+                        "    {id} -> {} [style=dotted{primary}];",
+                        dot_id(&definition.resolved_type_name)
+                    ));
+                }
+
+                continue;
+            }
+
+            for definition in &exported.definitions {
+                let style = if conditional_types.contains(exported.type_name.as_str()) {
+                    ", style=dashed"
+                } else {
+                    ""
+                };
+
+                lines.push(format!(
+                    "    {id} [shape=box, label=\"{}\\nscope: {}\"{style}];",
+                    escape(&exported.type_name),
+                    escape(&definition.scope)
+                ));
+
+                for dependency in &definition.dependencies {
+                    if !registered_types.contains(dependency.type_name.as_str()) {
+                        missing_types.push(dependency.type_name.as_str());
+                    }
+
+                    lines.push(format!(
+                        "    {id} -> {}{};",
+                        dot_id(&dependency.type_name),
+                        dependency_attributes(dependency)
+                    ));
+                }
+            }
+        }
+
+        failed_types.sort_unstable();
+        failed_types.dedup();
+
+        for type_name in failed_types {
+            lines.push(format!(
+                "    {} [shape=box, style=dashed, color=gray, fontcolor=gray];",
+                dot_id(type_name)
+            ));
+        }
+
+        missing_types.sort_unstable();
+        missing_types.dedup();
+
+        for type_name in missing_types {
+            lines.push(format!(
+                "    {} [color=red, fontcolor=red];",
+                dot_id(type_name)
+            ));
+        }
+
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+}
+
+fn dependency_attributes(dependency: &ExportedDependency) -> String {
+    let (style, kind) = match dependency.kind {
+        DependencyKind::Required => (None, None),
+        DependencyKind::Optional => (Some("dashed"), None),
+        DependencyKind::All => (None, Some("all")),
+        DependencyKind::Provider => (Some("dotted"), Some("provider")),
+        DependencyKind::Late => (Some("dashed"), Some("late")),
+    };
+
+    let label = match (kind, &dependency.name) {
+        (Some(kind), Some(name)) => Some(format!("{kind}: {name}")),
+        (Some(kind), None) => Some(kind.to_string()),
+        (None, Some(name)) => Some(name.clone()),
+        (None, None) => None,
+    };
+
+    let attributes = style
+        .map(|style| format!("style={style}"))
+        .into_iter()
+        .chain(label.map(|label| format!("label=\"{}\"", escape(&label))))
+        .collect::<Vec<_>>();
+
+    if attributes.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", attributes.join(", "))
+    }
+}
+
+fn dot_id(value: &str) -> String {
+    format!("\"{}\"", escape(value))
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::component_registry::export::{
+        ComponentRegistryExport, DiagnosedAlias, ExportedCondition, ExportedDefinition,
+        ExportedDependency, ExportedType,
+    };
+    use crate::component_registry::DependencyKind;
+
+    fn definition(
+        resolved_type_name: &str,
+        is_primary: bool,
+        dependencies: Vec<ExportedDependency>,
+    ) -> ExportedDefinition {
+        ExportedDefinition {
+            names: vec![],
+            is_primary,
+            scope: "SINGLETON".to_string(),
+            order: 0,
+            resolved_type_name: resolved_type_name.to_string(),
+            qualifier: None,
+            has_condition: false,
+            dependencies,
+        }
+    }
+
+    fn create_export() -> ComponentRegistryExport {
+        ComponentRegistryExport {
+            types: vec![
+                ExportedType {
+                    type_name: "Component".to_string(),
+                    is_alias: false,
+                    definitions: vec![definition(
+                        "Component",
+                        false,
+                        vec![
+                            ExportedDependency {
+                                type_name: "dyn Trait".to_string(),
+                                name: Some("named".to_string()),
+                                kind: DependencyKind::Late,
+                            },
+                            ExportedDependency {
+                                type_name: "Missing".to_string(),
+                                name: None,
+                                kind: DependencyKind::Required,
+                            },
+                        ],
+                    )],
+                },
+                ExportedType {
+                    type_name: "dyn Trait".to_string(),
+                    is_alias: true,
+                    definitions: vec![definition("Component", true, vec![])],
+                },
+            ],
+            conditions: vec![
+                ExportedCondition {
+                    type_name: "Component".to_string(),
+                    target_type_name: None,
+                    priority: 0,
+                    passed: true,
+                },
+                ExportedCondition {
+                    type_name: "Disabled".to_string(),
+                    target_type_name: None,
+                    priority: 0,
+                    passed: false,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn should_render_dot() {
+        let export = create_export();
+        assert_eq!(
+            export.to_dot(),
+            r#"digraph components {
+    "Component" [shape=box, label="Component\nscope: SINGLETON", style=dashed];
+    "Component" -> "dyn Trait" [style=dashed, label="late: named"];
+    "Component" -> "Missing";
+    "dyn Trait" [shape=ellipse];
+    "dyn Trait" -> "Component" [style=dotted, label="primary"];
+    "Disabled" [shape=box, style=dashed, color=gray, fontcolor=gray];
+    "Missing" [color=red, fontcolor=red];
+}"#
+        );
+    }
+
+    #[test]
+    fn should_summarize_diagnostics() {
+        let diagnostics = create_export().diagnostics();
+        assert_eq!(diagnostics.components.len(), 1);
+        assert_eq!(diagnostics.components[0].type_name, "Component");
+        assert_eq!(
+            diagnostics.aliases,
+            vec![DiagnosedAlias {
+                alias_type_name: "dyn Trait".to_string(),
+                target_type_name: "Component".to_string(),
+                is_primary: true,
+                has_condition: false,
+            }]
+        );
+        assert_eq!(diagnostics.skipped.len(), 1);
+        assert_eq!(diagnostics.skipped[0].type_name, "Disabled");
+    }
+}
@@ -0,0 +1,337 @@
+//! Validation of the component graph without creating any instances, which allows detecting
+//! wiring errors at startup or in tests, instead of on first use. Validation relies on
+//! [dependencies](super::ComponentDefinition::dependencies) declared by components - derive-based
+//! components declare all their dependencies, while manually registered ones might not declare any,
+//! in which case only their scopes are checked.
+//!
+//! ```
+//! use springtime_di::factory::ComponentFactoryBuilder;
+//!
+//! let factory = ComponentFactoryBuilder::new()
+//!     .expect("error initializing ComponentFactoryBuilder")
+//!     .build();
+//!
+//! for error in factory.validate() {
+//!     eprintln!("{error}");
+//! }
+//! ```
+
+use crate::component_registry::{ComponentDefinitionRegistry, DependencyKind};
+use fxhash::{FxHashMap, FxHashSet};
+use std::any::TypeId;
+use thiserror::Error;
+
+/// Problem found in the component graph.
+#[derive(Error, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug)]
+pub enum ValidationError {
+    #[error("Missing dependency {dependency_type} for component: {component_type}")]
+    MissingDependency {
+        component_type: String,
+        dependency_type: String,
+    },
+    #[error("Missing dependency {dependency_type} named {name} for component: {component_type}")]
+    MissingNamedDependency {
+        component_type: String,
+        dependency_type: String,
+        name: String,
+    },
+    #[error(
+        "Cannot find primary dependency {dependency_type} for component {component_type} among: {}",
+        .candidates.join(", ")
+    )]
+    AmbiguousPrimaryDependency {
+        component_type: String,
+        dependency_type: String,
+        candidates: Vec<String>,
+    },
+    #[error("Unrecognized scope {scope} for component: {component_type}")]
+    UnrecognizedScope {
+        component_type: String,
+        scope: String,
+    },
+    #[error("Dependency cycle: {}", .path.join(" -> "))]
+    DependencyCycle { path: Vec<String> },
+}
+
+/// Validates all definitions in given registry, reporting missing dependencies, ambiguous
+/// primaries, unrecognized scopes and dependency cycles. Late dependencies break cycles, since they
+/// are injected after construction. Returns errors sorted for deterministic output, or an empty
+/// list if no problems are found.
+pub fn validate_definitions<R: ComponentDefinitionRegistry + ?Sized>(
+    registry: &R,
+    is_scope_recognized: impl Fn(&str) -> bool,
+) -> Vec<ValidationError> {
+    let definitions = registry.all_definitions();
+
+    let mut errors = vec![];
+    let mut type_names = FxHashMap::default();
+    let mut edges: FxHashMap<TypeId, Vec<TypeId>> = FxHashMap::default();
+
+    for (type_id, definitions) in &definitions {
+        for definition in definitions {
+            if !is_scope_recognized(&definition.scope) {
+                errors.push(ValidationError::UnrecognizedScope {
+                    component_type: definition.resolved_type_name.clone(),
+                    scope: definition.scope.clone(),
+                });
+            }
+
+            // aliases share dependencies with their targets
+            if definition.resolved_type_id != *type_id {
+                continue;
+            }
+
+            type_names.insert(*type_id, definition.resolved_type_name.clone());
+
+            let component_edges = edges.entry(*type_id).or_default();
+            for dependency in &definition.dependencies {
+                let is_required = matches!(
+                    dependency.kind,
+                    DependencyKind::Required | DependencyKind::Late
+                );
+
+                let targets = match (dependency.kind, &dependency.name) {
+                    (DependencyKind::Provider, _) => continue,
+                    (DependencyKind::All, _) => registry.components_by_type(dependency.type_id),
+                    (_, Some(name)) => {
+                        let target = registry.component_by_name(name, dependency.type_id);
+                        if target.is_none() && is_required {
+                            errors.push(ValidationError::MissingNamedDependency {
+                                component_type: definition.resolved_type_name.clone(),
+                                dependency_type: dependency.type_name.clone(),
+                                name: name.clone(),
+                            });
+                        }
+
+                        target.into_iter().collect()
+                    }
+                    (_, None) => {
+                        let target = registry.primary_component(dependency.type_id);
+                        if target.is_none() && is_required {
+                            let candidates = registry.components_by_type(dependency.type_id);
+                            errors.push(if candidates.is_empty() {
+                                ValidationError::MissingDependency {
+                                    component_type: definition.resolved_type_name.clone(),
+                                    dependency_type: dependency.type_name.clone(),
+                                }
+                            } else {
+                                ValidationError::AmbiguousPrimaryDependency {
+                                    component_type: definition.resolved_type_name.clone(),
+                                    dependency_type: dependency.type_name.clone(),
+                                    candidates: candidates
+                                        .into_iter()
+                                        .map(|candidate| candidate.resolved_type_name.clone())
+                                        .collect(),
+                                }
+                            });
+                        }
+
+                        target.into_iter().collect()
+                    }
+                };
+
+                // late dependencies are injected after construction, so they can't form a cycle
+                if dependency.kind != DependencyKind::Late {
+                    component_edges
+                        .extend(targets.into_iter().map(|target| target.resolved_type_id));
+                }
+            }
+        }
+    }
+
+    let mut roots = edges.keys().copied().collect::<Vec<_>>();
+    roots.sort_by(|first, second| type_names[first].cmp(&type_names[second]));
+
+    let mut visited = FxHashSet::default();
+    let mut path = vec![];
+    for root in roots {
+        find_cycles(
+            root,
+            &edges,
+            &type_names,
+            &mut visited,
+            &mut path,
+            &mut errors,
+        );
+    }
+
+    errors.sort();
+    errors.dedup();
+    errors
+}
+
+fn find_cycles(
+    type_id: TypeId,
+    edges: &FxHashMap<TypeId, Vec<TypeId>>,
+    type_names: &FxHashMap<TypeId, String>,
+    visited: &mut FxHashSet<TypeId>,
+    path: &mut Vec<TypeId>,
+    errors: &mut Vec<ValidationError>,
+) {
+    if let Some(position) = path.iter().position(|entry| *entry == type_id) {
+        errors.push(ValidationError::DependencyCycle {
+            path: path[position..]
+                .iter()
+                .chain([type_id].iter())
+                .map(|entry| type_names[entry].clone())
+                .collect(),
+        });
+        return;
+    }
+
+    if !visited.insert(type_id) {
+        return;
+    }
+
+    path.push(type_id);
+    for target in edges.get(&type_id).into_iter().flatten() {
+        find_cycles(*target, edges, type_names, visited, path, errors);
+    }
+    path.pop();
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "async"))]
+    mod sync {
+        use crate::component_registry::conditional::SimpleContextFactory;
+        use crate::component_registry::validation::{validate_definitions, ValidationError};
+        use crate::component_registry::{
+            ComponentDefinitionRegistry, ComponentDependency, ComponentMetadata, DependencyKind,
+            StaticComponentDefinitionRegistry,
+        };
+        use crate::instance_provider::{
+            ComponentInstanceAnyPtr, ComponentInstanceProvider, ComponentInstanceProviderError,
+            ComponentInstancePtr,
+        };
+        use crate::scope::SINGLETON;
+        use std::any::{type_name, Any, TypeId};
+
+        fn constructor(
+            _instance_provider: &mut dyn ComponentInstanceProvider,
+        ) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
+            Ok(ComponentInstancePtr::new(0) as ComponentInstanceAnyPtr)
+        }
+
+        fn cast(
+            instance: ComponentInstanceAnyPtr,
+            _target: &mut dyn Any,
+        ) -> Result<(), ComponentInstanceAnyPtr> {
+            Err(instance)
+        }
+
+        fn dependency<T: 'static>(kind: DependencyKind) -> ComponentDependency {
+            ComponentDependency {
+                type_id: TypeId::of::<T>(),
+                type_name: type_name::<T>().to_string(),
+                name: None,
+                kind,
+            }
+        }
+
+        fn register<T: 'static>(
+            registry: &mut StaticComponentDefinitionRegistry,
+            scope: &str,
+            dependencies: Vec<ComponentDependency>,
+        ) {
+            registry
+                .register_component(
+                    TypeId::of::<T>(),
+                    type_name::<T>(),
+                    &ComponentMetadata {
+                        names: [type_name::<T>().to_string()].into_iter().collect(),
+                        scope: scope.to_string(),
+                        order: 0,
+                        constructor,
+                        late_injection: None,
+                        post_construct: None,
+                        pre_destroy: None,
+                        cast,
+                        dependencies,
+                    },
+                )
+                .unwrap();
+        }
+
+        fn create_registry() -> StaticComponentDefinitionRegistry {
+            StaticComponentDefinitionRegistry::new(false, &SimpleContextFactory::default()).unwrap()
+        }
+
+        #[test]
+        fn should_report_missing_dependencies_and_scopes() {
+            let mut registry = create_registry();
+            register::<i8>(
+                &mut registry,
+                "unknown",
+                vec![
+                    dependency::<u8>(DependencyKind::Required),
+                    dependency::<u16>(DependencyKind::Optional),
+                    dependency::<u32>(DependencyKind::All),
+                    dependency::<u64>(DependencyKind::Provider),
+                ],
+            );
+
+            assert_eq!(
+                validate_definitions(&registry, |scope| scope == SINGLETON),
+                vec![
+                    ValidationError::MissingDependency {
+                        component_type: type_name::<i8>().to_string(),
+                        dependency_type: type_name::<u8>().to_string(),
+                    },
+                    ValidationError::UnrecognizedScope {
+                        component_type: type_name::<i8>().to_string(),
+                        scope: "unknown".to_string(),
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn should_report_cycles() {
+            let mut registry = create_registry();
+            register::<i8>(
+                &mut registry,
+                SINGLETON,
+                vec![dependency::<i16>(DependencyKind::Required)],
+            );
+            register::<i16>(
+                &mut registry,
+                SINGLETON,
+                vec![dependency::<i8>(DependencyKind::Optional)],
+            );
+            register::<i32>(
+                &mut registry,
+                SINGLETON,
+                vec![dependency::<i8>(DependencyKind::Required)],
+            );
+
+            assert_eq!(
+                validate_definitions(&registry, |scope| scope == SINGLETON),
+                vec![ValidationError::DependencyCycle {
+                    path: vec![
+                        type_name::<i16>().to_string(),
+                        type_name::<i8>().to_string(),
+                        type_name::<i16>().to_string(),
+                    ],
+                }]
+            );
+        }
+
+        #[test]
+        fn should_not_report_late_cycles() {
+            let mut registry = create_registry();
+            register::<i8>(
+                &mut registry,
+                SINGLETON,
+                vec![dependency::<i16>(DependencyKind::Required)],
+            );
+            register::<i16>(
+                &mut registry,
+                SINGLETON,
+                vec![dependency::<i8>(DependencyKind::Late)],
+            );
+
+            assert!(validate_definitions(&registry, |scope| scope == SINGLETON).is_empty());
+        }
+    }
+}
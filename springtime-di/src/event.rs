@@ -0,0 +1,189 @@
+//! Application events allow components to communicate without depending on each other directly.
+//! Events of any `'static` type are published with the injectable [EventPublisher] and delivered
+//! to all components registered as [EventListener]s for the given event type. Listeners are
+//! registered like any other alias, using `#[event_listener]` (or `#[component_alias]`) on the
+//! trait implementation:
+//!
+//! ```
+//! use springtime_di::event::{EventListener, EventPublisher};
+//! use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
+//! use springtime_di::{event_listener, Component};
+//! # #[cfg(feature = "async")]
+//! # use springtime_di::future::{BoxFuture, FutureExt};
+//!
+//! struct UserCreated {
+//!     name: String,
+//! }
+//!
+//! #[derive(Component)]
+//! struct WelcomeMailer;
+//!
+//! #[event_listener]
+//! impl EventListener<UserCreated> for WelcomeMailer {
+//!     # #[cfg(not(feature = "async"))]
+//!     fn on_event(&self, event: &UserCreated) -> Result<(), ErrorPtr> {
+//!         println!("Welcome, {}!", event.name);
+//!         Ok(())
+//!     }
+//!     # #[cfg(feature = "async")]
+//!     # fn on_event<'a>(&'a self, event: &'a UserCreated) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+//!     #     async move { Ok(()) }.boxed()
+//!     # }
+//! }
+//!
+//! #[derive(Component)]
+//! struct UserService {
+//!     // publisher.publish(&UserCreated { ... }) notifies the WelcomeMailer
+//!     publisher: ComponentInstancePtr<EventPublisher>,
+//! }
+//! ```
+//!
+//! Listeners are resolved when an event is published, so they can depend on publishers without
+//! forming dependency cycles. With the `async` feature, listeners are notified concurrently.
+//! Otherwise, they are notified in their
+//! [order](crate::component_registry::ComponentDefinition::order).
+
+use crate::component::{Component, ComponentDowncast, Injectable};
+use crate::component_registry::internal::{
+    component_definition, ComponentRegistration, TypedComponentDefinition,
+};
+#[cfg(not(feature = "explicit-registration"))]
+use crate::component_registry::internal::{submit, ComponentDefinitionRegisterer};
+#[cfg(feature = "async")]
+use crate::future::{BoxFuture, FutureExt};
+use crate::instance_provider::{
+    ComponentInstanceAnyPtr, ComponentInstanceProvider, ComponentInstanceProviderError,
+    ComponentInstancePtr, ErrorPtr, FactoryHandle,
+};
+use crate::scope::SINGLETON;
+#[cfg(feature = "async")]
+use futures::future::try_join_all;
+use thiserror::Error;
+
+/// Errors related to publishing events.
+#[derive(Error, Debug, Clone)]
+pub enum EventPublishError {
+    /// Error resolving listeners for the event.
+    #[error("Error resolving event listeners: {0}")]
+    ListenerResolutionError(#[source] ComponentInstanceProviderError),
+    /// A listener returned an error.
+    #[error("Error in event listener: {0}")]
+    ListenerError(#[source] ErrorPtr),
+}
+
+/// Listener for events of type `E`. Implementations need to be registered as aliases for the
+/// listening components. See module documentation for more information.
+pub trait EventListener<E: ?Sized> {
+    /// Handles the published event.
+    #[cfg(not(feature = "async"))]
+    fn on_event(&self, event: &E) -> Result<(), ErrorPtr>;
+
+    /// Handles the published event.
+    #[cfg(feature = "async")]
+    fn on_event<'a>(&'a self, event: &'a E) -> BoxFuture<'a, Result<(), ErrorPtr>>;
+}
+
+#[cfg(feature = "threadsafe")]
+type DynEventListener<E> = dyn EventListener<E> + Send + Sync;
+#[cfg(not(feature = "threadsafe"))]
+type DynEventListener<E> = dyn EventListener<E>;
+
+impl<E: ?Sized + 'static> Injectable for DynEventListener<E> {}
+
+/// Component publishing events to all registered [EventListener]s.
+pub struct EventPublisher {
+    factory: FactoryHandle,
+}
+
+impl EventPublisher {
+    /// Creates a new publisher resolving listeners with given handle. Typically, the publisher is
+    /// created by the dependency injection framework instead.
+    pub fn new(factory: FactoryHandle) -> Self {
+        Self { factory }
+    }
+
+    /// Notifies all listeners for `E` about given event, stopping on first error.
+    #[cfg(not(feature = "async"))]
+    pub fn publish<E: 'static>(&self, event: &E) -> Result<(), EventPublishError> {
+        let listeners = self
+            .factory
+            .instances::<DynEventListener<E>>()
+            .map_err(EventPublishError::ListenerResolutionError)?;
+
+        for listener in listeners {
+            listener
+                .on_event(event)
+                .map_err(EventPublishError::ListenerError)?;
+        }
+
+        Ok(())
+    }
+
+    /// Notifies all listeners for `E` about given event concurrently, failing on first error.
+    #[cfg(feature = "async")]
+    pub fn publish<'a, E: Sync + 'static>(
+        &'a self,
+        event: &'a E,
+    ) -> BoxFuture<'a, Result<(), EventPublishError>> {
+        async move {
+            let listeners = self
+                .factory
+                .instances::<DynEventListener<E>>()
+                .await
+                .map_err(EventPublishError::ListenerResolutionError)?;
+
+            try_join_all(listeners.iter().map(|listener| listener.on_event(event)))
+                .await
+                .map_err(EventPublishError::ListenerError)?;
+
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+impl Injectable for EventPublisher {}
+
+impl ComponentDowncast<EventPublisher> for EventPublisher {
+    fn downcast(
+        source: ComponentInstanceAnyPtr,
+    ) -> Result<ComponentInstancePtr<Self>, ComponentInstanceAnyPtr> {
+        source.downcast()
+    }
+}
+
+impl Component for EventPublisher {
+    #[cfg(not(feature = "async"))]
+    fn create(
+        instance_provider: &mut dyn ComponentInstanceProvider,
+    ) -> Result<Self, ComponentInstanceProviderError> {
+        Ok(Self {
+            factory: FactoryHandle::new(instance_provider)?,
+        })
+    }
+
+    #[cfg(feature = "async")]
+    fn create(
+        instance_provider: &mut (dyn ComponentInstanceProvider + Sync + Send),
+    ) -> BoxFuture<'_, Result<Self, ComponentInstanceProviderError>> {
+        async move {
+            Ok(Self {
+                factory: FactoryHandle::new(instance_provider)?,
+            })
+        }
+        .boxed()
+    }
+}
+
+impl ComponentRegistration for EventPublisher {
+    fn definition() -> TypedComponentDefinition {
+        component_definition::<Self>(["event_publisher"], SINGLETON)
+    }
+}
+
+#[cfg(not(feature = "explicit-registration"))]
+submit! {
+    ComponentDefinitionRegisterer {
+        register: <EventPublisher as ComponentRegistration>::definition,
+    }
+}
@@ -1,26 +1,53 @@
 //! Core functionality for creating [Component](crate::component::Component) instances.
 
-use crate::component_registry::conditional::SimpleContextFactory;
+use crate::component::ComponentNamingStrategy;
+use crate::component_registry::conditional::{ContextFactory, SimpleContextFactory};
+use crate::component_registry::export::ComponentRegistryExport;
+use crate::component_registry::internal::ComponentInstanceBounds;
+use crate::component_registry::validation::{validate_definitions, ValidationError};
 use crate::component_registry::{
-    ComponentDefinition, ComponentDefinitionRegistry, ComponentDefinitionRegistryError,
-    StaticComponentDefinitionRegistry,
+    ComponentAliasMetadata, ComponentDefinition, ComponentDefinitionKey, ComponentDefinitionPtr,
+    ComponentDefinitionRegistry, ComponentDefinitionRegistryError, ComponentMetadata,
+    DefinitionVisitor, PreDestroy, ShadowedDefinition, StaticComponentDefinitionRegistry,
 };
 use crate::instance_provider::{
-    CastFunction, ComponentInstanceAnyPtr, ComponentInstanceProvider,
-    ComponentInstanceProviderError,
+    store_cast_result, CastFunction, ComponentInstanceAnyPtr, ComponentInstanceProvider,
+    ComponentInstanceProviderError, ComponentInstanceProviderPtr, ComponentInstancePtr, ErrorPtr,
+    InstanceFilter, NamedInstance,
 };
+use crate::post_processor::InstancePostProcessorPtr;
 use crate::scope::{
-    PrototypeScopeFactory, ScopeFactory, ScopePtr, SingletonScopeFactory, PROTOTYPE, SINGLETON,
+    with_active_context, ActiveContextGuard, EvictionListener, PrototypeScopeFactory, ScopeFactory,
+    ScopePtr, SingletonScopeFactory, WeakSingletonScopeFactory, PROTOTYPE, SINGLETON,
+    WEAK_SINGLETON,
 };
 #[cfg(feature = "async")]
-use futures::future::BoxFuture;
+use futures::channel::oneshot;
+#[cfg(feature = "async")]
+use futures::future::{BoxFuture, Shared};
 #[cfg(feature = "async")]
 use futures::FutureExt;
-use fxhash::{FxHashMap, FxHashSet};
+use fxhash::FxHashMap;
 #[cfg(not(feature = "async"))]
 use itertools::Itertools;
-use std::any::TypeId;
-use tracing::debug;
+use std::any::{type_name, Any, TypeId};
+#[cfg(not(feature = "threadsafe"))]
+use std::cell::RefCell;
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::ops::DerefMut;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(all(feature = "threadsafe", not(feature = "async")))]
+use std::sync::Condvar;
+#[cfg(feature = "threadsafe")]
+use std::sync::{Mutex, PoisonError, TryLockError};
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+use std::time::Instant;
+#[cfg(feature = "async")]
+use tracing::Instrument;
+use tracing::{debug, debug_span, enabled, field, info, warn, Level, Span};
 
 #[cfg(not(feature = "threadsafe"))]
 pub type ComponentDefinitionRegistryPtr = Box<dyn ComponentDefinitionRegistry>;
@@ -38,16 +65,41 @@ pub type ScopeFactoryRegistry = FxHashMap<String, ScopeFactoryPtr>;
 pub struct ComponentFactoryBuilder {
     definition_registry: ComponentDefinitionRegistryPtr,
     scope_factories: ScopeFactoryRegistry,
+    instances: Vec<(TypeId, ComponentInstanceAnyPtr)>,
+    post_processors: Vec<InstancePostProcessorPtr>,
 }
 
 impl ComponentFactoryBuilder {
     /// Creates a new builder with a default configuration.
     pub fn new() -> Result<Self, ComponentDefinitionRegistryError> {
+        Self::new_with_context_factory(&SimpleContextFactory)
+    }
+
+    /// Creates a new builder with a default configuration, but using given [ContextFactory] for
+    /// conditional registration, e.g.
+    /// [ProfileContextFactory](crate::component_registry::conditional::ProfileContextFactory) with
+    /// explicitly given active profiles.
+    pub fn new_with_context_factory<CF: ContextFactory>(
+        context_factory: &CF,
+    ) -> Result<Self, ComponentDefinitionRegistryError> {
+        Self::new_with_naming_strategy(context_factory, ComponentNamingStrategy::default())
+    }
+
+    /// Creates a new builder with a default configuration, but using given [ContextFactory] for
+    /// conditional registration and given [ComponentNamingStrategy] for generating default
+    /// component names.
+    pub fn new_with_naming_strategy<CF: ContextFactory>(
+        context_factory: &CF,
+        naming_strategy: ComponentNamingStrategy,
+    ) -> Result<Self, ComponentDefinitionRegistryError> {
         Ok(Self {
-            definition_registry: Box::new(StaticComponentDefinitionRegistry::new(
-                true,
-                &SimpleContextFactory,
-            )?),
+            definition_registry: Box::new(
+                StaticComponentDefinitionRegistry::new_with_naming_strategy(
+                    true,
+                    context_factory,
+                    naming_strategy,
+                )?,
+            ),
             scope_factories: [
                 (
                     SINGLETON.to_string(),
@@ -57,9 +109,15 @@ impl ComponentFactoryBuilder {
                     PROTOTYPE.to_string(),
                     Box::<PrototypeScopeFactory>::default() as ScopeFactoryPtr,
                 ),
+                (
+                    WEAK_SINGLETON.to_string(),
+                    Box::<WeakSingletonScopeFactory>::default() as ScopeFactoryPtr,
+                ),
             ]
             .into_iter()
             .collect(),
+            instances: vec![],
+            post_processors: vec![],
         })
     }
 
@@ -84,157 +142,2090 @@ impl ComponentFactoryBuilder {
         self
     }
 
+    /// Adds a new [post-processor](crate::post_processor) for created instances. Post-processors
+    /// are called in the order of registration.
+    pub fn with_post_processor(mut self, post_processor: InstancePostProcessorPtr) -> Self {
+        self.post_processors.push(post_processor);
+        self
+    }
+
+    /// Adds an already constructed instance, which becomes the [SINGLETON] for `T` and all its
+    /// aliases. Useful for passing resources created at runtime, e.g. database pools or command
+    /// line arguments, to the components.
+    ///
+    /// If `T` is not a registered component, e.g. it comes from a third-party crate, a new
+    /// definition named after the full type name is registered for it. Otherwise, the existing
+    /// definition is used and must use the [SINGLETON] scope. Note: seeded instances are not
+    /// destroyed on [shutdown](ComponentFactory::shutdown), since they are owned by the caller, and
+    /// are provided again if the factory is used afterwards.
+    pub fn with_instance<T: ComponentInstanceBounds + 'static>(
+        mut self,
+        instance: impl Into<ComponentInstancePtr<T>>,
+    ) -> Result<Self, ComponentDefinitionRegistryError> {
+        let type_id = TypeId::of::<T>();
+        if let Some(definition) = self.definition_registry.primary_component(type_id) {
+            if definition.scope != SINGLETON {
+                return Err(ComponentDefinitionRegistryError::NonSingletonInstance {
+                    type_name: type_name::<T>().to_string(),
+                    scope: definition.scope.clone(),
+                });
+            }
+        } else if !self.definition_registry.is_registered(type_id) {
+            self.definition_registry.register_component(
+                type_id,
+                type_name::<T>(),
+                &ComponentMetadata {
+                    names: [type_name::<T>().to_string()].into_iter().collect(),
+                    scope: SINGLETON.to_string(),
+                    order: 0,
+                    constructor: construct_seeded_instance::<T>,
+                    late_injection: None,
+                    post_construct: None,
+                    pre_destroy: None,
+                    cast: cast_seeded_instance::<T>,
+                    dependencies: vec![],
+                },
+            )?;
+        }
+
+        self.instances
+            .push((type_id, instance.into() as ComponentInstanceAnyPtr));
+        Ok(self)
+    }
+
+    /// Replaces all components registered for `T`, e.g. all implementations of a `dyn Trait`, with
+    /// given instance, which becomes the [SINGLETON] for `T`. Only the entries for `T` are
+    /// replaced, so the implementations can still be injected as themselves or their other aliases.
+    /// Useful for injecting test doubles:
+    ///
+    /// ```
+    /// use springtime_di::factory::ComponentFactoryBuilder;
+    /// use springtime_di::instance_provider::{ComponentInstancePtr, TypedComponentInstanceProvider};
+    /// use springtime_di::{component_alias, injectable, Component};
+    ///
+    /// #[injectable]
+    /// trait Clock {
+    ///     fn now(&self) -> u64;
+    /// }
+    ///
+    /// #[derive(Component)]
+    /// struct SystemClock;
+    ///
+    /// #[component_alias]
+    /// impl Clock for SystemClock {
+    ///     fn now(&self) -> u64 {
+    ///         unimplemented!()
+    ///     }
+    /// }
+    ///
+    /// struct FixedClock;
+    ///
+    /// impl Clock for FixedClock {
+    ///     fn now(&self) -> u64 {
+    ///         42
+    ///     }
+    /// }
+    ///
+    /// let mut factory = ComponentFactoryBuilder::new()
+    ///     .unwrap()
+    ///     .with_override::<dyn Clock + Send + Sync>(ComponentInstancePtr::new(FixedClock))
+    ///     .unwrap()
+    ///     .build();
+    ///
+    /// # #[cfg(not(feature = "async"))]
+    /// let clock = factory.primary_instance_typed::<dyn Clock + Send + Sync>().unwrap();
+    /// # #[cfg(not(feature = "async"))]
+    /// assert_eq!(clock.now(), 42);
+    /// ```
+    ///
+    /// Note: the instance is only available as `T`, so replacing a concrete type removes the
+    /// component along with its aliases. To replace a concrete component, while keeping its
+    /// aliases, use [with_instance](Self::with_instance).
+    #[cfg(feature = "test-util")]
+    pub fn with_override<T: ?Sized + 'static>(
+        mut self,
+        instance: ComponentInstancePtr<T>,
+    ) -> Result<Self, ComponentDefinitionRegistryError>
+    where
+        ComponentInstancePtr<T>: ComponentInstanceBounds,
+    {
+        // components behind a replaced alias stay available as themselves and their other aliases
+        let type_id = TypeId::of::<T>();
+        self.definition_registry.remove_alias(type_id)?;
+        self.definition_registry.remove_component(type_id);
+
+        self.definition_registry.register_component(
+            type_id,
+            type_name::<T>(),
+            &ComponentMetadata {
+                names: [type_name::<T>().to_string()].into_iter().collect(),
+                scope: SINGLETON.to_string(),
+                order: 0,
+                constructor: construct_seeded_instance::<T>,
+                late_injection: None,
+                post_construct: None,
+                pre_destroy: None,
+                cast: cast_overriding_instance::<T>,
+                dependencies: vec![],
+            },
+        )?;
+
+        // unsized instances cannot be type-erased directly, so they need another layer of pointers
+        self.instances.push((
+            type_id,
+            ComponentInstancePtr::new(instance) as ComponentInstanceAnyPtr,
+        ));
+        Ok(self)
+    }
+
     /// Builds resulting [ComponentFactory].
     pub fn build(self) -> ComponentFactory {
-        ComponentFactory::new(self.definition_registry, self.scope_factories)
+        ComponentFactory::from_parts(
+            self.definition_registry,
+            ComponentInstancePtr::new(self.scope_factories),
+            ComponentInstancePtr::new(self.post_processors),
+            ComponentInstancePtr::new(self.instances),
+        )
+    }
+}
+
+// seeded instances are stored again whenever the singleton scope is reset, so they are never
+// constructed, unless evicted explicitly
+#[cfg(not(feature = "async"))]
+fn construct_seeded_instance<T: ?Sized + 'static>(
+    _instance_provider: &mut dyn ComponentInstanceProvider,
+) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
+    Err(ComponentInstanceProviderError::NoPrimaryInstance {
+        type_id: TypeId::of::<T>(),
+        type_name: Some(type_name::<T>().to_string()),
+    })
+}
+
+#[cfg(feature = "async")]
+fn construct_seeded_instance<T: ?Sized + 'static>(
+    _instance_provider: &mut (dyn ComponentInstanceProvider + Sync + Send),
+) -> BoxFuture<'_, Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError>> {
+    async {
+        Err(ComponentInstanceProviderError::NoPrimaryInstance {
+            type_id: TypeId::of::<T>(),
+            type_name: Some(type_name::<T>().to_string()),
+        })
+    }
+    .boxed()
+}
+
+// cast functions of the component and its aliases expect the concrete component type
+fn check_post_processed_instance(
+    definition: &ComponentDefinition,
+    instance: &ComponentInstanceAnyPtr,
+) -> Result<(), ComponentInstanceProviderError> {
+    if (**instance).type_id() == definition.resolved_type_id {
+        Ok(())
+    } else {
+        Err(
+            ComponentInstanceProviderError::IncompatiblePostProcessedInstance {
+                type_id: definition.resolved_type_id,
+                type_name: definition.resolved_type_name.clone(),
+            },
+        )
+    }
+}
+
+fn cast_seeded_instance<T: ComponentInstanceBounds + 'static>(
+    instance: ComponentInstanceAnyPtr,
+    target: &mut dyn Any,
+) -> Result<(), ComponentInstanceAnyPtr> {
+    instance
+        .downcast::<T>()
+        .map(|instance| store_cast_result(instance, target))
+}
+
+#[cfg(feature = "test-util")]
+fn cast_overriding_instance<T: ?Sized + 'static>(
+    instance: ComponentInstanceAnyPtr,
+    target: &mut dyn Any,
+) -> Result<(), ComponentInstanceAnyPtr>
+where
+    ComponentInstancePtr<T>: ComponentInstanceBounds,
+{
+    instance
+        .downcast::<ComponentInstancePtr<T>>()
+        .map(|instance| store_cast_result(ComponentInstancePtr::clone(&*instance), target))
+}
+
+// spans of dependencies are nested in spans of components depending on them, so slow constructors
+// can be found in the startup trace
+fn construction_span(definition: &ComponentDefinition) -> Span {
+    debug_span!(
+        "create_instance",
+        resolved_type_name = definition.resolved_type_name,
+        scope = definition.scope,
+        elapsed_us = field::Empty,
+    )
+}
+
+// time is only measured when traced, which also avoids using clocks on platforms without them
+fn start_timer(span: &Span) -> Option<Instant> {
+    (!span.is_disabled()).then(Instant::now)
+}
+
+fn record_elapsed(span: &Span, start: Option<Instant>) {
+    if let Some(start) = start {
+        span.record("elapsed_us", start.elapsed().as_micros() as u64);
+    }
+}
+
+fn log_singletons_instantiated(count: usize, start: Option<Instant>) {
+    if let Some(start) = start {
+        info!(
+            count,
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "Instantiated all singletons."
+        );
+    }
+}
+
+#[cfg(feature = "threadsafe")]
+type SharedCell<T> = Mutex<T>;
+#[cfg(not(feature = "threadsafe"))]
+type SharedCell<T> = RefCell<T>;
+
+#[cfg(feature = "threadsafe")]
+fn borrow_shared<T>(cell: &SharedCell<T>) -> impl DerefMut<Target = T> + '_ {
+    cell.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(not(feature = "threadsafe"))]
+fn borrow_shared<T>(cell: &SharedCell<T>) -> impl DerefMut<Target = T> + '_ {
+    cell.borrow_mut()
+}
+
+#[cfg(feature = "threadsafe")]
+fn try_borrow_shared<T>(cell: &SharedCell<T>) -> Option<impl DerefMut<Target = T> + '_> {
+    match cell.try_lock() {
+        Ok(value) => Some(value),
+        Err(TryLockError::Poisoned(error)) => Some(error.into_inner()),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
+
+#[cfg(not(feature = "threadsafe"))]
+fn try_borrow_shared<T>(cell: &SharedCell<T>) -> Option<impl DerefMut<Target = T> + '_> {
+    cell.try_borrow_mut().ok()
+}
+
+type EvictedInstances = Vec<(ComponentDefinitionKey, ComponentInstanceAnyPtr)>;
+
+// scopes report evicted instances to the factory, which passes them to the listener only after
+// releasing the scope lock, so the listener can use the factory
+#[derive(Default)]
+struct ScopeEvictions {
+    pending: ComponentInstancePtr<SharedCell<EvictedInstances>>,
+    listener: SharedCell<Option<EvictionListener>>,
+}
+
+fn create_scope(factory: &ScopeFactoryPtr, evictions: &ScopeEvictions) -> ScopePtr {
+    let mut scope = factory.create_scope();
+    let pending = evictions.pending.clone();
+    scope.set_eviction_listener(Box::new(move |key, instance| {
+        borrow_shared(&pending).push((key.clone(), instance));
+    }));
+
+    scope
+}
+
+fn create_scopes(
+    scope_factories: &FxHashMap<String, ScopeFactoryPtr>,
+) -> (
+    FxHashMap<String, SharedCell<ScopePtr>>,
+    FxHashMap<String, ScopeEvictions>,
+) {
+    scope_factories
+        .iter()
+        .map(|(name, factory)| {
+            let evictions = ScopeEvictions::default();
+            (
+                (
+                    name.clone(),
+                    SharedCell::new(create_scope(factory, &evictions)),
+                ),
+                (name.clone(), evictions),
+            )
+        })
+        .unzip()
+}
+
+// state shared between all handles to the same factory; locks are never held while calling
+// constructors or callbacks, since those can recursively request other instances
+struct ComponentFactoryState {
+    definition_registry: SharedCell<ComponentDefinitionRegistryPtr>,
+    // configuration is additionally shared with forks
+    scope_factories: ComponentInstancePtr<FxHashMap<String, ScopeFactoryPtr>>,
+    seeds: ComponentInstancePtr<Vec<(TypeId, ComponentInstanceAnyPtr)>>,
+    // each scope is locked separately, so resolving components from different scopes doesn't
+    // contend on a single lock
+    scopes: FxHashMap<String, SharedCell<ScopePtr>>,
+    // singletons in creation order, which means dependencies come before dependents
+    singletons: SharedCell<Vec<(ComponentInstanceAnyPtr, Option<PreDestroy>)>>,
+    post_processors: ComponentInstancePtr<Vec<InstancePostProcessorPtr>>,
+    evictions: FxHashMap<String, ScopeEvictions>,
+    // instances being constructed, so concurrent requests wait for them instead of constructing
+    // their own
+    constructions: SharedCell<Constructions>,
+    #[cfg(all(feature = "threadsafe", not(feature = "async")))]
+    construction_finished: Condvar,
+}
+
+impl ComponentFactoryState {
+    // must be called after releasing the lock of given scope
+    fn notify_evictions(&self, scope: &str) {
+        let evictions = match self.evictions.get(scope) {
+            Some(evictions) => evictions,
+            None => return,
+        };
+
+        loop {
+            // if the listener is busy, the call using it will pick up the pending instances
+            let mut listener = match try_borrow_shared(&evictions.listener) {
+                Some(listener) => listener,
+                None => return,
+            };
+
+            loop {
+                let evicted = std::mem::take(&mut *borrow_shared(&evictions.pending));
+                if evicted.is_empty() {
+                    break;
+                }
+
+                if let Some(listener) = listener.as_mut() {
+                    for (key, instance) in evicted {
+                        listener(&key, instance);
+                    }
+                }
+            }
+
+            drop(listener);
+
+            // instances evicted while the listener was busy might have been left for this call
+            if borrow_shared(&evictions.pending).is_empty() {
+                return;
+            }
+        }
+    }
+}
+
+/// Generic factory for [Component](crate::component::Component) instances. Uses definitions from
+/// the [ComponentDefinitionRegistry] and [scopes](crate::scope) to create and store instances for
+/// reuse.
+///
+/// The factory can hand out [shared handles](ComponentInstanceProvider::shared_handle) to itself,
+/// which is used by [ComponentProvider](crate::instance_provider::ComponentProvider) to create
+/// instances on demand. All handles share definitions and scopes, but don't keep the factory alive,
+/// so singletons holding them don't create reference cycles. Using a handle after the factory has
+/// been dropped results in [ProviderDropped](ComponentInstanceProviderError::ProviderDropped).
+///
+/// Instances can also be resolved through a shared reference, since `&ComponentFactory` is a
+/// [ComponentInstanceProvider] itself, which allows multiple threads or tasks to resolve
+/// components concurrently without wrapping the factory in a mutex:
+///
+/// ```
+/// use springtime_di::factory::ComponentFactoryBuilder;
+/// use springtime_di::instance_provider::TypedComponentInstanceProvider;
+/// use springtime_di::Component;
+///
+/// #[derive(Component)]
+/// struct Service;
+///
+/// let factory = ComponentFactoryBuilder::new()
+///     .expect("error initializing ComponentFactoryBuilder")
+///     .build();
+///
+/// # #[cfg(not(feature = "async"))]
+/// let service = (&factory).primary_instance_typed::<Service>();
+/// ```
+///
+/// Instances requested concurrently from scopes other than [PROTOTYPE] are constructed only once -
+/// later requests wait for the first construction to finish and share its instance. Dependency
+/// cycles spanning multiple threads or tasks are reported as errors instead of waiting forever.
+///
+/// The factory is also a [ComponentDefinitionRegistry], which allows registering new components at
+/// runtime, e.g. plugins discovered after startup, using metadata created with
+/// [ComponentMetadata::for_component] or [ComponentMetadata::new]. New definitions are immediately
+/// visible to all handles, but instances which have already been created are not affected, e.g.
+/// existing `Vec<ComponentInstancePtr<dyn Trait>>` dependencies won't contain new components.
+/// Removing or replacing a definition also removes its instances from all scopes, so new ones are
+/// created according to the new definition.
+pub struct ComponentFactory {
+    state: ComponentInstancePtr<ComponentFactoryState>,
+    // ids and names of types being constructed, in construction order
+    types_under_construction: Vec<(ComponentDefinitionKey, String)>,
+}
+
+impl ComponentFactory {
+    /// Creates a new factory with given registry and scope factories. The factory map should
+    /// include built-in [SINGLETON] and [PROTOTYPE] for maximum compatibility with components,
+    /// since they are usually the most popular. This is not a hard requirement, but care needs to
+    /// be taken to ensue no component uses them.
+    pub fn new(
+        definition_registry: ComponentDefinitionRegistryPtr,
+        scope_factories: FxHashMap<String, ScopeFactoryPtr>,
+    ) -> Self {
+        Self::new_with_post_processors(definition_registry, scope_factories, vec![])
+    }
+
+    /// Creates a new factory like [new](Self::new), but additionally using given
+    /// [post-processors](crate::post_processor) for created instances.
+    pub fn new_with_post_processors(
+        definition_registry: ComponentDefinitionRegistryPtr,
+        scope_factories: FxHashMap<String, ScopeFactoryPtr>,
+        post_processors: Vec<InstancePostProcessorPtr>,
+    ) -> Self {
+        Self::from_parts(
+            definition_registry,
+            ComponentInstancePtr::new(scope_factories),
+            ComponentInstancePtr::new(post_processors),
+            Default::default(),
+        )
+    }
+
+    /// Creates an independent factory with a copy of the definitions, sharing scope factories,
+    /// post-processors and [seeded](ComponentFactoryBuilder::with_instance) instances with this
+    /// one, but with new, empty scopes. Useful for giving parallel tests isolated singletons
+    /// without rebuilding the registry. Definitions registered or removed at runtime only affect
+    /// the factory doing it. Fails if the registry doesn't support
+    /// [cloning](ComponentDefinitionRegistry::clone_registry).
+    pub fn fork(&self) -> Result<Self, ComponentDefinitionRegistryError> {
+        let definition_registry = borrow_shared(&self.state.definition_registry)
+            .clone_registry()
+            .ok_or(ComponentDefinitionRegistryError::CloningUnsupported)?;
+
+        Ok(Self::from_parts(
+            definition_registry,
+            self.state.scope_factories.clone(),
+            self.state.post_processors.clone(),
+            self.state.seeds.clone(),
+        ))
+    }
+
+    fn from_parts(
+        definition_registry: ComponentDefinitionRegistryPtr,
+        scope_factories: ComponentInstancePtr<FxHashMap<String, ScopeFactoryPtr>>,
+        post_processors: ComponentInstancePtr<Vec<InstancePostProcessorPtr>>,
+        seeds: ComponentInstancePtr<Vec<(TypeId, ComponentInstanceAnyPtr)>>,
+    ) -> Self {
+        let (scopes, evictions) = create_scopes(&scope_factories);
+        let factory = Self {
+            state: ComponentInstancePtr::new(ComponentFactoryState {
+                definition_registry: SharedCell::new(definition_registry),
+                scope_factories,
+                seeds,
+                scopes,
+                evictions,
+                singletons: Default::default(),
+                constructions: Default::default(),
+                #[cfg(all(feature = "threadsafe", not(feature = "async")))]
+                construction_finished: Default::default(),
+                post_processors,
+            }),
+            types_under_construction: Default::default(),
+        };
+
+        factory.seed_instances();
+        factory
+    }
+
+    // new handle sharing the state, but with its own construction chain
+    fn share(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            types_under_construction: Default::default(),
+        }
+    }
+
+    fn downgrade(&self) -> WeakComponentFactory {
+        WeakComponentFactory {
+            state: ComponentInstancePtr::downgrade(&self.state),
+        }
+    }
+
+    /// Destroys all singleton instances in reverse creation order, so dependents are destroyed
+    /// before their dependencies, and calls
+    /// [DisposableComponent::destroy](crate::component::DisposableComponent::destroy) on the
+    /// disposable ones. All instances are destroyed even if some of them fail, and the first error
+    /// is returned. New instances are created on demand if the factory is used afterwards.
+    #[cfg(feature = "async")]
+    pub async fn shutdown(&self) -> Result<(), ErrorPtr> {
+        self.reset_singleton_scope();
+
+        let mut result = Ok(());
+        while let Some((instance, pre_destroy)) = self.pop_singleton() {
+            if let Some(pre_destroy) = pre_destroy {
+                if let Err(error) = pre_destroy(instance).await {
+                    warn!(%error, "Error destroying component instance.");
+                    if result.is_ok() {
+                        result = Err(error);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Destroys all singleton instances in reverse creation order, so dependents are destroyed
+    /// before their dependencies, and calls
+    /// [DisposableComponent::destroy](crate::component::DisposableComponent::destroy) on the
+    /// disposable ones. All instances are destroyed even if some of them fail, and the first error
+    /// is returned. New instances are created on demand if the factory is used afterwards.
+    #[cfg(not(feature = "async"))]
+    pub fn shutdown(&self) -> Result<(), ErrorPtr> {
+        self.reset_singleton_scope();
+
+        let mut result = Ok(());
+        while let Some((instance, pre_destroy)) = self.pop_singleton() {
+            if let Some(pre_destroy) = pre_destroy {
+                if let Err(error) = pre_destroy(instance) {
+                    warn!(%error, "Error destroying component instance.");
+                    if result.is_ok() {
+                        result = Err(error);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Runs given closure with given context active in the [scope](crate::scope) with given name,
+    /// e.g. a request id in a request scope, so the scope can keep instances separately for it. The
+    /// context is active only on the current thread, so concurrent requests can run in different
+    /// contexts, and is exited when the closure returns. Contexts can be nested. See
+    /// [Scope::enter_context](crate::scope::Scope::enter_context).
+    #[cfg(not(feature = "async"))]
+    pub fn in_scope_context<R>(
+        &self,
+        scope: &str,
+        context: &str,
+        f: impl FnOnce() -> R,
+    ) -> Result<R, ComponentInstanceProviderError> {
+        let _entry = self.enter_scope_context(scope, context)?;
+        let _context = ActiveContextGuard::new(scope, context);
+        Ok(f())
+    }
+
+    /// Wraps given future, so given context is active in the [scope](crate::scope) with given name
+    /// whenever the future is polled, e.g. a request id in a request scope, so the scope can keep
+    /// instances separately for it. Concurrent tasks can run in different contexts and contexts
+    /// can be nested. The context is entered immediately and exited when the returned future is
+    /// dropped. Note: tasks spawned by the future need to be wrapped separately. See
+    /// [Scope::enter_context](crate::scope::Scope::enter_context).
+    #[cfg(feature = "async")]
+    pub fn in_scope_context<F: Future>(
+        &self,
+        scope: &str,
+        context: &str,
+        future: F,
+    ) -> Result<ScopeContextFuture<F>, ComponentInstanceProviderError> {
+        self.enter_scope_context(scope, context)
+            .map(|entry| ScopeContextFuture {
+                future: Box::pin(future),
+                entry,
+            })
+    }
+
+    /// Removes all instances stored in the [scope](crate::scope) with given name, so new ones are
+    /// created on demand, e.g. to release resources held by a tenant scope. Instances already
+    /// injected into other components are not affected. Note: evicted singletons are released by
+    /// the factory and not destroyed on [shutdown](Self::shutdown), so disposable ones should be
+    /// cleaned up by an [eviction listener](Self::set_eviction_listener).
+    /// [Seeded](ComponentFactoryBuilder::with_instance) singletons are stored again.
+    pub fn clear_scope(&self, scope: &str) -> Result<(), ComponentInstanceProviderError> {
+        self.modify_scope(scope, |scope| scope.clear())?;
+        if scope == SINGLETON {
+            borrow_shared(&self.state.singletons).clear();
+            self.seed_instances();
+        }
+
+        Ok(())
+    }
+
+    /// Sets the listener notified about instances evicted from the [scope](crate::scope) with
+    /// given name, e.g. to close files or flush metrics when a context is exited. The listener is
+    /// called after the scope is unlocked, so it can use the factory, e.g. to resolve other
+    /// components. See [Scope::set_eviction_listener](crate::scope::Scope::set_eviction_listener).
+    pub fn set_eviction_listener(
+        &self,
+        scope: &str,
+        listener: EvictionListener,
+    ) -> Result<(), ComponentInstanceProviderError> {
+        let evictions =
+            self.state.evictions.get(scope).ok_or_else(|| {
+                ComponentInstanceProviderError::UnrecognizedScope(scope.to_string())
+            })?;
+
+        *borrow_shared(&evictions.listener) = Some(listener);
+        Ok(())
+    }
+
+    /// Removes instances of all components registered for given type from all scopes, so new ones
+    /// are created on demand. Like with [clear_scope](Self::clear_scope), evicted singletons are
+    /// not destroyed on [shutdown](Self::shutdown).
+    pub fn evict_instances(&self, type_id: TypeId) {
+        let definitions =
+            borrow_shared(&self.state.definition_registry).components_by_type(type_id);
+
+        for definition in &definitions {
+            self.remove_instances(definition);
+        }
+    }
+
+    fn modify_scope(
+        &self,
+        name: &str,
+        modify: impl FnOnce(&mut ScopePtr),
+    ) -> Result<(), ComponentInstanceProviderError> {
+        let scope = self.scope(name)?;
+        modify(&mut borrow_shared(scope));
+        self.state.notify_evictions(name);
+        Ok(())
+    }
+
+    fn enter_scope_context(
+        &self,
+        scope: &str,
+        context: &str,
+    ) -> Result<ScopeContextEntry, ComponentInstanceProviderError> {
+        self.modify_scope(scope, |scope| scope.enter_context(context))
+            .map(|_| ScopeContextEntry {
+                state: self.state.clone(),
+                scope: scope.to_string(),
+                context: context.to_string(),
+            })
+    }
+
+    fn scope(&self, name: &str) -> Result<&SharedCell<ScopePtr>, ComponentInstanceProviderError> {
+        self.state
+            .scopes
+            .get(name)
+            .ok_or_else(|| ComponentInstanceProviderError::UnrecognizedScope(name.to_string()))
+    }
+
+    // singletons are created anew after shutdown, apart from seeded ones
+    fn reset_singleton_scope(&self) {
+        if let (Some(scope), Some(factory), Some(evictions)) = (
+            self.state.scopes.get(SINGLETON),
+            self.state.scope_factories.get(SINGLETON),
+            self.state.evictions.get(SINGLETON),
+        ) {
+            *borrow_shared(scope) = create_scope(factory, evictions);
+        }
+
+        self.seed_instances();
+    }
+
+    fn seed_instances(&self) {
+        for (type_id, instance) in self.state.seeds.iter() {
+            self.seed_instance(*type_id, instance.clone());
+        }
+    }
+
+    fn seed_instance(&self, type_id: TypeId, instance: ComponentInstanceAnyPtr) {
+        let definition = borrow_shared(&self.state.definition_registry).primary_component(type_id);
+        match (definition, self.state.scopes.get(SINGLETON)) {
+            (Some(definition), Some(scope)) => {
+                borrow_shared(scope).store_instance(&definition, instance);
+                self.state.notify_evictions(SINGLETON);
+            }
+            _ => warn!(
+                ?type_id,
+                "Cannot seed component instance without a singleton scope."
+            ),
+        }
+    }
+
+    fn filtered_definitions(
+        &self,
+        type_id: TypeId,
+        filter: &InstanceFilter,
+    ) -> Vec<ComponentDefinitionPtr> {
+        let mut definitions =
+            borrow_shared(&self.state.definition_registry).components_by_type(type_id);
+        definitions.retain(|definition| filter(definition));
+        definitions
+    }
+
+    // concrete singleton definitions in a stable order
+    fn singleton_definitions(&self) -> Vec<ComponentDefinitionPtr> {
+        let mut definitions = vec![];
+        borrow_shared(&self.state.definition_registry).visit_definitions(
+            &mut |type_id, type_definitions| {
+                definitions.extend(
+                    type_definitions
+                        .iter()
+                        .filter(|definition| {
+                            definition.resolved_type_id == type_id && definition.scope == SINGLETON
+                        })
+                        .cloned(),
+                );
+            },
+        );
+
+        definitions
+            .sort_by(|first, second| first.resolved_type_name.cmp(&second.resolved_type_name));
+        definitions
+    }
+
+    fn primary_definition(
+        &self,
+        type_id: TypeId,
+    ) -> Result<ComponentDefinitionPtr, ComponentInstanceProviderError> {
+        let registry = borrow_shared(&self.state.definition_registry);
+        registry.primary_component(type_id).ok_or_else(|| {
+            let candidates = registry.components_by_type(type_id);
+            if candidates.is_empty() {
+                ComponentInstanceProviderError::NoPrimaryInstance {
+                    type_id,
+                    type_name: None,
+                }
+            } else {
+                ComponentInstanceProviderError::AmbiguousPrimaryInstance {
+                    type_id,
+                    type_name: None,
+                    candidates: candidates
+                        .iter()
+                        .map(|definition| match &definition.qualifier {
+                            Some(qualifier) => {
+                                format!("{}({qualifier})", definition.resolved_type_name)
+                            }
+                            None => definition.resolved_type_name.clone(),
+                        })
+                        .collect(),
+                }
+            }
+        })
+    }
+
+    // instances of removed or replaced definitions should not be reused
+    fn remove_instances(&self, definition: &ComponentDefinition) {
+        for (name, scope) in &self.state.scopes {
+            let mut scope = borrow_shared(scope);
+            let singleton = (name == SINGLETON)
+                .then(|| scope.instance(definition))
+                .flatten();
+
+            scope.remove_instance(definition);
+            drop(scope);
+            self.state.notify_evictions(name);
+
+            if let Some(singleton) = singleton {
+                self.forget_singleton(&singleton);
+            }
+        }
+    }
+
+    // evicted singletons shouldn't be kept alive until shutdown
+    fn forget_singleton(&self, instance: &ComponentInstanceAnyPtr) {
+        borrow_shared(&self.state.singletons)
+            .retain(|(singleton, _)| !ComponentInstancePtr::ptr_eq(singleton, instance));
+    }
+
+    fn pop_singleton(&self) -> Option<(ComponentInstanceAnyPtr, Option<PreDestroy>)> {
+        borrow_shared(&self.state.singletons).pop()
+    }
+
+    /// Exports the contents of the underlying [ComponentDefinitionRegistry] for external tooling.
+    pub fn export_definitions(&self) -> ComponentRegistryExport {
+        borrow_shared(&self.state.definition_registry).export()
+    }
+
+    /// Validates the component graph without creating any instances. Please see
+    /// [validation](crate::component_registry::validation) for details. Returns an empty list if no
+    /// problems are found.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let registry = borrow_shared(&self.state.definition_registry);
+        validate_definitions(&**registry, |scope| {
+            self.state.scope_factories.contains_key(scope)
+        })
+    }
+
+    #[cfg(feature = "async")]
+    async fn call_constructor(
+        &mut self,
+        definition: &ComponentDefinition,
+    ) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
+        self.types_under_construction
+            .push((definition.key(), definition.resolved_type_name.clone()));
+        let instance = (definition.constructor)(self)
+            .await
+            .map_err(|error| self.add_construction_path(error));
+        self.types_under_construction.pop();
+
+        instance
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn call_constructor(
+        &mut self,
+        definition: &ComponentDefinition,
+    ) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
+        self.types_under_construction
+            .push((definition.key(), definition.resolved_type_name.clone()));
+        let instance =
+            (definition.constructor)(self).map_err(|error| self.add_construction_path(error));
+        self.types_under_construction.pop();
+
+        instance
+    }
+
+    // errors deep in the graph are wrapped once, where they occur, so the path leads to the
+    // component which failed; errors of directly requested components are passed as they are
+    fn add_construction_path(
+        &self,
+        error: ComponentInstanceProviderError,
+    ) -> ComponentInstanceProviderError {
+        if self.types_under_construction.len() < 2
+            || matches!(
+                error,
+                ComponentInstanceProviderError::DependencyError { .. }
+            )
+        {
+            return error;
+        }
+
+        ComponentInstanceProviderError::DependencyError {
+            path: self
+                .types_under_construction
+                .iter()
+                .map(|(_, type_name)| type_name.clone())
+                .collect(),
+            source: Box::new(error),
+        }
+    }
+
+    fn check_scope_instance(
+        &mut self,
+        definition: &ComponentDefinition,
+    ) -> Result<Option<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError>
+    {
+        if self.types_under_construction.iter().any(|(key, _)| {
+            key.type_id == definition.resolved_type_id && key.qualifier == definition.qualifier
+        }) {
+            return Err(self.cycle_error(definition));
+        }
+
+        Ok(
+            active_instance(&borrow_shared(self.scope(&definition.scope)?), definition)
+                .map(|instance| (instance, definition.cast)),
+        )
+    }
+
+    fn cycle_error(&self, definition: &ComponentDefinition) -> ComponentInstanceProviderError {
+        let error = ComponentInstanceProviderError::DependencyCycle {
+            type_id: definition.resolved_type_id,
+            type_name: None,
+        };
+
+        // the path ends with the whole cycle, so it can be found without looking at the code
+        if self.types_under_construction.len() < 2 {
+            return error;
+        }
+
+        ComponentInstanceProviderError::DependencyError {
+            path: self
+                .types_under_construction
+                .iter()
+                .map(|(_, type_name)| type_name.clone())
+                .chain([definition.resolved_type_name.clone()])
+                .collect(),
+            source: Box::new(error),
+        }
+    }
+
+    // prototypes are never shared, so they can be constructed concurrently
+    fn construction_key(&self, definition: &ComponentDefinition) -> Option<ConstructionKey> {
+        (definition.scope != PROTOTYPE).then(|| {
+            (
+                definition.key(),
+                with_active_context(&definition.scope, |context| context.map(str::to_string)),
+            )
+        })
+    }
+
+    #[cfg(any(feature = "threadsafe", feature = "async"))]
+    fn chain_keys(&self) -> Vec<ComponentDefinitionKey> {
+        self.types_under_construction
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    // starts constructing given instance or waits until another handle finishes constructing it,
+    // in which case None is returned and the scope should be checked again
+    #[cfg(not(feature = "async"))]
+    fn begin_construction(
+        &self,
+        definition: &ComponentDefinition,
+    ) -> Result<Option<ConstructionGuard>, ComponentInstanceProviderError> {
+        let key = match self.construction_key(definition) {
+            Some(key) => key,
+            None => return Ok(Some(ConstructionGuard::unguarded(self.state.clone()))),
+        };
+
+        let mut constructions = borrow_shared(&self.state.constructions);
+        let owner = match constructions.in_flight.get(&key) {
+            Some(construction) => construction,
+            None => {
+                constructions.in_flight.insert(
+                    key.clone(),
+                    InFlightConstruction {
+                        #[cfg(feature = "threadsafe")]
+                        thread: std::thread::current().id(),
+                    },
+                );
+
+                return Ok(Some(ConstructionGuard::new(self.state.clone(), key)));
+            }
+        };
+
+        // without threads, the instance can only be constructed further up the current call stack
+        #[cfg(not(feature = "threadsafe"))]
+        {
+            let _ = owner;
+            Err(self.cycle_error(definition))
+        }
+
+        #[cfg(feature = "threadsafe")]
+        {
+            let chain = self.chain_keys();
+            if owner.thread == std::thread::current().id()
+                || constructions.would_deadlock(&key.0, &chain)
+            {
+                return Err(self.cycle_error(definition));
+            }
+
+            let wait_id = constructions.start_waiting(chain, key.0.clone());
+            drop(constructions);
+
+            let mut constructions = self
+                .state
+                .constructions
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            while constructions.in_flight.contains_key(&key) {
+                constructions = self
+                    .state
+                    .construction_finished
+                    .wait(constructions)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+
+            constructions.waiting.remove(&wait_id);
+            Ok(None)
+        }
+    }
+
+    // starts constructing given instance or returns a future finishing when another handle
+    // finishes constructing it, after which the scope should be checked again
+    #[cfg(feature = "async")]
+    fn begin_construction(
+        &self,
+        definition: &ComponentDefinition,
+    ) -> Result<Result<ConstructionGuard, ConstructionWait>, ComponentInstanceProviderError> {
+        let key = match self.construction_key(definition) {
+            Some(key) => key,
+            None => return Ok(Ok(ConstructionGuard::unguarded(self.state.clone()))),
+        };
+
+        let mut constructions = borrow_shared(&self.state.constructions);
+        if let Some(construction) = constructions.in_flight.get(&key) {
+            let finished = construction.finished.clone();
+            let chain = self.chain_keys();
+            if constructions.would_deadlock(&key.0, &chain) {
+                return Err(self.cycle_error(definition));
+            }
+
+            let wait_id = constructions.start_waiting(chain, key.0);
+            return Ok(Err(ConstructionWait {
+                state: self.state.clone(),
+                wait_id,
+                finished,
+            }));
+        }
+
+        let (finished_sender, finished) = oneshot::channel();
+        constructions.in_flight.insert(
+            key.clone(),
+            InFlightConstruction {
+                finished: finished.shared(),
+            },
+        );
+
+        let mut guard = ConstructionGuard::new(self.state.clone(), key);
+        guard.finished = Some(finished_sender);
+        Ok(Ok(guard))
+    }
+
+    // returns the instance stored in the meantime by another handle, if any, which should be used
+    // instead of the given one
+    fn store_instance_in_scope(
+        &self,
+        definition: &ComponentDefinition,
+        instance: ComponentInstanceAnyPtr,
+    ) -> Result<Option<ComponentInstanceAnyPtr>, ComponentInstanceProviderError> {
+        let mut scope = borrow_shared(self.scope(&definition.scope)?);
+        if let Some(existing) = active_instance(&scope, definition) {
+            return Ok(Some(existing));
+        }
+
+        with_active_context(&definition.scope, |context| match context {
+            Some(context) => scope.store_context_instance(definition, context, instance.clone()),
+            None => scope.store_instance(definition, instance.clone()),
+        });
+        drop(scope);
+        self.state.notify_evictions(&definition.scope);
+
+        if definition.scope == SINGLETON {
+            borrow_shared(&self.state.singletons).push((instance, definition.pre_destroy));
+        }
+
+        Ok(None)
+    }
+
+    fn is_stored_in_scope(
+        &self,
+        definition: &ComponentDefinition,
+    ) -> Result<bool, ComponentInstanceProviderError> {
+        Ok(active_instance(&borrow_shared(self.scope(&definition.scope)?), definition).is_some())
+    }
+
+    // instances which failed to initialize shouldn't be handed out later on
+    fn discard_instance(
+        &self,
+        definition: &ComponentDefinition,
+        instance: &ComponentInstanceAnyPtr,
+    ) {
+        if let Ok(scope) = self.scope(&definition.scope) {
+            let mut scope = borrow_shared(scope);
+            if active_instance(&scope, definition)
+                .map(|stored| ComponentInstancePtr::ptr_eq(&stored, instance))
+                .unwrap_or(false)
+            {
+                scope.remove_instance(definition);
+            }
+
+            drop(scope);
+            self.state.notify_evictions(&definition.scope);
+        }
+
+        if definition.scope == SINGLETON {
+            self.forget_singleton(instance);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    async fn create_instance(
+        &mut self,
+        definition: &ComponentDefinition,
+    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
+        let _construction = loop {
+            if let Some(instance) = self.check_scope_instance(definition)? {
+                return Ok(instance);
+            }
+
+            match self.begin_construction(definition)? {
+                Ok(construction) => {
+                    // the instance might have been stored right before starting the construction
+                    if let Some(instance) = self.check_scope_instance(definition)? {
+                        return Ok(instance);
+                    }
+
+                    break construction;
+                }
+                Err(wait) => wait.finished().await,
+            }
+        };
+
+        let span = construction_span(definition);
+        let start = start_timer(&span);
+        let result = self
+            .construct_instance(definition)
+            .instrument(span.clone())
+            .await;
+
+        record_elapsed(&span, start);
+        result
+    }
+
+    #[cfg(feature = "async")]
+    async fn construct_instance(
+        &mut self,
+        definition: &ComponentDefinition,
+    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
+        debug!("Creating new component instance.");
+
+        let mut instance = self.call_constructor(definition).await?;
+
+        let state = self.state.clone();
+        for post_processor in state.post_processors.iter() {
+            instance = post_processor
+                .post_process(definition, instance)
+                .await
+                .map_err(ComponentInstanceProviderError::PostProcessorError)?;
+            check_post_processed_instance(definition, &instance)?;
+        }
+
+        if let Some(existing) = self.store_instance_in_scope(definition, instance.clone())? {
+            return Ok((existing, definition.cast));
+        }
+
+        if let Err(error) = self.initialize_instance(definition, &instance).await {
+            self.discard_instance(definition, &instance);
+            return Err(error);
+        }
+
+        Ok((instance, definition.cast))
+    }
+
+    #[cfg(feature = "async")]
+    async fn initialize_instance(
+        &mut self,
+        definition: &ComponentDefinition,
+        instance: &ComponentInstanceAnyPtr,
+    ) -> Result<(), ComponentInstanceProviderError> {
+        // at this point the instance is available in its scope, so late dependencies can refer
+        // back to it, unless it's not shared, e.g. a prototype - such instance stays on the
+        // construction chain, so referring back to it is reported as a cycle instead of
+        // recursing indefinitely
+        if let Some(late_injection) = definition.late_injection {
+            let shared = self.is_stored_in_scope(definition)?;
+            if !shared {
+                self.types_under_construction
+                    .push((definition.key(), definition.resolved_type_name.clone()));
+            }
+
+            let result = late_injection(instance.clone(), self).await;
+            if !shared {
+                self.types_under_construction.pop();
+            }
+
+            result?;
+        }
+
+        if let Some(post_construct) = definition.post_construct {
+            post_construct(instance.clone())
+                .await
+                .map_err(ComponentInstanceProviderError::PostConstructError)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn create_instance(
+        &mut self,
+        definition: &ComponentDefinition,
+    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
+        let _construction = loop {
+            if let Some(instance) = self.check_scope_instance(definition)? {
+                return Ok(instance);
+            }
+
+            if let Some(construction) = self.begin_construction(definition)? {
+                // the instance might have been stored right before starting the construction
+                if let Some(instance) = self.check_scope_instance(definition)? {
+                    return Ok(instance);
+                }
+
+                break construction;
+            }
+        };
+
+        let span = construction_span(definition);
+        let start = start_timer(&span);
+        let result = span.in_scope(|| self.construct_instance(definition));
+
+        record_elapsed(&span, start);
+        result
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn construct_instance(
+        &mut self,
+        definition: &ComponentDefinition,
+    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
+        debug!("Creating new component instance.");
+
+        let mut instance = self.call_constructor(definition)?;
+
+        for post_processor in self.state.post_processors.iter() {
+            instance = post_processor
+                .post_process(definition, instance)
+                .map_err(ComponentInstanceProviderError::PostProcessorError)?;
+            check_post_processed_instance(definition, &instance)?;
+        }
+
+        if let Some(existing) = self.store_instance_in_scope(definition, instance.clone())? {
+            return Ok((existing, definition.cast));
+        }
+
+        if let Err(error) = self.initialize_instance(definition, &instance) {
+            self.discard_instance(definition, &instance);
+            return Err(error);
+        }
+
+        Ok((instance, definition.cast))
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn initialize_instance(
+        &mut self,
+        definition: &ComponentDefinition,
+        instance: &ComponentInstanceAnyPtr,
+    ) -> Result<(), ComponentInstanceProviderError> {
+        // at this point the instance is available in its scope, so late dependencies can refer
+        // back to it, unless it's not shared, e.g. a prototype - such instance stays on the
+        // construction chain, so referring back to it is reported as a cycle instead of
+        // recursing indefinitely
+        if let Some(late_injection) = definition.late_injection {
+            let shared = self.is_stored_in_scope(definition)?;
+            if !shared {
+                self.types_under_construction
+                    .push((definition.key(), definition.resolved_type_name.clone()));
+            }
+
+            let result = late_injection(instance.clone(), self);
+            if !shared {
+                self.types_under_construction.pop();
+            }
+
+            result?;
+        }
+
+        if let Some(post_construct) = definition.post_construct {
+            post_construct(instance.clone())
+                .map_err(ComponentInstanceProviderError::PostConstructError)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ComponentDefinitionRegistry for ComponentFactory {
+    fn register_component(
+        &mut self,
+        target: TypeId,
+        target_name: &str,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        borrow_shared(&self.state.definition_registry).register_component(
+            target,
+            target_name,
+            metadata,
+        )
+    }
+
+    fn register_qualified_component(
+        &mut self,
+        target: TypeId,
+        target_name: &str,
+        qualifier: &str,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        borrow_shared(&self.state.definition_registry).register_qualified_component(
+            target,
+            target_name,
+            qualifier,
+            metadata,
+        )
+    }
+
+    fn register_alias(
+        &mut self,
+        alias_type: TypeId,
+        target_type: TypeId,
+        alias_name: &str,
+        target_name: &str,
+        metadata: &ComponentAliasMetadata,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        borrow_shared(&self.state.definition_registry).register_alias(
+            alias_type,
+            target_type,
+            alias_name,
+            target_name,
+            metadata,
+        )
+    }
+
+    fn remove_component(&mut self, target: TypeId) -> Option<ComponentDefinitionPtr> {
+        let mut definition_registry = borrow_shared(&self.state.definition_registry);
+
+        // qualified definitions are removed along with the default one
+        let mut removed = definition_registry.components_by_type(target);
+        removed.retain(|definition| definition.resolved_type_id == target);
+
+        let definition = definition_registry.remove_component(target);
+        drop(definition_registry);
+
+        for definition in &removed {
+            self.remove_instances(definition);
+        }
+
+        definition
+    }
+
+    fn remove_alias(
+        &mut self,
+        alias_type: TypeId,
+    ) -> Result<Vec<ComponentDefinitionPtr>, ComponentDefinitionRegistryError> {
+        // alias entries share instances with their components, so there's nothing to evict
+        borrow_shared(&self.state.definition_registry).remove_alias(alias_type)
+    }
+
+    fn replace_component(
+        &mut self,
+        target: TypeId,
+        target_name: &str,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        let mut definition_registry = borrow_shared(&self.state.definition_registry);
+        let previous = definition_registry
+            .components_by_type(target)
+            .into_iter()
+            .find(|definition| {
+                definition.resolved_type_id == target && definition.qualifier.is_none()
+            });
+
+        definition_registry.replace_component(target, target_name, metadata)?;
+        drop(definition_registry);
+
+        if let Some(previous) = &previous {
+            self.remove_instances(previous);
+        }
+
+        Ok(())
+    }
+
+    fn components_by_type(&self, type_id: TypeId) -> Vec<ComponentDefinitionPtr> {
+        borrow_shared(&self.state.definition_registry).components_by_type(type_id)
+    }
+
+    fn component_by_name(&self, name: &str, type_id: TypeId) -> Option<ComponentDefinitionPtr> {
+        borrow_shared(&self.state.definition_registry).component_by_name(name, type_id)
+    }
+
+    fn primary_component(&self, type_id: TypeId) -> Option<ComponentDefinitionPtr> {
+        borrow_shared(&self.state.definition_registry).primary_component(type_id)
+    }
+
+    fn set_primary_component(
+        &mut self,
+        type_id: TypeId,
+        name: &str,
+    ) -> Result<(), ComponentDefinitionRegistryError> {
+        borrow_shared(&self.state.definition_registry).set_primary_component(type_id, name)
+    }
+
+    fn definition_by_name(&self, name: &str) -> Option<ComponentDefinitionPtr> {
+        borrow_shared(&self.state.definition_registry).definition_by_name(name)
+    }
+
+    fn type_id_by_name(&self, type_name: &str) -> Result<TypeId, ComponentDefinitionRegistryError> {
+        borrow_shared(&self.state.definition_registry).type_id_by_name(type_name)
+    }
+
+    fn is_registered(&self, type_id: TypeId) -> bool {
+        borrow_shared(&self.state.definition_registry).is_registered(type_id)
+    }
+
+    fn is_name_registered(&self, name: &str) -> bool {
+        borrow_shared(&self.state.definition_registry).is_name_registered(name)
+    }
+
+    fn all_definitions(&self) -> FxHashMap<TypeId, Vec<ComponentDefinitionPtr>> {
+        borrow_shared(&self.state.definition_registry).all_definitions()
+    }
+
+    fn visit_definitions<'a>(&self, visitor: &mut DefinitionVisitor<'a>) {
+        borrow_shared(&self.state.definition_registry).visit_definitions(visitor)
+    }
+
+    fn shadowed_definitions(&self) -> Vec<ShadowedDefinition> {
+        borrow_shared(&self.state.definition_registry).shadowed_definitions()
+    }
+
+    fn clone_registry(&self) -> Option<ComponentDefinitionRegistryPtr> {
+        borrow_shared(&self.state.definition_registry).clone_registry()
+    }
+
+    fn export(&self) -> ComponentRegistryExport {
+        self.export_definitions()
+    }
+}
+
+impl ComponentInstanceProvider for ComponentFactory {
+    fn shared_handle(&self) -> Option<ComponentInstanceProviderPtr> {
+        Some(Box::new(self.downgrade()))
+    }
+
+    #[cfg(feature = "async")]
+    fn dependency_handle(&self) -> Option<ComponentInstanceProviderPtr> {
+        Some(Box::new(Self {
+            state: self.state.clone(),
+            types_under_construction: self.types_under_construction.clone(),
+        }))
+    }
+
+    #[cfg(feature = "async")]
+    fn instantiate_all_singletons(
+        &mut self,
+    ) -> BoxFuture<'_, Result<(), ComponentInstanceProviderError>> {
+        async move {
+            let start = enabled!(Level::INFO).then(Instant::now);
+            let definitions = self.singleton_definitions();
+            for definition in &definitions {
+                self.create_instance(definition).await?;
+            }
+
+            log_singletons_instantiated(definitions.len(), start);
+            Ok(())
+        }
+        .boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn instantiate_all_singletons(&mut self) -> Result<(), ComponentInstanceProviderError> {
+        let start = enabled!(Level::INFO).then(Instant::now);
+        let definitions = self.singleton_definitions();
+        for definition in &definitions {
+            self.create_instance(definition)?;
+        }
+
+        log_singletons_instantiated(definitions.len(), start);
+        Ok(())
+    }
+
+    fn register_missing_component(
+        &mut self,
+        target: TypeId,
+        target_name: &str,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentInstanceProviderError> {
+        let mut registry = borrow_shared(&self.state.definition_registry);
+        if registry.is_registered(target) {
+            return Ok(());
+        }
+
+        registry
+            .register_component(target, target_name, metadata)
+            .map_err(ComponentInstanceProviderError::RegistrationError)
+    }
+
+    #[cfg(feature = "async")]
+    fn primary_instance(
+        &mut self,
+        type_id: TypeId,
+    ) -> BoxFuture<
+        '_,
+        Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>,
+    > {
+        async move {
+            let definition = self.primary_definition(type_id)?;
+
+            self.create_instance(&definition).await
+        }
+        .boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn primary_instance(
+        &mut self,
+        type_id: TypeId,
+    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
+        let definition = self.primary_definition(type_id)?;
+
+        self.create_instance(&definition)
+    }
+
+    #[cfg(feature = "async")]
+    fn instances(
+        &mut self,
+        type_id: TypeId,
+    ) -> BoxFuture<
+        '_,
+        Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError>,
+    > {
+        async move {
+            let definitions =
+                borrow_shared(&self.state.definition_registry).components_by_type(type_id);
+
+            let mut result = Vec::with_capacity(definitions.len());
+            for definition in &definitions {
+                result.push(self.create_instance(definition).await?);
+            }
+
+            Ok(result)
+        }
+        .boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn instances(
+        &mut self,
+        type_id: TypeId,
+    ) -> Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError> {
+        let definitions =
+            borrow_shared(&self.state.definition_registry).components_by_type(type_id);
+
+        definitions
+            .iter()
+            .map(|definition| self.create_instance(definition))
+            .try_collect()
+    }
+
+    #[cfg(feature = "async")]
+    fn filtered_instances<'a>(
+        &mut self,
+        type_id: TypeId,
+        filter: &InstanceFilter<'a>,
+    ) -> BoxFuture<
+        '_,
+        Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError>,
+    > {
+        let definitions = self.filtered_definitions(type_id, filter);
+        async move {
+            let mut result = Vec::with_capacity(definitions.len());
+            for definition in &definitions {
+                result.push(self.create_instance(definition).await?);
+            }
+
+            Ok(result)
+        }
+        .boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn filtered_instances<'a>(
+        &mut self,
+        type_id: TypeId,
+        filter: &InstanceFilter<'a>,
+    ) -> Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError> {
+        self.filtered_definitions(type_id, filter)
+            .iter()
+            .map(|definition| self.create_instance(definition))
+            .try_collect()
+    }
+
+    #[cfg(feature = "async")]
+    fn named_instances(
+        &mut self,
+        type_id: TypeId,
+    ) -> BoxFuture<'_, Result<Vec<NamedInstance>, ComponentInstanceProviderError>> {
+        async move {
+            let definitions =
+                borrow_shared(&self.state.definition_registry).components_by_type(type_id);
+
+            let mut result = Vec::with_capacity(definitions.len());
+            for definition in &definitions {
+                let (instance, cast) = self.create_instance(definition).await?;
+                result.extend(
+                    definition
+                        .names
+                        .iter()
+                        .map(|name| (name.clone(), instance.clone(), cast)),
+                );
+            }
+
+            Ok(result)
+        }
+        .boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn named_instances(
+        &mut self,
+        type_id: TypeId,
+    ) -> Result<Vec<NamedInstance>, ComponentInstanceProviderError> {
+        let definitions =
+            borrow_shared(&self.state.definition_registry).components_by_type(type_id);
+
+        let mut result = Vec::with_capacity(definitions.len());
+        for definition in &definitions {
+            let (instance, cast) = self.create_instance(definition)?;
+            result.extend(
+                definition
+                    .names
+                    .iter()
+                    .map(|name| (name.clone(), instance.clone(), cast)),
+            );
+        }
+
+        Ok(result)
+    }
+
+    #[cfg(feature = "async")]
+    fn instance_by_name(
+        &mut self,
+        name: &str,
+        type_id: TypeId,
+    ) -> BoxFuture<
+        '_,
+        Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>,
+    > {
+        let name = name.to_string();
+        async move {
+            let definition = borrow_shared(&self.state.definition_registry)
+                .component_by_name(&name, type_id)
+                .ok_or_else(|| ComponentInstanceProviderError::NoNamedInstance(name.to_string()))?;
+
+            self.create_instance(&definition).await
+        }
+        .boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn instance_by_name(
+        &mut self,
+        name: &str,
+        type_id: TypeId,
+    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
+        let definition = borrow_shared(&self.state.definition_registry)
+            .component_by_name(name, type_id)
+            .ok_or_else(|| ComponentInstanceProviderError::NoNamedInstance(name.to_string()))?;
+
+        self.create_instance(&definition)
+    }
+
+    #[cfg(feature = "async")]
+    fn any_instance_by_name(
+        &mut self,
+        name: &str,
+    ) -> BoxFuture<
+        '_,
+        Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>,
+    > {
+        let name = name.to_string();
+        async move {
+            let definition = borrow_shared(&self.state.definition_registry)
+                .definition_by_name(&name)
+                .ok_or(ComponentInstanceProviderError::NoNamedInstance(name))?;
+
+            self.create_instance(&definition).await
+        }
+        .boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn any_instance_by_name(
+        &mut self,
+        name: &str,
+    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
+        let definition = borrow_shared(&self.state.definition_registry)
+            .definition_by_name(name)
+            .ok_or_else(|| ComponentInstanceProviderError::NoNamedInstance(name.to_string()))?;
+
+        self.create_instance(&definition)
+    }
+}
+
+// each request is resolved by a separate handle with its own construction chain, so concurrent
+// requests don't interfere with each other
+impl ComponentInstanceProvider for &ComponentFactory {
+    fn shared_handle(&self) -> Option<ComponentInstanceProviderPtr> {
+        Some(Box::new(self.downgrade()))
+    }
+
+    #[cfg(feature = "async")]
+    fn dependency_handle(&self) -> Option<ComponentInstanceProviderPtr> {
+        (**self).dependency_handle()
+    }
+
+    #[cfg(feature = "async")]
+    fn instantiate_all_singletons(
+        &mut self,
+    ) -> BoxFuture<'_, Result<(), ComponentInstanceProviderError>> {
+        let mut handle = self.share();
+        async move { handle.instantiate_all_singletons().await }.boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn instantiate_all_singletons(&mut self) -> Result<(), ComponentInstanceProviderError> {
+        self.share().instantiate_all_singletons()
+    }
+
+    fn register_missing_component(
+        &mut self,
+        target: TypeId,
+        target_name: &str,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentInstanceProviderError> {
+        self.share()
+            .register_missing_component(target, target_name, metadata)
+    }
+
+    #[cfg(feature = "async")]
+    fn primary_instance(
+        &mut self,
+        type_id: TypeId,
+    ) -> BoxFuture<
+        '_,
+        Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>,
+    > {
+        let mut handle = self.share();
+        async move { handle.primary_instance(type_id).await }.boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn primary_instance(
+        &mut self,
+        type_id: TypeId,
+    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
+        self.share().primary_instance(type_id)
+    }
+
+    #[cfg(feature = "async")]
+    fn instances(
+        &mut self,
+        type_id: TypeId,
+    ) -> BoxFuture<
+        '_,
+        Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError>,
+    > {
+        let mut handle = self.share();
+        async move { handle.instances(type_id).await }.boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn instances(
+        &mut self,
+        type_id: TypeId,
+    ) -> Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError> {
+        self.share().instances(type_id)
+    }
+
+    #[cfg(feature = "async")]
+    fn filtered_instances<'a>(
+        &mut self,
+        type_id: TypeId,
+        filter: &InstanceFilter<'a>,
+    ) -> BoxFuture<
+        '_,
+        Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError>,
+    > {
+        let definitions = self.filtered_definitions(type_id, filter);
+        let mut handle = self.share();
+        async move {
+            let mut result = Vec::with_capacity(definitions.len());
+            for definition in &definitions {
+                result.push(handle.create_instance(definition).await?);
+            }
+
+            Ok(result)
+        }
+        .boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn filtered_instances<'a>(
+        &mut self,
+        type_id: TypeId,
+        filter: &InstanceFilter<'a>,
+    ) -> Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError> {
+        self.share().filtered_instances(type_id, filter)
+    }
+
+    #[cfg(feature = "async")]
+    fn named_instances(
+        &mut self,
+        type_id: TypeId,
+    ) -> BoxFuture<'_, Result<Vec<NamedInstance>, ComponentInstanceProviderError>> {
+        let mut handle = self.share();
+        async move { handle.named_instances(type_id).await }.boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn named_instances(
+        &mut self,
+        type_id: TypeId,
+    ) -> Result<Vec<NamedInstance>, ComponentInstanceProviderError> {
+        self.share().named_instances(type_id)
+    }
+
+    #[cfg(feature = "async")]
+    fn instance_by_name(
+        &mut self,
+        name: &str,
+        type_id: TypeId,
+    ) -> BoxFuture<
+        '_,
+        Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>,
+    > {
+        let mut handle = self.share();
+        let name = name.to_string();
+        async move { handle.instance_by_name(&name, type_id).await }.boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn instance_by_name(
+        &mut self,
+        name: &str,
+        type_id: TypeId,
+    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
+        self.share().instance_by_name(name, type_id)
+    }
+
+    #[cfg(feature = "async")]
+    fn any_instance_by_name(
+        &mut self,
+        name: &str,
+    ) -> BoxFuture<
+        '_,
+        Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>,
+    > {
+        let mut handle = self.share();
+        let name = name.to_string();
+        async move { handle.any_instance_by_name(&name).await }.boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn any_instance_by_name(
+        &mut self,
+        name: &str,
+    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
+        self.share().any_instance_by_name(name)
+    }
+}
+
+// instances are resolved from the context active for the caller, if any
+fn active_instance(
+    scope: &ScopePtr,
+    definition: &ComponentDefinition,
+) -> Option<ComponentInstanceAnyPtr> {
+    with_active_context(&definition.scope, |context| match context {
+        Some(context) => scope.context_instance(definition, context),
+        None => scope.instance(definition),
+    })
+}
+
+// definition and scope context of an instance being constructed
+type ConstructionKey = (ComponentDefinitionKey, Option<String>);
+
+#[derive(Default)]
+struct Constructions {
+    in_flight: FxHashMap<ConstructionKey, InFlightConstruction>,
+    // construction chains waiting for other constructions, used to detect dependency cycles
+    // spanning multiple threads or tasks, which would otherwise wait for each other forever
+    #[cfg(any(feature = "threadsafe", feature = "async"))]
+    waiting: FxHashMap<usize, (Vec<ComponentDefinitionKey>, ComponentDefinitionKey)>,
+    #[cfg(any(feature = "threadsafe", feature = "async"))]
+    next_wait_id: usize,
+}
+
+#[cfg(any(feature = "threadsafe", feature = "async"))]
+impl Constructions {
+    fn start_waiting(
+        &mut self,
+        chain: Vec<ComponentDefinitionKey>,
+        target: ComponentDefinitionKey,
+    ) -> usize {
+        let id = self.next_wait_id;
+        self.next_wait_id = self.next_wait_id.wrapping_add(1);
+        self.waiting.insert(id, (chain, target));
+        id
+    }
+
+    // the chain constructing the target might itself wait, directly or not, for an instance being
+    // constructed by the given chain
+    fn would_deadlock(
+        &self,
+        target: &ComponentDefinitionKey,
+        chain: &[ComponentDefinitionKey],
+    ) -> bool {
+        let mut visited = fxhash::FxHashSet::default();
+        let mut pending = vec![target];
+        while let Some(target) = pending.pop() {
+            if chain.contains(target) {
+                return true;
+            }
+
+            if visited.insert(target) {
+                pending.extend(
+                    self.waiting
+                        .values()
+                        .filter(|(waiting_chain, _)| waiting_chain.contains(target))
+                        .map(|(_, waiting_target)| waiting_target),
+                );
+            }
+        }
+
+        false
     }
 }
 
-/// Generic factory for [Component](crate::component::Component) instances. Uses definitions from
-/// the [ComponentDefinitionRegistry] and [scopes](crate::scope) to create and store instances for
-/// reuse.
-pub struct ComponentFactory {
-    definition_registry: ComponentDefinitionRegistryPtr,
-    scope_factories: FxHashMap<String, ScopeFactoryPtr>,
-    scopes: FxHashMap<String, ScopePtr>,
-    types_under_construction: FxHashSet<TypeId>,
+struct InFlightConstruction {
+    #[cfg(all(feature = "threadsafe", not(feature = "async")))]
+    thread: std::thread::ThreadId,
+    #[cfg(feature = "async")]
+    finished: Shared<oneshot::Receiver<()>>,
 }
 
-impl ComponentFactory {
-    /// Creates a new factory with given registry and scope factories. The factory map should
-    /// include built-in [SINGLETON] and [PROTOTYPE] for maximum compatibility with components,
-    /// since they are usually the most popular. This is not a hard requirement, but care needs to
-    /// be taken to ensue no component uses them.
-    pub fn new(
-        definition_registry: ComponentDefinitionRegistryPtr,
-        scope_factories: FxHashMap<String, ScopeFactoryPtr>,
-    ) -> Self {
+// finishes the construction when dropped, also when failed or cancelled, waking up waiting handles
+struct ConstructionGuard {
+    state: ComponentInstancePtr<ComponentFactoryState>,
+    key: Option<ConstructionKey>,
+    #[cfg(feature = "async")]
+    finished: Option<oneshot::Sender<()>>,
+}
+
+impl ConstructionGuard {
+    fn new(state: ComponentInstancePtr<ComponentFactoryState>, key: ConstructionKey) -> Self {
         Self {
-            definition_registry,
-            scope_factories,
-            scopes: Default::default(),
-            types_under_construction: Default::default(),
+            state,
+            key: Some(key),
+            #[cfg(feature = "async")]
+            finished: None,
         }
     }
 
-    #[cfg(feature = "async")]
-    async fn call_constructor(
-        &mut self,
-        definition: &ComponentDefinition,
-    ) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
-        self.types_under_construction
-            .insert(definition.resolved_type_id);
-        let instance = (definition.constructor)(self).await;
-        self.types_under_construction
-            .remove(&definition.resolved_type_id);
-
-        instance
+    fn unguarded(state: ComponentInstancePtr<ComponentFactoryState>) -> Self {
+        Self {
+            state,
+            key: None,
+            #[cfg(feature = "async")]
+            finished: None,
+        }
     }
+}
 
-    #[cfg(not(feature = "async"))]
-    fn call_constructor(
-        &mut self,
-        definition: &ComponentDefinition,
-    ) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
-        self.types_under_construction
-            .insert(definition.resolved_type_id);
-        let instance = (definition.constructor)(self);
-        self.types_under_construction
-            .remove(&definition.resolved_type_id);
+impl Drop for ConstructionGuard {
+    fn drop(&mut self) {
+        if let Some(key) = &self.key {
+            borrow_shared(&self.state.constructions)
+                .in_flight
+                .remove(key);
 
-        instance
+            #[cfg(all(feature = "threadsafe", not(feature = "async")))]
+            self.state.construction_finished.notify_all();
+        }
     }
+}
 
-    fn check_scope_instance(
-        &mut self,
-        definition: &ComponentDefinition,
-    ) -> Result<Option<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError>
-    {
-        if self
-            .types_under_construction
-            .contains(&definition.resolved_type_id)
-        {
-            return Err(ComponentInstanceProviderError::DependencyCycle {
-                type_id: definition.resolved_type_id,
-                type_name: None,
-            });
-        }
+#[cfg(feature = "async")]
+struct ConstructionWait {
+    state: ComponentInstancePtr<ComponentFactoryState>,
+    wait_id: usize,
+    finished: Shared<oneshot::Receiver<()>>,
+}
 
-        let scope = {
-            if let Some(scope) = self.scopes.get(&definition.scope) {
-                scope
-            } else {
-                let factory = self.scope_factories.get(&definition.scope).ok_or_else(|| {
-                    ComponentInstanceProviderError::UnrecognizedScope(definition.scope.to_string())
-                })?;
+#[cfg(feature = "async")]
+impl ConstructionWait {
+    async fn finished(self) {
+        // the sender is only dropped, so the result is always an error
+        let _ = self.finished.clone().await;
+    }
+}
 
-                self.scopes
-                    .entry(definition.scope.clone())
-                    .or_insert(factory.create_scope())
-            }
-        };
+#[cfg(feature = "async")]
+impl Drop for ConstructionWait {
+    fn drop(&mut self) {
+        borrow_shared(&self.state.constructions)
+            .waiting
+            .remove(&self.wait_id);
+    }
+}
+
+// exits the entered scope context when dropped, also when unwinding
+struct ScopeContextEntry {
+    state: ComponentInstancePtr<ComponentFactoryState>,
+    scope: String,
+    context: String,
+}
 
-        Ok(scope
-            .instance(definition)
-            .map(|instance| (instance, definition.cast)))
+impl Drop for ScopeContextEntry {
+    fn drop(&mut self) {
+        if let Some(scope) = self.state.scopes.get(&self.scope) {
+            borrow_shared(scope).exit_context(&self.context);
+            self.state.notify_evictions(&self.scope);
+        }
     }
+}
 
-    fn store_instance_in_scope(
-        &mut self,
-        definition: &ComponentDefinition,
-        instance: ComponentInstanceAnyPtr,
-    ) -> Result<(), ComponentInstanceProviderError> {
-        let scope = self.scopes.get_mut(&definition.scope).ok_or_else(|| {
-            ComponentInstanceProviderError::UnrecognizedScope(definition.scope.to_string())
-        })?;
+/// Future running with a scope context active, created by
+/// [ComponentFactory::in_scope_context].
+#[cfg(feature = "async")]
+pub struct ScopeContextFuture<F> {
+    future: Pin<Box<F>>,
+    entry: ScopeContextEntry,
+}
 
-        scope.store_instance(definition, instance);
+#[cfg(feature = "async")]
+impl<F: Future> Future for ScopeContextFuture<F> {
+    type Output = F::Output;
 
-        Ok(())
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let _context = ActiveContextGuard::new(&this.entry.scope, &this.entry.context);
+        this.future.as_mut().poll(cx)
     }
+}
 
-    #[cfg(feature = "async")]
-    async fn create_instance(
-        &mut self,
-        definition: &ComponentDefinition,
-    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
-        if let Some(instance) = self.check_scope_instance(definition)? {
-            return Ok(instance);
-        }
+#[cfg(not(feature = "threadsafe"))]
+type ComponentFactoryStateWeakPtr = std::rc::Weak<ComponentFactoryState>;
+#[cfg(feature = "threadsafe")]
+type ComponentFactoryStateWeakPtr = std::sync::Weak<ComponentFactoryState>;
 
-        debug!(
-            resolved_type_name = definition.resolved_type_name,
-            "Creating new component instance."
-        );
+// shared handles are usually stored in singletons, which are owned by the factory state, so they
+// can't keep the state alive
+struct WeakComponentFactory {
+    state: ComponentFactoryStateWeakPtr,
+}
 
-        let instance = self.call_constructor(definition).await?;
+impl WeakComponentFactory {
+    fn upgrade(&self) -> Result<ComponentFactory, ComponentInstanceProviderError> {
+        self.state
+            .upgrade()
+            .map(|state| ComponentFactory {
+                state,
+                types_under_construction: Default::default(),
+            })
+            .ok_or(ComponentInstanceProviderError::ProviderDropped)
+    }
+}
 
-        self.store_instance_in_scope(definition, instance.clone())?;
-        Ok((instance, definition.cast))
+impl ComponentInstanceProvider for WeakComponentFactory {
+    fn shared_handle(&self) -> Option<ComponentInstanceProviderPtr> {
+        Some(Box::new(Self {
+            state: self.state.clone(),
+        }))
     }
 
-    #[cfg(not(feature = "async"))]
-    fn create_instance(
-        &mut self,
-        definition: &ComponentDefinition,
-    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
-        if let Some(instance) = self.check_scope_instance(definition)? {
-            return Ok(instance);
-        }
+    #[cfg(feature = "async")]
+    fn dependency_handle(&self) -> Option<ComponentInstanceProviderPtr> {
+        self.upgrade().ok()?.dependency_handle()
+    }
 
-        debug!(
-            resolved_type_name = definition.resolved_type_name,
-            "Creating new component instance."
-        );
+    #[cfg(feature = "async")]
+    fn instantiate_all_singletons(
+        &mut self,
+    ) -> BoxFuture<'_, Result<(), ComponentInstanceProviderError>> {
+        let handle = self.upgrade();
+        async move { handle?.instantiate_all_singletons().await }.boxed()
+    }
 
-        let instance = self.call_constructor(definition)?;
+    #[cfg(not(feature = "async"))]
+    fn instantiate_all_singletons(&mut self) -> Result<(), ComponentInstanceProviderError> {
+        self.upgrade()?.instantiate_all_singletons()
+    }
 
-        self.store_instance_in_scope(definition, instance.clone())?;
-        Ok((instance, definition.cast))
+    fn register_missing_component(
+        &mut self,
+        target: TypeId,
+        target_name: &str,
+        metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentInstanceProviderError> {
+        self.upgrade()?
+            .register_missing_component(target, target_name, metadata)
     }
-}
 
-impl ComponentInstanceProvider for ComponentFactory {
     #[cfg(feature = "async")]
     fn primary_instance(
         &mut self,
@@ -243,17 +2234,8 @@ impl ComponentInstanceProvider for ComponentFactory {
         '_,
         Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>,
     > {
-        async move {
-            let definition = self.definition_registry.primary_component(type_id).ok_or(
-                ComponentInstanceProviderError::NoPrimaryInstance {
-                    type_id,
-                    type_name: None,
-                },
-            )?;
-
-            self.create_instance(&definition).await
-        }
-        .boxed()
+        let handle = self.upgrade();
+        async move { handle?.primary_instance(type_id).await }.boxed()
     }
 
     #[cfg(not(feature = "async"))]
@@ -261,14 +2243,7 @@ impl ComponentInstanceProvider for ComponentFactory {
         &mut self,
         type_id: TypeId,
     ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
-        let definition = self.definition_registry.primary_component(type_id).ok_or(
-            ComponentInstanceProviderError::NoPrimaryInstance {
-                type_id,
-                type_name: None,
-            },
-        )?;
-
-        self.create_instance(&definition)
+        self.upgrade()?.primary_instance(type_id)
     }
 
     #[cfg(feature = "async")]
@@ -279,12 +2254,36 @@ impl ComponentInstanceProvider for ComponentFactory {
         '_,
         Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError>,
     > {
-        async move {
-            let definitions = self.definition_registry.components_by_type(type_id);
+        let handle = self.upgrade();
+        async move { handle?.instances(type_id).await }.boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn instances(
+        &mut self,
+        type_id: TypeId,
+    ) -> Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError> {
+        self.upgrade()?.instances(type_id)
+    }
 
+    #[cfg(feature = "async")]
+    fn filtered_instances<'a>(
+        &mut self,
+        type_id: TypeId,
+        filter: &InstanceFilter<'a>,
+    ) -> BoxFuture<
+        '_,
+        Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError>,
+    > {
+        let handle = self.upgrade().map(|handle| {
+            let definitions = handle.filtered_definitions(type_id, filter);
+            (handle, definitions)
+        });
+        async move {
+            let (mut handle, definitions) = handle?;
             let mut result = Vec::with_capacity(definitions.len());
             for definition in &definitions {
-                result.push(self.create_instance(definition).await?);
+                result.push(handle.create_instance(definition).await?);
             }
 
             Ok(result)
@@ -293,15 +2292,29 @@ impl ComponentInstanceProvider for ComponentFactory {
     }
 
     #[cfg(not(feature = "async"))]
-    fn instances(
+    fn filtered_instances<'a>(
         &mut self,
         type_id: TypeId,
+        filter: &InstanceFilter<'a>,
     ) -> Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError> {
-        self.definition_registry
-            .components_by_type(type_id)
-            .iter()
-            .map(|definition| self.create_instance(definition))
-            .try_collect()
+        self.upgrade()?.filtered_instances(type_id, filter)
+    }
+
+    #[cfg(feature = "async")]
+    fn named_instances(
+        &mut self,
+        type_id: TypeId,
+    ) -> BoxFuture<'_, Result<Vec<NamedInstance>, ComponentInstanceProviderError>> {
+        let handle = self.upgrade();
+        async move { handle?.named_instances(type_id).await }.boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn named_instances(
+        &mut self,
+        type_id: TypeId,
+    ) -> Result<Vec<NamedInstance>, ComponentInstanceProviderError> {
+        self.upgrade()?.named_instances(type_id)
     }
 
     #[cfg(feature = "async")]
@@ -313,16 +2326,9 @@ impl ComponentInstanceProvider for ComponentFactory {
         '_,
         Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>,
     > {
+        let handle = self.upgrade();
         let name = name.to_string();
-        async move {
-            let definition = self
-                .definition_registry
-                .component_by_name(&name, type_id)
-                .ok_or_else(|| ComponentInstanceProviderError::NoNamedInstance(name.to_string()))?;
-
-            self.create_instance(&definition).await
-        }
-        .boxed()
+        async move { handle?.instance_by_name(&name, type_id).await }.boxed()
     }
 
     #[cfg(not(feature = "async"))]
@@ -331,12 +2337,28 @@ impl ComponentInstanceProvider for ComponentFactory {
         name: &str,
         type_id: TypeId,
     ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
-        let definition = self
-            .definition_registry
-            .component_by_name(name, type_id)
-            .ok_or_else(|| ComponentInstanceProviderError::NoNamedInstance(name.to_string()))?;
+        self.upgrade()?.instance_by_name(name, type_id)
+    }
 
-        self.create_instance(&definition)
+    #[cfg(feature = "async")]
+    fn any_instance_by_name(
+        &mut self,
+        name: &str,
+    ) -> BoxFuture<
+        '_,
+        Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>,
+    > {
+        let handle = self.upgrade();
+        let name = name.to_string();
+        async move { handle?.any_instance_by_name(&name).await }.boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn any_instance_by_name(
+        &mut self,
+        name: &str,
+    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
+        self.upgrade()?.any_instance_by_name(name)
     }
 }
 
@@ -345,9 +2367,16 @@ impl ComponentInstanceProvider for ComponentFactory {
 mod tests {
     #[cfg(not(feature = "async"))]
     mod sync {
+        use crate::component_registry::conditional::SimpleContextFactory;
+        #[cfg(feature = "threadsafe")]
+        use crate::component_registry::ComponentDefinitionKey;
         use crate::component_registry::{
-            ComponentDefinition, ComponentDefinitionRegistry, MockComponentDefinitionRegistry,
+            ComponentDefinition, ComponentDefinitionPtr, ComponentDefinitionRegistry,
+            ComponentMetadata, Constructor, MockComponentDefinitionRegistry,
+            StaticComponentDefinitionRegistry,
         };
+        #[cfg(feature = "threadsafe")]
+        use crate::factory::Constructions;
         use crate::factory::{ComponentDefinitionRegistryPtr, ComponentFactory, ScopeFactoryPtr};
         use crate::instance_provider::{
             ComponentInstanceAnyPtr, ComponentInstanceProvider, ComponentInstanceProviderError,
@@ -361,7 +2390,8 @@ mod tests {
 
         fn cast(
             instance: ComponentInstanceAnyPtr,
-        ) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr> {
+            _target: &mut dyn Any,
+        ) -> Result<(), ComponentInstanceAnyPtr> {
             Err(instance)
         }
 
@@ -388,16 +2418,38 @@ mod tests {
                 .map(|(instance, _)| instance)
         }
 
+        fn first_cycle_constructor(
+            instance_provider: &mut dyn ComponentInstanceProvider,
+        ) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
+            instance_provider
+                .primary_instance(TypeId::of::<i16>())
+                .map(|(instance, _)| instance)
+        }
+
+        fn second_cycle_constructor(
+            instance_provider: &mut dyn ComponentInstanceProvider,
+        ) -> Result<ComponentInstanceAnyPtr, ComponentInstanceProviderError> {
+            instance_provider
+                .primary_instance(TypeId::of::<i8>())
+                .map(|(instance, _)| instance)
+        }
+
         fn create_definition() -> (ComponentDefinition, TypeId) {
             (
                 ComponentDefinition {
                     names: ["name".to_string()].into_iter().collect(),
                     is_primary: false,
                     scope: PROTOTYPE.to_string(),
+                    order: 0,
                     resolved_type_id: TypeId::of::<i8>(),
+                    qualifier: None,
                     resolved_type_name: type_name::<i8>().to_string(),
                     constructor,
+                    late_injection: None,
+                    post_construct: None,
+                    pre_destroy: None,
                     cast,
+                    dependencies: vec![],
                 },
                 TypeId::of::<i8>(),
             )
@@ -426,7 +2478,7 @@ mod tests {
                 .expect_primary_component()
                 .with(eq(id))
                 .times(1)
-                .return_const(Some(definition));
+                .return_const(Some(ComponentDefinitionPtr::new(definition)));
 
             let mut factory = create_factory(registry);
             assert!(factory.primary_instance(id).is_ok());
@@ -439,10 +2491,16 @@ mod tests {
                 names: Default::default(),
                 is_primary: false,
                 scope: PROTOTYPE.to_string(),
+                order: 0,
                 resolved_type_id: TypeId::of::<i8>(),
+                qualifier: None,
                 resolved_type_name: type_name::<i8>().to_string(),
                 constructor: recursive_constructor,
+                late_injection: None,
+                post_construct: None,
+                pre_destroy: None,
                 cast,
+                dependencies: vec![],
             };
 
             let mut registry = MockComponentDefinitionRegistry::new();
@@ -450,7 +2508,7 @@ mod tests {
                 .expect_primary_component()
                 .with(eq(id))
                 .times(2)
-                .return_const(Some(definition));
+                .return_const(Some(ComponentDefinitionPtr::new(definition)));
 
             let mut factory = create_factory(registry);
             assert!(matches!(
@@ -459,6 +2517,79 @@ mod tests {
             ));
         }
 
+        #[test]
+        fn should_report_whole_dependency_cycle() {
+            let mut registry =
+                StaticComponentDefinitionRegistry::new(false, &SimpleContextFactory).unwrap();
+            for (type_id, type_name, constructor) in [
+                (
+                    TypeId::of::<i8>(),
+                    type_name::<i8>(),
+                    first_cycle_constructor as Constructor,
+                ),
+                (
+                    TypeId::of::<i16>(),
+                    type_name::<i16>(),
+                    second_cycle_constructor as Constructor,
+                ),
+            ] {
+                registry
+                    .register_component(
+                        type_id,
+                        type_name,
+                        &ComponentMetadata {
+                            names: [type_name.to_string()].into_iter().collect(),
+                            scope: PROTOTYPE.to_string(),
+                            order: 0,
+                            constructor,
+                            late_injection: None,
+                            post_construct: None,
+                            pre_destroy: None,
+                            cast,
+                            dependencies: vec![],
+                        },
+                    )
+                    .unwrap();
+            }
+
+            let mut factory = create_factory(registry);
+            let error = factory.primary_instance(TypeId::of::<i8>()).unwrap_err();
+            assert!(matches!(
+                &error,
+                ComponentInstanceProviderError::DependencyError { path, .. } if *path == vec![
+                    type_name::<i8>().to_string(),
+                    type_name::<i16>().to_string(),
+                    type_name::<i8>().to_string(),
+                ]
+            ));
+            assert!(matches!(
+                error.root_cause(),
+                ComponentInstanceProviderError::DependencyCycle { type_id, .. } if *type_id == TypeId::of::<i8>()
+            ));
+        }
+
+        #[cfg(feature = "threadsafe")]
+        #[test]
+        fn should_detect_construction_deadlocks() {
+            let key = |type_id| ComponentDefinitionKey {
+                type_id,
+                qualifier: None,
+            };
+            let first = key(TypeId::of::<i8>());
+            let second = key(TypeId::of::<i16>());
+            let third = key(TypeId::of::<i32>());
+
+            let mut constructions = Constructions::default();
+            let wait_id = constructions.start_waiting(vec![first.clone()], second.clone());
+
+            assert!(constructions.would_deadlock(&first, std::slice::from_ref(&second)));
+            assert!(!constructions.would_deadlock(&first, std::slice::from_ref(&third)));
+            assert!(!constructions.would_deadlock(&second, std::slice::from_ref(&first)));
+
+            constructions.waiting.remove(&wait_id);
+            assert!(!constructions.would_deadlock(&first, &[second]));
+        }
+
         #[test]
         fn should_not_return_missing_primary_instance() {
             let id = TypeId::of::<i8>();
@@ -469,6 +2600,11 @@ mod tests {
                 .with(eq(id))
                 .times(1)
                 .return_const(None);
+            registry
+                .expect_components_by_type()
+                .with(eq(id))
+                .times(1)
+                .return_const(vec![]);
 
             let mut factory = create_factory(registry);
             assert!(matches!(
@@ -484,10 +2620,16 @@ mod tests {
                 names: Default::default(),
                 is_primary: false,
                 scope: SINGLETON.to_string(),
+                order: 0,
                 resolved_type_id: TypeId::of::<i8>(),
+                qualifier: None,
                 resolved_type_name: type_name::<i8>().to_string(),
                 constructor,
+                late_injection: None,
+                post_construct: None,
+                pre_destroy: None,
                 cast,
+                dependencies: vec![],
             };
 
             let mut registry = MockComponentDefinitionRegistry::new();
@@ -495,7 +2637,7 @@ mod tests {
                 .expect_primary_component()
                 .with(eq(id))
                 .times(1)
-                .return_const(Some(definition));
+                .return_const(Some(ComponentDefinitionPtr::new(definition)));
 
             let mut factory = create_factory(registry);
             assert!(matches!(
@@ -511,10 +2653,16 @@ mod tests {
                 names: Default::default(),
                 is_primary: false,
                 scope: PROTOTYPE.to_string(),
+                order: 0,
                 resolved_type_id: TypeId::of::<i8>(),
+                qualifier: None,
                 resolved_type_name: type_name::<i8>().to_string(),
                 constructor: error_constructor,
+                late_injection: None,
+                post_construct: None,
+                pre_destroy: None,
                 cast,
+                dependencies: vec![],
             };
 
             let mut registry = MockComponentDefinitionRegistry::new();
@@ -522,7 +2670,7 @@ mod tests {
                 .expect_primary_component()
                 .with(eq(id))
                 .times(1)
-                .return_const(Some(definition));
+                .return_const(Some(ComponentDefinitionPtr::new(definition)));
 
             let mut factory = create_factory(registry);
             assert!(matches!(
@@ -540,13 +2688,17 @@ mod tests {
                 .expect_primary_component()
                 .with(eq(id))
                 .times(1)
-                .return_const(Some(definition));
+                .return_const(Some(ComponentDefinitionPtr::new(definition)));
 
             let mut scope_factory = MockScopeFactory::new();
             scope_factory.expect_create_scope().returning(|| {
                 let mut scope = MockScope::new();
                 scope.expect_store_instance().times(1).return_const(());
                 scope.expect_instance().return_const(None);
+                scope
+                    .expect_set_eviction_listener()
+                    .times(1)
+                    .return_const(());
 
                 Box::new(scope) as ScopePtr
             });
@@ -573,7 +2725,10 @@ mod tests {
                 .expect_components_by_type()
                 .with(eq(id))
                 .times(1)
-                .return_const(vec![definition.clone(), definition]);
+                .return_const(vec![
+                    ComponentDefinitionPtr::new(definition.clone()),
+                    ComponentDefinitionPtr::new(definition),
+                ]);
 
             let mut factory = create_factory(registry);
             assert_eq!(factory.instances(id).unwrap().len(), 2);
@@ -588,10 +2743,62 @@ mod tests {
                 .expect_component_by_name()
                 .with(eq("name"), eq(id))
                 .times(1)
-                .return_const(Some(definition));
+                .return_const(Some(ComponentDefinitionPtr::new(definition)));
 
             let mut factory = create_factory(registry);
             assert!(factory.instance_by_name("name", id).is_ok());
         }
+
+        #[test]
+        fn should_instantiate_all_singletons() {
+            let (mut definition, id) = create_definition();
+            definition.scope = SINGLETON.to_string();
+
+            let (prototype_definition, _) = create_definition();
+
+            let definitions = [
+                (id, vec![ComponentDefinitionPtr::new(definition)]),
+                // aliases and other scopes should be skipped
+                (
+                    TypeId::of::<u8>(),
+                    vec![ComponentDefinitionPtr::new(prototype_definition)],
+                ),
+            ];
+
+            let mut registry = MockComponentDefinitionRegistry::new();
+            registry
+                .expect_visit_definitions()
+                .times(1)
+                .returning(move |visitor| {
+                    for (type_id, definitions) in &definitions {
+                        visitor(*type_id, definitions);
+                    }
+                });
+
+            let mut scope_factory = MockScopeFactory::new();
+            scope_factory.expect_create_scope().times(1).returning(|| {
+                let mut scope = MockScope::new();
+                scope.expect_store_instance().times(1).return_const(());
+                scope.expect_instance().return_const(None);
+                scope
+                    .expect_set_eviction_listener()
+                    .times(1)
+                    .return_const(());
+
+                Box::new(scope) as ScopePtr
+            });
+
+            let mut factory = ComponentFactory::new(
+                Box::new(registry) as ComponentDefinitionRegistryPtr,
+                [(
+                    SINGLETON.to_string(),
+                    Box::new(scope_factory) as ScopeFactoryPtr,
+                )]
+                .into_iter()
+                .collect(),
+            );
+
+            factory.instantiate_all_singletons().unwrap();
+        }
     }
 }
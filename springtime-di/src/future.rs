@@ -1,2 +1,3 @@
+pub use futures::future::try_join;
 pub use futures::future::BoxFuture;
 pub use futures::future::FutureExt;
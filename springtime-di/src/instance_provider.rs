@@ -1,20 +1,28 @@
 //! The core functionality of creating and managing [Component](crate::component::Component)
 //! instances.
 
-use crate::component::Injectable;
+use crate::component::Component;
+use crate::component_registry::internal::{default_component_metadata, ComponentInstanceBounds};
+use crate::component_registry::{
+    ComponentDefinition, ComponentDefinitionRegistryError, ComponentMetadata,
+};
 #[cfg(feature = "async")]
 use futures::future::BoxFuture;
 #[cfg(feature = "async")]
 use futures::FutureExt;
+use fxhash::FxHashMap;
 use itertools::Itertools;
-#[cfg(test)]
+#[cfg(any(test, feature = "test-util"))]
 use mockall::automock;
 use std::any::{type_name, Any, TypeId};
+#[cfg(not(feature = "threadsafe"))]
+use std::cell::RefCell;
 use std::error::Error;
+use std::marker::PhantomData;
 #[cfg(not(feature = "threadsafe"))]
 use std::rc::Rc;
 #[cfg(feature = "threadsafe")]
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
 use thiserror::Error;
 
 #[cfg(not(feature = "threadsafe"))]
@@ -22,7 +30,9 @@ pub type ErrorPtr = Rc<dyn Error>;
 #[cfg(feature = "threadsafe")]
 pub type ErrorPtr = Arc<dyn Error + Send + Sync>;
 
-/// Errors related to creating and managing components.
+/// Errors related to creating and managing components. New kinds of errors can be added in minor
+/// releases, so matching needs a wildcard arm.
+#[non_exhaustive]
 #[derive(Error, Debug, Clone)]
 pub enum ComponentInstanceProviderError {
     /// Primary instance of a given component is not specified, if many components exist for a given
@@ -32,9 +42,22 @@ pub enum ComponentInstanceProviderError {
         type_id: TypeId,
         type_name: Option<String>,
     },
+    /// Multiple components exist for a given type, but none is marked as primary. Candidates
+    /// contain type names of the conflicting components, followed by the qualifier in parentheses
+    /// for qualified definitions.
+    #[error("Cannot find a primary instance for component '{type_id:?}/{type_name:?}' - multiple exist without a primary marker: {}.", .candidates.join(", "))]
+    AmbiguousPrimaryInstance {
+        type_id: TypeId,
+        type_name: Option<String>,
+        candidates: Vec<String>,
+    },
     /// Tired to case one type to another, incompatible one.
     #[error("Tried to downcast component to incompatible type: {type_id:?}/{type_name}")]
     IncompatibleComponent { type_id: TypeId, type_name: String },
+    /// Tried to take ownership of an instance, which is shared with other components, e.g. it's not
+    /// a [prototype](crate::scope::PROTOTYPE).
+    #[error("Cannot take ownership of shared component instance: {type_id:?}/{type_name}")]
+    SharedInstance { type_id: TypeId, type_name: String },
     /// Cannot find component with given name.
     #[error("Cannot find named component: {0}")]
     NoNamedInstance(String),
@@ -43,7 +66,9 @@ pub enum ComponentInstanceProviderError {
     #[error("Unrecognized scope: {0}")]
     UnrecognizedScope(String),
     #[error("Detected dependency cycle for: {type_id:?}/{type_name:?}")]
-    /// Found a cycle when creating given type.
+    /// Found a cycle when creating given type. Cycles spanning multiple components are reported
+    /// as the source of a [DependencyError](Self::DependencyError), whose path ends with the
+    /// whole cycle.
     DependencyCycle {
         type_id: TypeId,
         type_name: Option<String>,
@@ -51,6 +76,60 @@ pub enum ComponentInstanceProviderError {
     /// Custom constructor returned an error.
     #[error("Error in component constructor: {0}")]
     ConstructorError(#[source] ErrorPtr),
+    /// [ComponentLifecycle::post_construct](crate::component::ComponentLifecycle::post_construct)
+    /// returned an error.
+    #[error("Error in component post-construct callback: {0}")]
+    PostConstructError(#[source] ErrorPtr),
+    /// [InstancePostProcessor](crate::post_processor::InstancePostProcessor) returned an error.
+    #[error("Error in instance post-processor: {0}")]
+    PostProcessorError(#[source] ErrorPtr),
+    /// [InstancePostProcessor](crate::post_processor::InstancePostProcessor) replaced an instance
+    /// with one of a different type than the component.
+    #[error("Instance post-processor replaced component with an incompatible type: {type_id:?}/{type_name}")]
+    IncompatiblePostProcessedInstance { type_id: TypeId, type_name: String },
+    /// The provider doesn't support [shared handles](ComponentInstanceProvider::shared_handle),
+    /// which are required by [ComponentProvider] and [FactoryHandle], or dependency handles, which
+    /// are required by components constructed concurrently.
+    #[error("Instance provider doesn't support shared handles")]
+    SharedHandleUnsupported,
+    /// The provider doesn't support registering
+    /// [missing components](ComponentInstanceProvider::register_missing_component).
+    #[error("Instance provider doesn't support registering components")]
+    RegistrationUnsupported,
+    /// A [shared handle](ComponentInstanceProvider::shared_handle) was used after the provider
+    /// it refers to has been dropped.
+    #[error("Instance provider has been dropped")]
+    ProviderDropped,
+    /// Error registering a missing component.
+    #[error("Error registering component: {0}")]
+    RegistrationError(#[source] ComponentDefinitionRegistryError),
+    /// The provider doesn't support returning
+    /// [named instances](ComponentInstanceProvider::named_instances).
+    #[error("Instance provider doesn't support named instances")]
+    NamedInstancesUnsupported,
+    /// The provider doesn't support
+    /// [filtering instances](ComponentInstanceProvider::filtered_instances) by their definitions.
+    #[error("Instance provider doesn't support filtering instances")]
+    FilteredInstancesUnsupported,
+    /// Error creating a component deep in the dependency graph. The path contains names of
+    /// components being constructed, starting with the requested one and ending with the one which
+    /// failed.
+    #[error("Error creating {}: {source}", .path.join(" -> "))]
+    DependencyError {
+        path: Vec<String>,
+        #[source]
+        source: Box<ComponentInstanceProviderError>,
+    },
+}
+
+impl ComponentInstanceProviderError {
+    /// Returns the original error, without the [DependencyError](Self::DependencyError) context.
+    pub fn root_cause(&self) -> &ComponentInstanceProviderError {
+        match self {
+            ComponentInstanceProviderError::DependencyError { source, .. } => source.root_cause(),
+            _ => self,
+        }
+    }
 }
 
 #[cfg(not(feature = "threadsafe"))]
@@ -64,15 +143,43 @@ pub type ComponentInstanceAnyPtr = ComponentInstancePtr<dyn Any + 'static>;
 pub type ComponentInstanceAnyPtr = ComponentInstancePtr<dyn Any + Send + Sync + 'static>;
 
 /// (Usually generated) cast function which consumes given type-erased instance pointer and casts it
-/// to the desired [`ComponentInstancePtr<T>`]. The result is then returned as type-erased `Box` which
-/// is then converted back to [`ComponentInstancePtr<T>`]. Such shenanigans are needed to be able to
-/// convert between two `dyn Traits`.
-pub type CastFunction =
-    fn(instance: ComponentInstanceAnyPtr) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr>;
+/// to the desired [`ComponentInstancePtr<T>`]. The result is then written to the type-erased
+/// `target`, which is an `Option<ComponentInstancePtr<T>>` provided by the caller, using
+/// [store_cast_result]. Such shenanigans are needed to be able to convert between two `dyn Traits`
+/// without allocating intermediate values.
+pub type CastFunction = fn(
+    instance: ComponentInstanceAnyPtr,
+    target: &mut dyn Any,
+) -> Result<(), ComponentInstanceAnyPtr>;
+
+/// Stores the result of a [CastFunction] in given type-erased target. Does nothing if the target is
+/// not an `Option<ComponentInstancePtr<T>>`, which is reported as an incompatible component by the
+/// caller.
+#[inline]
+pub fn store_cast_result<T: ?Sized + 'static>(
+    instance: ComponentInstancePtr<T>,
+    target: &mut dyn Any,
+) {
+    if let Some(target) = target.downcast_mut::<Option<ComponentInstancePtr<T>>>() {
+        *target = Some(instance);
+    }
+}
+
+/// Type-erased instance along with the component name it was requested by.
+pub type NamedInstance = (String, ComponentInstanceAnyPtr, CastFunction);
+
+/// Predicate deciding which definitions should be instantiated by
+/// [ComponentInstanceProvider::filtered_instances].
+pub type InstanceFilter<'a> = dyn Fn(&ComponentDefinition) -> bool + 'a;
+
+#[cfg(not(feature = "threadsafe"))]
+pub type ComponentInstanceProviderPtr = Box<dyn ComponentInstanceProvider>;
+#[cfg(feature = "threadsafe")]
+pub type ComponentInstanceProviderPtr = Box<dyn ComponentInstanceProvider + Send + Sync>;
 
 /// Generic provider for component instances.
 #[cfg(feature = "async")]
-#[cfg_attr(test, automock)]
+#[cfg_attr(any(test, feature = "test-util"), automock)]
 pub trait ComponentInstanceProvider {
     /// Tries to return a primary instance of a given component. A primary component is either the
     /// only one registered or one marked as primary.
@@ -86,7 +193,8 @@ pub trait ComponentInstanceProvider {
 
     /// Tries to instantiate and return all registered components for given type, stopping on first
     /// error. Be aware this might be an expensive operation if the number of registered components
-    /// is high.
+    /// is high. Instances are returned in the
+    /// [order](crate::component_registry::ComponentDefinition::order) of their definitions.
     fn instances(
         &mut self,
         type_id: TypeId,
@@ -95,6 +203,36 @@ pub trait ComponentInstanceProvider {
         Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError>,
     >;
 
+    /// Tries to instantiate and return registered components for given type, whose definitions
+    /// match given filter, stopping on first error. Definitions not matching the filter are not
+    /// instantiated. Instances are returned in the
+    /// [order](crate::component_registry::ComponentDefinition::order) of their definitions.
+    /// Returns
+    /// [FilteredInstancesUnsupported](ComponentInstanceProviderError::FilteredInstancesUnsupported)
+    /// by default, since providers without definitions cannot apply the filter.
+    fn filtered_instances<'a>(
+        &mut self,
+        _type_id: TypeId,
+        _filter: &InstanceFilter<'a>,
+    ) -> BoxFuture<
+        '_,
+        Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError>,
+    > {
+        async { Err(ComponentInstanceProviderError::FilteredInstancesUnsupported) }.boxed()
+    }
+
+    /// Tries to instantiate and return all registered components for given type along with their
+    /// names, stopping on first error. Components with multiple names are returned once per name,
+    /// but instantiated only once. Returns
+    /// [NamedInstancesUnsupported](ComponentInstanceProviderError::NamedInstancesUnsupported) by
+    /// default.
+    fn named_instances(
+        &mut self,
+        _type_id: TypeId,
+    ) -> BoxFuture<'_, Result<Vec<NamedInstance>, ComponentInstanceProviderError>> {
+        async { Err(ComponentInstanceProviderError::NamedInstancesUnsupported) }.boxed()
+    }
+
     /// Tries to return an instance with the given name and type.
     fn instance_by_name(
         &mut self,
@@ -104,10 +242,62 @@ pub trait ComponentInstanceProvider {
         '_,
         Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>,
     >;
+
+    /// Tries to return an instance with the given name, regardless of its type. The returned cast
+    /// function casts to the concrete component type. Returns
+    /// [NoNamedInstance](ComponentInstanceProviderError::NoNamedInstance) by default.
+    fn any_instance_by_name(
+        &mut self,
+        name: &str,
+    ) -> BoxFuture<
+        '_,
+        Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>,
+    > {
+        let name = name.to_string();
+        async move { Err(ComponentInstanceProviderError::NoNamedInstance(name)) }.boxed()
+    }
+
+    /// Returns a new handle to this provider, which shares its state (e.g. scoped instances) and
+    /// can be stored to request instances later on. Returns `None` if not supported, which is the
+    /// default. Since handles are usually stored in singletons, they shouldn't keep the provider
+    /// alive, which would create a reference cycle - using a handle after the provider has been
+    /// dropped should result in [ProviderDropped](ComponentInstanceProviderError::ProviderDropped).
+    fn shared_handle(&self) -> Option<ComponentInstanceProviderPtr> {
+        None
+    }
+
+    /// Returns a new handle to this provider, which continues the construction of the component
+    /// currently being created, so dependencies of the component can be resolved concurrently,
+    /// each with its own handle, while cycles are still detected. Used by components marked as
+    /// `#[component(concurrent)]`. Returns `None` if not supported, which is the default.
+    fn dependency_handle(&self) -> Option<ComponentInstanceProviderPtr> {
+        None
+    }
+
+    /// Creates all singleton instances up front, so configuration and construction errors surface
+    /// early instead of on first use. Does nothing by default.
+    fn instantiate_all_singletons(
+        &mut self,
+    ) -> BoxFuture<'_, Result<(), ComponentInstanceProviderError>> {
+        async { Ok(()) }.boxed()
+    }
+
+    /// Registers a component with given metadata, unless a component of given type is already
+    /// registered, e.g. to fall back to a default implementation. Returns
+    /// [RegistrationUnsupported](ComponentInstanceProviderError::RegistrationUnsupported) by
+    /// default.
+    fn register_missing_component(
+        &mut self,
+        _target: TypeId,
+        _target_name: &str,
+        _metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentInstanceProviderError> {
+        Err(ComponentInstanceProviderError::RegistrationUnsupported)
+    }
 }
 
 #[cfg(not(feature = "async"))]
-#[cfg_attr(test, automock)]
+#[cfg_attr(any(test, feature = "test-util"), automock)]
 pub trait ComponentInstanceProvider {
     /// Tries to return a primary instance of a given component. A primary component is either the
     /// only one registered or one marked as primary.
@@ -118,90 +308,247 @@ pub trait ComponentInstanceProvider {
 
     /// Tries to instantiate and return all registered components for given type, stopping on first
     /// error. Be aware this might be an expensive operation if the number of registered components
-    /// is high.
+    /// is high. Instances are returned in the
+    /// [order](crate::component_registry::ComponentDefinition::order) of their definitions.
     fn instances(
         &mut self,
         type_id: TypeId,
     ) -> Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError>;
 
+    /// Tries to instantiate and return registered components for given type, whose definitions
+    /// match given filter, stopping on first error. Definitions not matching the filter are not
+    /// instantiated. Instances are returned in the
+    /// [order](crate::component_registry::ComponentDefinition::order) of their definitions.
+    /// Returns
+    /// [FilteredInstancesUnsupported](ComponentInstanceProviderError::FilteredInstancesUnsupported)
+    /// by default, since providers without definitions cannot apply the filter.
+    fn filtered_instances<'a>(
+        &mut self,
+        _type_id: TypeId,
+        _filter: &InstanceFilter<'a>,
+    ) -> Result<Vec<(ComponentInstanceAnyPtr, CastFunction)>, ComponentInstanceProviderError> {
+        Err(ComponentInstanceProviderError::FilteredInstancesUnsupported)
+    }
+
+    /// Tries to instantiate and return all registered components for given type along with their
+    /// names, stopping on first error. Components with multiple names are returned once per name,
+    /// but instantiated only once. Returns
+    /// [NamedInstancesUnsupported](ComponentInstanceProviderError::NamedInstancesUnsupported) by
+    /// default.
+    fn named_instances(
+        &mut self,
+        _type_id: TypeId,
+    ) -> Result<Vec<NamedInstance>, ComponentInstanceProviderError> {
+        Err(ComponentInstanceProviderError::NamedInstancesUnsupported)
+    }
+
     /// Tries to return an instance with the given name and type.
     fn instance_by_name(
         &mut self,
         name: &str,
         type_id: TypeId,
     ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>;
+
+    /// Tries to return an instance with the given name, regardless of its type. The returned cast
+    /// function casts to the concrete component type. Returns
+    /// [NoNamedInstance](ComponentInstanceProviderError::NoNamedInstance) by default.
+    fn any_instance_by_name(
+        &mut self,
+        name: &str,
+    ) -> Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError> {
+        Err(ComponentInstanceProviderError::NoNamedInstance(
+            name.to_string(),
+        ))
+    }
+
+    /// Returns a new handle to this provider, which shares its state (e.g. scoped instances) and
+    /// can be stored to request instances later on. Returns `None` if not supported, which is the
+    /// default. Since handles are usually stored in singletons, they shouldn't keep the provider
+    /// alive, which would create a reference cycle - using a handle after the provider has been
+    /// dropped should result in [ProviderDropped](ComponentInstanceProviderError::ProviderDropped).
+    fn shared_handle(&self) -> Option<ComponentInstanceProviderPtr> {
+        None
+    }
+
+    /// Creates all singleton instances up front, so configuration and construction errors surface
+    /// early instead of on first use. Does nothing by default.
+    fn instantiate_all_singletons(&mut self) -> Result<(), ComponentInstanceProviderError> {
+        Ok(())
+    }
+
+    /// Registers a component with given metadata, unless a component of given type is already
+    /// registered, e.g. to fall back to a default implementation. Returns
+    /// [RegistrationUnsupported](ComponentInstanceProviderError::RegistrationUnsupported) by
+    /// default.
+    fn register_missing_component(
+        &mut self,
+        _target: TypeId,
+        _target_name: &str,
+        _metadata: &ComponentMetadata,
+    ) -> Result<(), ComponentInstanceProviderError> {
+        Err(ComponentInstanceProviderError::RegistrationUnsupported)
+    }
 }
 
 /// Helper trait for [ComponentInstanceProvider] providing strongly-typed access.
 #[cfg(feature = "async")]
 pub trait TypedComponentInstanceProvider {
     /// Typesafe version of [ComponentInstanceProvider::primary_instance].
-    fn primary_instance_typed<T: Injectable + ?Sized>(
+    fn primary_instance_typed<T: ?Sized + 'static>(
         &mut self,
     ) -> BoxFuture<'_, Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>>;
 
     /// Tries to get an instance like [TypedComponentInstanceProvider::primary_instance_typed] does,
     /// but returns `None` on missing instance.
-    fn primary_instance_option<T: Injectable + ?Sized>(
+    fn primary_instance_option<T: ?Sized + 'static>(
         &mut self,
     ) -> BoxFuture<'_, Result<Option<ComponentInstancePtr<T>>, ComponentInstanceProviderError>>;
 
+    /// Tries to get an instance like [TypedComponentInstanceProvider::primary_instance_typed] does,
+    /// but if no component is registered for `T`, registers a [singleton](crate::scope::SINGLETON)
+    /// created with [Default::default] and returns it. Useful for simple, value-like services,
+    /// which don't need a dedicated fallback component.
+    fn primary_instance_or_default<T: Default + Component + ComponentInstanceBounds + 'static>(
+        &mut self,
+    ) -> BoxFuture<'_, Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>>;
+
     /// Typesafe version of [ComponentInstanceProvider::instances].
-    fn instances_typed<T: Injectable + ?Sized>(
+    fn instances_typed<T: ?Sized + 'static>(
         &mut self,
     ) -> BoxFuture<'_, Result<Vec<ComponentInstancePtr<T>>, ComponentInstanceProviderError>>;
 
+    /// Typesafe version of [ComponentInstanceProvider::filtered_instances], which instantiates
+    /// only components whose definitions match given predicate, e.g. on names or scope.
+    fn instances_filtered<T: ?Sized + 'static>(
+        &mut self,
+        filter: impl Fn(&ComponentDefinition) -> bool,
+    ) -> BoxFuture<'_, Result<Vec<ComponentInstancePtr<T>>, ComponentInstanceProviderError>>;
+
+    /// Typesafe version of [ComponentInstanceProvider::named_instances], returning instances keyed
+    /// by name.
+    fn named_instances_typed<T: ?Sized + 'static>(
+        &mut self,
+    ) -> BoxFuture<
+        '_,
+        Result<FxHashMap<String, ComponentInstancePtr<T>>, ComponentInstanceProviderError>,
+    >;
+
     /// Typesafe version of [ComponentInstanceProvider::instance_by_name].
-    fn instance_by_name_typed<T: Injectable + ?Sized>(
+    fn instance_by_name_typed<T: ?Sized + 'static>(
         &mut self,
         name: &str,
     ) -> BoxFuture<'_, Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>>;
 
     /// Tries to get an instance like [TypedComponentInstanceProvider::instance_by_name_typed] does,
     /// but returns `None` on missing instance.
-    fn instance_by_name_option<T: Injectable + ?Sized>(
+    fn instance_by_name_option<T: ?Sized + 'static>(
         &mut self,
         name: &str,
     ) -> BoxFuture<'_, Result<Option<ComponentInstancePtr<T>>, ComponentInstanceProviderError>>;
+
+    /// Typesafe version of [ComponentInstanceProvider::any_instance_by_name], which checks if the
+    /// named component is of the concrete type `T` after looking it up. Use
+    /// [TypedComponentInstanceProvider::instance_by_name_typed] for aliases, e.g. `dyn Trait`.
+    fn instance_by_name_any<T: 'static>(
+        &mut self,
+        name: &str,
+    ) -> BoxFuture<'_, Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>>;
+
+    /// Tries to get an instance like [TypedComponentInstanceProvider::primary_instance_typed] does,
+    /// but takes ownership of it. Only possible for instances not shared with anyone else, e.g.
+    /// [prototypes](crate::scope::PROTOTYPE).
+    fn primary_instance_owned<T: Send + 'static>(
+        &mut self,
+    ) -> BoxFuture<'_, Result<T, ComponentInstanceProviderError>>;
+
+    /// Tries to get an instance like [TypedComponentInstanceProvider::instance_by_name_typed] does,
+    /// but takes ownership of it. Only possible for instances not shared with anyone else, e.g.
+    /// [prototypes](crate::scope::PROTOTYPE).
+    fn instance_by_name_owned<T: Send + 'static>(
+        &mut self,
+        name: &str,
+    ) -> BoxFuture<'_, Result<T, ComponentInstanceProviderError>>;
 }
 
 /// Helper trait for [ComponentInstanceProvider] providing strongly-typed access.
 #[cfg(not(feature = "async"))]
 pub trait TypedComponentInstanceProvider {
     /// Typesafe version of [ComponentInstanceProvider::primary_instance].
-    fn primary_instance_typed<T: Injectable + ?Sized>(
+    fn primary_instance_typed<T: ?Sized + 'static>(
         &mut self,
     ) -> Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>;
 
     /// Tries to get an instance like [TypedComponentInstanceProvider::primary_instance_typed] does,
     /// but returns `None` on missing instance.
-    fn primary_instance_option<T: Injectable + ?Sized>(
+    fn primary_instance_option<T: ?Sized + 'static>(
         &mut self,
     ) -> Result<Option<ComponentInstancePtr<T>>, ComponentInstanceProviderError>;
 
+    /// Tries to get an instance like [TypedComponentInstanceProvider::primary_instance_typed] does,
+    /// but if no component is registered for `T`, registers a [singleton](crate::scope::SINGLETON)
+    /// created with [Default::default] and returns it. Useful for simple, value-like services,
+    /// which don't need a dedicated fallback component.
+    fn primary_instance_or_default<T: Default + Component + ComponentInstanceBounds + 'static>(
+        &mut self,
+    ) -> Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>;
+
     /// Typesafe version of [ComponentInstanceProvider::instances].
-    fn instances_typed<T: Injectable + ?Sized>(
+    fn instances_typed<T: ?Sized + 'static>(
+        &mut self,
+    ) -> Result<Vec<ComponentInstancePtr<T>>, ComponentInstanceProviderError>;
+
+    /// Typesafe version of [ComponentInstanceProvider::filtered_instances], which instantiates
+    /// only components whose definitions match given predicate, e.g. on names or scope.
+    fn instances_filtered<T: ?Sized + 'static>(
         &mut self,
+        filter: impl Fn(&ComponentDefinition) -> bool,
     ) -> Result<Vec<ComponentInstancePtr<T>>, ComponentInstanceProviderError>;
 
+    /// Typesafe version of [ComponentInstanceProvider::named_instances], returning instances keyed
+    /// by name.
+    fn named_instances_typed<T: ?Sized + 'static>(
+        &mut self,
+    ) -> Result<FxHashMap<String, ComponentInstancePtr<T>>, ComponentInstanceProviderError>;
+
     /// Typesafe version of [ComponentInstanceProvider::instance_by_name].
-    fn instance_by_name_typed<T: Injectable + ?Sized>(
+    fn instance_by_name_typed<T: ?Sized + 'static>(
         &mut self,
         name: &str,
     ) -> Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>;
 
     /// Tries to get an instance like [TypedComponentInstanceProvider::instance_by_name_typed] does,
     /// but returns `None` on missing instance.
-    fn instance_by_name_option<T: Injectable + ?Sized>(
+    fn instance_by_name_option<T: ?Sized + 'static>(
         &mut self,
         name: &str,
     ) -> Result<Option<ComponentInstancePtr<T>>, ComponentInstanceProviderError>;
+
+    /// Typesafe version of [ComponentInstanceProvider::any_instance_by_name], which checks if the
+    /// named component is of the concrete type `T` after looking it up. Use
+    /// [TypedComponentInstanceProvider::instance_by_name_typed] for aliases, e.g. `dyn Trait`.
+    fn instance_by_name_any<T: 'static>(
+        &mut self,
+        name: &str,
+    ) -> Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>;
+
+    /// Tries to get an instance like [TypedComponentInstanceProvider::primary_instance_typed] does,
+    /// but takes ownership of it. Only possible for instances not shared with anyone else, e.g.
+    /// [prototypes](crate::scope::PROTOTYPE).
+    fn primary_instance_owned<T: 'static>(&mut self) -> Result<T, ComponentInstanceProviderError>;
+
+    /// Tries to get an instance like [TypedComponentInstanceProvider::instance_by_name_typed] does,
+    /// but takes ownership of it. Only possible for instances not shared with anyone else, e.g.
+    /// [prototypes](crate::scope::PROTOTYPE).
+    fn instance_by_name_owned<T: 'static>(
+        &mut self,
+        name: &str,
+    ) -> Result<T, ComponentInstanceProviderError>;
 }
 
 //noinspection DuplicatedCode
 #[cfg(feature = "async")]
 impl<CIP: ComponentInstanceProvider + ?Sized + Sync + Send> TypedComponentInstanceProvider for CIP {
-    fn primary_instance_typed<T: Injectable + ?Sized>(
+    fn primary_instance_typed<T: ?Sized + 'static>(
         &mut self,
     ) -> BoxFuture<'_, Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>> {
         async {
@@ -214,7 +561,7 @@ impl<CIP: ComponentInstanceProvider + ?Sized + Sync + Send> TypedComponentInstan
         .boxed()
     }
 
-    fn primary_instance_option<T: Injectable + ?Sized>(
+    fn primary_instance_option<T: ?Sized + 'static>(
         &mut self,
     ) -> BoxFuture<'_, Result<Option<ComponentInstancePtr<T>>, ComponentInstanceProviderError>>
     {
@@ -228,7 +575,25 @@ impl<CIP: ComponentInstanceProvider + ?Sized + Sync + Send> TypedComponentInstan
         .boxed()
     }
 
-    fn instances_typed<T: Injectable + ?Sized>(
+    fn primary_instance_or_default<T: Default + Component + ComponentInstanceBounds + 'static>(
+        &mut self,
+    ) -> BoxFuture<'_, Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>> {
+        async {
+            if let Some(instance) = self.primary_instance_option::<T>().await? {
+                return Ok(instance);
+            }
+
+            self.register_missing_component(
+                TypeId::of::<T>(),
+                type_name::<T>(),
+                &default_component_metadata::<T>(),
+            )?;
+            self.primary_instance_typed::<T>().await
+        }
+        .boxed()
+    }
+
+    fn instances_typed<T: ?Sized + 'static>(
         &mut self,
     ) -> BoxFuture<'_, Result<Vec<ComponentInstancePtr<T>>, ComponentInstanceProviderError>> {
         async {
@@ -246,7 +611,50 @@ impl<CIP: ComponentInstanceProvider + ?Sized + Sync + Send> TypedComponentInstan
         .boxed()
     }
 
-    fn instance_by_name_typed<T: Injectable + ?Sized>(
+    fn instances_filtered<T: ?Sized + 'static>(
+        &mut self,
+        filter: impl Fn(&ComponentDefinition) -> bool,
+    ) -> BoxFuture<'_, Result<Vec<ComponentInstancePtr<T>>, ComponentInstanceProviderError>> {
+        let type_id = TypeId::of::<T>();
+        let instances = self.filtered_instances(type_id, &filter);
+        async move {
+            instances
+                .await
+                .and_then(|instances| {
+                    instances
+                        .into_iter()
+                        .map(move |(p, cast)| cast_instance(p, cast, type_id))
+                        .try_collect()
+                })
+                .map_err(|error| enrich_error::<T>(error))
+        }
+        .boxed()
+    }
+
+    fn named_instances_typed<T: ?Sized + 'static>(
+        &mut self,
+    ) -> BoxFuture<
+        '_,
+        Result<FxHashMap<String, ComponentInstancePtr<T>>, ComponentInstanceProviderError>,
+    > {
+        async {
+            let type_id = TypeId::of::<T>();
+            self.named_instances(type_id)
+                .await
+                .and_then(|instances| {
+                    instances
+                        .into_iter()
+                        .map(move |(name, p, cast)| {
+                            cast_instance(p, cast, type_id).map(|p| (name, p))
+                        })
+                        .try_collect()
+                })
+                .map_err(|error| enrich_error::<T>(error))
+        }
+        .boxed()
+    }
+
+    fn instance_by_name_typed<T: ?Sized + 'static>(
         &mut self,
         name: &str,
     ) -> BoxFuture<'_, Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>> {
@@ -261,7 +669,7 @@ impl<CIP: ComponentInstanceProvider + ?Sized + Sync + Send> TypedComponentInstan
         .boxed()
     }
 
-    fn instance_by_name_option<T: Injectable + ?Sized>(
+    fn instance_by_name_option<T: ?Sized + 'static>(
         &mut self,
         name: &str,
     ) -> BoxFuture<'_, Result<Option<ComponentInstancePtr<T>>, ComponentInstanceProviderError>>
@@ -276,12 +684,50 @@ impl<CIP: ComponentInstanceProvider + ?Sized + Sync + Send> TypedComponentInstan
         }
         .boxed()
     }
+
+    fn instance_by_name_any<T: 'static>(
+        &mut self,
+        name: &str,
+    ) -> BoxFuture<'_, Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>> {
+        let name = name.to_string();
+        async move {
+            self.any_instance_by_name(&name)
+                .await
+                .and_then(|(p, cast)| cast_instance(p, cast, TypeId::of::<T>()))
+                .map_err(|error| enrich_error::<T>(error))
+        }
+        .boxed()
+    }
+
+    fn primary_instance_owned<T: Send + 'static>(
+        &mut self,
+    ) -> BoxFuture<'_, Result<T, ComponentInstanceProviderError>> {
+        async {
+            self.primary_instance_typed::<T>()
+                .await
+                .and_then(into_owned)
+        }
+        .boxed()
+    }
+
+    fn instance_by_name_owned<T: Send + 'static>(
+        &mut self,
+        name: &str,
+    ) -> BoxFuture<'_, Result<T, ComponentInstanceProviderError>> {
+        let name = name.to_string();
+        async move {
+            self.instance_by_name_typed::<T>(&name)
+                .await
+                .and_then(into_owned)
+        }
+        .boxed()
+    }
 }
 
 //noinspection DuplicatedCode
 #[cfg(not(feature = "async"))]
 impl<CIP: ComponentInstanceProvider + ?Sized> TypedComponentInstanceProvider for CIP {
-    fn primary_instance_typed<T: Injectable + ?Sized>(
+    fn primary_instance_typed<T: ?Sized + 'static>(
         &mut self,
     ) -> Result<ComponentInstancePtr<T>, ComponentInstanceProviderError> {
         let type_id = TypeId::of::<T>();
@@ -290,7 +736,7 @@ impl<CIP: ComponentInstanceProvider + ?Sized> TypedComponentInstanceProvider for
             .map_err(|error| enrich_error::<T>(error))
     }
 
-    fn primary_instance_option<T: Injectable + ?Sized>(
+    fn primary_instance_option<T: ?Sized + 'static>(
         &mut self,
     ) -> Result<Option<ComponentInstancePtr<T>>, ComponentInstanceProviderError> {
         match self.primary_instance_typed::<T>() {
@@ -300,7 +746,22 @@ impl<CIP: ComponentInstanceProvider + ?Sized> TypedComponentInstanceProvider for
         }
     }
 
-    fn instances_typed<T: Injectable + ?Sized>(
+    fn primary_instance_or_default<T: Default + Component + ComponentInstanceBounds + 'static>(
+        &mut self,
+    ) -> Result<ComponentInstancePtr<T>, ComponentInstanceProviderError> {
+        if let Some(instance) = self.primary_instance_option::<T>()? {
+            return Ok(instance);
+        }
+
+        self.register_missing_component(
+            TypeId::of::<T>(),
+            type_name::<T>(),
+            &default_component_metadata::<T>(),
+        )?;
+        self.primary_instance_typed::<T>()
+    }
+
+    fn instances_typed<T: ?Sized + 'static>(
         &mut self,
     ) -> Result<Vec<ComponentInstancePtr<T>>, ComponentInstanceProviderError> {
         let type_id = TypeId::of::<T>();
@@ -314,7 +775,36 @@ impl<CIP: ComponentInstanceProvider + ?Sized> TypedComponentInstanceProvider for
             .map_err(|error| enrich_error::<T>(error))
     }
 
-    fn instance_by_name_typed<T: Injectable + ?Sized>(
+    fn instances_filtered<T: ?Sized + 'static>(
+        &mut self,
+        filter: impl Fn(&ComponentDefinition) -> bool,
+    ) -> Result<Vec<ComponentInstancePtr<T>>, ComponentInstanceProviderError> {
+        let type_id = TypeId::of::<T>();
+        self.filtered_instances(type_id, &filter)
+            .and_then(|instances| {
+                instances
+                    .into_iter()
+                    .map(move |(p, cast)| cast_instance(p, cast, type_id))
+                    .try_collect()
+            })
+            .map_err(|error| enrich_error::<T>(error))
+    }
+
+    fn named_instances_typed<T: ?Sized + 'static>(
+        &mut self,
+    ) -> Result<FxHashMap<String, ComponentInstancePtr<T>>, ComponentInstanceProviderError> {
+        let type_id = TypeId::of::<T>();
+        self.named_instances(type_id)
+            .and_then(|instances| {
+                instances
+                    .into_iter()
+                    .map(move |(name, p, cast)| cast_instance(p, cast, type_id).map(|p| (name, p)))
+                    .try_collect()
+            })
+            .map_err(|error| enrich_error::<T>(error))
+    }
+
+    fn instance_by_name_typed<T: ?Sized + 'static>(
         &mut self,
         name: &str,
     ) -> Result<ComponentInstancePtr<T>, ComponentInstanceProviderError> {
@@ -324,7 +814,7 @@ impl<CIP: ComponentInstanceProvider + ?Sized> TypedComponentInstanceProvider for
             .map_err(|error| enrich_error::<T>(error))
     }
 
-    fn instance_by_name_option<T: Injectable + ?Sized>(
+    fn instance_by_name_option<T: ?Sized + 'static>(
         &mut self,
         name: &str,
     ) -> Result<Option<ComponentInstancePtr<T>>, ComponentInstanceProviderError> {
@@ -334,6 +824,295 @@ impl<CIP: ComponentInstanceProvider + ?Sized> TypedComponentInstanceProvider for
             Err(error) => Err(enrich_error::<T>(error)),
         }
     }
+
+    fn instance_by_name_any<T: 'static>(
+        &mut self,
+        name: &str,
+    ) -> Result<ComponentInstancePtr<T>, ComponentInstanceProviderError> {
+        self.any_instance_by_name(name)
+            .and_then(|(p, cast)| cast_instance(p, cast, TypeId::of::<T>()))
+            .map_err(|error| enrich_error::<T>(error))
+    }
+
+    fn primary_instance_owned<T: 'static>(&mut self) -> Result<T, ComponentInstanceProviderError> {
+        self.primary_instance_typed::<T>().and_then(into_owned)
+    }
+
+    fn instance_by_name_owned<T: 'static>(
+        &mut self,
+        name: &str,
+    ) -> Result<T, ComponentInstanceProviderError> {
+        self.instance_by_name_typed::<T>(name).and_then(into_owned)
+    }
+}
+
+/// Injectable handle for requesting instances of `T` on demand, rather than at construction time.
+/// This is mostly useful for [prototype](crate::scope::PROTOTYPE) components injected into
+/// longer-lived ones, which would otherwise be frozen at construction time. Fields can be marked
+/// with `#[component(name = "...")]` to request a named instance.
+///
+/// ```
+/// use springtime_di::instance_provider::ComponentProvider;
+/// use springtime_di::Component;
+///
+/// #[derive(Component)]
+/// #[component(scope = "PROTOTYPE")]
+/// struct Request;
+///
+/// #[derive(Component)]
+/// struct Handler {
+///     // each get() returns a new Request
+///     requests: ComponentProvider<Request>,
+/// }
+/// ```
+pub struct ComponentProvider<T: ?Sized + 'static> {
+    instance_provider: ComponentInstanceProviderPtr,
+    name: Option<String>,
+    _phantom: PhantomData<fn() -> ComponentInstancePtr<T>>,
+}
+
+impl<T: ?Sized + 'static> ComponentProvider<T> {
+    /// Creates a new provider for the primary instance of `T`, using a
+    /// [shared handle](ComponentInstanceProvider::shared_handle) to the given instance provider.
+    pub fn new(
+        instance_provider: &dyn ComponentInstanceProvider,
+    ) -> Result<Self, ComponentInstanceProviderError> {
+        Self::with_name(instance_provider, None)
+    }
+
+    /// Creates a new provider for the instance of `T` with the given name, using a
+    /// [shared handle](ComponentInstanceProvider::shared_handle) to the given instance provider.
+    pub fn named(
+        instance_provider: &dyn ComponentInstanceProvider,
+        name: &str,
+    ) -> Result<Self, ComponentInstanceProviderError> {
+        Self::with_name(instance_provider, Some(name.to_string()))
+    }
+
+    fn with_name(
+        instance_provider: &dyn ComponentInstanceProvider,
+        name: Option<String>,
+    ) -> Result<Self, ComponentInstanceProviderError> {
+        instance_provider
+            .shared_handle()
+            .map(|instance_provider| Self {
+                instance_provider,
+                name,
+                _phantom: PhantomData,
+            })
+            .ok_or(ComponentInstanceProviderError::SharedHandleUnsupported)
+    }
+
+    /// Returns the primary or named instance of `T`, respecting its scope.
+    #[cfg(feature = "async")]
+    pub fn get(
+        &self,
+    ) -> BoxFuture<'_, Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>> {
+        async {
+            let mut instance_provider = self
+                .instance_provider
+                .shared_handle()
+                .ok_or(ComponentInstanceProviderError::SharedHandleUnsupported)?;
+
+            match &self.name {
+                Some(name) => instance_provider.instance_by_name_typed::<T>(name).await,
+                None => instance_provider.primary_instance_typed::<T>().await,
+            }
+        }
+        .boxed()
+    }
+
+    /// Returns the primary or named instance of `T`, respecting its scope.
+    #[cfg(not(feature = "async"))]
+    pub fn get(&self) -> Result<ComponentInstancePtr<T>, ComponentInstanceProviderError> {
+        let mut instance_provider = self
+            .instance_provider
+            .shared_handle()
+            .ok_or(ComponentInstanceProviderError::SharedHandleUnsupported)?;
+
+        match &self.name {
+            Some(name) => instance_provider.instance_by_name_typed::<T>(name),
+            None => instance_provider.primary_instance_typed::<T>(),
+        }
+    }
+}
+
+/// Injectable handle to the [ComponentInstanceProvider] constructing the component, which allows
+/// looking up instances by type or name at runtime, e.g. for registries dispatching to components
+/// chosen dynamically. Prefer regular injection or [ComponentProvider] where possible, since such
+/// lookups are not visible to graph validation.
+///
+/// ```
+/// use springtime_di::instance_provider::FactoryHandle;
+/// use springtime_di::Component;
+///
+/// #[derive(Component)]
+/// struct CommandDispatcher {
+///     // e.g. factory.instance_by_name::<dyn Command + Send + Sync>(command_name)
+///     factory: FactoryHandle,
+/// }
+/// ```
+pub struct FactoryHandle {
+    instance_provider: ComponentInstanceProviderPtr,
+}
+
+impl FactoryHandle {
+    /// Creates a new handle using a [shared handle](ComponentInstanceProvider::shared_handle) to
+    /// the given instance provider.
+    pub fn new(
+        instance_provider: &dyn ComponentInstanceProvider,
+    ) -> Result<Self, ComponentInstanceProviderError> {
+        instance_provider
+            .shared_handle()
+            .map(|instance_provider| Self { instance_provider })
+            .ok_or(ComponentInstanceProviderError::SharedHandleUnsupported)
+    }
+
+    fn handle(&self) -> Result<ComponentInstanceProviderPtr, ComponentInstanceProviderError> {
+        self.instance_provider
+            .shared_handle()
+            .ok_or(ComponentInstanceProviderError::SharedHandleUnsupported)
+    }
+
+    /// Returns the primary instance of `T`, respecting its scope.
+    #[cfg(feature = "async")]
+    pub fn primary_instance<T: ?Sized + 'static>(
+        &self,
+    ) -> BoxFuture<'_, Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>> {
+        async { self.handle()?.primary_instance_typed::<T>().await }.boxed()
+    }
+
+    /// Returns the primary instance of `T`, respecting its scope.
+    #[cfg(not(feature = "async"))]
+    pub fn primary_instance<T: ?Sized + 'static>(
+        &self,
+    ) -> Result<ComponentInstancePtr<T>, ComponentInstanceProviderError> {
+        self.handle()?.primary_instance_typed::<T>()
+    }
+
+    /// Returns the instance of `T` with the given name, respecting its scope.
+    #[cfg(feature = "async")]
+    pub fn instance_by_name<'a, T: ?Sized + 'static>(
+        &'a self,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<ComponentInstancePtr<T>, ComponentInstanceProviderError>> {
+        async move { self.handle()?.instance_by_name_typed::<T>(name).await }.boxed()
+    }
+
+    /// Returns the instance of `T` with the given name, respecting its scope.
+    #[cfg(not(feature = "async"))]
+    pub fn instance_by_name<T: ?Sized + 'static>(
+        &self,
+        name: &str,
+    ) -> Result<ComponentInstancePtr<T>, ComponentInstanceProviderError> {
+        self.handle()?.instance_by_name_typed::<T>(name)
+    }
+
+    /// Returns all instances of `T`, in the order of their definitions.
+    #[cfg(feature = "async")]
+    pub fn instances<T: ?Sized + 'static>(
+        &self,
+    ) -> BoxFuture<'_, Result<Vec<ComponentInstancePtr<T>>, ComponentInstanceProviderError>> {
+        async { self.handle()?.instances_typed::<T>().await }.boxed()
+    }
+
+    /// Returns all instances of `T`, in the order of their definitions.
+    #[cfg(not(feature = "async"))]
+    pub fn instances<T: ?Sized + 'static>(
+        &self,
+    ) -> Result<Vec<ComponentInstancePtr<T>>, ComponentInstanceProviderError> {
+        self.handle()?.instances_typed::<T>()
+    }
+}
+
+/// Injectable proxy for components living in narrower scopes than the owning component, e.g. a
+/// [contextual](crate::scope::ContextualScope) request-scoped component injected into a singleton.
+/// Injecting such component directly would freeze the instance active at construction time, while
+/// the proxy resolves the instance from the currently active scope context on each
+/// [get](ComponentProvider::get). This is a [ComponentProvider] named after its purpose, so it also
+/// supports `#[component(name = "...")]`.
+///
+/// ```
+/// use springtime_di::instance_provider::ScopedProxy;
+/// use springtime_di::Component;
+///
+/// #[derive(Component)]
+/// #[component(scope = "REQUEST")]
+/// struct RequestContext;
+///
+/// #[derive(Component)]
+/// struct RequestHandler {
+///     // each get() returns the RequestContext of the currently entered request
+///     context: ScopedProxy<RequestContext>,
+/// }
+/// ```
+pub type ScopedProxy<T> = ComponentProvider<T>;
+
+/// Dependency injected after the owning component is constructed and stored in its scope, which
+/// makes circular dependencies possible, e.g. between an event bus and its listeners. Fields of
+/// this type need to be marked with `#[component(late)]`. Since the value is injected after
+/// construction, it's not available in custom constructors.
+///
+/// Note: components referring to each other form reference cycles, which are never freed. This is
+/// usually not a problem for singletons living as long as the application.
+///
+/// ```
+/// use springtime_di::instance_provider::{ComponentInstancePtr, Late};
+/// use springtime_di::Component;
+///
+/// #[derive(Component)]
+/// struct EventBus {
+///     #[component(late)]
+///     listener: Late<Listener>,
+/// }
+///
+/// #[derive(Component)]
+/// struct Listener {
+///     bus: ComponentInstancePtr<EventBus>,
+/// }
+/// ```
+pub struct Late<T: ?Sized + 'static> {
+    #[cfg(feature = "threadsafe")]
+    instance: Mutex<Option<ComponentInstancePtr<T>>>,
+    #[cfg(not(feature = "threadsafe"))]
+    instance: RefCell<Option<ComponentInstancePtr<T>>>,
+}
+
+impl<T: ?Sized + 'static> Default for Late<T> {
+    fn default() -> Self {
+        Self {
+            instance: Default::default(),
+        }
+    }
+}
+
+impl<T: ?Sized + 'static> Late<T> {
+    /// Returns the injected instance or `None`, if not injected yet.
+    #[cfg(feature = "threadsafe")]
+    pub fn get(&self) -> Option<ComponentInstancePtr<T>> {
+        self.instance
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Returns the injected instance or `None`, if not injected yet.
+    #[cfg(not(feature = "threadsafe"))]
+    pub fn get(&self) -> Option<ComponentInstancePtr<T>> {
+        self.instance.borrow().clone()
+    }
+
+    /// Sets the instance. Usually called by generated code.
+    #[cfg(feature = "threadsafe")]
+    pub fn set(&self, instance: ComponentInstancePtr<T>) {
+        *self.instance.lock().unwrap_or_else(PoisonError::into_inner) = Some(instance);
+    }
+
+    /// Sets the instance. Usually called by generated code.
+    #[cfg(not(feature = "threadsafe"))]
+    pub fn set(&self, instance: ComponentInstancePtr<T>) {
+        *self.instance.borrow_mut() = Some(instance);
+    }
 }
 
 fn enrich_error<T: ?Sized>(
@@ -347,6 +1126,15 @@ fn enrich_error<T: ?Sized>(
             type_id,
             type_name: Some(type_name::<T>().to_string()),
         },
+        ComponentInstanceProviderError::AmbiguousPrimaryInstance {
+            type_id,
+            type_name: None,
+            candidates,
+        } => ComponentInstanceProviderError::AmbiguousPrimaryInstance {
+            type_id,
+            type_name: Some(type_name::<T>().to_string()),
+            candidates,
+        },
         ComponentInstanceProviderError::DependencyCycle {
             type_id,
             type_name: None,
@@ -358,25 +1146,30 @@ fn enrich_error<T: ?Sized>(
     }
 }
 
-fn cast_instance<T: Injectable + ?Sized>(
+fn cast_instance<T: ?Sized + 'static>(
     instance: ComponentInstanceAnyPtr,
     cast: CastFunction,
     type_id: TypeId,
 ) -> Result<ComponentInstancePtr<T>, ComponentInstanceProviderError> {
     debug_assert_eq!(type_id, TypeId::of::<T>());
-    cast(instance)
-        .map_err(|_| ComponentInstanceProviderError::IncompatibleComponent {
+    let mut target: Option<ComponentInstancePtr<T>> = None;
+    cast(instance, &mut target).ok().and(target).ok_or_else(|| {
+        ComponentInstanceProviderError::IncompatibleComponent {
             type_id,
             type_name: type_name::<T>().to_string(),
-        })
-        .and_then(|p| {
-            p.downcast::<ComponentInstancePtr<T>>()
-                .map(|p| (*p).clone())
-                .map_err(|_| ComponentInstanceProviderError::IncompatibleComponent {
-                    type_id,
-                    type_name: type_name::<T>().to_string(),
-                })
-        })
+        }
+    })
+}
+
+fn into_owned<T: 'static>(
+    instance: ComponentInstancePtr<T>,
+) -> Result<T, ComponentInstanceProviderError> {
+    ComponentInstancePtr::try_unwrap(instance).map_err(|_| {
+        ComponentInstanceProviderError::SharedInstance {
+            type_id: TypeId::of::<T>(),
+            type_name: type_name::<T>().to_string(),
+        }
+    })
 }
 
 #[cfg(test)]
@@ -385,9 +1178,11 @@ mod tests {
     #[cfg(not(feature = "async"))]
     mod sync {
         use crate::component::Injectable;
+        use crate::component_registry::internal::cast_factory_product;
         use crate::instance_provider::{
-            CastFunction, ComponentInstanceAnyPtr, ComponentInstancePtr,
-            MockComponentInstanceProvider, TypedComponentInstanceProvider,
+            store_cast_result, CastFunction, ComponentInstanceAnyPtr,
+            ComponentInstanceProviderError, ComponentInstancePtr, MockComponentInstanceProvider,
+            TypedComponentInstanceProvider,
         };
         use mockall::predicate::*;
         use std::any::{Any, TypeId};
@@ -396,12 +1191,17 @@ mod tests {
 
         impl Injectable for TestComponent {}
 
+        struct OtherComponent;
+
+        impl Injectable for OtherComponent {}
+
         fn test_cast(
             instance: ComponentInstanceAnyPtr,
-        ) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr> {
+            target: &mut dyn Any,
+        ) -> Result<(), ComponentInstanceAnyPtr> {
             instance
                 .downcast::<TestComponent>()
-                .map(|p| Box::new(p) as Box<dyn Any>)
+                .map(|p| store_cast_result(p, target))
         }
 
         #[test]
@@ -457,6 +1257,25 @@ mod tests {
                 .is_empty());
         }
 
+        #[test]
+        fn should_provide_named_instances_typed() {
+            let mut instance_provider = MockComponentInstanceProvider::new();
+            instance_provider
+                .expect_named_instances()
+                .with(eq(TypeId::of::<TestComponent>()))
+                .times(1)
+                .return_const(Ok(vec![(
+                    "name".to_string(),
+                    ComponentInstancePtr::new(TestComponent) as ComponentInstanceAnyPtr,
+                    test_cast as CastFunction,
+                )]));
+
+            assert!(instance_provider
+                .named_instances_typed::<TestComponent>()
+                .unwrap()
+                .contains_key("name"));
+        }
+
         #[test]
         fn should_provide_instance_by_name_typed() {
             let name = "name";
@@ -495,5 +1314,35 @@ mod tests {
                 .unwrap()
                 .is_some());
         }
+
+        #[test]
+        fn should_not_store_cast_result_in_incompatible_target() {
+            let mut target: Option<ComponentInstancePtr<OtherComponent>> = None;
+            assert!(cast_factory_product::<TestComponent>(
+                ComponentInstancePtr::new(TestComponent) as ComponentInstanceAnyPtr,
+                &mut target
+            )
+            .is_ok());
+            assert!(target.is_none());
+        }
+
+        #[test]
+        fn should_report_incompatible_cast_target() {
+            let mut instance_provider = MockComponentInstanceProvider::new();
+            instance_provider
+                .expect_primary_instance()
+                .with(eq(TypeId::of::<OtherComponent>()))
+                .times(1)
+                .return_const(Ok((
+                    ComponentInstancePtr::new(TestComponent) as ComponentInstanceAnyPtr,
+                    cast_factory_product::<TestComponent> as CastFunction,
+                )));
+
+            assert!(matches!(
+                instance_provider.primary_instance_typed::<OtherComponent>(),
+                Err(ComponentInstanceProviderError::IncompatibleComponent { type_id, .. })
+                    if type_id == TypeId::of::<OtherComponent>()
+            ));
+        }
     }
 }
@@ -43,13 +43,31 @@
 //! * `derive` - automatically import helper proc macros
 //! * `threadsafe` - use threadsafe pointers and `Send + Sync` trait bounds
 //! * `async` - turn all creation functions async
+//! * `serde` - make [registry exports](component_registry::export) serializable
+//! * `test-util` - enable utilities for replacing components in tests, along with
+//!   [mockall](https://docs.rs/mockall) mocks of
+//!   [ComponentInstanceProvider](instance_provider::ComponentInstanceProvider) and
+//!   [ComponentDefinitionRegistry](component_registry::ComponentDefinitionRegistry) for unit
+//!   testing components
+//! * `automatic-registration` - register components automatically, using link-time collection
+//!   with [inventory](https://docs.rs/inventory); enabled by default
+//! * `explicit-registration` - don't register components automatically and rely on
+//!   [collect_components] instead; `automatic-registration` can then be disabled to drop the
+//!   `inventory` dependency
+
+#[cfg(not(any(feature = "automatic-registration", feature = "explicit-registration")))]
+compile_error!(
+    "Either the `automatic-registration` or the `explicit-registration` feature needs to be enabled."
+);
 
 pub mod component;
 pub mod component_registry;
+pub mod event;
 pub mod factory;
 #[cfg(feature = "async")]
 pub mod future;
 pub mod instance_provider;
+pub mod post_processor;
 pub mod scope;
 
 #[cfg(feature = "derive")]
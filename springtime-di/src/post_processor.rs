@@ -0,0 +1,96 @@
+//! Instance post-processors are an extension point for cross-cutting concerns, e.g. tracing,
+//! caching or metrics. Each [InstancePostProcessor] registered with
+//! [ComponentFactoryBuilder::with_post_processor](crate::factory::ComponentFactoryBuilder::with_post_processor)
+//! is called with every newly constructed instance, in registration order, before the instance is
+//! stored in its [scope](crate::scope). A post-processor can inspect the instance, replace it or
+//! pass it on unchanged.
+//!
+//! Instances are shared between the concrete component and all its aliases, which are resolved by
+//! [cast functions](crate::instance_provider::CastFunction) expecting the concrete component type.
+//! Therefore, a replacement needs to be of the same concrete type, e.g. a reconfigured copy of the
+//! original, and anything else fails with
+//! [IncompatiblePostProcessedInstance](crate::instance_provider::ComponentInstanceProviderError::IncompatiblePostProcessedInstance).
+//! Post-processors cannot wrap a `dyn Trait` in a decorator of another type - such decorators need
+//! to be regular components, which implement the trait, inject the decorated concrete component and
+//! register a primary alias.
+//!
+//! ```
+//! use springtime_di::component_registry::ComponentDefinition;
+//! use springtime_di::instance_provider::{ComponentInstanceAnyPtr, ComponentInstancePtr, ErrorPtr};
+//! use springtime_di::post_processor::InstancePostProcessor;
+//! use springtime_di::Component;
+//! # #[cfg(feature = "async")]
+//! # use springtime_di::future::{BoxFuture, FutureExt};
+//!
+//! #[derive(Component)]
+//! struct HttpSettings {
+//!     #[component(default)]
+//!     retries: u8,
+//! }
+//!
+//! // replaces unconfigured settings with sensible defaults
+//! struct DefaultRetries;
+//!
+//! impl DefaultRetries {
+//!     fn apply(instance: ComponentInstanceAnyPtr) -> ComponentInstanceAnyPtr {
+//!         match instance.downcast::<HttpSettings>() {
+//!             Ok(settings) if settings.retries == 0 => {
+//!                 ComponentInstancePtr::new(HttpSettings { retries: 3 })
+//!             }
+//!             Ok(settings) => settings,
+//!             Err(instance) => instance,
+//!         }
+//!     }
+//! }
+//!
+//! impl InstancePostProcessor for DefaultRetries {
+//!     # #[cfg(not(feature = "async"))]
+//!     fn post_process(
+//!         &self,
+//!         _definition: &ComponentDefinition,
+//!         instance: ComponentInstanceAnyPtr,
+//!     ) -> Result<ComponentInstanceAnyPtr, ErrorPtr> {
+//!         Ok(Self::apply(instance))
+//!     }
+//!     # #[cfg(feature = "async")]
+//!     # fn post_process<'a>(
+//!     #     &'a self,
+//!     #     _definition: &'a ComponentDefinition,
+//!     #     instance: ComponentInstanceAnyPtr,
+//!     # ) -> BoxFuture<'a, Result<ComponentInstanceAnyPtr, ErrorPtr>> {
+//!     #     async move { Ok(Self::apply(instance)) }.boxed()
+//!     # }
+//! }
+//! ```
+
+use crate::component_registry::ComponentDefinition;
+#[cfg(feature = "async")]
+use crate::future::BoxFuture;
+use crate::instance_provider::{ComponentInstanceAnyPtr, ErrorPtr};
+
+#[cfg(not(feature = "threadsafe"))]
+pub type InstancePostProcessorPtr = Box<dyn InstancePostProcessor>;
+#[cfg(feature = "threadsafe")]
+pub type InstancePostProcessorPtr = Box<dyn InstancePostProcessor + Send + Sync>;
+
+/// Processor called with newly constructed instances, before they are stored in their scopes. See
+/// module documentation for more information.
+pub trait InstancePostProcessor {
+    /// Processes the instance created for given definition and returns the instance which should
+    /// be used instead. Returning an error fails the creation of the instance.
+    #[cfg(not(feature = "async"))]
+    fn post_process(
+        &self,
+        definition: &ComponentDefinition,
+        instance: ComponentInstanceAnyPtr,
+    ) -> Result<ComponentInstanceAnyPtr, ErrorPtr>;
+
+    /// Processes the instance created for given definition and returns the instance which should
+    /// be used instead. Returning an error fails the creation of the instance.
+    #[cfg(feature = "async")]
+    fn post_process<'a>(
+        &'a self,
+        definition: &'a ComponentDefinition,
+        instance: ComponentInstanceAnyPtr,
+    ) -> BoxFuture<'a, Result<ComponentInstanceAnyPtr, ErrorPtr>>;
+}
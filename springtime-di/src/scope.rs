@@ -7,14 +7,37 @@
 //! consequences if incompatible scopes are mixed together, e.g. a [singleton](SINGLETON) component
 //! can depend on a [prototype](PROTOTYPE) one. In such case when creating the singleton, a new
 //! instance of the dependency will be created, since it's a prototype, but then that single
-//! instance will live as long as the singleton lives.
+//! instance will live as long as the singleton lives. To get a new instance on each request,
+//! inject a [ComponentProvider](crate::instance_provider::ComponentProvider) instead.
+//!
+//! ## Contextual scopes
+//!
+//! Some scopes keep instances per some external context, e.g. a web request or a background job.
+//! Such contexts are activated for a given closure or future with
+//! [ComponentFactory::in_scope_context](crate::factory::ComponentFactory::in_scope_context), which
+//! notifies the scope with given name. The active context is tracked per thread (and per task for
+//! futures), so concurrent requests can use different contexts of the same scope at the same time.
+//! [ContextualScope] is a ready to use implementation, which needs to be registered under a custom
+//! name, e.g. `REQUEST`. Resources held by instances can be released when their context is exited
+//! with an [EvictionListener], set via
+//! [ComponentFactory::set_eviction_listener](crate::factory::ComponentFactory::set_eviction_listener).
+//!
+//! Longer-lived components should not depend on contextual ones directly, since they would keep the
+//! instance from the context active at their construction. Inject a
+//! [ScopedProxy](crate::instance_provider::ScopedProxy) instead, which resolves the instance from
+//! the context active for the caller on each access.
 
-use crate::component_registry::ComponentDefinition;
-use crate::instance_provider::ComponentInstanceAnyPtr;
+use crate::component_registry::{ComponentDefinition, ComponentDefinitionKey};
+use crate::instance_provider::{ComponentInstanceAnyPtr, ComponentInstancePtr};
 use fxhash::FxHashMap;
 #[cfg(test)]
 use mockall::automock;
-use std::any::TypeId;
+use std::any::Any;
+use std::cell::RefCell;
+#[cfg(not(feature = "threadsafe"))]
+use std::rc::Weak;
+#[cfg(feature = "threadsafe")]
+use std::sync::Weak;
 
 #[cfg(not(feature = "threadsafe"))]
 pub type ScopePtr = Box<dyn Scope>;
@@ -27,7 +50,26 @@ pub const SINGLETON: &str = "SINGLETON";
 /// Name of the [PrototypeScope].
 pub const PROTOTYPE: &str = "PROTOTYPE";
 
+/// Name of the [WeakSingletonScope].
+pub const WEAK_SINGLETON: &str = "WEAK_SINGLETON";
+
+/// Listener called with instances evicted from a [Scope], e.g. when the scope is cleared or a
+/// context is exited, so resources held by them can be released. Note: other components might
+/// still hold references to evicted instances.
+#[cfg(not(feature = "threadsafe"))]
+pub type EvictionListener = Box<dyn FnMut(&ComponentDefinitionKey, ComponentInstanceAnyPtr)>;
+#[cfg(feature = "threadsafe")]
+pub type EvictionListener =
+    Box<dyn FnMut(&ComponentDefinitionKey, ComponentInstanceAnyPtr) + Send + Sync>;
+
+#[cfg(not(feature = "threadsafe"))]
+type ComponentInstanceWeakPtr = Weak<dyn Any + 'static>;
+#[cfg(feature = "threadsafe")]
+type ComponentInstanceWeakPtr = Weak<dyn Any + Send + Sync + 'static>;
+
 /// A scope containing component instances. See module documentation for information on scopes.
+/// Instances should be stored per [ComponentDefinition::key], since a concrete type can have
+/// multiple definitions.
 #[cfg_attr(test, automock)]
 pub trait Scope {
     /// Gets an instance requested for the given definition, if available in this scope.
@@ -40,28 +82,150 @@ pub trait Scope {
         definition: &ComponentDefinition,
         instance: ComponentInstanceAnyPtr,
     );
+
+    /// Removes the instance stored for given definition, e.g. when the definition is removed or
+    /// replaced in the registry. Does nothing by default.
+    fn remove_instance(&mut self, _definition: &ComponentDefinition) {}
+
+    /// Removes all stored instances, e.g. to release resources held by a tenant scope. Does nothing
+    /// by default.
+    fn clear(&mut self) {}
+
+    /// Sets the listener notified about instances evicted from the scope, replacing the previous
+    /// one. Does nothing by default, which is appropriate for scopes not storing instances.
+    fn set_eviction_listener(&mut self, _listener: EvictionListener) {}
+
+    /// Gets an instance requested for the given definition in given context, if available in this
+    /// scope. Falls back to [instance](Self::instance) by default, which is appropriate for scopes
+    /// not supporting contexts.
+    fn context_instance(
+        &self,
+        definition: &ComponentDefinition,
+        _context: &str,
+    ) -> Option<ComponentInstanceAnyPtr> {
+        self.instance(definition)
+    }
+
+    /// Stores given instance in given context. Falls back to [store_instance](Self::store_instance)
+    /// by default, which is appropriate for scopes not supporting contexts.
+    fn store_context_instance(
+        &mut self,
+        definition: &ComponentDefinition,
+        _context: &str,
+        instance: ComponentInstanceAnyPtr,
+    ) {
+        self.store_instance(definition, instance);
+    }
+
+    /// Activates given context, so the scope can keep instances separately for it. The same context
+    /// can be entered multiple times, e.g. by concurrent tasks, and each call is paired with an
+    /// [exit](Self::exit_context). Does nothing by default.
+    fn enter_context(&mut self, _context: &str) {}
+
+    /// Deactivates given context, which ends it when it has been exited as many times as entered.
+    /// Does nothing by default.
+    fn exit_context(&mut self, _context: &str) {}
 }
 
 /// Scope for instances shared between components. Stateless components are good candidates to be
 /// stored in the singleton scope.
 #[derive(Default)]
 pub struct SingletonScope {
-    instances: FxHashMap<TypeId, ComponentInstanceAnyPtr>,
+    instances: FxHashMap<ComponentDefinitionKey, ComponentInstanceAnyPtr>,
+    eviction_listener: Option<EvictionListener>,
 }
 
 impl Scope for SingletonScope {
     #[inline]
     fn instance(&self, definition: &ComponentDefinition) -> Option<ComponentInstanceAnyPtr> {
-        self.instances.get(&definition.resolved_type_id).cloned()
+        self.instances.get(&definition.key()).cloned()
+    }
+
+    #[inline]
+    fn store_instance(
+        &mut self,
+        definition: &ComponentDefinition,
+        instance: ComponentInstanceAnyPtr,
+    ) {
+        let key = definition.key();
+        if let Some(previous) = self.instances.insert(key.clone(), instance) {
+            notify_eviction(&mut self.eviction_listener, &key, previous);
+        }
+    }
+
+    fn remove_instance(&mut self, definition: &ComponentDefinition) {
+        let key = definition.key();
+        if let Some(instance) = self.instances.remove(&key) {
+            notify_eviction(&mut self.eviction_listener, &key, instance);
+        }
+    }
+
+    fn clear(&mut self) {
+        for (key, instance) in self.instances.drain() {
+            notify_eviction(&mut self.eviction_listener, &key, instance);
+        }
+    }
+
+    #[inline]
+    fn set_eviction_listener(&mut self, listener: EvictionListener) {
+        self.eviction_listener = Some(listener);
     }
+}
+
+/// Scope for shared instances, which keeps only weak references to them. An instance lives as long
+/// as it's used by other components or code, and is recreated on the next request after all strong
+/// references have been dropped. Large, cache-like components, which should be reclaimable, are
+/// good candidates to be stored in the weak singleton scope. Note: such instances are not
+/// [destroyed](crate::component::DisposableComponent) on shutdown.
+#[derive(Default)]
+pub struct WeakSingletonScope {
+    instances: FxHashMap<ComponentDefinitionKey, ComponentInstanceWeakPtr>,
+    eviction_listener: Option<EvictionListener>,
+}
 
+impl Scope for WeakSingletonScope {
     #[inline]
+    fn instance(&self, definition: &ComponentDefinition) -> Option<ComponentInstanceAnyPtr> {
+        self.instances
+            .get(&definition.key())
+            .and_then(|instance| instance.upgrade())
+    }
+
     fn store_instance(
         &mut self,
         definition: &ComponentDefinition,
         instance: ComponentInstanceAnyPtr,
     ) {
-        self.instances.insert(definition.resolved_type_id, instance);
+        // dropped instances don't need to be kept around
+        self.instances
+            .retain(|_, instance| instance.strong_count() > 0);
+        self.instances
+            .insert(definition.key(), ComponentInstancePtr::downgrade(&instance));
+    }
+
+    // instances which have already been dropped cannot be passed to the listener
+    fn remove_instance(&mut self, definition: &ComponentDefinition) {
+        let key = definition.key();
+        if let Some(instance) = self
+            .instances
+            .remove(&key)
+            .and_then(|instance| instance.upgrade())
+        {
+            notify_eviction(&mut self.eviction_listener, &key, instance);
+        }
+    }
+
+    fn clear(&mut self) {
+        for (key, instance) in self.instances.drain() {
+            if let Some(instance) = instance.upgrade() {
+                notify_eviction(&mut self.eviction_listener, &key, instance);
+            }
+        }
+    }
+
+    #[inline]
+    fn set_eviction_listener(&mut self, listener: EvictionListener) {
+        self.eviction_listener = Some(listener);
     }
 }
 
@@ -85,6 +249,157 @@ impl Scope for PrototypeScope {
     }
 }
 
+/// A scope which keeps instances separately for each active context, e.g. a web request. Instances
+/// are released when their context is exited by everyone who entered it. Without an active
+/// context, a new instance is created on each request, like in the [PrototypeScope].
+#[derive(Default)]
+pub struct ContextualScope {
+    // number of times each context has been entered and not yet exited
+    contexts: FxHashMap<String, usize>,
+    instances: FxHashMap<String, FxHashMap<ComponentDefinitionKey, ComponentInstanceAnyPtr>>,
+    eviction_listener: Option<EvictionListener>,
+}
+
+impl Scope for ContextualScope {
+    #[inline]
+    fn instance(&self, _definition: &ComponentDefinition) -> Option<ComponentInstanceAnyPtr> {
+        None
+    }
+
+    #[inline]
+    fn store_instance(
+        &mut self,
+        _definition: &ComponentDefinition,
+        _instance: ComponentInstanceAnyPtr,
+    ) {
+    }
+
+    fn context_instance(
+        &self,
+        definition: &ComponentDefinition,
+        context: &str,
+    ) -> Option<ComponentInstanceAnyPtr> {
+        self.instances
+            .get(context)
+            .and_then(|instances| instances.get(&definition.key()))
+            .cloned()
+    }
+
+    fn store_context_instance(
+        &mut self,
+        definition: &ComponentDefinition,
+        context: &str,
+        instance: ComponentInstanceAnyPtr,
+    ) {
+        // instances stored after the context has ended would never be released
+        if self.contexts.contains_key(context) {
+            self.instances
+                .entry(context.to_string())
+                .or_default()
+                .insert(definition.key(), instance);
+        }
+    }
+
+    fn remove_instance(&mut self, definition: &ComponentDefinition) {
+        let key = definition.key();
+        for instances in self.instances.values_mut() {
+            if let Some(instance) = instances.remove(&key) {
+                notify_eviction(&mut self.eviction_listener, &key, instance);
+            }
+        }
+    }
+
+    // active contexts are kept, since they are controlled externally
+    fn clear(&mut self) {
+        for (key, instance) in self.instances.drain().flat_map(|(_, instances)| instances) {
+            notify_eviction(&mut self.eviction_listener, &key, instance);
+        }
+    }
+
+    #[inline]
+    fn set_eviction_listener(&mut self, listener: EvictionListener) {
+        self.eviction_listener = Some(listener);
+    }
+
+    fn enter_context(&mut self, context: &str) {
+        *self.contexts.entry(context.to_string()).or_default() += 1;
+    }
+
+    fn exit_context(&mut self, context: &str) {
+        if let Some(count) = self.contexts.get_mut(context) {
+            *count -= 1;
+            if *count == 0 {
+                self.contexts.remove(context);
+                for (key, instance) in self.instances.remove(context).into_iter().flatten() {
+                    notify_eviction(&mut self.eviction_listener, &key, instance);
+                }
+            }
+        }
+    }
+}
+
+thread_local! {
+    // contexts active on the current thread per scope name, with the innermost one last; futures
+    // set their context only for the duration of each poll
+    static ACTIVE_CONTEXTS: RefCell<FxHashMap<String, Vec<String>>> = RefCell::default();
+}
+
+/// Calls given function with the context active on the current thread in the scope with given name.
+pub(crate) fn with_active_context<R>(scope: &str, f: impl FnOnce(Option<&str>) -> R) -> R {
+    ACTIVE_CONTEXTS.with(|contexts| {
+        f(contexts
+            .borrow()
+            .get(scope)
+            .and_then(|contexts| contexts.last())
+            .map(String::as_str))
+    })
+}
+
+/// Makes given context active on the current thread until dropped, restoring the previous one
+/// afterwards, also when unwinding.
+pub(crate) struct ActiveContextGuard<'a> {
+    scope: &'a str,
+}
+
+impl<'a> ActiveContextGuard<'a> {
+    pub(crate) fn new(scope: &'a str, context: &str) -> Self {
+        ACTIVE_CONTEXTS.with(|contexts| {
+            contexts
+                .borrow_mut()
+                .entry(scope.to_string())
+                .or_default()
+                .push(context.to_string())
+        });
+
+        Self { scope }
+    }
+}
+
+impl Drop for ActiveContextGuard<'_> {
+    fn drop(&mut self) {
+        ACTIVE_CONTEXTS.with(|contexts| {
+            let mut contexts = contexts.borrow_mut();
+            if let Some(scope_contexts) = contexts.get_mut(self.scope) {
+                scope_contexts.pop();
+                if scope_contexts.is_empty() {
+                    contexts.remove(self.scope);
+                }
+            }
+        });
+    }
+}
+
+#[inline]
+fn notify_eviction(
+    listener: &mut Option<EvictionListener>,
+    key: &ComponentDefinitionKey,
+    instance: ComponentInstanceAnyPtr,
+) {
+    if let Some(listener) = listener {
+        listener(key, instance);
+    }
+}
+
 /// Factory for custom [Scope]s.
 #[cfg_attr(test, automock)]
 pub trait ScopeFactory {
@@ -109,6 +424,24 @@ impl ScopeFactory for PrototypeScopeFactory {
     }
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Default)]
+pub struct WeakSingletonScopeFactory;
+
+impl ScopeFactory for WeakSingletonScopeFactory {
+    fn create_scope(&self) -> ScopePtr {
+        Box::<WeakSingletonScope>::default()
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Default)]
+pub struct ContextualScopeFactory;
+
+impl ScopeFactory for ContextualScopeFactory {
+    fn create_scope(&self) -> ScopePtr {
+        Box::<ContextualScope>::default()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(not(feature = "async"))]
@@ -118,8 +451,13 @@ mod tests {
         use crate::instance_provider::{
             ComponentInstanceAnyPtr, ComponentInstanceProvider, ComponentInstancePtr,
         };
-        use crate::scope::{PrototypeScopeFactory, ScopeFactory, SingletonScopeFactory};
+        use crate::scope::{
+            ContextualScopeFactory, PrototypeScopeFactory, ScopeFactory, SingletonScopeFactory,
+            WeakSingletonScopeFactory,
+        };
         use std::any::{type_name, Any, TypeId};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
 
         fn test_constructor(
             _instance_provider: &mut dyn ComponentInstanceProvider,
@@ -132,7 +470,8 @@ mod tests {
 
         fn test_cast(
             instance: ComponentInstanceAnyPtr,
-        ) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr> {
+            _target: &mut dyn Any,
+        ) -> Result<(), ComponentInstanceAnyPtr> {
             Err(instance)
         }
 
@@ -141,10 +480,16 @@ mod tests {
                 names: Default::default(),
                 is_primary: false,
                 scope: "".to_string(),
+                order: 0,
                 resolved_type_id: TypeId::of::<u8>(),
+                qualifier: None,
                 resolved_type_name: type_name::<u8>().to_string(),
                 constructor: test_constructor,
+                late_injection: None,
+                post_construct: None,
+                pre_destroy: None,
                 cast: test_cast,
+                dependencies: vec![],
             }
         }
 
@@ -158,6 +503,48 @@ mod tests {
             scope.store_instance(&definition, instance.clone());
 
             assert!(scope.instance(&definition).is_some());
+
+            scope.clear();
+            assert!(scope.instance(&definition).is_none());
+        }
+
+        #[test]
+        fn should_notify_about_evicted_instances() {
+            let definition = create_definition();
+            let factory = ContextualScopeFactory;
+            let mut scope = factory.create_scope();
+
+            let evicted = Arc::new(AtomicUsize::new(0));
+            let listener_evicted = evicted.clone();
+            scope.set_eviction_listener(Box::new(move |key, _| {
+                assert_eq!(key.type_id, TypeId::of::<u8>());
+                listener_evicted.fetch_add(1, Ordering::SeqCst);
+            }));
+
+            let instance = ComponentInstancePtr::new(0) as ComponentInstanceAnyPtr;
+            scope.enter_context("first");
+            scope.store_context_instance(&definition, "first", instance.clone());
+            scope.exit_context("first");
+            assert_eq!(evicted.load(Ordering::SeqCst), 1);
+
+            scope.enter_context("second");
+            scope.store_context_instance(&definition, "second", instance);
+            scope.clear();
+            assert_eq!(evicted.load(Ordering::SeqCst), 2);
+        }
+
+        #[test]
+        fn should_support_weak_singletons() {
+            let definition = create_definition();
+            let factory = WeakSingletonScopeFactory;
+            let mut scope = factory.create_scope();
+
+            let instance = ComponentInstancePtr::new(0) as ComponentInstanceAnyPtr;
+            scope.store_instance(&definition, instance.clone());
+            assert!(scope.instance(&definition).is_some());
+
+            drop(instance);
+            assert!(scope.instance(&definition).is_none());
         }
 
         #[test]
@@ -171,5 +558,35 @@ mod tests {
 
             assert!(scope.instance(&definition).is_none());
         }
+
+        #[test]
+        fn should_support_contexts() {
+            let definition = create_definition();
+            let factory = ContextualScopeFactory;
+            let mut scope = factory.create_scope();
+
+            let instance = ComponentInstancePtr::new(0) as ComponentInstanceAnyPtr;
+            scope.store_instance(&definition, instance.clone());
+            assert!(scope.instance(&definition).is_none());
+
+            scope.store_context_instance(&definition, "first", instance.clone());
+            assert!(scope.context_instance(&definition, "first").is_none());
+
+            scope.enter_context("first");
+            scope.store_context_instance(&definition, "first", instance.clone());
+            assert!(scope.context_instance(&definition, "first").is_some());
+            assert!(scope.instance(&definition).is_none());
+
+            scope.enter_context("second");
+            assert!(scope.context_instance(&definition, "second").is_none());
+
+            // the context is still entered once
+            scope.enter_context("first");
+            scope.exit_context("first");
+            assert!(scope.context_instance(&definition, "first").is_some());
+
+            scope.exit_context("first");
+            assert!(scope.context_instance(&definition, "first").is_none());
+        }
     }
 }
@@ -0,0 +1,94 @@
+#[cfg(feature = "derive")]
+mod allocation_test {
+    use springtime_di::component_registry::conditional::SimpleContextFactory;
+    use springtime_di::component_registry::{
+        ComponentDefinitionRegistry, ComponentDefinitions, ComponentMetadata,
+        StaticComponentDefinitionRegistry, TypedComponentDefinitionRegistry,
+    };
+    use springtime_di::scope::SINGLETON;
+    use springtime_di::Component;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::any::TypeId;
+    use std::cell::Cell;
+
+    // counts allocations made by the current thread, so concurrently running tests don't interfere
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn count_allocations(f: impl FnOnce()) -> usize {
+        let start = ALLOCATIONS.with(Cell::get);
+        f();
+        ALLOCATIONS.with(Cell::get) - start
+    }
+
+    const LOOKUPS: usize = 1000;
+
+    #[derive(Component)]
+    struct LookedUpComponent;
+
+    fn create_registry() -> StaticComponentDefinitionRegistry {
+        let mut registry = StaticComponentDefinitionRegistry::new_with_definitions(
+            false,
+            &SimpleContextFactory,
+            ComponentDefinitions::default(),
+        )
+        .unwrap();
+        let metadata = ComponentMetadata::for_component::<LookedUpComponent>(
+            ["first", "second", "third"],
+            SINGLETON,
+        );
+        registry
+            .register_component_typed::<LookedUpComponent>(&metadata)
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn should_not_allocate_when_looking_up_definitions() {
+        let registry = create_registry();
+        let type_id = TypeId::of::<LookedUpComponent>();
+
+        let allocations = count_allocations(|| {
+            for _ in 0..LOOKUPS {
+                assert!(registry.primary_component(type_id).is_some());
+                assert!(registry.component_by_name("second", type_id).is_some());
+            }
+        });
+        assert_eq!(allocations, 0);
+    }
+
+    #[test]
+    fn should_only_allocate_result_when_looking_up_definitions_by_type() {
+        let registry = create_registry();
+
+        // the returned vector is the only allocation, regardless of definition contents
+        let allocations = count_allocations(|| {
+            for _ in 0..LOOKUPS {
+                assert_eq!(
+                    registry
+                        .components_by_type_typed::<LookedUpComponent>()
+                        .len(),
+                    1
+                );
+            }
+        });
+        assert_eq!(allocations, LOOKUPS);
+    }
+}
@@ -1,23 +1,44 @@
+#![cfg(not(feature = "explicit-registration"))]
+
 #[cfg(feature = "derive")]
 mod component_derive_test {
-    use springtime_di::component::{Component, ComponentDowncast};
+    use fxhash::FxHashMap;
+    use springtime_di::component::{
+        qualifier_name, Component, ComponentDowncast, ComponentLifecycle, ComponentNamingStrategy,
+        DisposableComponent,
+    };
     use springtime_di::component_registry::conditional::{
-        ConditionMetadata, Context, SimpleContextFactory,
+        env_var_set, ComponentDefinitionRegistryFacade, Condition, ConditionMetadata, Context,
+        ContextFactory, EnvVarSet, ProfileContextFactory, SimpleContextFactory,
     };
+    use springtime_di::component_registry::validation::ValidationError;
     use springtime_di::component_registry::{
-        ComponentDefinitionRegistry, StaticComponentDefinitionRegistry,
+        ComponentDefinition, ComponentDefinitionRegistry, ComponentDefinitionRegistryError,
+        ComponentDefinitions, ComponentMetadata, StaticComponentDefinitionRegistry,
         TypedComponentDefinitionRegistry,
     };
+    use springtime_di::event::{EventListener, EventPublisher};
+    use springtime_di::factory::{ComponentFactory, ComponentFactoryBuilder};
     #[cfg(feature = "async")]
     use springtime_di::future::BoxFuture;
     #[cfg(feature = "async")]
     use springtime_di::future::FutureExt;
     use springtime_di::instance_provider::{
-        CastFunction, ComponentInstanceAnyPtr, ComponentInstanceProvider, ComponentInstancePtr,
+        store_cast_result, CastFunction, ComponentInstanceAnyPtr, ComponentInstanceProvider,
+        ComponentInstancePtr, ComponentProvider, FactoryHandle, Late, ScopedProxy,
+    };
+    use springtime_di::instance_provider::{
+        ComponentInstanceProviderError, ErrorPtr, TypedComponentInstanceProvider,
     };
-    use springtime_di::instance_provider::{ComponentInstanceProviderError, ErrorPtr};
-    use springtime_di::{component_alias, injectable, Component};
-    use std::any::{Any, TypeId};
+    use springtime_di::post_processor::InstancePostProcessor;
+    use springtime_di::scope::{ContextualScopeFactory, SINGLETON};
+    use springtime_di::{
+        all_of, any_of, collect_components, component_alias, component_factory, event_listener,
+        injectable, not, register_components, register_generic_component, Component,
+    };
+    use std::any::{type_name, Any, TypeId};
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
     #[injectable]
     trait TestTrait1 {}
@@ -165,22 +186,24 @@ mod component_derive_test {
 
     fn cast_dependency(
         instance: ComponentInstanceAnyPtr,
-    ) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr> {
-        TestDependency::downcast(instance).map(|p| Box::new(p) as Box<dyn Any>)
+        target: &mut dyn Any,
+    ) -> Result<(), ComponentInstanceAnyPtr> {
+        TestDependency::downcast(instance).map(|p| store_cast_result(p, target))
     }
 
     fn cast_trait(
         instance: ComponentInstanceAnyPtr,
-    ) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr> {
+        target: &mut dyn Any,
+    ) -> Result<(), ComponentInstanceAnyPtr> {
         #[cfg(feature = "threadsafe")]
         {
             <dyn TestTrait3 + Sync + Send as ComponentDowncast<TestDependency>>::downcast(instance)
-                .map(|p| Box::new(p) as Box<dyn Any>)
+                .map(|p| store_cast_result(p, target))
         }
         #[cfg(not(feature = "threadsafe"))]
         {
             <dyn TestTrait3 as ComponentDowncast<TestDependency>>::downcast(instance)
-                .map(|p| Box::new(p) as Box<dyn Any>)
+                .map(|p| store_cast_result(p, target))
         }
     }
 
@@ -329,6 +352,206 @@ mod component_derive_test {
         false
     }
 
+    #[derive(Component, Default)]
+    #[component(condition = "disabled_condition")]
+    struct DefaultedComponent {
+        #[component(default)]
+        value: i8,
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_register_default_primary_instance() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let instance = factory
+            .primary_instance_or_default::<DefaultedComponent>()
+            .unwrap();
+        assert_eq!(instance.value, 0);
+        assert!(ComponentInstancePtr::ptr_eq(
+            &instance,
+            &factory
+                .primary_instance_typed::<DefaultedComponent>()
+                .unwrap()
+        ));
+        assert!(ComponentInstancePtr::ptr_eq(
+            &instance,
+            &factory
+                .primary_instance_or_default::<DefaultedComponent>()
+                .unwrap()
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_register_default_primary_instance() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let instance = factory
+            .primary_instance_or_default::<DefaultedComponent>()
+            .await
+            .unwrap();
+        assert_eq!(instance.value, 0);
+        assert!(ComponentInstancePtr::ptr_eq(
+            &instance,
+            &factory
+                .primary_instance_typed::<DefaultedComponent>()
+                .await
+                .unwrap()
+        ));
+        assert!(ComponentInstancePtr::ptr_eq(
+            &instance,
+            &factory
+                .primary_instance_or_default::<DefaultedComponent>()
+                .await
+                .unwrap()
+        ));
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_fork_factory_with_empty_scopes() {
+        let mut factory = seeded_factory();
+        let instance = factory.primary_instance_typed::<TestDependency>().unwrap();
+
+        let mut fork = factory.fork().unwrap();
+        let forked_instance = fork.primary_instance_typed::<TestDependency>().unwrap();
+        assert!(!ComponentInstancePtr::ptr_eq(&instance, &forked_instance));
+        assert!(ComponentInstancePtr::ptr_eq(
+            &forked_instance,
+            &fork.primary_instance_typed::<TestDependency>().unwrap()
+        ));
+        assert!(ComponentInstancePtr::ptr_eq(
+            &instance,
+            &factory.primary_instance_typed::<TestDependency>().unwrap()
+        ));
+
+        // seeded instances are shared, while definitions are not
+        assert!(ComponentInstancePtr::ptr_eq(
+            &factory.primary_instance_typed::<SeededComponent>().unwrap(),
+            &fork.primary_instance_typed::<SeededComponent>().unwrap()
+        ));
+
+        fork.register_component_typed::<DisabledComponent>(&disabled_component_metadata())
+            .unwrap();
+        assert!(fork.is_registered_typed::<DisabledComponent>());
+        assert!(!factory.is_registered_typed::<DisabledComponent>());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_fork_factory_with_empty_scopes() {
+        let mut factory = seeded_factory();
+        let instance = factory
+            .primary_instance_typed::<TestDependency>()
+            .await
+            .unwrap();
+
+        let mut fork = factory.fork().unwrap();
+        let forked_instance = fork
+            .primary_instance_typed::<TestDependency>()
+            .await
+            .unwrap();
+        assert!(!ComponentInstancePtr::ptr_eq(&instance, &forked_instance));
+        assert!(ComponentInstancePtr::ptr_eq(
+            &forked_instance,
+            &fork
+                .primary_instance_typed::<TestDependency>()
+                .await
+                .unwrap()
+        ));
+        assert!(ComponentInstancePtr::ptr_eq(
+            &instance,
+            &factory
+                .primary_instance_typed::<TestDependency>()
+                .await
+                .unwrap()
+        ));
+
+        // seeded instances are shared, while definitions are not
+        assert!(ComponentInstancePtr::ptr_eq(
+            &factory
+                .primary_instance_typed::<SeededComponent>()
+                .await
+                .unwrap(),
+            &fork
+                .primary_instance_typed::<SeededComponent>()
+                .await
+                .unwrap()
+        ));
+
+        fork.register_component_typed::<DisabledComponent>(&disabled_component_metadata())
+            .unwrap();
+        assert!(fork.is_registered_typed::<DisabledComponent>());
+        assert!(!factory.is_registered_typed::<DisabledComponent>());
+    }
+
+    #[derive(Component)]
+    #[component(naming = "crate_prefix")]
+    struct CratePrefixedComponent;
+
+    #[derive(Component)]
+    #[component(naming = "module_path")]
+    struct ModulePathComponent;
+
+    #[test]
+    fn should_generate_names_with_naming_strategy() {
+        let registry =
+            StaticComponentDefinitionRegistry::new(false, &SimpleContextFactory::default())
+                .unwrap();
+        for name in [
+            "test_dependency",
+            "component::crate_prefixed_component",
+            "component::component_derive_test::module_path_component",
+        ] {
+            assert!(ComponentDefinitionRegistry::is_name_registered(
+                &registry, name
+            ));
+        }
+    }
+
+    fn prefixed_name(name: &str, _module_path: &str) -> String {
+        format!("prefixed_{name}")
+    }
+
+    #[test]
+    fn should_generate_default_names_with_registry_naming_strategy() {
+        let registry = StaticComponentDefinitionRegistry::new_with_naming_strategy(
+            false,
+            &SimpleContextFactory,
+            ComponentNamingStrategy::Custom(prefixed_name),
+        )
+        .unwrap();
+
+        // derived components and factory products with default names follow the registry strategy
+        for name in [
+            "prefixed_test_dependency",
+            "prefixed_default_connection",
+            "prefixed_first_qualified_component",
+            qualifier_name::<FirstQualifier>(),
+        ] {
+            assert!(ComponentDefinitionRegistry::is_name_registered(
+                &registry, name
+            ));
+        }
+
+        // explicit names and naming strategies take precedence
+        for name in [
+            "dep2",
+            "foreign_client",
+            "component::crate_prefixed_component",
+            "component::component_derive_test::module_path_component",
+        ] {
+            assert!(ComponentDefinitionRegistry::is_name_registered(
+                &registry, name
+            ));
+        }
+
+        for name in ["test_dependency", "default_connection", "prefixed_dep2"] {
+            assert!(!ComponentDefinitionRegistry::is_name_registered(
+                &registry, name
+            ));
+        }
+    }
+
     #[test]
     fn should_not_register_disabled_component() {
         let registry =
@@ -339,19 +562,2660 @@ mod component_derive_test {
         >(&registry));
     }
 
+    #[derive(Component)]
+    #[component(condition = "all_of!(enabled_condition, not!(disabled_condition))")]
+    struct AllOfComponent;
+
+    #[derive(Component)]
+    #[component(condition = "any_of!(disabled_condition, not!(enabled_condition))")]
+    struct AnyOfComponent;
+
+    fn enabled_condition(_context: &dyn Context, _metadata: ConditionMetadata) -> bool {
+        true
+    }
+
+    #[test]
+    fn should_combine_conditions() {
+        let registry =
+            StaticComponentDefinitionRegistry::new(false, &SimpleContextFactory::default())
+                .unwrap();
+        assert!(registry.is_registered_typed::<AllOfComponent>());
+        assert!(!registry.is_registered_typed::<AnyOfComponent>());
+    }
+
+    const TEST_ENV_VAR: &str = "SPRINGTIME_TEST_FEATURE";
+
+    // the environment is injected, so conditions don't depend on the process environment
+    struct EnvContext<'a> {
+        context: Box<dyn Context + 'a>,
+    }
+
+    impl Context for EnvContext<'_> {
+        fn registry(&self) -> &dyn ComponentDefinitionRegistryFacade {
+            self.context.registry()
+        }
+
+        fn is_profile_active(&self, profile: &str) -> bool {
+            self.context.is_profile_active(profile)
+        }
+
+        fn active_profiles(&self) -> Vec<String> {
+            self.context.active_profiles()
+        }
+
+        fn env_var(&self, name: &str) -> Option<String> {
+            (name == TEST_ENV_VAR).then(|| "enabled".to_string())
+        }
+    }
+
+    struct EnvContextFactory(ProfileContextFactory);
+
+    impl ContextFactory for EnvContextFactory {
+        fn create_context<'a>(
+            &self,
+            registry: &'a dyn ComponentDefinitionRegistryFacade,
+        ) -> Box<dyn Context + 'a> {
+            Box::new(EnvContext {
+                context: self.0.create_context(registry),
+            })
+        }
+    }
+
+    #[derive(Component)]
+    #[component(condition = "test_env_condition")]
+    struct EnvComponent;
+
+    fn test_env_condition(context: &dyn Context, _metadata: ConditionMetadata) -> bool {
+        env_var_set(context, TEST_ENV_VAR)
+            && context.active_profiles().contains(&"test".to_string())
+    }
+
+    #[test]
+    fn should_read_environment_in_conditions() {
+        let registry = StaticComponentDefinitionRegistry::new(
+            false,
+            &EnvContextFactory(ProfileContextFactory::new(["test"])),
+        )
+        .unwrap();
+        assert!(registry.is_registered_typed::<EnvComponent>());
+
+        let registry =
+            StaticComponentDefinitionRegistry::new(false, &ProfileContextFactory::new(["test"]))
+                .unwrap();
+        assert!(!registry.is_registered_typed::<EnvComponent>());
+    }
+
+    #[derive(Component)]
+    #[component(condition = "all_of!(EnvVarSet::new(TEST_ENV_VAR), UnregisteredName(\"missing\"))")]
+    struct ParameterizedConditionComponent;
+
+    #[derive(Component)]
+    #[component(
+        condition = "any_of!(not!(UnregisteredName(\"missing\")), not!(EnvVarSet::new(TEST_ENV_VAR)))"
+    )]
+    struct DisabledParameterizedConditionComponent;
+
+    struct UnregisteredName(&'static str);
+
+    impl Condition for UnregisteredName {
+        fn evaluate(&self, context: &dyn Context, _metadata: ConditionMetadata) -> bool {
+            !context.registry().is_name_registered(self.0)
+        }
+    }
+
+    #[test]
+    fn should_evaluate_parameterized_conditions() {
+        let registry = StaticComponentDefinitionRegistry::new(
+            false,
+            &EnvContextFactory(ProfileContextFactory::new(["test"])),
+        )
+        .unwrap();
+        assert!(registry.is_registered_typed::<ParameterizedConditionComponent>());
+        assert!(!registry.is_registered_typed::<DisabledParameterizedConditionComponent>());
+    }
+
+    #[derive(Component)]
+    #[component(profile = "test")]
+    struct ProfileComponent;
+
+    #[derive(Component)]
+    #[component(profile = "!test", condition = "disabled_condition")]
+    struct InactiveProfileComponent;
+
+    #[test]
+    fn should_register_components_for_active_profiles() {
+        let registry =
+            StaticComponentDefinitionRegistry::new(false, &ProfileContextFactory::new(["test"]))
+                .unwrap();
+        assert!(registry.is_registered_typed::<ProfileComponent>());
+        assert!(!registry.is_registered_typed::<InactiveProfileComponent>());
+
+        let registry =
+            StaticComponentDefinitionRegistry::new(false, &ProfileContextFactory::default())
+                .unwrap();
+        assert!(!registry.is_registered_typed::<ProfileComponent>());
+        assert!(!registry.is_registered_typed::<InactiveProfileComponent>());
+    }
+
+    #[derive(Component)]
+    #[component(names = ["greeting"])]
+    struct DefaultGreeting;
+
+    #[derive(Component)]
+    #[component(names = ["greeting"], override)]
+    struct CustomGreeting;
+
+    #[derive(Component)]
+    #[component(names = ["farewell"], override)]
+    struct DefaultFarewell;
+
+    #[derive(Component)]
+    #[component(names = ["farewell"], override, priority = 1)]
+    struct PrioritizedFarewell;
+
+    #[test]
+    fn should_shadow_components_with_lower_precedence() {
+        let registry =
+            StaticComponentDefinitionRegistry::new(false, &SimpleContextFactory::default())
+                .unwrap();
+        assert!(registry.is_registered_typed::<CustomGreeting>());
+        assert!(!registry.is_registered_typed::<DefaultGreeting>());
+        assert!(registry.is_registered_typed::<PrioritizedFarewell>());
+        assert!(!registry.is_registered_typed::<DefaultFarewell>());
+
+        let mut shadowed = registry
+            .shadowed_definitions()
+            .into_iter()
+            .map(|shadowed| {
+                (
+                    shadowed.definition.resolved_type_name.clone(),
+                    shadowed.name,
+                    shadowed.shadowed_by,
+                )
+            })
+            .collect::<Vec<_>>();
+        shadowed.sort();
+
+        assert_eq!(
+            shadowed,
+            [
+                (
+                    type_name::<DefaultFarewell>().to_string(),
+                    "farewell".to_string(),
+                    type_name::<PrioritizedFarewell>().to_string()
+                ),
+                (
+                    type_name::<DefaultGreeting>().to_string(),
+                    "greeting".to_string(),
+                    type_name::<CustomGreeting>().to_string()
+                ),
+            ]
+        );
+    }
+
     #[cfg(not(feature = "async"))]
-    mod sync {
-        use crate::component_derive_test::{
-            TestComponent1, TestComponent2, TestDependencyInstanceProvider,
+    #[test]
+    fn should_look_up_components_by_name_only() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        assert_eq!(
+            factory
+                .definition_by_name("greeting")
+                .unwrap()
+                .resolved_type_id,
+            TypeId::of::<CustomGreeting>()
+        );
+
+        assert!(factory
+            .instance_by_name_any::<CustomGreeting>("greeting")
+            .is_ok());
+        assert!(matches!(
+            factory.instance_by_name_any::<DefaultFarewell>("greeting"),
+            Err(ComponentInstanceProviderError::IncompatibleComponent { .. })
+        ));
+        assert!(matches!(
+            factory.instance_by_name_any::<CustomGreeting>("unknown"),
+            Err(ComponentInstanceProviderError::NoNamedInstance(_))
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_look_up_components_by_name_only() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        assert_eq!(
+            factory
+                .definition_by_name("greeting")
+                .unwrap()
+                .resolved_type_id,
+            TypeId::of::<CustomGreeting>()
+        );
+
+        assert!(factory
+            .instance_by_name_any::<CustomGreeting>("greeting")
+            .await
+            .is_ok());
+        assert!(matches!(
+            factory
+                .instance_by_name_any::<DefaultFarewell>("greeting")
+                .await,
+            Err(ComponentInstanceProviderError::IncompatibleComponent { .. })
+        ));
+        assert!(matches!(
+            factory
+                .instance_by_name_any::<CustomGreeting>("unknown")
+                .await,
+            Err(ComponentInstanceProviderError::NoNamedInstance(_))
+        ));
+    }
+
+    fn disabled_component_metadata() -> ComponentMetadata {
+        ComponentMetadata::for_component::<DisabledComponent>(["runtime_component"], SINGLETON)
+    }
+
+    fn register_disabled_component(factory: &mut ComponentFactory) {
+        factory
+            .register_component_typed::<DisabledComponent>(&disabled_component_metadata())
+            .unwrap();
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_register_components_at_runtime() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        assert!(factory
+            .primary_instance_typed::<DisabledComponent>()
+            .is_err());
+
+        register_disabled_component(&mut factory);
+        assert!(factory
+            .instance_by_name_typed::<DisabledComponent>("runtime_component")
+            .is_ok());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_register_components_at_runtime() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        assert!(factory
+            .primary_instance_typed::<DisabledComponent>()
+            .await
+            .is_err());
+
+        register_disabled_component(&mut factory);
+        assert!(factory
+            .instance_by_name_typed::<DisabledComponent>("runtime_component")
+            .await
+            .is_ok());
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_create_new_instance_after_replacing_component() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        register_disabled_component(&mut factory);
+
+        let first = factory
+            .primary_instance_typed::<DisabledComponent>()
+            .unwrap();
+        factory
+            .replace_component_typed::<DisabledComponent>(&disabled_component_metadata())
+            .unwrap();
+
+        let second = factory
+            .primary_instance_typed::<DisabledComponent>()
+            .unwrap();
+        assert!(!ComponentInstancePtr::ptr_eq(&first, &second));
+
+        factory.remove_component_typed::<DisabledComponent>();
+        assert!(factory
+            .primary_instance_typed::<DisabledComponent>()
+            .is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_create_new_instance_after_replacing_component() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        register_disabled_component(&mut factory);
+
+        let first = factory
+            .primary_instance_typed::<DisabledComponent>()
+            .await
+            .unwrap();
+        factory
+            .replace_component_typed::<DisabledComponent>(&disabled_component_metadata())
+            .unwrap();
+
+        let second = factory
+            .primary_instance_typed::<DisabledComponent>()
+            .await
+            .unwrap();
+        assert!(!ComponentInstancePtr::ptr_eq(&first, &second));
+
+        factory.remove_component_typed::<DisabledComponent>();
+        assert!(factory
+            .primary_instance_typed::<DisabledComponent>()
+            .await
+            .is_err());
+    }
+
+    #[derive(Component)]
+    #[component(names_from = "dynamic_names")]
+    struct DynamicallyNamedComponent;
+
+    fn dynamic_names() -> Vec<String> {
+        vec![format!("dynamic_{}", 1 + 1)]
+    }
+
+    #[test]
+    fn should_register_dynamic_names() {
+        let registry =
+            StaticComponentDefinitionRegistry::new(false, &SimpleContextFactory::default())
+                .unwrap();
+
+        assert!(registry
+            .component_by_name("dynamic_2", TypeId::of::<DynamicallyNamedComponent>())
+            .is_some());
+    }
+
+    #[derive(Component)]
+    #[component(lifecycle)]
+    struct LifecycleComponent {
+        _dependency: ComponentInstancePtr<TestDependency>,
+        #[component(default)]
+        initialized: AtomicBool,
+    }
+
+    impl ComponentLifecycle for LifecycleComponent {
+        #[cfg(not(feature = "async"))]
+        fn post_construct(&self) -> Result<(), ErrorPtr> {
+            self.initialized.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        #[cfg(feature = "async")]
+        fn post_construct(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+            async {
+                self.initialized.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+            .boxed()
+        }
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_call_post_construct() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let component = factory
+            .primary_instance_typed::<LifecycleComponent>()
+            .unwrap();
+
+        assert!(component.initialized.load(Ordering::SeqCst));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_call_post_construct() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let component = factory
+            .primary_instance_typed::<LifecycleComponent>()
+            .await
+            .unwrap();
+
+        assert!(component.initialized.load(Ordering::SeqCst));
+    }
+
+    #[derive(Component)]
+    #[component(scope = "PROTOTYPE")]
+    struct PrototypeComponent;
+
+    #[derive(Component)]
+    struct ProviderComponent {
+        prototypes: ComponentProvider<PrototypeComponent>,
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_provide_fresh_prototypes() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let component = factory
+            .primary_instance_typed::<ProviderComponent>()
+            .unwrap();
+
+        let first = component.prototypes.get().unwrap();
+        let second = component.prototypes.get().unwrap();
+        assert!(!ComponentInstancePtr::ptr_eq(&first, &second));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_provide_fresh_prototypes() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let component = factory
+            .primary_instance_typed::<ProviderComponent>()
+            .await
+            .unwrap();
+
+        let first = component.prototypes.get().await.unwrap();
+        let second = component.prototypes.get().await.unwrap();
+        assert!(!ComponentInstancePtr::ptr_eq(&first, &second));
+    }
+
+    #[derive(Component)]
+    #[component(names = ["request"], scope = "REQUEST")]
+    struct RequestComponent;
+
+    fn request_factory() -> ComponentFactory {
+        ComponentFactoryBuilder::new()
+            .unwrap()
+            .with_scope_factory("REQUEST", Box::new(ContextualScopeFactory))
+            .build()
+    }
+
+    static FAILING_LIFECYCLE_ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+    // kept out of the singleton scope, so instantiating all singletons doesn't fail
+    #[derive(Component)]
+    #[component(scope = "REQUEST", lifecycle)]
+    struct FailingLifecycleComponent;
+
+    impl FailingLifecycleComponent {
+        fn initialize(&self) -> Result<(), ErrorPtr> {
+            if FAILING_LIFECYCLE_ATTEMPTS.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(ComponentInstancePtr::new(std::fmt::Error) as ErrorPtr)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl ComponentLifecycle for FailingLifecycleComponent {
+        #[cfg(not(feature = "async"))]
+        fn post_construct(&self) -> Result<(), ErrorPtr> {
+            self.initialize()
+        }
+
+        #[cfg(feature = "async")]
+        fn post_construct(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+            async { self.initialize() }.boxed()
+        }
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_not_store_instances_failing_post_construct() {
+        let factory = request_factory();
+        factory
+            .in_scope_context("REQUEST", "request", || {
+                assert!(matches!(
+                    (&factory).primary_instance_typed::<FailingLifecycleComponent>(),
+                    Err(ComponentInstanceProviderError::PostConstructError(_))
+                ));
+                assert!((&factory)
+                    .primary_instance_typed::<FailingLifecycleComponent>()
+                    .is_ok());
+            })
+            .unwrap();
+        assert_eq!(FAILING_LIFECYCLE_ATTEMPTS.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_not_store_instances_failing_post_construct() {
+        let factory = request_factory();
+        factory
+            .in_scope_context("REQUEST", "request", async {
+                assert!(matches!(
+                    (&factory)
+                        .primary_instance_typed::<FailingLifecycleComponent>()
+                        .await,
+                    Err(ComponentInstanceProviderError::PostConstructError(_))
+                ));
+                assert!((&factory)
+                    .primary_instance_typed::<FailingLifecycleComponent>()
+                    .await
+                    .is_ok());
+            })
+            .unwrap()
+            .await;
+        assert_eq!(FAILING_LIFECYCLE_ATTEMPTS.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_keep_instances_per_scope_context() {
+        let factory = request_factory();
+        let request = || {
+            (&factory)
+                .primary_instance_typed::<RequestComponent>()
+                .unwrap()
         };
-        use springtime_di::component::Component;
 
-        #[test]
-        fn should_directly_create_with_explicit_dependency() {
+        factory
+            .in_scope_context("REQUEST", "first", || {
+                let first = request();
+                assert!(ComponentInstancePtr::ptr_eq(&first, &request()));
+
+                let second = factory
+                    .in_scope_context("REQUEST", "second", request)
+                    .unwrap();
+                assert!(!ComponentInstancePtr::ptr_eq(&first, &second));
+
+                assert!(ComponentInstancePtr::ptr_eq(&first, &request()));
+            })
+            .unwrap();
+
+        // instances are released with their context
+        let first = factory
+            .in_scope_context("REQUEST", "first", request)
+            .unwrap();
+        assert!(!ComponentInstancePtr::ptr_eq(
+            &first,
+            &factory
+                .in_scope_context("REQUEST", "first", request)
+                .unwrap()
+        ));
+
+        assert!(factory.in_scope_context("UNKNOWN", "first", || ()).is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_keep_instances_per_scope_context() {
+        let factory = request_factory();
+        let request = || async {
+            (&factory)
+                .primary_instance_typed::<RequestComponent>()
+                .await
+                .unwrap()
+        };
+
+        factory
+            .in_scope_context("REQUEST", "first", async {
+                let first = request().await;
+                assert!(ComponentInstancePtr::ptr_eq(&first, &request().await));
+
+                let second = factory
+                    .in_scope_context("REQUEST", "second", request())
+                    .unwrap()
+                    .await;
+                assert!(!ComponentInstancePtr::ptr_eq(&first, &second));
+
+                assert!(ComponentInstancePtr::ptr_eq(&first, &request().await));
+            })
+            .unwrap()
+            .await;
+
+        // instances are released with their context
+        let first = factory
+            .in_scope_context("REQUEST", "first", request())
+            .unwrap()
+            .await;
+        assert!(!ComponentInstancePtr::ptr_eq(
+            &first,
+            &factory
+                .in_scope_context("REQUEST", "first", request())
+                .unwrap()
+                .await
+        ));
+
+        assert!(factory
+            .in_scope_context("UNKNOWN", "first", async {})
+            .is_err());
+    }
+
+    #[cfg(all(not(feature = "async"), feature = "threadsafe"))]
+    #[test]
+    fn should_keep_concurrent_scope_contexts_separate() {
+        let factory = std::sync::Arc::new(request_factory());
+        let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+        let [first, second] = ["first", "second"]
+            .map(|context| {
+                let factory = factory.clone();
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    factory
+                        .in_scope_context("REQUEST", context, || {
+                            let instance = (&*factory)
+                                .primary_instance_typed::<RequestComponent>()
+                                .unwrap();
+                            // both contexts are active at the same time
+                            barrier.wait();
+                            assert!(ComponentInstancePtr::ptr_eq(
+                                &instance,
+                                &(&*factory)
+                                    .primary_instance_typed::<RequestComponent>()
+                                    .unwrap()
+                            ));
+                            instance
+                        })
+                        .unwrap()
+                })
+            })
+            .map(|handle| handle.join().unwrap());
+        assert!(!ComponentInstancePtr::ptr_eq(&first, &second));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_keep_concurrent_scope_contexts_separate() {
+        let factory = request_factory();
+        let barrier = tokio::sync::Barrier::new(2);
+        let request = |context: &'static str| {
+            factory
+                .in_scope_context("REQUEST", context, async {
+                    let instance = (&factory)
+                        .primary_instance_typed::<RequestComponent>()
+                        .await
+                        .unwrap();
+                    // both contexts are active at the same time
+                    barrier.wait().await;
+                    assert!(ComponentInstancePtr::ptr_eq(
+                        &instance,
+                        &(&factory)
+                            .primary_instance_typed::<RequestComponent>()
+                            .await
+                            .unwrap()
+                    ));
+                    instance
+                })
+                .unwrap()
+        };
+
+        let (first, second) = tokio::join!(request("first"), request("second"));
+        assert!(!ComponentInstancePtr::ptr_eq(&first, &second));
+    }
+
+    fn evicting_request_factory() -> (
+        std::sync::Arc<ComponentFactory>,
+        std::sync::Arc<AtomicUsize>,
+    ) {
+        let factory = std::sync::Arc::new(request_factory());
+        let listener_factory = std::sync::Arc::downgrade(&factory);
+        let evicted = std::sync::Arc::new(AtomicUsize::new(0));
+        let listener_evicted = evicted.clone();
+        factory
+            .set_eviction_listener(
+                "REQUEST",
+                Box::new(move |_, _| {
+                    // the scope isn't locked while listeners are called, so they can use the
+                    // factory
+                    if listener_evicted.fetch_add(1, Ordering::SeqCst) == 0 {
+                        listener_factory
+                            .upgrade()
+                            .unwrap()
+                            .clear_scope("REQUEST")
+                            .unwrap();
+                    }
+                }),
+            )
+            .unwrap();
+
+        (factory, evicted)
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_allow_eviction_listeners_to_use_factory() {
+        let (factory, evicted) = evicting_request_factory();
+        let request = || {
+            (&*factory)
+                .primary_instance_typed::<RequestComponent>()
+                .unwrap();
+        };
+
+        factory
+            .in_scope_context("REQUEST", "first", || {
+                request();
+                factory
+                    .in_scope_context("REQUEST", "second", request)
+                    .unwrap();
+
+                // the instance from the first context is evicted by the listener
+                assert_eq!(evicted.load(Ordering::SeqCst), 2);
+            })
+            .unwrap();
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_allow_eviction_listeners_to_use_factory() {
+        let (factory, evicted) = evicting_request_factory();
+        let request = || async {
+            (&*factory)
+                .primary_instance_typed::<RequestComponent>()
+                .await
+                .unwrap();
+        };
+
+        factory
+            .in_scope_context("REQUEST", "first", async {
+                request().await;
+                factory
+                    .in_scope_context("REQUEST", "second", request())
+                    .unwrap()
+                    .await;
+
+                // the instance from the first context is evicted by the listener
+                assert_eq!(evicted.load(Ordering::SeqCst), 2);
+            })
+            .unwrap()
+            .await;
+    }
+
+    static SLOW_CONSTRUCTIONS: AtomicUsize = AtomicUsize::new(0);
+
+    fn construct_slowly() -> usize {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        SLOW_CONSTRUCTIONS.fetch_add(1, Ordering::SeqCst)
+    }
+
+    #[derive(Component)]
+    #[component(scope = "WEAK_SINGLETON")]
+    struct SlowComponent {
+        #[component(default = "construct_slowly")]
+        _construction: usize,
+    }
+
+    #[cfg(all(not(feature = "async"), feature = "threadsafe"))]
+    #[test]
+    fn should_construct_concurrently_requested_instances_once() {
+        let factory = std::sync::Arc::new(ComponentFactoryBuilder::new().unwrap().build());
+        let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+        let [first, second] = [(), ()]
+            .map(|_| {
+                let factory = factory.clone();
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    (&*factory)
+                        .primary_instance_typed::<SlowComponent>()
+                        .unwrap()
+                })
+            })
+            .map(|handle| handle.join().unwrap());
+
+        assert!(ComponentInstancePtr::ptr_eq(&first, &second));
+        assert_eq!(SLOW_CONSTRUCTIONS.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn should_construct_concurrently_requested_instances_once() {
+        let factory = std::sync::Arc::new(ComponentFactoryBuilder::new().unwrap().build());
+        let barrier = std::sync::Arc::new(tokio::sync::Barrier::new(2));
+        let [first, second] = [(), ()].map(|_| {
+            let factory = factory.clone();
+            let barrier = barrier.clone();
+            tokio::spawn(async move {
+                barrier.wait().await;
+                (&*factory)
+                    .primary_instance_typed::<SlowComponent>()
+                    .await
+                    .unwrap()
+            })
+        });
+        let (first, second) = (first.await.unwrap(), second.await.unwrap());
+
+        assert!(ComponentInstancePtr::ptr_eq(&first, &second));
+        assert_eq!(SLOW_CONSTRUCTIONS.load(Ordering::SeqCst), 1);
+    }
+
+    #[derive(Component)]
+    struct RequestProxyComponent {
+        request: ScopedProxy<RequestComponent>,
+        #[component(name = "request")]
+        named_request: ComponentProvider<RequestComponent>,
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_resolve_proxied_instances_from_active_context() {
+        let mut factory = request_factory();
+        let component = factory
+            .primary_instance_typed::<RequestProxyComponent>()
+            .unwrap();
+
+        factory
+            .in_scope_context("REQUEST", "first", || {
+                let first = component.request.get().unwrap();
+                assert!(ComponentInstancePtr::ptr_eq(
+                    &first,
+                    &(&factory)
+                        .primary_instance_typed::<RequestComponent>()
+                        .unwrap()
+                ));
+
+                let second = factory
+                    .in_scope_context("REQUEST", "second", || component.request.get().unwrap())
+                    .unwrap();
+                assert!(!ComponentInstancePtr::ptr_eq(&first, &second));
+
+                assert!(ComponentInstancePtr::ptr_eq(
+                    &first,
+                    &component.request.get().unwrap()
+                ));
+                assert!(ComponentInstancePtr::ptr_eq(
+                    &first,
+                    &component.named_request.get().unwrap()
+                ));
+            })
+            .unwrap();
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_resolve_proxied_instances_from_active_context() {
+        let mut factory = request_factory();
+        let component = factory
+            .primary_instance_typed::<RequestProxyComponent>()
+            .await
+            .unwrap();
+
+        factory
+            .in_scope_context("REQUEST", "first", async {
+                let first = component.request.get().await.unwrap();
+                assert!(ComponentInstancePtr::ptr_eq(
+                    &first,
+                    &(&factory)
+                        .primary_instance_typed::<RequestComponent>()
+                        .await
+                        .unwrap()
+                ));
+
+                let second = factory
+                    .in_scope_context("REQUEST", "second", component.request.get())
+                    .unwrap()
+                    .await
+                    .unwrap();
+                assert!(!ComponentInstancePtr::ptr_eq(&first, &second));
+
+                assert!(ComponentInstancePtr::ptr_eq(
+                    &first,
+                    &component.request.get().await.unwrap()
+                ));
+                assert!(ComponentInstancePtr::ptr_eq(
+                    &first,
+                    &component.named_request.get().await.unwrap()
+                ));
+            })
+            .unwrap()
+            .await;
+    }
+
+    #[derive(Component)]
+    #[component(scope = "WEAK_SINGLETON")]
+    struct WeakSingletonComponent;
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_recreate_dropped_weak_singletons() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let first = factory
+            .primary_instance_typed::<WeakSingletonComponent>()
+            .unwrap();
+        assert!(ComponentInstancePtr::ptr_eq(
+            &first,
+            &factory
+                .primary_instance_typed::<WeakSingletonComponent>()
+                .unwrap()
+        ));
+
+        let weak_first = ComponentInstancePtr::downgrade(&first);
+        drop(first);
+
+        let _second = factory
+            .primary_instance_typed::<WeakSingletonComponent>()
+            .unwrap();
+        assert!(weak_first.upgrade().is_none());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_recreate_dropped_weak_singletons() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let first = factory
+            .primary_instance_typed::<WeakSingletonComponent>()
+            .await
+            .unwrap();
+        assert!(ComponentInstancePtr::ptr_eq(
+            &first,
+            &factory
+                .primary_instance_typed::<WeakSingletonComponent>()
+                .await
+                .unwrap()
+        ));
+
+        let weak_first = ComponentInstancePtr::downgrade(&first);
+        drop(first);
+
+        let _second = factory
+            .primary_instance_typed::<WeakSingletonComponent>()
+            .await
+            .unwrap();
+        assert!(weak_first.upgrade().is_none());
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_recreate_evicted_instances() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let first = factory.primary_instance_typed::<TestDependency>().unwrap();
+
+        factory.clear_scope(SINGLETON).unwrap();
+        let second = factory.primary_instance_typed::<TestDependency>().unwrap();
+        assert!(!ComponentInstancePtr::ptr_eq(&first, &second));
+
+        factory.evict_instances(TypeId::of::<TestDependency>());
+        let third = factory.primary_instance_typed::<TestDependency>().unwrap();
+        assert!(!ComponentInstancePtr::ptr_eq(&second, &third));
+
+        // evicted instances are released by the factory
+        let evicted = [
+            ComponentInstancePtr::downgrade(&first),
+            ComponentInstancePtr::downgrade(&second),
+        ];
+        drop((first, second));
+        assert!(evicted.iter().all(|instance| instance.upgrade().is_none()));
+
+        assert!(factory.clear_scope("UNKNOWN").is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_recreate_evicted_instances() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let first = factory
+            .primary_instance_typed::<TestDependency>()
+            .await
+            .unwrap();
+
+        factory.clear_scope(SINGLETON).unwrap();
+        let second = factory
+            .primary_instance_typed::<TestDependency>()
+            .await
+            .unwrap();
+        assert!(!ComponentInstancePtr::ptr_eq(&first, &second));
+
+        factory.evict_instances(TypeId::of::<TestDependency>());
+        let third = factory
+            .primary_instance_typed::<TestDependency>()
+            .await
+            .unwrap();
+        assert!(!ComponentInstancePtr::ptr_eq(&second, &third));
+
+        // evicted instances are released by the factory
+        let evicted = [
+            ComponentInstancePtr::downgrade(&first),
+            ComponentInstancePtr::downgrade(&second),
+        ];
+        drop((first, second));
+        assert!(evicted.iter().all(|instance| instance.upgrade().is_none()));
+
+        assert!(factory.clear_scope("UNKNOWN").is_err());
+    }
+
+    #[derive(Component)]
+    struct CircularComponent1 {
+        #[component(late)]
+        dependency: Late<CircularComponent2>,
+    }
+
+    #[derive(Component)]
+    struct CircularComponent2 {
+        dependency: ComponentInstancePtr<CircularComponent1>,
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_inject_late_dependencies() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let component = factory
+            .primary_instance_typed::<CircularComponent1>()
+            .unwrap();
+
+        let dependency = component.dependency.get().unwrap();
+        assert!(ComponentInstancePtr::ptr_eq(
+            &dependency.dependency,
+            &component
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_inject_late_dependencies() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let component = factory
+            .primary_instance_typed::<CircularComponent1>()
+            .await
+            .unwrap();
+
+        let dependency = component.dependency.get().unwrap();
+        assert!(ComponentInstancePtr::ptr_eq(
+            &dependency.dependency,
+            &component
+        ));
+    }
+
+    #[derive(Component)]
+    #[component(scope = "PROTOTYPE")]
+    struct SelfReferencingPrototype {
+        #[component(late)]
+        _dependency: Late<SelfReferencingPrototype>,
+    }
+
+    #[derive(Component)]
+    #[component(scope = "PROTOTYPE")]
+    struct CircularPrototype1 {
+        #[component(late)]
+        _dependency: Late<CircularPrototype2>,
+    }
+
+    #[derive(Component)]
+    #[component(scope = "PROTOTYPE")]
+    struct CircularPrototype2 {
+        #[component(late)]
+        _dependency: Late<CircularPrototype1>,
+    }
+
+    fn is_cycle(error: &ComponentInstanceProviderError) -> bool {
+        match error {
+            ComponentInstanceProviderError::DependencyCycle { .. } => true,
+            ComponentInstanceProviderError::DependencyError { source, .. } => is_cycle(source),
+            _ => false,
+        }
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_detect_late_prototype_cycles() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        assert!(is_cycle(
+            &factory
+                .primary_instance_typed::<SelfReferencingPrototype>()
+                .err()
+                .unwrap()
+        ));
+        assert!(is_cycle(
+            &factory
+                .primary_instance_typed::<CircularPrototype1>()
+                .err()
+                .unwrap()
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_detect_late_prototype_cycles() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        assert!(is_cycle(
+            &factory
+                .primary_instance_typed::<SelfReferencingPrototype>()
+                .await
+                .err()
+                .unwrap()
+        ));
+        assert!(is_cycle(
+            &factory
+                .primary_instance_typed::<CircularPrototype1>()
+                .await
+                .err()
+                .unwrap()
+        ));
+    }
+
+    static DESTROY_SEQUENCE: AtomicUsize = AtomicUsize::new(1);
+    static DEPENDENCY_DESTROYED_AT: AtomicUsize = AtomicUsize::new(0);
+    static DEPENDENT_DESTROYED_AT: AtomicUsize = AtomicUsize::new(0);
+
+    fn record_destroy(destroyed_at: &AtomicUsize) {
+        destroyed_at.store(
+            DESTROY_SEQUENCE.fetch_add(1, Ordering::SeqCst),
+            Ordering::SeqCst,
+        );
+    }
+
+    fn assert_destroyed_in_reverse_order() {
+        let dependent = DEPENDENT_DESTROYED_AT.load(Ordering::SeqCst);
+        let dependency = DEPENDENCY_DESTROYED_AT.load(Ordering::SeqCst);
+        assert!(dependent > 0 && dependent < dependency);
+    }
+
+    #[derive(Component)]
+    #[component(disposable)]
+    struct DisposableDependency;
+
+    impl DisposableComponent for DisposableDependency {
+        #[cfg(not(feature = "async"))]
+        fn destroy(&self) -> Result<(), ErrorPtr> {
+            record_destroy(&DEPENDENCY_DESTROYED_AT);
+            Ok(())
+        }
+
+        #[cfg(feature = "async")]
+        fn destroy(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+            async {
+                record_destroy(&DEPENDENCY_DESTROYED_AT);
+                Ok(())
+            }
+            .boxed()
+        }
+    }
+
+    #[derive(Component)]
+    #[component(disposable)]
+    struct DisposableDependent {
+        _dependency: ComponentInstancePtr<DisposableDependency>,
+    }
+
+    impl DisposableComponent for DisposableDependent {
+        #[cfg(not(feature = "async"))]
+        fn destroy(&self) -> Result<(), ErrorPtr> {
+            record_destroy(&DEPENDENT_DESTROYED_AT);
+            Ok(())
+        }
+
+        #[cfg(feature = "async")]
+        fn destroy(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+            async {
+                record_destroy(&DEPENDENT_DESTROYED_AT);
+                Ok(())
+            }
+            .boxed()
+        }
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_destroy_in_reverse_dependency_order() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        factory
+            .primary_instance_typed::<DisposableDependent>()
+            .unwrap();
+        factory.shutdown().unwrap();
+
+        assert_destroyed_in_reverse_order();
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_destroy_in_reverse_dependency_order() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        factory
+            .primary_instance_typed::<DisposableDependent>()
+            .await
+            .unwrap();
+        factory.shutdown().await.unwrap();
+
+        assert_destroyed_in_reverse_order();
+    }
+
+    #[injectable]
+    struct SeededResource {
+        value: i8,
+    }
+
+    #[derive(Component)]
+    struct SeededResourceConsumer {
+        resource: ComponentInstancePtr<SeededResource>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Component)]
+    struct SeededResourceConsumerHolder {
+        consumer: ComponentInstancePtr<SeededResourceConsumer>,
+    }
+
+    #[injectable]
+    trait SeededTrait {
+        fn value(&self) -> i8;
+    }
+
+    #[derive(Component)]
+    struct SeededComponent {
+        #[component(default)]
+        value: i8,
+    }
+
+    #[component_alias]
+    impl SeededTrait for SeededComponent {
+        fn value(&self) -> i8 {
+            self.value
+        }
+    }
+
+    fn seeded_factory() -> ComponentFactory {
+        ComponentFactoryBuilder::new()
+            .unwrap()
+            .with_instance(SeededResource { value: 5 })
+            .unwrap()
+            .with_instance(SeededComponent { value: 7 })
+            .unwrap()
+            .build()
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_inject_seeded_instances() {
+        let mut factory = seeded_factory();
+        let consumer = factory
+            .primary_instance_typed::<SeededResourceConsumer>()
+            .unwrap();
+        assert_eq!(consumer.resource.value, 5);
+
+        #[cfg(feature = "threadsafe")]
+        let component = factory
+            .primary_instance_typed::<dyn SeededTrait + Sync + Send>()
+            .unwrap();
+        #[cfg(not(feature = "threadsafe"))]
+        let component = factory.primary_instance_typed::<dyn SeededTrait>().unwrap();
+        assert_eq!(component.value(), 7);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_inject_seeded_instances() {
+        let mut factory = seeded_factory();
+        let consumer = factory
+            .primary_instance_typed::<SeededResourceConsumer>()
+            .await
+            .unwrap();
+        assert_eq!(consumer.resource.value, 5);
+
+        let component = factory
+            .primary_instance_typed::<dyn SeededTrait + Sync + Send>()
+            .await
+            .unwrap();
+        assert_eq!(component.value(), 7);
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_keep_seeded_instances_after_shutdown() {
+        let mut factory = seeded_factory();
+        let component = factory.primary_instance_typed::<SeededComponent>().unwrap();
+
+        factory.shutdown().unwrap();
+        assert!(ComponentInstancePtr::ptr_eq(
+            &component,
+            &factory.primary_instance_typed::<SeededComponent>().unwrap()
+        ));
+
+        factory.clear_scope(SINGLETON).unwrap();
+        assert_eq!(
+            factory
+                .primary_instance_typed::<SeededResourceConsumer>()
+                .unwrap()
+                .resource
+                .value,
+            5
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_keep_seeded_instances_after_shutdown() {
+        let mut factory = seeded_factory();
+        let component = factory
+            .primary_instance_typed::<SeededComponent>()
+            .await
+            .unwrap();
+
+        factory.shutdown().await.unwrap();
+        assert!(ComponentInstancePtr::ptr_eq(
+            &component,
+            &factory
+                .primary_instance_typed::<SeededComponent>()
+                .await
+                .unwrap()
+        ));
+
+        factory.clear_scope(SINGLETON).unwrap();
+        assert_eq!(
+            factory
+                .primary_instance_typed::<SeededResourceConsumer>()
+                .await
+                .unwrap()
+                .resource
+                .value,
+            5
+        );
+    }
+
+    #[test]
+    fn should_not_seed_non_singleton_instances() {
+        assert!(matches!(
+            ComponentFactoryBuilder::new()
+                .unwrap()
+                .with_instance(RequestComponent)
+                .err(),
+            Some(ComponentDefinitionRegistryError::NonSingletonInstance { scope, .. }) if scope == "REQUEST"
+        ));
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_instantiate_all_singletons() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        assert!(matches!(
+            factory.instantiate_all_singletons().unwrap_err(),
+            ComponentInstanceProviderError::NoPrimaryInstance { type_id, .. } if type_id == TypeId::of::<SeededResource>()
+        ));
+
+        let mut factory = seeded_factory();
+        factory.instantiate_all_singletons().unwrap();
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_instantiate_all_singletons() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        assert!(matches!(
+            factory.instantiate_all_singletons().await.unwrap_err(),
+            ComponentInstanceProviderError::NoPrimaryInstance { type_id, .. } if type_id == TypeId::of::<SeededResource>()
+        ));
+
+        let mut factory = seeded_factory();
+        factory.instantiate_all_singletons().await.unwrap();
+    }
+
+    fn assert_dependency_path(error: ComponentInstanceProviderError) {
+        assert!(matches!(
+            &error,
+            ComponentInstanceProviderError::DependencyError { path, .. } if *path == vec![
+                type_name::<SeededResourceConsumerHolder>().to_string(),
+                type_name::<SeededResourceConsumer>().to_string(),
+            ]
+        ));
+        assert!(matches!(
+            error.root_cause(),
+            ComponentInstanceProviderError::NoPrimaryInstance { type_id, .. } if *type_id == TypeId::of::<SeededResource>()
+        ));
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_report_dependency_path() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        assert_dependency_path(
+            factory
+                .primary_instance_typed::<SeededResourceConsumerHolder>()
+                .err()
+                .unwrap(),
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_report_dependency_path() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        assert_dependency_path(
+            factory
+                .primary_instance_typed::<SeededResourceConsumerHolder>()
+                .await
+                .err()
+                .unwrap(),
+        );
+    }
+
+    #[test]
+    fn should_validate_component_graph() {
+        let factory = ComponentFactoryBuilder::new().unwrap().build();
+        assert_eq!(
+            factory.validate(),
+            vec![
+                ValidationError::MissingDependency {
+                    component_type: type_name::<SeededResourceConsumer>().to_string(),
+                    dependency_type: type_name::<SeededResource>().to_string(),
+                },
+                ValidationError::UnrecognizedScope {
+                    component_type: type_name::<FailingLifecycleComponent>().to_string(),
+                    scope: "REQUEST".to_string(),
+                },
+                ValidationError::UnrecognizedScope {
+                    component_type: type_name::<RequestComponent>().to_string(),
+                    scope: "REQUEST".to_string(),
+                },
+            ]
+        );
+
+        let factory = ComponentFactoryBuilder::new()
+            .unwrap()
+            .with_scope_factory("REQUEST", Box::new(ContextualScopeFactory))
+            .with_instance(SeededResource { value: 5 })
+            .unwrap()
+            .build();
+        assert!(factory.validate().is_empty());
+    }
+
+    #[cfg(feature = "test-util")]
+    #[injectable]
+    trait Clock {
+        fn now(&self) -> u64;
+    }
+
+    #[cfg(feature = "test-util")]
+    #[derive(Component)]
+    struct SystemClock;
+
+    #[cfg(feature = "test-util")]
+    #[component_alias]
+    impl Clock for SystemClock {
+        fn now(&self) -> u64 {
+            0
+        }
+    }
+
+    #[cfg(feature = "test-util")]
+    #[injectable]
+    trait Ticker {
+        fn tick(&self) -> u64;
+    }
+
+    #[cfg(feature = "test-util")]
+    #[component_alias]
+    impl Ticker for SystemClock {
+        fn tick(&self) -> u64 {
+            1
+        }
+    }
+
+    #[cfg(feature = "test-util")]
+    struct FixedClock;
+
+    #[cfg(feature = "test-util")]
+    impl Clock for FixedClock {
+        fn now(&self) -> u64 {
+            42
+        }
+    }
+
+    #[cfg(feature = "test-util")]
+    #[derive(Component)]
+    struct ClockConsumer {
+        clock: ComponentInstancePtr<dyn Clock + Send + Sync>,
+    }
+
+    #[cfg(feature = "test-util")]
+    fn create_overriding_factory() -> ComponentFactory {
+        ComponentFactoryBuilder::new()
+            .unwrap()
+            .with_override::<dyn Clock + Send + Sync>(ComponentInstancePtr::new(FixedClock))
+            .unwrap()
+            .build()
+    }
+
+    #[cfg(all(feature = "test-util", not(feature = "async")))]
+    #[test]
+    fn should_override_trait_components() {
+        let mut factory = create_overriding_factory();
+        assert_eq!(
+            factory
+                .instances_typed::<dyn Clock + Send + Sync>()
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let consumer = factory.primary_instance_typed::<ClockConsumer>().unwrap();
+        assert_eq!(consumer.clock.now(), 42);
+
+        assert!(factory.primary_instance_typed::<SystemClock>().is_ok());
+        assert_eq!(
+            factory
+                .primary_instance_typed::<dyn Ticker + Send + Sync>()
+                .unwrap()
+                .tick(),
+            1
+        );
+    }
+
+    #[cfg(all(feature = "test-util", feature = "async"))]
+    #[tokio::test]
+    async fn should_override_trait_components() {
+        let mut factory = create_overriding_factory();
+        assert_eq!(
+            factory
+                .instances_typed::<dyn Clock + Send + Sync>()
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let consumer = factory
+            .primary_instance_typed::<ClockConsumer>()
+            .await
+            .unwrap();
+        assert_eq!(consumer.clock.now(), 42);
+
+        assert!(factory
+            .primary_instance_typed::<SystemClock>()
+            .await
+            .is_ok());
+        assert_eq!(
+            factory
+                .primary_instance_typed::<dyn Ticker + Send + Sync>()
+                .await
+                .unwrap()
+                .tick(),
+            1
+        );
+    }
+
+    struct UserCreated {
+        logins: usize,
+    }
+
+    #[derive(Component)]
+    struct LoginCounter {
+        #[component(default)]
+        logins: AtomicUsize,
+    }
+
+    #[derive(Component)]
+    struct UserCounter {
+        #[component(default)]
+        users: AtomicUsize,
+    }
+
+    #[event_listener]
+    impl EventListener<UserCreated> for LoginCounter {
+        #[cfg(not(feature = "async"))]
+        fn on_event(&self, event: &UserCreated) -> Result<(), ErrorPtr> {
+            self.logins.fetch_add(event.logins, Ordering::SeqCst);
+            Ok(())
+        }
+
+        #[cfg(feature = "async")]
+        fn on_event<'a>(&'a self, event: &'a UserCreated) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            async move {
+                self.logins.fetch_add(event.logins, Ordering::SeqCst);
+                Ok(())
+            }
+            .boxed()
+        }
+    }
+
+    #[event_listener]
+    impl EventListener<UserCreated> for UserCounter {
+        #[cfg(not(feature = "async"))]
+        fn on_event(&self, _event: &UserCreated) -> Result<(), ErrorPtr> {
+            self.users.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        #[cfg(feature = "async")]
+        fn on_event<'a>(&'a self, _event: &'a UserCreated) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            async move {
+                self.users.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            .boxed()
+        }
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_publish_events_to_listeners() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let publisher = factory.primary_instance_typed::<EventPublisher>().unwrap();
+
+        publisher.publish(&UserCreated { logins: 2 }).unwrap();
+        publisher.publish(&UserCreated { logins: 3 }).unwrap();
+        publisher.publish(&TestDependency).unwrap();
+
+        let logins = factory.primary_instance_typed::<LoginCounter>().unwrap();
+        assert_eq!(logins.logins.load(Ordering::SeqCst), 5);
+
+        let users = factory.primary_instance_typed::<UserCounter>().unwrap();
+        assert_eq!(users.users.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_publish_events_to_listeners() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let publisher = factory
+            .primary_instance_typed::<EventPublisher>()
+            .await
+            .unwrap();
+
+        publisher.publish(&UserCreated { logins: 2 }).await.unwrap();
+        publisher.publish(&UserCreated { logins: 3 }).await.unwrap();
+        publisher.publish(&TestDependency).await.unwrap();
+
+        let logins = factory
+            .primary_instance_typed::<LoginCounter>()
+            .await
+            .unwrap();
+        assert_eq!(logins.logins.load(Ordering::SeqCst), 5);
+
+        let users = factory
+            .primary_instance_typed::<UserCounter>()
+            .await
+            .unwrap();
+        assert_eq!(users.users.load(Ordering::SeqCst), 2);
+    }
+
+    #[derive(Component)]
+    struct ProcessedSettings {
+        #[component(default)]
+        retries: u8,
+    }
+
+    #[derive(Component)]
+    struct RejectedComponent;
+
+    #[derive(Component)]
+    struct MismatchedComponent;
+
+    struct SettingsPostProcessor;
+
+    impl SettingsPostProcessor {
+        fn apply(instance: ComponentInstanceAnyPtr) -> Result<ComponentInstanceAnyPtr, ErrorPtr> {
+            if instance.is::<RejectedComponent>() {
+                return Err(ComponentInstancePtr::new(std::fmt::Error) as ErrorPtr);
+            }
+
+            if instance.is::<MismatchedComponent>() {
+                return Ok(ComponentInstancePtr::new(RejectedComponent));
+            }
+
+            Ok(match instance.downcast::<ProcessedSettings>() {
+                Ok(_) => ComponentInstancePtr::new(ProcessedSettings { retries: 3 }),
+                Err(instance) => instance,
+            })
+        }
+    }
+
+    impl InstancePostProcessor for SettingsPostProcessor {
+        #[cfg(not(feature = "async"))]
+        fn post_process(
+            &self,
+            _definition: &ComponentDefinition,
+            instance: ComponentInstanceAnyPtr,
+        ) -> Result<ComponentInstanceAnyPtr, ErrorPtr> {
+            Self::apply(instance)
+        }
+
+        #[cfg(feature = "async")]
+        fn post_process<'a>(
+            &'a self,
+            _definition: &'a ComponentDefinition,
+            instance: ComponentInstanceAnyPtr,
+        ) -> BoxFuture<'a, Result<ComponentInstanceAnyPtr, ErrorPtr>> {
+            async move { Self::apply(instance) }.boxed()
+        }
+    }
+
+    fn create_post_processing_factory() -> ComponentFactory {
+        ComponentFactoryBuilder::new()
+            .unwrap()
+            .with_post_processor(Box::new(SettingsPostProcessor))
+            .build()
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_post_process_instances() {
+        let mut factory = create_post_processing_factory();
+        let settings = factory
+            .primary_instance_typed::<ProcessedSettings>()
+            .unwrap();
+        assert_eq!(settings.retries, 3);
+
+        assert!(matches!(
+            factory.primary_instance_typed::<RejectedComponent>(),
+            Err(ComponentInstanceProviderError::PostProcessorError(_))
+        ));
+        assert!(matches!(
+            factory.primary_instance_typed::<MismatchedComponent>(),
+            Err(ComponentInstanceProviderError::IncompatiblePostProcessedInstance { .. })
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_post_process_instances() {
+        let mut factory = create_post_processing_factory();
+        let settings = factory
+            .primary_instance_typed::<ProcessedSettings>()
+            .await
+            .unwrap();
+        assert_eq!(settings.retries, 3);
+
+        assert!(matches!(
+            factory.primary_instance_typed::<RejectedComponent>().await,
+            Err(ComponentInstanceProviderError::PostProcessorError(_))
+        ));
+        assert!(matches!(
+            factory
+                .primary_instance_typed::<MismatchedComponent>()
+                .await,
+            Err(ComponentInstanceProviderError::IncompatiblePostProcessedInstance { .. })
+        ));
+    }
+
+    #[derive(Component)]
+    #[component(scope = "PROTOTYPE")]
+    struct OwnedBuffer {
+        #[component(default)]
+        data: Vec<u8>,
+    }
+
+    #[derive(Component)]
+    struct BufferOwner {
+        #[component(owned)]
+        buffer: OwnedBuffer,
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_inject_owned_prototypes() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let owner = factory.primary_instance_typed::<BufferOwner>().unwrap();
+        assert!(owner.buffer.data.is_empty());
+
+        let mut buffer = factory.primary_instance_owned::<OwnedBuffer>().unwrap();
+        buffer.data.push(1);
+
+        assert!(matches!(
+            factory.primary_instance_owned::<TestDependency>(),
+            Err(ComponentInstanceProviderError::SharedInstance { .. })
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_inject_owned_prototypes() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let owner = factory
+            .primary_instance_typed::<BufferOwner>()
+            .await
+            .unwrap();
+        assert!(owner.buffer.data.is_empty());
+
+        let mut buffer = factory
+            .primary_instance_owned::<OwnedBuffer>()
+            .await
+            .unwrap();
+        buffer.data.push(1);
+
+        assert!(matches!(
+            factory.primary_instance_owned::<TestDependency>().await,
+            Err(ComponentInstanceProviderError::SharedInstance { .. })
+        ));
+    }
+
+    #[derive(Component)]
+    struct DynamicLookup {
+        factory: FactoryHandle,
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_inject_factory_handle() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let lookup = factory.primary_instance_typed::<DynamicLookup>().unwrap();
+
+        let dependency = lookup
+            .factory
+            .instance_by_name::<TestDependency>("test_dependency")
+            .unwrap();
+        assert!(ComponentInstancePtr::ptr_eq(
+            &dependency,
+            &factory.primary_instance_typed::<TestDependency>().unwrap()
+        ));
+        assert_eq!(
+            lookup
+                .factory
+                .instances::<dyn TestTrait2 + Sync + Send>()
+                .unwrap()
+                .len(),
+            factory
+                .instances_typed::<dyn TestTrait2 + Sync + Send>()
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_not_keep_factory_alive_from_handles() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let lookup = factory.primary_instance_typed::<DynamicLookup>().unwrap();
+        let weak_lookup = ComponentInstancePtr::downgrade(&lookup);
+
+        drop(factory);
+        assert!(matches!(
+            lookup
+                .factory
+                .instance_by_name::<TestDependency>("test_dependency"),
+            Err(ComponentInstanceProviderError::ProviderDropped)
+        ));
+
+        drop(lookup);
+        assert!(weak_lookup.upgrade().is_none());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_not_keep_factory_alive_from_handles() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let lookup = factory
+            .primary_instance_typed::<DynamicLookup>()
+            .await
+            .unwrap();
+        let weak_lookup = ComponentInstancePtr::downgrade(&lookup);
+
+        drop(factory);
+        assert!(matches!(
+            lookup
+                .factory
+                .instance_by_name::<TestDependency>("test_dependency")
+                .await,
+            Err(ComponentInstanceProviderError::ProviderDropped)
+        ));
+
+        drop(lookup);
+        assert!(weak_lookup.upgrade().is_none());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_inject_factory_handle() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let lookup = factory
+            .primary_instance_typed::<DynamicLookup>()
+            .await
+            .unwrap();
+
+        let dependency = lookup
+            .factory
+            .instance_by_name::<TestDependency>("test_dependency")
+            .await
+            .unwrap();
+        assert!(ComponentInstancePtr::ptr_eq(
+            &dependency,
+            &factory
+                .primary_instance_typed::<TestDependency>()
+                .await
+                .unwrap()
+        ));
+        assert_eq!(
+            lookup
+                .factory
+                .instances::<dyn TestTrait2 + Sync + Send>()
+                .await
+                .unwrap()
+                .len(),
+            factory
+                .instances_typed::<dyn TestTrait2 + Sync + Send>()
+                .await
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_filter_instances_by_definition() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let instances = factory
+            .instances_filtered::<dyn TestTrait2 + Sync + Send>(|definition| {
+                definition.names.contains("test_dependency")
+            })
+            .unwrap();
+        assert_eq!(instances.len(), 1);
+
+        assert!(factory
+            .instances_filtered::<dyn TestTrait2 + Sync + Send>(|_| false)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_filter_instances_by_definition() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let instances = factory
+            .instances_filtered::<dyn TestTrait2 + Sync + Send>(|definition| {
+                definition.names.contains("test_dependency")
+            })
+            .await
+            .unwrap();
+        assert_eq!(instances.len(), 1);
+
+        assert!(factory
+            .instances_filtered::<dyn TestTrait2 + Sync + Send>(|_| false)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    // doesn't implement Component nor Injectable, e.g. comes from another crate
+    struct ForeignClient {
+        url: String,
+    }
+
+    struct ForeignClientFactory;
+
+    #[component_factory]
+    impl ForeignClientFactory {
+        #[component(names = ["foreign_client"])]
+        fn foreign_client(
+            _dependency: ComponentInstancePtr<TestDependency>,
+            #[component(default = "foreign_url")] url: String,
+        ) -> Result<ForeignClient, ErrorPtr> {
+            Ok(ForeignClient { url })
+        }
+    }
+
+    fn foreign_url() -> String {
+        "localhost".to_string()
+    }
+
+    #[derive(Component)]
+    struct ForeignClientConsumer {
+        client: ComponentInstancePtr<ForeignClient>,
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_inject_factory_method_products() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let consumer = factory
+            .primary_instance_typed::<ForeignClientConsumer>()
+            .unwrap();
+        assert_eq!(consumer.client.url, "localhost");
+
+        let client = factory
+            .instance_by_name_typed::<ForeignClient>("foreign_client")
+            .unwrap();
+        assert!(ComponentInstancePtr::ptr_eq(&client, &consumer.client));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_inject_factory_method_products() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let consumer = factory
+            .primary_instance_typed::<ForeignClientConsumer>()
+            .await
+            .unwrap();
+        assert_eq!(consumer.client.url, "localhost");
+
+        let client = factory
+            .instance_by_name_typed::<ForeignClient>("foreign_client")
+            .await
+            .unwrap();
+        assert!(ComponentInstancePtr::ptr_eq(&client, &consumer.client));
+    }
+
+    struct PooledConnection {
+        pool: &'static str,
+    }
+
+    struct ReadOnlyPool;
+
+    struct ConnectionFactory;
+
+    #[component_factory]
+    impl ConnectionFactory {
+        fn default_connection() -> PooledConnection {
+            PooledConnection { pool: "default" }
+        }
+
+        #[component(qualifier = ReadOnlyPool)]
+        fn read_only_connection() -> PooledConnection {
+            PooledConnection { pool: "read_only" }
+        }
+    }
+
+    #[derive(Component)]
+    struct ConnectionConsumer {
+        connection: ComponentInstancePtr<PooledConnection>,
+        #[component(qualifier = ReadOnlyPool)]
+        read_only: ComponentInstancePtr<PooledConnection>,
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_inject_qualified_factory_method_products() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let consumer = factory
+            .primary_instance_typed::<ConnectionConsumer>()
+            .unwrap();
+
+        assert_eq!(consumer.connection.pool, "default");
+        assert_eq!(consumer.read_only.pool, "read_only");
+        assert_eq!(
+            factory.instances_typed::<PooledConnection>().unwrap().len(),
+            2
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_inject_qualified_factory_method_products() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let consumer = factory
+            .primary_instance_typed::<ConnectionConsumer>()
+            .await
+            .unwrap();
+
+        assert_eq!(consumer.connection.pool, "default");
+        assert_eq!(consumer.read_only.pool, "read_only");
+        assert_eq!(
+            factory
+                .instances_typed::<PooledConnection>()
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    struct FirstEntity;
+
+    struct SecondEntity;
+
+    #[derive(Component)]
+    struct GenericComponent<T: 'static> {
+        _dependency: ComponentInstancePtr<TestDependency>,
+        #[component(default)]
+        entities: Vec<T>,
+    }
+
+    register_generic_component!(GenericComponent<FirstEntity>);
+    register_generic_component!(GenericComponent<SecondEntity>, names = ["second_entities"]);
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_register_generic_components() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let first = factory
+            .primary_instance_typed::<GenericComponent<FirstEntity>>()
+            .unwrap();
+        let second = factory
+            .instance_by_name_typed::<GenericComponent<SecondEntity>>("second_entities")
+            .unwrap();
+
+        assert!(first.entities.is_empty());
+        assert!(second.entities.is_empty());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_register_generic_components() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let first = factory
+            .primary_instance_typed::<GenericComponent<FirstEntity>>()
+            .await
+            .unwrap();
+        let second = factory
+            .instance_by_name_typed::<GenericComponent<SecondEntity>>("second_entities")
+            .await
+            .unwrap();
+
+        assert!(first.entities.is_empty());
+        assert!(second.entities.is_empty());
+    }
+
+    struct FirstQualifier;
+
+    struct SecondQualifier;
+
+    #[injectable]
+    trait QualifiedTrait {
+        fn value(&self) -> i8;
+    }
+
+    #[derive(Component)]
+    #[component(qualifier = FirstQualifier)]
+    struct FirstQualifiedComponent;
+
+    #[component_alias]
+    impl QualifiedTrait for FirstQualifiedComponent {
+        fn value(&self) -> i8 {
+            1
+        }
+    }
+
+    #[derive(Component)]
+    #[component(names = ["second_qualified"], qualifier = SecondQualifier)]
+    struct SecondQualifiedComponent;
+
+    #[component_alias]
+    impl QualifiedTrait for SecondQualifiedComponent {
+        fn value(&self) -> i8 {
+            2
+        }
+    }
+
+    #[derive(Component)]
+    struct QualifierConsumer {
+        #[cfg(feature = "threadsafe")]
+        #[component(qualifier = SecondQualifier)]
+        second: ComponentInstancePtr<dyn QualifiedTrait + Send + Sync>,
+        #[cfg(not(feature = "threadsafe"))]
+        #[component(qualifier = SecondQualifier)]
+        second: ComponentInstancePtr<dyn QualifiedTrait>,
+        #[component(qualifier = FirstQualifier)]
+        first: Option<ComponentInstancePtr<FirstQualifiedComponent>>,
+    }
+
+    #[injectable]
+    trait OrderedTrait {
+        fn value(&self) -> i8;
+    }
+
+    #[derive(Component)]
+    #[component(order = 10)]
+    struct FirstOrderedComponent;
+
+    #[component_alias]
+    impl OrderedTrait for FirstOrderedComponent {
+        fn value(&self) -> i8 {
+            1
+        }
+    }
+
+    #[derive(Component)]
+    #[component(order = -10)]
+    struct SecondOrderedComponent;
+
+    #[component_alias]
+    impl OrderedTrait for SecondOrderedComponent {
+        fn value(&self) -> i8 {
+            2
+        }
+    }
+
+    #[derive(Component)]
+    struct OrderedInstancesConsumer {
+        #[cfg(feature = "threadsafe")]
+        instances: Vec<ComponentInstancePtr<dyn OrderedTrait + Send + Sync>>,
+        #[cfg(not(feature = "threadsafe"))]
+        instances: Vec<ComponentInstancePtr<dyn OrderedTrait>>,
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_inject_instances_in_declared_order() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let consumer = factory
+            .primary_instance_typed::<OrderedInstancesConsumer>()
+            .unwrap();
+
+        let values: Vec<_> = consumer
+            .instances
+            .iter()
+            .map(|instance| instance.value())
+            .collect();
+        assert_eq!(values, [2, 1]);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_inject_instances_in_declared_order() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let consumer = factory
+            .primary_instance_typed::<OrderedInstancesConsumer>()
+            .await
+            .unwrap();
+
+        let values: Vec<_> = consumer
+            .instances
+            .iter()
+            .map(|instance| instance.value())
+            .collect();
+        assert_eq!(values, [2, 1]);
+    }
+
+    #[derive(Component)]
+    struct NamedInstancesConsumer {
+        #[cfg(feature = "threadsafe")]
+        instances: FxHashMap<String, ComponentInstancePtr<dyn QualifiedTrait + Send + Sync>>,
+        #[cfg(not(feature = "threadsafe"))]
+        instances: FxHashMap<String, ComponentInstancePtr<dyn QualifiedTrait>>,
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_inject_instances_by_name() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let consumer = factory
+            .primary_instance_typed::<NamedInstancesConsumer>()
+            .unwrap();
+
+        assert_eq!(consumer.instances["first_qualified_component"].value(), 1);
+        assert_eq!(consumer.instances["second_qualified"].value(), 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_inject_instances_by_name() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let consumer = factory
+            .primary_instance_typed::<NamedInstancesConsumer>()
+            .await
+            .unwrap();
+
+        assert_eq!(consumer.instances["first_qualified_component"].value(), 1);
+        assert_eq!(consumer.instances["second_qualified"].value(), 2);
+    }
+
+    fn assert_primary_candidates(error: ComponentInstanceProviderError) {
+        assert!(matches!(
+            error,
+            ComponentInstanceProviderError::AmbiguousPrimaryInstance { candidates, .. } if candidates == vec![
+                type_name::<FirstQualifiedComponent>().to_string(),
+                type_name::<SecondQualifiedComponent>().to_string(),
+            ]
+        ));
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_report_primary_candidates() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+
+        #[cfg(feature = "threadsafe")]
+        let error = factory
+            .primary_instance_typed::<dyn QualifiedTrait + Send + Sync>()
+            .err()
+            .unwrap();
+        #[cfg(not(feature = "threadsafe"))]
+        let error = factory
+            .primary_instance_typed::<dyn QualifiedTrait>()
+            .err()
+            .unwrap();
+
+        assert_primary_candidates(error);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_report_primary_candidates() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let error = factory
+            .primary_instance_typed::<dyn QualifiedTrait + Send + Sync>()
+            .await
+            .err()
+            .unwrap();
+
+        assert_primary_candidates(error);
+    }
+
+    #[cfg(all(feature = "threadsafe", not(feature = "async")))]
+    #[test]
+    fn should_resolve_concurrently() {
+        let factory = ComponentInstancePtr::new(ComponentFactoryBuilder::new().unwrap().build());
+        let handles = (0..4)
+            .map(|_| {
+                let factory = factory.clone();
+                std::thread::spawn(move || {
+                    (&*factory)
+                        .primary_instance_typed::<TestDependency>()
+                        .unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let instances = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+        assert!(instances
+            .windows(2)
+            .all(|pair| ComponentInstancePtr::ptr_eq(&pair[0], &pair[1])));
+    }
+
+    #[cfg(all(feature = "threadsafe", feature = "async"))]
+    #[tokio::test]
+    async fn should_resolve_concurrently() {
+        let factory = ComponentInstancePtr::new(ComponentFactoryBuilder::new().unwrap().build());
+        let handles = (0..4)
+            .map(|_| {
+                let factory = factory.clone();
+                tokio::spawn(async move {
+                    (&*factory)
+                        .primary_instance_typed::<TestDependency>()
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut instances = vec![];
+        for handle in handles {
+            instances.push(handle.await.unwrap());
+        }
+
+        assert!(instances
+            .windows(2)
+            .all(|pair| ComponentInstancePtr::ptr_eq(&pair[0], &pair[1])));
+    }
+
+    #[cfg(feature = "async")]
+    #[derive(Component)]
+    struct ConcurrencyProbe {
+        #[component(default)]
+        started: AtomicUsize,
+    }
+
+    #[cfg(feature = "async")]
+    impl ConcurrencyProbe {
+        // returns if a sibling started construction before this one finished
+        async fn wait_for_sibling(&self) -> bool {
+            self.started.fetch_add(1, Ordering::SeqCst);
+            for _ in 0..100 {
+                if self.started.load(Ordering::SeqCst) >= 2 {
+                    return true;
+                }
+
+                tokio::task::yield_now().await;
+            }
+
+            false
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[derive(Component)]
+    #[component(
+        constructor = "first_concurrent_dependency",
+        constructor_parameters = "ConcurrencyProbe"
+    )]
+    struct FirstConcurrentDependency {
+        #[component(ignore)]
+        overlapped: bool,
+    }
+
+    #[cfg(feature = "async")]
+    async fn first_concurrent_dependency(
+        probe: ComponentInstancePtr<ConcurrencyProbe>,
+    ) -> Result<FirstConcurrentDependency, ErrorPtr> {
+        Ok(FirstConcurrentDependency {
+            overlapped: probe.wait_for_sibling().await,
+        })
+    }
+
+    #[cfg(feature = "async")]
+    #[derive(Component)]
+    #[component(
+        constructor = "second_concurrent_dependency",
+        constructor_parameters = "ConcurrencyProbe"
+    )]
+    struct SecondConcurrentDependency {
+        #[component(ignore)]
+        overlapped: bool,
+    }
+
+    #[cfg(feature = "async")]
+    async fn second_concurrent_dependency(
+        probe: ComponentInstancePtr<ConcurrencyProbe>,
+    ) -> Result<SecondConcurrentDependency, ErrorPtr> {
+        Ok(SecondConcurrentDependency {
+            overlapped: probe.wait_for_sibling().await,
+        })
+    }
+
+    #[cfg(feature = "async")]
+    #[derive(Component)]
+    #[component(concurrent)]
+    struct ConcurrentComponent {
+        first: ComponentInstancePtr<FirstConcurrentDependency>,
+        second: ComponentInstancePtr<SecondConcurrentDependency>,
+        #[component(default)]
+        value: i8,
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_construct_dependencies_concurrently() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let component = factory
+            .primary_instance_typed::<ConcurrentComponent>()
+            .await
+            .unwrap();
+
+        assert!(component.first.overlapped);
+        assert!(component.second.overlapped);
+        assert_eq!(component.value, 0);
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_inject_qualified_components() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let consumer = factory
+            .primary_instance_typed::<QualifierConsumer>()
+            .unwrap();
+
+        assert_eq!(consumer.second.value(), 2);
+        assert!(consumer.first.is_some());
+        assert!(factory
+            .instance_by_name_typed::<SecondQualifiedComponent>("second_qualified")
+            .is_ok());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_inject_qualified_components() {
+        let mut factory = ComponentFactoryBuilder::new().unwrap().build();
+        let consumer = factory
+            .primary_instance_typed::<QualifierConsumer>()
+            .await
+            .unwrap();
+
+        assert_eq!(consumer.second.value(), 2);
+        assert!(consumer.first.is_some());
+        assert!(factory
+            .instance_by_name_typed::<SecondQualifiedComponent>("second_qualified")
+            .await
+            .is_ok());
+    }
+
+    #[derive(Component)]
+    #[component(condition = "memoized_condition")]
+    struct MemoizedComponent;
+
+    #[derive(Component)]
+    #[component(order = -1)]
+    struct OrderedComponent;
+
+    fn memoized_condition(context: &dyn Context, _metadata: ConditionMetadata) -> bool {
+        context.memoize("memoized_condition", &|| true)
+    }
+
+    #[derive(Default)]
+    struct CountingContextFactory {
+        count: Cell<usize>,
+    }
+
+    impl ContextFactory for CountingContextFactory {
+        fn create_context<'a>(
+            &self,
+            registry: &'a dyn ComponentDefinitionRegistryFacade,
+        ) -> Box<dyn Context + 'a> {
+            self.count.set(self.count.get() + 1);
+            SimpleContextFactory.create_context(registry)
+        }
+    }
+
+    #[test]
+    fn should_register_collected_components() {
+        let registry = StaticComponentDefinitionRegistry::new_with_definitions(
+            false,
+            &SimpleContextFactory,
+            collect_components!(TestDependency => [dyn TestTrait2 + Sync + Send]),
+        )
+        .unwrap();
+
+        assert!(registry.is_registered_typed::<TestDependency>());
+        assert_eq!(
+            registry
+                .components_by_type_typed::<dyn TestTrait2 + Sync + Send>()
+                .len(),
+            1
+        );
+        assert!(!registry.is_registered_typed::<TestComponent1>());
+    }
+
+    #[test]
+    fn should_register_subset_of_components() {
+        let mut registry = StaticComponentDefinitionRegistry::new_with_definitions(
+            false,
+            &SimpleContextFactory,
+            ComponentDefinitions::default(),
+        )
+        .unwrap();
+
+        register_components!(registry, &SimpleContextFactory, TestDependency).unwrap();
+        assert!(registry.is_registered_typed::<TestDependency>());
+
+        // aliases can target components registered earlier
+        let aliases = collect_components!(TestDependency => [dyn TestTrait2 + Sync + Send]).aliases;
+        registry
+            .register_definitions(
+                ComponentDefinitions {
+                    components: vec![],
+                    aliases,
+                },
+                &SimpleContextFactory,
+            )
+            .unwrap();
+        assert_eq!(
+            registry
+                .components_by_type_typed::<dyn TestTrait2 + Sync + Send>()
+                .len(),
+            1
+        );
+
+        // failed registrations leave the registry unchanged
+        assert!(register_components!(
+            registry,
+            &SimpleContextFactory,
+            TestComponent1,
+            TestDependency
+        )
+        .is_err());
+        assert!(!registry.is_registered_typed::<TestComponent1>());
+    }
+
+    #[test]
+    fn should_share_context_during_registration() {
+        let context_factory = CountingContextFactory::default();
+        let registry = StaticComponentDefinitionRegistry::new(false, &context_factory).unwrap();
+
+        assert_eq!(context_factory.count.get(), 1);
+        assert!(TypedComponentDefinitionRegistry::is_registered_typed::<
+            MemoizedComponent,
+        >(&registry));
+    }
+
+    #[test]
+    fn should_register_component_order() {
+        let registry =
+            StaticComponentDefinitionRegistry::new(false, &SimpleContextFactory).unwrap();
+
+        assert_eq!(
+            registry.components_by_type_typed::<OrderedComponent>()[0].order,
+            -1
+        );
+    }
+
+    #[test]
+    fn should_export_definitions() {
+        let registry =
+            StaticComponentDefinitionRegistry::new(false, &SimpleContextFactory::default())
+                .unwrap();
+        let export = registry.export();
+
+        let component = export
+            .types
+            .iter()
+            .find(|exported| exported.type_name.ends_with("TestComponent2"))
+            .unwrap();
+        assert!(!component.is_alias);
+        assert_eq!(component.definitions[0].names, vec!["dep2".to_string()]);
+        assert!(component.definitions[0].has_condition);
+        assert!(component.definitions[0]
+            .dependencies
+            .iter()
+            .any(|dependency| dependency.type_name.ends_with("TestDependency")));
+
+        let alias = export
+            .types
+            .iter()
+            .find(|exported| exported.type_name.contains("TestTrait1"))
+            .unwrap();
+        assert!(alias.is_alias);
+        assert!(alias.definitions[0]
+            .resolved_type_name
+            .ends_with("TestComponent2"));
+
+        let conditional_alias = export
+            .types
+            .iter()
+            .find(|exported| exported.type_name.contains("TestTrait2"))
+            .unwrap();
+        assert!(conditional_alias.definitions[0].has_condition);
+
+        let dependency = export
+            .types
+            .iter()
+            .find(|exported| exported.type_name.ends_with("TestDependency"))
+            .unwrap();
+        assert!(!dependency.definitions[0].has_condition);
+
+        let condition = export
+            .conditions
+            .iter()
+            .find(|condition| condition.type_name.ends_with("DisabledComponent"))
+            .unwrap();
+        assert!(!condition.passed);
+    }
+
+    #[cfg(not(feature = "async"))]
+    mod sync {
+        use crate::component_derive_test::{
+            TestComponent1, TestComponent2, TestDependencyInstanceProvider,
+        };
+        use springtime_di::component::Component;
+        use springtime_di::instance_provider::{
+            ComponentInstanceProvider, ComponentInstanceProviderError,
+        };
+        use std::any::TypeId;
+
+        #[test]
+        fn should_directly_create_with_explicit_dependency() {
             let mut instance_provider = TestDependencyInstanceProvider;
             assert!(TestComponent1::create(&mut instance_provider).is_ok());
             assert!(TestComponent2::create(&mut instance_provider).is_ok());
         }
+
+        #[test]
+        fn should_not_support_named_instances_by_default() {
+            let mut instance_provider = TestDependencyInstanceProvider;
+            assert!(matches!(
+                instance_provider.named_instances(TypeId::of::<TestComponent1>()),
+                Err(ComponentInstanceProviderError::NamedInstancesUnsupported)
+            ));
+        }
+
+        #[test]
+        fn should_not_support_filtered_instances_by_default() {
+            let mut instance_provider = TestDependencyInstanceProvider;
+            assert!(matches!(
+                instance_provider.filtered_instances(TypeId::of::<TestComponent1>(), &|_| true),
+                Err(ComponentInstanceProviderError::FilteredInstancesUnsupported)
+            ));
+        }
     }
 
     #[test]
@@ -0,0 +1,79 @@
+#![cfg(all(
+    feature = "derive",
+    feature = "explicit-registration",
+    feature = "threadsafe"
+))]
+
+mod explicit_registration_test {
+    use springtime_di::component_registry::conditional::SimpleContextFactory;
+    use springtime_di::component_registry::{
+        StaticComponentDefinitionRegistry, TypedComponentDefinitionRegistry,
+    };
+    use springtime_di::factory::ComponentFactoryBuilder;
+    use springtime_di::instance_provider::{ComponentInstancePtr, TypedComponentInstanceProvider};
+    use springtime_di::{collect_components, component_alias, injectable, Component};
+
+    #[injectable]
+    trait Greeter {
+        fn greet(&self) -> &'static str;
+    }
+
+    #[derive(Component)]
+    struct EnglishGreeter;
+
+    #[component_alias]
+    impl Greeter for EnglishGreeter {
+        fn greet(&self) -> &'static str {
+            "hello"
+        }
+    }
+
+    #[derive(Component)]
+    struct GreetingService {
+        greeter: ComponentInstancePtr<dyn Greeter + Send + Sync>,
+    }
+
+    fn create_registry() -> StaticComponentDefinitionRegistry {
+        StaticComponentDefinitionRegistry::new_with_definitions(
+            false,
+            &SimpleContextFactory,
+            collect_components!(EnglishGreeter => [dyn Greeter + Send + Sync], GreetingService),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn should_not_register_components_automatically() {
+        let registry =
+            StaticComponentDefinitionRegistry::new(false, &SimpleContextFactory).unwrap();
+        assert!(!registry.is_registered_typed::<EnglishGreeter>());
+        assert!(!registry.is_registered_typed::<GreetingService>());
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_create_collected_components() {
+        let mut factory = ComponentFactoryBuilder::new()
+            .unwrap()
+            .with_definition_registry(Box::new(create_registry()))
+            .build();
+
+        let service = factory.primary_instance_typed::<GreetingService>().unwrap();
+        assert_eq!(service.greeter.greet(), "hello");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_create_collected_components() {
+        let mut factory = ComponentFactoryBuilder::new()
+            .unwrap()
+            .with_definition_registry(Box::new(create_registry()))
+            .build();
+
+        let service = factory
+            .primary_instance_typed::<GreetingService>()
+            .await
+            .unwrap();
+        assert_eq!(service.greeter.greet(), "hello");
+    }
+}
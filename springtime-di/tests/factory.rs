@@ -1,8 +1,22 @@
+#![cfg(not(feature = "explicit-registration"))]
+
 #[cfg(feature = "derive")]
 mod factory_test {
     use springtime_di::component_registry::conditional::unregistered_component;
-    use springtime_di::instance_provider::ComponentInstancePtr;
+    use springtime_di::factory::ComponentFactoryBuilder;
+    use springtime_di::instance_provider::{ComponentInstanceProvider, ComponentInstancePtr};
     use springtime_di::{component_alias, injectable, Component};
+    use std::any::type_name;
+    use std::io;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    #[cfg(not(feature = "async"))]
+    use tracing::dispatcher;
+    #[cfg(feature = "async")]
+    use tracing::instrument::WithSubscriber;
+    use tracing::{Dispatch, Level};
+    use tracing_subscriber::fmt::format::FmtSpan;
+    use tracing_subscriber::fmt::MakeWriter;
 
     #[injectable]
     trait TestTrait1 {}
@@ -65,6 +79,87 @@ mod factory_test {
 
     impl TestComponent {}
 
+    // captures formatted tracing output, including construction spans when they are closed
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl CapturedLogs {
+        fn dispatch(&self) -> Dispatch {
+            tracing_subscriber::fmt()
+                .with_max_level(Level::DEBUG)
+                .with_ansi(false)
+                .with_span_events(FmtSpan::CLOSE)
+                .with_writer(self.clone())
+                .finish()
+                .into()
+        }
+
+        fn assert_construction_traced(&self) {
+            let logs = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+
+            // the elapsed time is recorded when construction finishes, so it's present on close
+            let span = format!(
+                "create_instance{{resolved_type_name=\"{}\" scope=\"SINGLETON\" elapsed_us=",
+                type_name::<TestComponent>()
+            );
+            assert!(logs
+                .lines()
+                .any(|line| line.contains(&span) && line.contains("close")));
+            assert!(logs
+                .lines()
+                .any(|line| line.contains("Instantiated all singletons.")
+                    && line.contains("count=")
+                    && line.contains("elapsed_ms=")));
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for CapturedLogs {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    impl Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_trace_component_construction() {
+        let logs = CapturedLogs::default();
+        let mut component_factory = ComponentFactoryBuilder::new().unwrap().build();
+
+        dispatcher::with_default(&logs.dispatch(), || {
+            component_factory.instantiate_all_singletons().unwrap();
+        });
+
+        logs.assert_construction_traced();
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_trace_component_construction() {
+        let logs = CapturedLogs::default();
+        let mut component_factory = ComponentFactoryBuilder::new().unwrap().build();
+
+        component_factory
+            .instantiate_all_singletons()
+            .with_subscriber(logs.dispatch())
+            .await
+            .unwrap();
+
+        logs.assert_construction_traced();
+    }
+
     #[cfg(not(feature = "async"))]
     mod sync {
         use crate::factory_test::TestComponent;
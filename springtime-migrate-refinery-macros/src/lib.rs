@@ -1,10 +1,13 @@
+#![cfg_attr(feature = "nightly", feature(proc_macro_tracked_path))]
+
 mod migration;
 
-use crate::migration::generate_migrations;
+use crate::migration::{generate_exported_migrations, generate_migrations};
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
-use syn::{parse_macro_input, Error, LitStr};
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, Error, Ident, LitStr, Token, Visibility};
 
 #[proc_macro]
 pub fn embed_migrations(input: TokenStream) -> TokenStream {
@@ -18,3 +21,69 @@ pub fn embed_migrations(input: TokenStream) -> TokenStream {
     let migrations = migrations.unwrap_or_else(Error::into_compile_error);
     quote!(#migrations).into()
 }
+
+struct ExportMigrationsInput {
+    visibility: Visibility,
+    ident: Ident,
+    namespace: LitStr,
+    path: Option<LitStr>,
+}
+
+impl Parse for ExportMigrationsInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let visibility = input.parse()?;
+        let ident: Ident = input.parse()?;
+
+        let mut namespace = None;
+        let mut path = None;
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value: LitStr = input.parse()?;
+
+            if key == "namespace" {
+                namespace = Some(value);
+            } else if key == "path" {
+                path = Some(value);
+            } else {
+                return Err(Error::new(key.span(), "Unknown argument"));
+            }
+        }
+
+        let namespace =
+            namespace.ok_or_else(|| Error::new(ident.span(), "Missing namespace argument"))?;
+
+        Ok(Self {
+            visibility,
+            ident,
+            namespace,
+            path,
+        })
+    }
+}
+
+#[proc_macro]
+pub fn export_migrations(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as ExportMigrationsInput);
+    let (path, span) = input
+        .path
+        .as_ref()
+        .map(|path| (path.value(), path.span()))
+        .unwrap_or_else(|| ("migrations".to_string(), Span::call_site()));
+
+    let migrations = generate_exported_migrations(
+        &input.visibility,
+        &input.ident,
+        &input.namespace,
+        &path,
+        span,
+    )
+    .unwrap_or_else(Error::into_compile_error);
+
+    quote!(#migrations).into()
+}
@@ -2,8 +2,11 @@ use itertools::Itertools;
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use refinery_core::{find_migration_files, MigrationType};
-use std::path::Path;
-use syn::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use syn::{Error, Ident, LitStr, Result, Visibility};
+
+const DOWN_MIGRATION_SUFFIX: &str = ".down.sql";
 
 fn generate_migration(path: &Path, item_span: Span) -> Result<TokenStream> {
     let filename = path
@@ -24,7 +27,66 @@ fn generate_migration(path: &Path, item_span: Span) -> Result<TokenStream> {
     })
 }
 
-pub fn generate_migrations(path: &str, item_span: Span) -> Result<TokenStream> {
+fn generate_down_migration(path: &Path, item_span: Span) -> Result<TokenStream> {
+    let name = path
+        .file_name()
+        .and_then(|file| file.to_str())
+        .and_then(|file| file.strip_suffix(DOWN_MIGRATION_SUFFIX))
+        .ok_or_else(|| {
+            Error::new(
+                item_span,
+                format!("Cannot extract down migration name: {}", path.display()),
+            )
+        })?;
+
+    let path = path.display().to_string();
+
+    Ok(quote! {
+        DownMigration::new(#name, include_str!(#path))
+            .map_err(|error| std::sync::Arc::new(error) as ErrorPtr)?
+    })
+}
+
+fn find_down_migration_files(path: &Path, result: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(path)? {
+        let entry_path = entry?.path();
+        if entry_path.is_dir() {
+            find_down_migration_files(&entry_path, result)?;
+        } else if entry_path
+            .file_name()
+            .and_then(|file| file.to_str())
+            .map(|file| file.ends_with(DOWN_MIGRATION_SUFFIX))
+            .unwrap_or(false)
+        {
+            result.push(entry_path);
+        }
+    }
+
+    Ok(())
+}
+
+// new files don't change the generated code by themselves, so the compiler needs to know which
+// directories to watch
+#[cfg(feature = "nightly")]
+fn track_directories(path: &Path) -> std::io::Result<()> {
+    if let Some(path) = path.to_str() {
+        proc_macro::tracked::path(path);
+    }
+
+    for entry in fs::read_dir(path)? {
+        let entry_path = entry?.path();
+        if entry_path.is_dir() {
+            track_directories(&entry_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn generate_migration_lists(
+    path: &str,
+    item_span: Span,
+) -> Result<(Vec<TokenStream>, Vec<TokenStream>)> {
     let files = find_migration_files(path, MigrationType::Sql).map_err(|error| {
         Error::new(
             item_span,
@@ -32,35 +94,108 @@ pub fn generate_migrations(path: &str, item_span: Span) -> Result<TokenStream> {
         )
     })?;
 
-    files
-        .map(|path| {
-            generate_migration(&path, item_span).map(|migration| {
-                quote! {
-                    #migration
-                }
-            })
+    let mut down_files = vec![];
+    Path::new(path)
+        .canonicalize()
+        .and_then(|path| {
+            #[cfg(feature = "nightly")]
+            track_directories(&path)?;
+
+            find_down_migration_files(&path, &mut down_files)
         })
-        .try_collect()
-        .map(|migrations: Vec<_>| {
-            quote! {
-                #[automatically_derived]
-                mod migrations {
-                    use springtime::future::{BoxFuture, FutureExt};
-                    use springtime::runner::ErrorPtr;
-                    use springtime_di::{component_alias, Component};
-                    use springtime_migrate_refinery::migration::MigrationSource;
-                    use springtime_migrate_refinery::refinery::Migration;
-
-                    #[derive(Component)]
-                    struct GenratedMigrationSource;
-
-                    #[component_alias]
-                    impl MigrationSource for GenratedMigrationSource {
-                        fn migrations(&self) -> Result<Vec<Migration>, ErrorPtr> {
-                            Ok(vec![#(#migrations),*])
-                        }
-                    }
+        .map_err(|error| {
+            Error::new(
+                item_span,
+                format!("Error looking for down migrations in {path}: {error}"),
+            )
+        })?;
+
+    // sort for stable output
+    down_files.sort();
+
+    let down_migrations: Vec<_> = down_files
+        .iter()
+        .map(|path| generate_down_migration(path, item_span))
+        .try_collect()?;
+
+    let migrations: Vec<_> = files
+        .map(|path| generate_migration(&path, item_span))
+        .try_collect()?;
+
+    Ok((migrations, down_migrations))
+}
+
+pub fn generate_migrations(path: &str, item_span: Span) -> Result<TokenStream> {
+    let (migrations, down_migrations) = generate_migration_lists(path, item_span)?;
+
+    Ok(quote! {
+        #[automatically_derived]
+        mod migrations {
+            use springtime::future::{BoxFuture, FutureExt};
+            use springtime::runner::ErrorPtr;
+            use springtime_di::{component_alias, Component};
+            use springtime_migrate_refinery::migration::{DownMigration, MigrationSource};
+            use springtime_migrate_refinery::refinery::Migration;
+
+            #[derive(Component)]
+            struct GenratedMigrationSource;
+
+            #[component_alias]
+            impl MigrationSource for GenratedMigrationSource {
+                fn migrations(&self) -> Result<Vec<Migration>, ErrorPtr> {
+                    Ok(vec![#(#migrations),*])
+                }
+
+                fn down_migrations(&self) -> Result<Vec<DownMigration>, ErrorPtr> {
+                    Ok(vec![#(#down_migrations),*])
                 }
             }
-        })
+        }
+    })
+}
+
+pub fn generate_exported_migrations(
+    visibility: &Visibility,
+    ident: &Ident,
+    namespace: &LitStr,
+    path: &str,
+    item_span: Span,
+) -> Result<TokenStream> {
+    let (migrations, down_migrations) = generate_migration_lists(path, item_span)?;
+
+    Ok(quote! {
+        #[derive(springtime_di::Component)]
+        #visibility struct #ident;
+
+        #[springtime_di::component_alias]
+        impl springtime_migrate_refinery::migration::MigrationSource for #ident {
+            fn migrations(
+                &self,
+            ) -> Result<
+                Vec<springtime_migrate_refinery::refinery::Migration>,
+                springtime::runner::ErrorPtr,
+            > {
+                use springtime::runner::ErrorPtr;
+                use springtime_migrate_refinery::refinery::Migration;
+
+                Ok(vec![#(#migrations),*])
+            }
+
+            fn down_migrations(
+                &self,
+            ) -> Result<
+                Vec<springtime_migrate_refinery::migration::DownMigration>,
+                springtime::runner::ErrorPtr,
+            > {
+                use springtime::runner::ErrorPtr;
+                use springtime_migrate_refinery::migration::DownMigration;
+
+                Ok(vec![#(#down_migrations),*])
+            }
+
+            fn namespace(&self) -> Option<&str> {
+                Some(#namespace)
+            }
+        }
+    })
 }
@@ -3,7 +3,10 @@
 use springtime::application;
 use springtime_di::instance_provider::ErrorPtr;
 use springtime_di::{component_alias, Component};
-use springtime_migrate_refinery::migration::MigrationSource;
+use springtime_migrate_refinery::migration::code::{
+    build_down_migrations, build_migrations, CodeMigration,
+};
+use springtime_migrate_refinery::migration::{DownMigration, MigrationSource};
 use springtime_migrate_refinery::refinery::Migration;
 use std::sync::Arc;
 
@@ -21,6 +24,36 @@ impl MigrationSource for ExampleMigrationSource {
     }
 }
 
+// migrations can also be generated at runtime with the CodeMigration builder, which provides
+// helpers for common operations, along with their reverting counterparts
+#[derive(Component)]
+struct GeneratedMigrationSource;
+
+impl GeneratedMigrationSource {
+    fn create_migrations() -> Vec<CodeMigration> {
+        // e.g. a table per tenant
+        ["first", "second"]
+            .iter()
+            .enumerate()
+            .map(|(index, tenant)| {
+                CodeMigration::versioned(index as u32 + 1, format!("create_{tenant}"))
+                    .create_table(&format!("{tenant}_data"), &["id INTEGER PRIMARY KEY"])
+            })
+            .collect()
+    }
+}
+
+#[component_alias]
+impl MigrationSource for GeneratedMigrationSource {
+    fn migrations(&self) -> Result<Vec<Migration>, ErrorPtr> {
+        build_migrations(&Self::create_migrations())
+    }
+
+    fn down_migrations(&self) -> Result<Vec<DownMigration>, ErrorPtr> {
+        build_down_migrations(&Self::create_migrations())
+    }
+}
+
 // note: for the sake of simplicity, errors are unwrapped, rather than gracefully handled
 #[tokio::main]
 async fn main() {
@@ -2,19 +2,52 @@
 //! used to retrieve [MigrationConfig].
 //!
 //! By default, the config is created with opinionated default values, which can then be overwritten
-//! by values from `springtime.json` file under the `migration` key.
+//! by values from [MergedConfig] (e.g. `springtime.json` file) under the `migration` key. Since
+//! deployment tooling often needs to pin or disable migrations, `target` and
+//! `run_migrations_on_start` can additionally be set with `SPRINGTIME_MIGRATION_TARGET`
+//! (e.g. `Version:42`, see [Target::from_str]) and `SPRINGTIME_MIGRATION_RUN_MIGRATIONS_ON_START`
+//! environment variables. Such variables are provided as a [ConfigSource], so they override the
+//! config file, but are overridden by command line arguments (e.g.
+//! `--springtime.migration.target=Fake`) and generic `SPRINGTIME_MIGRATION__*` variables.
 
-use config::{Config, File};
-use serde::Deserialize;
-use springtime::config::CONFIG_FILE;
+use crate::refinery::Migration;
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer};
+use springtime::config::source::{ConfigMap, ConfigSource};
+use springtime::config::{ConfigSection, MergedConfig};
 use springtime::future::{BoxFuture, FutureExt};
-use springtime_di::component_registry::conditional::unregistered_component;
-use springtime_di::instance_provider::ErrorPtr;
+use springtime_di::component_registry::conditional::{
+    env_var_set, unregistered_component, ConditionMetadata, Context,
+};
+use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
 use springtime_di::{component_alias, injectable, Component};
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
+use thiserror::Error;
 
-/// A [Deserialize] version of [Target](refinery_core::Target).
-#[derive(Clone, Copy, Debug, Deserialize)]
+const TARGET_ENV: &str = "SPRINGTIME_MIGRATION_TARGET";
+const RUN_MIGRATIONS_ON_START_ENV: &str = "SPRINGTIME_MIGRATION_RUN_MIGRATIONS_ON_START";
+const TARGET_KEY: &str = "migration.target";
+const RUN_MIGRATIONS_ON_START_KEY: &str = "migration.run_migrations_on_start";
+
+/// Errors related to migration configuration.
+#[derive(Error, Debug)]
+pub enum MigrationConfigError {
+    /// Given value cannot be parsed as a [Target].
+    #[error("Invalid migration target: {0}")]
+    InvalidTarget(String),
+    /// Given environment variable contains an invalid value.
+    #[error("Invalid value of environment variable {name}: {value}")]
+    InvalidEnvironmentVariable { name: &'static str, value: String },
+}
+
+/// A [Deserialize] version of [Target](refinery_core::Target). Besides the usual enum
+/// representation, e.g. `{"Version": 42}`, targets can be given as strings accepted by
+/// [Target::from_str], e.g. `"Version:42"`.
+#[derive(Clone, Copy, Debug)]
 pub enum Target {
     /// Latest version.
     Latest,
@@ -26,6 +59,54 @@ pub enum Target {
     FakeVersion(u32),
 }
 
+impl FromStr for Target {
+    type Err = MigrationConfigError;
+
+    /// Parses `Latest`, `Fake`, `Version:{version}` or `FakeVersion:{version}`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid_target = || MigrationConfigError::InvalidTarget(value.to_string());
+        let parse_version = |version: &str| version.trim().parse().map_err(|_| invalid_target());
+
+        match value.trim().split_once(':') {
+            Some(("Version", version)) => parse_version(version).map(Target::Version),
+            Some(("FakeVersion", version)) => parse_version(version).map(Target::FakeVersion),
+            None if value.trim() == "Latest" => Ok(Target::Latest),
+            None if value.trim() == "Fake" => Ok(Target::Fake),
+            _ => Err(invalid_target()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Target {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "Target")]
+        enum TaggedTarget {
+            Latest,
+            Version(u32),
+            Fake,
+            FakeVersion(u32),
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum TargetValue {
+            Text(String),
+            Tagged(TaggedTarget),
+        }
+
+        match TargetValue::deserialize(deserializer)? {
+            TargetValue::Text(value) => value.parse().map_err(D::Error::custom),
+            TargetValue::Tagged(TaggedTarget::Latest) => Ok(Target::Latest),
+            TargetValue::Tagged(TaggedTarget::Version(version)) => Ok(Target::Version(version)),
+            TargetValue::Tagged(TaggedTarget::Fake) => Ok(Target::Fake),
+            TargetValue::Tagged(TaggedTarget::FakeVersion(version)) => {
+                Ok(Target::FakeVersion(version))
+            }
+        }
+    }
+}
+
 impl From<Target> for refinery_core::Target {
     fn from(value: Target) -> Self {
         match value {
@@ -37,6 +118,29 @@ impl From<Target> for refinery_core::Target {
     }
 }
 
+/// Migration excluded from running, identified by version or name, e.g. `5` or `"add_users"`.
+/// Names can also include the prefix and version, e.g. `"V5__add_users"`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum SkippedMigration {
+    /// Migration version.
+    Version(u32),
+    /// Migration name.
+    Name(String),
+}
+
+impl SkippedMigration {
+    /// Checks if given migration should be skipped.
+    pub fn matches(&self, migration: &Migration) -> bool {
+        match self {
+            SkippedMigration::Version(version) => migration.version() == *version,
+            SkippedMigration::Name(name) => {
+                migration.name() == name || migration.to_string() == *name
+            }
+        }
+    }
+}
+
 /// Migration configuration.
 #[non_exhaustive]
 #[derive(Clone, Debug, Deserialize)]
@@ -44,6 +148,9 @@ impl From<Target> for refinery_core::Target {
 pub struct MigrationConfig {
     /// Should migrations run on application start.
     pub run_migrations_on_start: bool,
+    /// Should [seeds](crate::seed::Seed) be applied on application start, after successfully
+    /// running migrations. Seeds are not applied if migrations don't run on start.
+    pub run_seeds_on_start: bool,
     /// The target version up to which migrate.
     pub target: Target,
     /// Group migrations in a single transaction.
@@ -57,30 +164,273 @@ pub struct MigrationConfig {
     pub abort_missing: bool,
     /// Table name for migration data.
     pub migration_table_name: String,
+    /// Maximum time a single statement can wait, in seconds. Applied by built-in executors when
+    /// establishing connections, using the closest mechanism provided by the database, e.g. lock
+    /// wait timeouts.
+    pub statement_timeout_secs: Option<u64>,
+    /// Maximum time of running all migrations, in seconds. Requires running in a Tokio runtime
+    /// with timers enabled, otherwise migrations fail with
+    /// [RunTimeoutUnsupported](crate::runner::MigrationError::RunTimeoutUnsupported).
+    pub run_timeout_secs: Option<u64>,
+    /// Should the migration process stop on first executor failure. If not, remaining executors
+    /// still run migrations and all errors are returned together. Can be overridden per database,
+    /// in which case the policy of the failing executor's database applies.
+    pub stop_on_failure: bool,
+    /// Only log pending migrations, without applying them. Requires executors to support
+    /// [applied_migrations](crate::runner::MigrationRunnerExecutor::applied_migrations).
+    pub dry_run: bool,
+    /// Only validate local migrations against applied ones on start, without applying them. Fails
+    /// if divergent or missing migrations are found and corresponding `abort_*` flag is set.
+    /// Requires executors to support
+    /// [applied_migrations](crate::runner::MigrationRunnerExecutor::applied_migrations).
+    pub validate: bool,
+    /// If set, applied migrations with greater versions are reverted on start, instead of running
+    /// migrations. Can also be set from the command line with
+    /// `--springtime.migration.rollback_target=version`.
+    pub rollback_target: Option<u32>,
+    /// If set, a JSON [report](crate::report::MigrationRunReport) is written to given path after
+    /// running migrations or a dry run. Requires executors to support
+    /// [applied_migrations](crate::runner::MigrationRunnerExecutor::applied_migrations).
+    pub report_path: Option<PathBuf>,
+    /// If set, migrations are rendered into [SQL scripts](crate::script::MigrationScript) in given
+    /// directory on start, one per executor, instead of being applied. No database connection is
+    /// made, so all migrations up to the target are rendered, unless `script_from_version` is set.
+    pub script_directory: Option<PathBuf>,
+    /// Only render migrations with greater versions into scripts, e.g. the version currently
+    /// applied to the target database.
+    pub script_from_version: Option<u32>,
+    /// Migrations excluded from running, e.g. to work around a manually fixed database state.
+    /// Skipped migrations are not passed to `refinery` at all, so if they are already applied,
+    /// `abort_missing` needs to be disabled.
+    pub skip_migrations: Vec<SkippedMigration>,
+    /// Validation of local migration names.
+    pub naming: MigrationNamingConfig,
+    /// Connection retries of built-in executors, when the database is not available yet. Not
+    /// applicable to embedded databases, e.g. SQLite.
+    pub connection_retry: ConnectionRetryConfig,
+    /// Database-level locking around running migrations.
+    pub lock: MigrationLockConfig,
+    /// Per-executor settings, keyed by
+    /// [executor names](crate::runner::MigrationRunnerExecutor::name).
+    pub executors: HashMap<String, ExecutorConfig>,
+    /// Per-database overrides, keyed by
+    /// [database names](crate::runner::MigrationRunnerExecutor::database).
+    pub databases: HashMap<String, DatabaseMigrationConfig>,
+}
+
+impl MigrationConfig {
+    /// Returns config for migrations from given
+    /// [namespace](crate::migration::MigrationSource::namespace), which are tracked in a separate
+    /// migration table.
+    pub fn for_namespace(&self, namespace: Option<&str>) -> MigrationConfig {
+        let mut config = self.clone();
+        if let Some(namespace) = namespace {
+            config.migration_table_name = format!("{namespace}_{}", self.migration_table_name);
+        }
+
+        config
+    }
+
+    /// Returns settings for an executor with given name, or defaults if not present.
+    pub fn for_executor(&self, name: Option<&str>) -> ExecutorConfig {
+        name.and_then(|name| self.executors.get(name))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns config with overrides for given database applied, if present.
+    pub fn for_database(&self, database: Option<&str>) -> MigrationConfig {
+        let mut config = self.clone();
+        if let Some(overrides) = database.and_then(|database| self.databases.get(database)) {
+            if let Some(target) = overrides.target {
+                config.target = target;
+            }
+            if let Some(grouped) = overrides.grouped {
+                config.grouped = grouped;
+            }
+            if let Some(abort_divergent) = overrides.abort_divergent {
+                config.abort_divergent = abort_divergent;
+            }
+            if let Some(abort_missing) = overrides.abort_missing {
+                config.abort_missing = abort_missing;
+            }
+            if let Some(migration_table_name) = &overrides.migration_table_name {
+                config.migration_table_name = migration_table_name.clone();
+            }
+            if let Some(statement_timeout_secs) = overrides.statement_timeout_secs {
+                config.statement_timeout_secs = Some(statement_timeout_secs);
+            }
+            if let Some(stop_on_failure) = overrides.stop_on_failure {
+                config.stop_on_failure = stop_on_failure;
+            }
+            if let Some(script_from_version) = overrides.script_from_version {
+                config.script_from_version = Some(script_from_version);
+            }
+            config
+                .skip_migrations
+                .extend(overrides.skip_migrations.iter().cloned());
+        }
+
+        config
+    }
+}
+
+/// Configuration of database-level locks, which prevent multiple application instances from
+/// running migrations concurrently. Requires executors to support
+/// [lock](crate::runner::MigrationRunnerExecutor::lock).
+#[non_exhaustive]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct MigrationLockConfig {
+    /// Should executors acquire a lock before running migrations.
+    pub enabled: bool,
+    /// Name of the lock.
+    pub key: String,
+    /// How long to wait for the lock to be acquired, in seconds.
+    pub timeout_secs: u64,
+}
+
+impl Default for MigrationLockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key: "springtime_migrations".to_string(),
+            timeout_secs: 60,
+        }
+    }
+}
+
+/// Rules for validating local migration names on start. See [crate::naming].
+#[non_exhaustive]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct MigrationNamingConfig {
+    /// Should migration names be validated. Duplicate versions are always reported when enabled.
+    pub enabled: bool,
+    /// Allowed migration prefixes. Default `V` and `U`.
+    pub allowed_prefixes: Vec<String>,
+    /// Regular expression, which migration names (without prefix and version) need to match.
+    pub name_pattern: Option<String>,
+    /// Require versioned migrations to have contiguous versions, without gaps.
+    pub contiguous_versions: bool,
+}
+
+impl Default for MigrationNamingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allowed_prefixes: vec!["V".to_string(), "U".to_string()],
+            name_pattern: None,
+            contiguous_versions: false,
+        }
+    }
+}
+
+/// Connection retry settings for built-in executors.
+#[non_exhaustive]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ConnectionRetryConfig {
+    /// Maximum number of connection attempts. Default 1, i.e. no retries.
+    pub attempts: u32,
+    /// Delay before the first retry, in milliseconds, doubled after every attempt. Default 500.
+    pub initial_backoff_ms: u64,
+    /// Maximum delay between attempts, in milliseconds. Default 10000.
+    pub max_backoff_ms: u64,
+    /// Maximum total time of retrying, in seconds.
+    pub max_wait_secs: Option<u64>,
+}
+
+impl Default for ConnectionRetryConfig {
+    fn default() -> Self {
+        Self {
+            attempts: 1,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 10_000,
+            max_wait_secs: None,
+        }
+    }
+}
+
+/// Settings of a single [MigrationRunnerExecutor](crate::runner::MigrationRunnerExecutor).
+#[non_exhaustive]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ExecutorConfig {
+    /// Should the executor run migrations, e.g. to exclude a database in given environment.
+    pub enabled: bool,
+    /// Overrides the [order](crate::runner::MigrationRunnerExecutor::order) of the executor.
+    pub order: Option<i32>,
+}
+
+impl Default for ExecutorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            order: None,
+        }
+    }
+}
+
+/// Migration configuration overrides for a single database. Values which are not present are taken
+/// from the global [MigrationConfig]. Connection settings for built-in executors are bound to a
+/// database by setting `database_name` in their config sections, e.g.
+/// `migration.mysql.database_name`.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DatabaseMigrationConfig {
+    /// The target version up to which migrate.
+    pub target: Option<Target>,
+    /// Group migrations in a single transaction.
+    pub grouped: Option<bool>,
+    /// Should abort migration process if divergent migrations are found.
+    pub abort_divergent: Option<bool>,
+    /// Should abort if missing migrations are found.
+    pub abort_missing: Option<bool>,
+    /// Table name for migration data.
+    pub migration_table_name: Option<String>,
+    /// Maximum time a single statement can wait, in seconds.
+    pub statement_timeout_secs: Option<u64>,
+    /// Only render migrations with greater versions into scripts.
+    pub script_from_version: Option<u32>,
+    /// Additional migrations excluded from running, besides the global ones.
+    pub skip_migrations: Vec<SkippedMigration>,
+    /// Should the migration process stop when running migrations for this database fails.
+    pub stop_on_failure: Option<bool>,
 }
 
 impl Default for MigrationConfig {
     fn default() -> Self {
         Self {
             run_migrations_on_start: true,
+            run_seeds_on_start: true,
             target: Target::Latest,
             grouped: false,
             abort_divergent: true,
             abort_missing: true,
             migration_table_name: "refinery_schema_history".to_string(),
+            statement_timeout_secs: None,
+            run_timeout_secs: None,
+            stop_on_failure: true,
+            dry_run: false,
+            validate: false,
+            rollback_target: None,
+            report_path: None,
+            script_directory: None,
+            script_from_version: None,
+            skip_migrations: vec![],
+            naming: Default::default(),
+            connection_retry: Default::default(),
+            lock: Default::default(),
+            executors: HashMap::new(),
+            databases: HashMap::new(),
         }
     }
 }
 
-impl MigrationConfig {
-    fn init_from_config() -> Result<Self, ErrorPtr> {
-        Config::builder()
-            .add_source(File::with_name(CONFIG_FILE).required(false))
-            .build()
-            .and_then(|config| config.try_deserialize::<MigrationConfigWrapper>())
-            .map(|config| config.migration)
-            .map_err(|error| Arc::new(error) as ErrorPtr)
-    }
+impl ConfigSection for MigrationConfig {
+    const KEY: &'static str = "migration";
 }
 
 /// Provider for [MigrationConfig]. The primary instance of the provider will be used to retrieve
@@ -92,7 +442,7 @@ pub trait MigrationConfigProvider {
 }
 
 #[derive(Component)]
-#[component(priority = -128, condition = "unregistered_component::<dyn MigrationConfigProvider + Send + Sync>", constructor = "DefaultMigrationConfigProvider::new")]
+#[component(priority = -128, condition = "unregistered_component::<dyn MigrationConfigProvider + Send + Sync>", constructor = "DefaultMigrationConfigProvider::new", constructor_parameters = "MergedConfig")]
 struct DefaultMigrationConfigProvider {
     // cached init result
     #[component(ignore)]
@@ -113,18 +463,180 @@ impl MigrationConfigProvider for DefaultMigrationConfigProvider {
 }
 
 impl DefaultMigrationConfigProvider {
-    fn new() -> BoxFuture<'static, Result<Self, ErrorPtr>> {
-        async {
+    fn new(
+        merged_config: ComponentInstancePtr<MergedConfig>,
+    ) -> BoxFuture<'static, Result<Self, ErrorPtr>> {
+        async move {
             Ok(Self {
-                config: MigrationConfig::init_from_config(),
+                config: merged_config.section(),
             })
         }
         .boxed()
     }
 }
 
-#[derive(Deserialize, Default)]
-#[serde(default)]
-struct MigrationConfigWrapper {
-    migration: MigrationConfig,
+fn migration_env_present(context: &dyn Context, _metadata: ConditionMetadata) -> bool {
+    env_var_set(context, TARGET_ENV) || env_var_set(context, RUN_MIGRATIONS_ON_START_ENV)
+}
+
+// exposes dedicated env variables as config values, so they take part in the standard config chain
+#[derive(Component)]
+#[component(condition = "migration_env_present")]
+struct MigrationEnvConfigSource;
+
+#[component_alias]
+impl ConfigSource for MigrationEnvConfigSource {
+    fn load(&self) -> BoxFuture<'_, Result<ConfigMap, ErrorPtr>> {
+        async { env_values(|name| env::var(name).ok()) }.boxed()
+    }
+
+    // env variables override other sources, but not the ones overriding everything, e.g. in tests
+    fn priority(&self) -> i8 {
+        i8::MAX - 1
+    }
+}
+
+fn env_values(env_var: impl Fn(&str) -> Option<String>) -> Result<ConfigMap, ErrorPtr> {
+    let mut values = ConfigMap::new();
+
+    if let Some(target) = env_var(TARGET_ENV) {
+        target
+            .parse::<Target>()
+            .map_err(|error| Arc::new(error) as ErrorPtr)?;
+        values.insert(TARGET_KEY.to_string(), target.trim().into());
+    }
+
+    if let Some(run_migrations_on_start) = env_var(RUN_MIGRATIONS_ON_START_ENV) {
+        let run_migrations_on_start: bool =
+            run_migrations_on_start.trim().parse().map_err(|_| {
+                Arc::new(MigrationConfigError::InvalidEnvironmentVariable {
+                    name: RUN_MIGRATIONS_ON_START_ENV,
+                    value: run_migrations_on_start.clone(),
+                }) as ErrorPtr
+            })?;
+        values.insert(
+            RUN_MIGRATIONS_ON_START_KEY.to_string(),
+            run_migrations_on_start.into(),
+        );
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{
+        env_values, DatabaseMigrationConfig, MigrationConfig, SkippedMigration, Target,
+        RUN_MIGRATIONS_ON_START_ENV, TARGET_ENV,
+    };
+    use crate::refinery::Migration;
+    use springtime::config::MergedConfig;
+
+    #[test]
+    fn should_apply_database_overrides() {
+        let mut config = MigrationConfig::default();
+        config.databases.insert(
+            "analytics".to_string(),
+            DatabaseMigrationConfig {
+                target: Some(Target::Version(3)),
+                migration_table_name: Some("analytics_history".to_string()),
+                stop_on_failure: Some(false),
+                ..Default::default()
+            },
+        );
+
+        let analytics = config.for_database(Some("analytics"));
+        assert!(matches!(analytics.target, Target::Version(3)));
+        assert_eq!(analytics.migration_table_name, "analytics_history");
+        assert_eq!(analytics.grouped, config.grouped);
+        assert!(!analytics.stop_on_failure);
+
+        let main = config.for_database(Some("main"));
+        assert!(matches!(main.target, Target::Latest));
+        assert_eq!(main.migration_table_name, config.migration_table_name);
+    }
+
+    #[test]
+    fn should_match_skipped_migrations() {
+        let migration = Migration::unapplied("V05__add_users", "test").unwrap();
+
+        assert!(SkippedMigration::Version(5).matches(&migration));
+        assert!(SkippedMigration::Name("add_users".to_string()).matches(&migration));
+        assert!(SkippedMigration::Name("V5__add_users".to_string()).matches(&migration));
+        assert!(!SkippedMigration::Version(4).matches(&migration));
+        assert!(!SkippedMigration::Name("add_roles".to_string()).matches(&migration));
+    }
+
+    #[test]
+    fn should_parse_targets() {
+        assert!(matches!("Latest".parse(), Ok(Target::Latest)));
+        assert!(matches!("Fake".parse(), Ok(Target::Fake)));
+        assert!(matches!("Version:42".parse(), Ok(Target::Version(42))));
+        assert!(matches!(
+            "FakeVersion: 7".parse(),
+            Ok(Target::FakeVersion(7))
+        ));
+        assert!("Version:x".parse::<Target>().is_err());
+        assert!("Other".parse::<Target>().is_err());
+    }
+
+    #[test]
+    fn should_deserialize_targets() {
+        assert!(matches!(
+            serde_json::from_str(r#"{"Version": 3}"#),
+            Ok(Target::Version(3))
+        ));
+        assert!(matches!(
+            serde_json::from_str(r#""Version:3""#),
+            Ok(Target::Version(3))
+        ));
+        assert!(matches!(
+            serde_json::from_str(r#""Fake""#),
+            Ok(Target::Fake)
+        ));
+        assert!(serde_json::from_str::<Target>(r#""Other""#).is_err());
+    }
+
+    #[test]
+    fn should_read_env_values() {
+        let merged_config = MergedConfig::from_source_values(
+            vec![env_values(|name| match name {
+                TARGET_ENV => Some("Version:42".to_string()),
+                RUN_MIGRATIONS_ON_START_ENV => Some("false".to_string()),
+                _ => None,
+            })
+            .unwrap()],
+            vec![],
+        );
+
+        let config = merged_config.section::<MigrationConfig>().unwrap();
+        assert!(matches!(config.target, Target::Version(42)));
+        assert!(!config.run_migrations_on_start);
+
+        assert!(env_values(|name| {
+            (name == RUN_MIGRATIONS_ON_START_ENV).then(|| "maybe".to_string())
+        })
+        .is_err());
+        assert!(env_values(|name| (name == TARGET_ENV).then(|| "Other".to_string())).is_err());
+    }
+
+    #[test]
+    fn should_prefer_cli_arguments_over_env_values() {
+        let merged_config = MergedConfig::from_source_values(
+            vec![env_values(|name| match name {
+                TARGET_ENV => Some("Version:42".to_string()),
+                RUN_MIGRATIONS_ON_START_ENV => Some("false".to_string()),
+                _ => None,
+            })
+            .unwrap()],
+            vec![
+                "--springtime.migration.target=FakeVersion:7".into(),
+                "--springtime.migration.run_migrations_on_start=true".into(),
+            ],
+        );
+
+        let config = merged_config.section::<MigrationConfig>().unwrap();
+        assert!(matches!(config.target, Target::FakeVersion(7)));
+        assert!(config.run_migrations_on_start);
+    }
 }
@@ -0,0 +1,109 @@
+//! Built-in [MigrationRunnerExecutors](crate::runner::MigrationRunnerExecutor) for supported
+//! databases. Each executor is enabled by the corresponding feature and registered only when its
+//! config section exists.
+
+#[cfg(feature = "mysql_async")]
+pub mod mysql;
+
+#[cfg(feature = "rusqlite-bundled")]
+pub mod sqlite;
+
+#[cfg(feature = "tiberius")]
+pub mod mssql;
+
+use crate::config::{ConnectionRetryConfig, MigrationConfigProvider};
+use springtime_di::instance_provider::ErrorPtr;
+use std::time::Duration;
+
+/// Connection settings shared by executors, resolved from the
+/// [MigrationConfig](crate::config::MigrationConfig) for the database they run migrations for.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct ExecutorConnectionSettings {
+    /// Name of the database, which the executor runs migrations for. See
+    /// [MigrationRunnerExecutor::database](crate::runner::MigrationRunnerExecutor::database).
+    pub database_name: Option<String>,
+    /// Maximum time a single statement can wait, in seconds.
+    pub statement_timeout_secs: Option<u64>,
+    /// Connection retries, when the database is not available yet.
+    pub connection_retry: ConnectionRetryConfig,
+}
+
+impl ExecutorConnectionSettings {
+    /// Resolves settings for given database, applying its overrides from the config.
+    pub async fn resolve(
+        database_name: Option<String>,
+        config_provider: &(dyn MigrationConfigProvider + Send + Sync),
+    ) -> Result<Self, ErrorPtr> {
+        let config = config_provider
+            .config()
+            .await?
+            .for_database(database_name.as_deref());
+
+        Ok(Self {
+            database_name,
+            statement_timeout_secs: config.statement_timeout_secs,
+            connection_retry: config.connection_retry,
+        })
+    }
+
+    /// Returns the database name, if present.
+    pub fn database(&self) -> Option<&str> {
+        self.database_name.as_deref()
+    }
+
+    /// Returns the statement timeout, if present.
+    pub fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout_secs.map(Duration::from_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{DatabaseMigrationConfig, MigrationConfig, MigrationConfigProvider};
+    use crate::executor::ExecutorConnectionSettings;
+    use springtime::future::{BoxFuture, FutureExt};
+    use springtime_di::instance_provider::ErrorPtr;
+    use std::time::Duration;
+
+    struct TestMigrationConfigProvider {
+        config: MigrationConfig,
+    }
+
+    impl MigrationConfigProvider for TestMigrationConfigProvider {
+        fn config(&self) -> BoxFuture<'_, Result<&MigrationConfig, ErrorPtr>> {
+            async { Ok(&self.config) }.boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_resolve_settings_for_database() {
+        let mut config = MigrationConfig {
+            statement_timeout_secs: Some(10),
+            ..Default::default()
+        };
+        config.connection_retry.attempts = 3;
+        config.databases.insert(
+            "analytics".to_string(),
+            DatabaseMigrationConfig {
+                statement_timeout_secs: Some(60),
+                ..Default::default()
+            },
+        );
+        let config_provider = TestMigrationConfigProvider { config };
+
+        let settings =
+            ExecutorConnectionSettings::resolve(Some("analytics".to_string()), &config_provider)
+                .await
+                .unwrap();
+        assert_eq!(settings.database(), Some("analytics"));
+        assert_eq!(settings.statement_timeout(), Some(Duration::from_secs(60)));
+        assert_eq!(settings.connection_retry.attempts, 3);
+
+        let settings = ExecutorConnectionSettings::resolve(None, &config_provider)
+            .await
+            .unwrap();
+        assert_eq!(settings.database(), None);
+        assert_eq!(settings.statement_timeout_secs, Some(10));
+    }
+}
@@ -0,0 +1,334 @@
+//! [MigrationRunnerExecutor] for SQL Server, based on `tiberius`. The executor is registered when
+//! the `migration.mssql` config section exists, e.g.:
+//!
+//! ```json
+//! {
+//!   "migration": {
+//!     "mssql": {
+//!       "host": "localhost",
+//!       "database": "db",
+//!       "authentication": {
+//!         "SqlServer": {
+//!           "user": "sa",
+//!           "password": "password"
+//!         }
+//!       },
+//!       "encryption": "Required"
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Note: encrypted connections require enabling one of the TLS features of `tiberius`. Migration
+//! locks are implemented with `sp_getapplock`. Statement timeouts are applied as `LOCK_TIMEOUT`.
+
+use crate::config::{MigrationConfigProvider, MigrationLockConfig};
+use crate::executor::ExecutorConnectionSettings;
+use crate::lock::MigrationLockGuard;
+use crate::refinery::tiberius::{Client, Config};
+use crate::refinery::{Migration, Runner};
+use crate::retry::retry_connection;
+use crate::rollback::Rollback;
+use crate::runner::{MigrationError, MigrationRunnerExecutor};
+use crate::seed::{apply_seeds_async, Seed};
+use serde::Deserialize;
+use springtime::config::{config_section_present, ConfigSection, MergedConfig};
+use springtime::future::{BoxFuture, FutureExt};
+use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
+use springtime_di::{component_alias, Component};
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
+
+/// SQL Server authentication method.
+#[derive(Clone, Default, Deserialize)]
+pub enum MssqlAuthentication {
+    /// No authentication.
+    #[default]
+    None,
+    /// SQL Server user and password.
+    SqlServer { user: String, password: String },
+}
+
+// the config can end up in logs, so the password is never printed
+impl Debug for MssqlAuthentication {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("None"),
+            Self::SqlServer { user, .. } => f
+                .debug_struct("SqlServer")
+                .field("user", user)
+                .field("password", &"<redacted>")
+                .finish(),
+        }
+    }
+}
+
+/// SQL Server connection encryption level.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub enum MssqlEncryption {
+    /// Encrypt only the login procedure.
+    Off,
+    /// Encrypt everything.
+    Required,
+    /// Don't encrypt anything. Dangerous - credentials are sent in plain text.
+    NotSupported,
+}
+
+/// SQL Server connection configuration.
+#[non_exhaustive]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct MssqlConfig {
+    /// Name of the database, which the executor runs migrations for. See
+    /// [MigrationRunnerExecutor::database].
+    pub database_name: Option<String>,
+    /// Server host. Default `localhost`.
+    pub host: String,
+    /// Server port. Default 1433.
+    pub port: u16,
+    /// Database to connect to. Uses server default, if not present.
+    pub database: Option<String>,
+    /// Authentication method.
+    pub authentication: MssqlAuthentication,
+    /// Encryption level. Uses `tiberius` default, if not present.
+    pub encryption: Option<MssqlEncryption>,
+    /// Trust server certificate without validation. Dangerous - use only for testing.
+    pub trust_certificate: bool,
+}
+
+impl Default for MssqlConfig {
+    fn default() -> Self {
+        Self {
+            database_name: None,
+            host: "localhost".to_string(),
+            port: 1433,
+            database: None,
+            authentication: Default::default(),
+            encryption: None,
+            trust_certificate: false,
+        }
+    }
+}
+
+impl ConfigSection for MssqlConfig {
+    const KEY: &'static str = "migration.mssql";
+}
+
+impl MssqlConfig {
+    // encryption levels available in tiberius depend on enabled TLS features, so the config is
+    // passed as a connection string, which tiberius parses accordingly
+    fn to_ado_string(&self) -> String {
+        let mut parameters = vec![("server", format!("tcp:{},{}", self.host, self.port))];
+
+        if let Some(database) = &self.database {
+            parameters.push(("database", database.clone()));
+        }
+
+        if let MssqlAuthentication::SqlServer { user, password } = &self.authentication {
+            parameters.push(("user", user.clone()));
+            parameters.push(("password", password.clone()));
+        }
+
+        if let Some(encryption) = self.encryption {
+            let encrypt = match encryption {
+                MssqlEncryption::Off => "false",
+                MssqlEncryption::Required => "true",
+                MssqlEncryption::NotSupported => "DANGER_PLAINTEXT",
+            };
+            parameters.push(("encrypt", encrypt.to_string()));
+        }
+
+        if self.trust_certificate {
+            parameters.push(("TrustServerCertificate", "true".to_string()));
+        }
+
+        parameters
+            .into_iter()
+            .map(|(key, value)| format!("{key}='{}'", value.replace('\'', "''")))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+/// [MigrationRunnerExecutor] using a `tiberius` client created from [MssqlConfig]. A new connection
+/// is established for each operation.
+#[derive(Component)]
+#[component(
+    condition = "config_section_present::<MssqlConfig>",
+    constructor = "MssqlMigrationRunnerExecutor::new",
+    constructor_parameters = "MergedConfig,dyn MigrationConfigProvider + Send + Sync"
+)]
+pub struct MssqlMigrationRunnerExecutor {
+    #[component(ignore)]
+    settings: ExecutorConnectionSettings,
+    #[component(ignore)]
+    config: Config,
+}
+
+impl MssqlMigrationRunnerExecutor {
+    fn new(
+        merged_config: ComponentInstancePtr<MergedConfig>,
+        config_provider: ComponentInstancePtr<dyn MigrationConfigProvider + Send + Sync>,
+    ) -> BoxFuture<'static, Result<Self, ErrorPtr>> {
+        async move {
+            let config: MssqlConfig = merged_config.section()?;
+            Ok(Self {
+                config: Config::from_ado_string(&config.to_ado_string())
+                    .map_err(|error| Arc::new(error) as ErrorPtr)?,
+                settings: ExecutorConnectionSettings::resolve(
+                    config.database_name,
+                    &*config_provider,
+                )
+                .await?,
+            })
+        }
+        .boxed()
+    }
+
+    async fn connect(&self) -> Result<Client<Compat<TcpStream>>, ErrorPtr> {
+        retry_connection(&self.settings.connection_retry, || self.try_connect()).await
+    }
+
+    async fn try_connect(&self) -> Result<Client<Compat<TcpStream>>, ErrorPtr> {
+        let tcp = TcpStream::connect(self.config.get_addr())
+            .await
+            .map_err(|error| Arc::new(error) as ErrorPtr)?;
+        tcp.set_nodelay(true)
+            .map_err(|error| Arc::new(error) as ErrorPtr)?;
+
+        let mut client = Client::connect(self.config.clone(), tcp.compat_write())
+            .await
+            .map_err(|error| Arc::new(error) as ErrorPtr)?;
+
+        if let Some(timeout) = self.settings.statement_timeout() {
+            // LOCK_TIMEOUT is an int number of milliseconds
+            let timeout_ms = timeout.as_millis().min(i32::MAX as u128);
+            client
+                .execute(format!("SET LOCK_TIMEOUT {timeout_ms}"), &[])
+                .await
+                .map_err(|error| Arc::new(error) as ErrorPtr)?;
+        }
+
+        Ok(client)
+    }
+}
+
+// application lock held by a dedicated session
+struct MssqlMigrationLockGuard {
+    client: Client<Compat<TcpStream>>,
+    key: String,
+}
+
+impl MigrationLockGuard for MssqlMigrationLockGuard {
+    fn release(self: Box<Self>) -> BoxFuture<'static, Result<(), ErrorPtr>> {
+        async move {
+            let mut client = self.client;
+            client
+                .execute(
+                    "EXEC sp_releaseapplock @Resource = @P1, @LockOwner = 'Session'",
+                    &[&self.key.as_str()],
+                )
+                .await
+                .map(|_| ())
+                .map_err(|error| Arc::new(error) as ErrorPtr)
+        }
+        .boxed()
+    }
+}
+
+#[component_alias]
+impl MigrationRunnerExecutor for MssqlMigrationRunnerExecutor {
+    fn database(&self) -> Option<&str> {
+        self.settings.database()
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.settings.database().or(Some("mssql"))
+    }
+
+    fn run_migrations<'a>(&'a self, runner: &'a Runner) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+        async move {
+            let mut client = self.connect().await?;
+            runner
+                .run_async(&mut client)
+                .await
+                .map(|_| ())
+                .map_err(|error| Arc::new(error) as ErrorPtr)
+        }
+        .boxed()
+    }
+
+    fn applied_migrations<'a>(
+        &'a self,
+        runner: &'a Runner,
+    ) -> BoxFuture<'a, Result<Vec<Migration>, ErrorPtr>> {
+        async move {
+            let mut client = self.connect().await?;
+            runner
+                .get_applied_migrations_async(&mut client)
+                .await
+                .map_err(|error| Arc::new(error) as ErrorPtr)
+        }
+        .boxed()
+    }
+
+    fn rollback_migrations<'a>(
+        &'a self,
+        rollback: &'a Rollback,
+    ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+        async move {
+            let mut client = self.connect().await?;
+            rollback.run_async(&mut client).await
+        }
+        .boxed()
+    }
+
+    fn apply_seeds<'a>(&'a self, seeds: &'a [Seed]) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+        async move {
+            let mut client = self.connect().await?;
+            apply_seeds_async(seeds, &mut client).await
+        }
+        .boxed()
+    }
+
+    fn lock<'a>(
+        &'a self,
+        config: &'a MigrationLockConfig,
+    ) -> BoxFuture<'a, Result<Box<dyn MigrationLockGuard>, ErrorPtr>> {
+        async move {
+            let mut client = self.connect().await?;
+            let timeout_ms =
+                i32::try_from(config.timeout_secs.saturating_mul(1000)).unwrap_or(i32::MAX);
+
+            // non-negative result means the lock has been acquired
+            let result = client
+                .query(
+                    "DECLARE @result INT; \
+                    EXEC @result = sp_getapplock @Resource = @P1, @LockMode = 'Exclusive', \
+                    @LockOwner = 'Session', @LockTimeout = @P2; \
+                    SELECT @result",
+                    &[&config.key.as_str(), &timeout_ms],
+                )
+                .await
+                .map_err(|error| Arc::new(error) as ErrorPtr)?
+                .into_row()
+                .await
+                .map_err(|error| Arc::new(error) as ErrorPtr)?
+                .and_then(|row| row.get::<i32, _>(0));
+
+            if !result.map(|result| result >= 0).unwrap_or(false) {
+                return Err(
+                    Arc::new(MigrationError::LockNotAcquired(config.key.clone())) as ErrorPtr,
+                );
+            }
+
+            Ok(Box::new(MssqlMigrationLockGuard {
+                client,
+                key: config.key.clone(),
+            }) as Box<dyn MigrationLockGuard>)
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,174 @@
+//! [MigrationRunnerExecutor] for SQLite, based on `rusqlite`. The executor is registered when the
+//! `migration.sqlite` config section exists, e.g.:
+//!
+//! ```json
+//! {
+//!   "migration": {
+//!     "sqlite": {
+//!       "path": "app.db"
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Statement timeouts are applied as the busy timeout, i.e. time spent waiting for database locks.
+//!
+//! Note: [locking](MigrationRunnerExecutor::lock) is a no-op for SQLite. An exclusive transaction
+//! would also block the connection running the migrations, so multiple processes sharing the same
+//! database file should not run migrations concurrently.
+
+use crate::config::{MigrationConfigProvider, MigrationLockConfig};
+use crate::executor::ExecutorConnectionSettings;
+use crate::lock::{MigrationLockGuard, NoopMigrationLockGuard};
+use crate::refinery::rusqlite::Connection;
+use crate::refinery::{Migration, Runner};
+use crate::rollback::Rollback;
+use crate::runner::MigrationRunnerExecutor;
+use crate::seed::{apply_seeds, Seed};
+use serde::Deserialize;
+use springtime::config::{config_section_present, ConfigSection, MergedConfig};
+use springtime::future::{BoxFuture, FutureExt};
+use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
+use springtime_di::{component_alias, Component};
+use std::sync::{Arc, Mutex, MutexGuard};
+use thiserror::Error;
+
+/// Path of an in-memory database.
+pub const IN_MEMORY_PATH: &str = ":memory:";
+
+/// SQLite connection configuration.
+#[non_exhaustive]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SqliteConfig {
+    /// Name of the database, which the executor runs migrations for. See
+    /// [MigrationRunnerExecutor::database].
+    pub database_name: Option<String>,
+    /// Path to the database file or [IN_MEMORY_PATH] for an in-memory database, which is useful for
+    /// tests. Default [IN_MEMORY_PATH].
+    pub path: String,
+}
+
+impl Default for SqliteConfig {
+    fn default() -> Self {
+        Self {
+            database_name: None,
+            path: IN_MEMORY_PATH.to_string(),
+        }
+    }
+}
+
+impl ConfigSection for SqliteConfig {
+    const KEY: &'static str = "migration.sqlite";
+}
+
+#[derive(Error, Debug)]
+#[error("SQLite connection lock poisoned")]
+struct PoisonedConnectionError;
+
+/// [MigrationRunnerExecutor] using a `rusqlite` connection created from [SqliteConfig]. The
+/// connection is kept open for the lifetime of the executor, so in-memory databases retain their
+/// state.
+#[derive(Component)]
+#[component(
+    condition = "config_section_present::<SqliteConfig>",
+    constructor = "SqliteMigrationRunnerExecutor::new",
+    constructor_parameters = "MergedConfig,dyn MigrationConfigProvider + Send + Sync"
+)]
+pub struct SqliteMigrationRunnerExecutor {
+    #[component(ignore)]
+    settings: ExecutorConnectionSettings,
+    #[component(ignore)]
+    connection: Mutex<Connection>,
+}
+
+impl SqliteMigrationRunnerExecutor {
+    fn new(
+        merged_config: ComponentInstancePtr<MergedConfig>,
+        config_provider: ComponentInstancePtr<dyn MigrationConfigProvider + Send + Sync>,
+    ) -> BoxFuture<'static, Result<Self, ErrorPtr>> {
+        async move {
+            let config: SqliteConfig = merged_config.section()?;
+            let connection = if config.path == IN_MEMORY_PATH {
+                Connection::open_in_memory()
+            } else {
+                Connection::open(&config.path)
+            }
+            .map_err(|error| Arc::new(error) as ErrorPtr)?;
+
+            let settings =
+                ExecutorConnectionSettings::resolve(config.database_name, &*config_provider)
+                    .await?;
+            if let Some(timeout) = settings.statement_timeout() {
+                connection
+                    .busy_timeout(timeout)
+                    .map_err(|error| Arc::new(error) as ErrorPtr)?;
+            }
+
+            Ok(Self {
+                settings,
+                connection: Mutex::new(connection),
+            })
+        }
+        .boxed()
+    }
+
+    fn lock_connection(&self) -> Result<MutexGuard<'_, Connection>, ErrorPtr> {
+        self.connection
+            .lock()
+            .map_err(|_| Arc::new(PoisonedConnectionError) as ErrorPtr)
+    }
+}
+
+#[component_alias]
+impl MigrationRunnerExecutor for SqliteMigrationRunnerExecutor {
+    fn database(&self) -> Option<&str> {
+        self.settings.database()
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.settings.database().or(Some("sqlite"))
+    }
+
+    fn run_migrations<'a>(&'a self, runner: &'a Runner) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+        async move {
+            runner
+                .run(&mut *self.lock_connection()?)
+                .map(|_| ())
+                .map_err(|error| Arc::new(error) as ErrorPtr)
+        }
+        .boxed()
+    }
+
+    fn applied_migrations<'a>(
+        &'a self,
+        runner: &'a Runner,
+    ) -> BoxFuture<'a, Result<Vec<Migration>, ErrorPtr>> {
+        async move {
+            runner
+                .get_applied_migrations(&mut *self.lock_connection()?)
+                .map_err(|error| Arc::new(error) as ErrorPtr)
+        }
+        .boxed()
+    }
+
+    fn rollback_migrations<'a>(
+        &'a self,
+        rollback: &'a Rollback,
+    ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+        async move { rollback.run(&mut *self.lock_connection()?) }.boxed()
+    }
+
+    fn apply_seeds<'a>(&'a self, seeds: &'a [Seed]) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+        async move { apply_seeds(seeds, &mut *self.lock_connection()?) }.boxed()
+    }
+
+    fn lock<'a>(
+        &'a self,
+        _config: &'a MigrationLockConfig,
+    ) -> BoxFuture<'a, Result<Box<dyn MigrationLockGuard>, ErrorPtr>> {
+        // an exclusive lock would block our own connection, so concurrent runs from other processes
+        // using the same file are not prevented
+        async { Ok(Box::new(NoopMigrationLockGuard) as Box<dyn MigrationLockGuard>) }.boxed()
+    }
+}
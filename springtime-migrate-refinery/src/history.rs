@@ -0,0 +1,104 @@
+//! Read access to the migration history table, independent of running migrations.
+
+use crate::refinery::Migration;
+use crate::runner::MigrationRunner;
+use springtime::future::{BoxFuture, FutureExt};
+use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
+use springtime_di::Component;
+use time::OffsetDateTime;
+
+/// Migration history of a single [MigrationRunnerExecutor](crate::runner::MigrationRunnerExecutor).
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct MigrationHistory {
+    /// Index of the executor in the order of execution.
+    pub executor_index: usize,
+    /// Name of the database, if the executor specifies one.
+    pub database: Option<String>,
+    /// [Namespace](crate::migration::MigrationSource::namespace) of the migrations.
+    pub namespace: Option<String>,
+    /// Applied migrations, sorted by version.
+    pub applied_migrations: Vec<Migration>,
+}
+
+impl MigrationHistory {
+    /// Versions of applied migrations, in ascending order.
+    pub fn applied_versions(&self) -> Vec<u32> {
+        self.applied_migrations
+            .iter()
+            .map(|migration| migration.version())
+            .collect()
+    }
+
+    /// Version of the last applied migration.
+    pub fn current_version(&self) -> Option<u32> {
+        self.applied_migrations
+            .iter()
+            .map(|migration| migration.version())
+            .max()
+    }
+
+    /// Time of applying the most recent migration.
+    pub fn last_applied_on(&self) -> Option<OffsetDateTime> {
+        self.applied_migrations
+            .iter()
+            .filter_map(|migration| migration.applied_on())
+            .max()
+            .copied()
+    }
+}
+
+/// Reads the `refinery` migration history table through registered
+/// [MigrationRunnerExecutors](crate::runner::MigrationRunnerExecutor), e.g. for health checks or
+/// admin endpoints. Requires executors to support
+/// [applied_migrations](crate::runner::MigrationRunnerExecutor::applied_migrations).
+#[derive(Component)]
+pub struct MigrationHistoryRepository {
+    runner: ComponentInstancePtr<MigrationRunner>,
+}
+
+impl MigrationHistoryRepository {
+    /// Returns migration history for each executor.
+    pub fn histories(&self) -> BoxFuture<'_, Result<Vec<MigrationHistory>, ErrorPtr>> {
+        self.runner.executor_histories().boxed()
+    }
+
+    /// Returns migration history for given database and
+    /// [namespace](crate::migration::MigrationSource::namespace), if any executor runs migrations
+    /// for them.
+    pub fn history<'a>(
+        &'a self,
+        database: Option<&'a str>,
+        namespace: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Option<MigrationHistory>, ErrorPtr>> {
+        async move {
+            Ok(self.histories().await?.into_iter().find(|history| {
+                history.database.as_deref() == database && history.namespace.as_deref() == namespace
+            }))
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::history::MigrationHistory;
+    use crate::refinery::Migration;
+
+    #[test]
+    fn should_summarize_history() {
+        let history = MigrationHistory {
+            executor_index: 0,
+            database: None,
+            namespace: None,
+            applied_migrations: vec![
+                Migration::unapplied("V01__first", "test").unwrap(),
+                Migration::unapplied("V03__second", "test").unwrap(),
+            ],
+        };
+
+        assert_eq!(history.applied_versions(), vec![1, 3]);
+        assert_eq!(history.current_version(), Some(3));
+        assert_eq!(history.last_applied_on(), None);
+    }
+}
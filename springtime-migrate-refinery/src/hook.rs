@@ -0,0 +1,70 @@
+//! Hooks invoked around running migrations.
+
+use crate::refinery::Migration;
+use springtime::future::{BoxFuture, FutureExt};
+use springtime_di::injectable;
+use springtime_di::instance_provider::ErrorPtr;
+
+/// Migrations applied by a [MigrationRunnerExecutor](crate::runner::MigrationRunnerExecutor).
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct ExecutorMigrationReport {
+    /// Index of the executor in the order of execution.
+    pub executor_index: usize,
+    /// [Namespace](crate::migration::MigrationSource::namespace) of the migrations.
+    pub namespace: Option<String>,
+    /// Applied migrations, in order of application.
+    pub applied_migrations: Vec<Migration>,
+}
+
+/// Summary of a migration run.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct MigrationReport {
+    /// Reports for all executors, in order of execution.
+    pub executor_reports: Vec<ExecutorMigrationReport>,
+}
+
+/// Hook invoked by the [MigrationRunner](crate::runner::MigrationRunner) when running migrations,
+/// useful e.g. for cache invalidation, notifications or auditing. An error returned from any
+/// callback aborts the migration process.
+///
+/// Note: in order to report individual migrations, the runner needs to know which migrations are
+/// pending, which requires executors to support
+/// [applied_migrations](crate::runner::MigrationRunnerExecutor::applied_migrations). Pending
+/// migrations are then applied one by one, unless
+/// [grouped](crate::config::MigrationConfig::grouped) is set, in which case they are applied
+/// together and `before_each`/`after_each` are called for all of them around the whole group.
+/// Executors not supporting it run migrations as usual, but `before_each`/`after_each` are not
+/// called for them and their reports contain no applied migrations.
+#[injectable]
+pub trait MigrationHook {
+    /// Called before running any migrations.
+    fn before_all(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+        async { Ok(()) }.boxed()
+    }
+
+    /// Called before applying given migration.
+    fn before_each<'a>(&'a self, _migration: &'a Migration) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+        async { Ok(()) }.boxed()
+    }
+
+    /// Called after applying given migration, with the result of applying it. If applying failed,
+    /// an error returned from the hook is attached to the migration failure as
+    /// [AfterEachHookFailed](crate::runner::MigrationError::AfterEachHookFailed).
+    fn after_each<'a>(
+        &'a self,
+        _migration: &'a Migration,
+        _result: &'a Result<(), ErrorPtr>,
+    ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+        async { Ok(()) }.boxed()
+    }
+
+    /// Called after successfully running all migrations.
+    fn after_all<'a>(
+        &'a self,
+        _report: &'a MigrationReport,
+    ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+        async { Ok(()) }.boxed()
+    }
+}
@@ -7,15 +7,37 @@
 //! applying migrations, either from files or Rust code.
 //!
 //! The crate defines an [application runner](springtime::runner::ApplicationRunner) with a priority
-//! of 100, which runs migrations on application start, by default.
+//! of 100, which runs migrations on application start, by default. Afterwards, another runner with
+//! a priority of 90 applies [seeds](seed::Seed) from registered [SeedSources](seed::SeedSource).
 //!
 //! ### Features
 //!
 //! * `refinery` async db features: `mysql_async`, `rusqlite-bundled`, `tiberius`,
 //! `tiberius-config`, `tokio-postgres`
+//! * `mysql_async` - additionally provides a config-driven
+//! [MysqlMigrationRunnerExecutor](executor::mysql::MysqlMigrationRunnerExecutor)
+//! * `rusqlite-bundled` - additionally provides a config-driven
+//! [SqliteMigrationRunnerExecutor](executor::sqlite::SqliteMigrationRunnerExecutor)
+//! * `tiberius` - additionally provides a config-driven
+//! [MssqlMigrationRunnerExecutor](executor::mssql::MssqlMigrationRunnerExecutor)
+//! * `nightly` - make [embed_migrations](migration::embed_migrations) and
+//! [export_migrations](migration::export_migrations) track migration directories, so adding a
+//! migration triggers recompilation (requires a nightly compiler)
 
 pub mod config;
+pub mod executor;
+pub mod history;
+pub mod hook;
+pub mod lock;
 pub mod migration;
+pub mod naming;
+pub mod report;
+pub mod retry;
+pub mod rollback;
 pub mod runner;
+pub mod script;
+pub mod seed;
+pub mod status;
+pub mod validation;
 
 pub use refinery_core as refinery;
@@ -0,0 +1,23 @@
+//! Database-level locking, which prevents multiple application instances from running migrations
+//! concurrently.
+
+use springtime::future::{BoxFuture, FutureExt};
+use springtime_di::instance_provider::ErrorPtr;
+
+/// Lock acquired by a [MigrationRunnerExecutor](crate::runner::MigrationRunnerExecutor). Since
+/// releasing a lock usually requires communicating with the database, it needs to be released
+/// explicitly.
+pub trait MigrationLockGuard: Send {
+    /// Releases the lock.
+    fn release(self: Box<Self>) -> BoxFuture<'static, Result<(), ErrorPtr>>;
+}
+
+/// Guard for databases which don't need or don't support locking, e.g. SQLite.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopMigrationLockGuard;
+
+impl MigrationLockGuard for NoopMigrationLockGuard {
+    fn release(self: Box<Self>) -> BoxFuture<'static, Result<(), ErrorPtr>> {
+        async { Ok(()) }.boxed()
+    }
+}
@@ -1,24 +1,114 @@
 //! Bridge between *Springtime* and `refinery` migrations.
 
-#[cfg(test)]
-use mockall::automock;
-use refinery_core::Migration;
+pub mod code;
+
+use refinery_core::{parse_migration_name, Migration};
 use springtime::runner::ErrorPtr;
 use springtime_di::injectable;
 
 /// Embed migrations from a given path (`migrations` by default). Path is inspected for `*.sql`
-/// files, which are converted into [MigrationSources](MigrationSource).
+/// files, which are converted into [MigrationSources](MigrationSource). Files named
+/// `V{version}__{name}.down.sql` are treated as [DownMigrations](DownMigration) for the
+/// corresponding versioned migrations.
 ///
 /// ```no_run
 /// use springtime_migrate_refinery::migration::embed_migrations;
 /// embed_migrations!("examples/migrations");
 /// ```
+///
+/// Editing an embedded migration triggers recompilation, but adding a new one doesn't, unless the
+/// `nightly` feature is enabled. On stable compilers, a build script can watch the directory
+/// instead:
+///
+/// ```no_run
+/// println!("cargo:rerun-if-changed=migrations");
+/// ```
 pub use springtime_migrate_refinery_macros::embed_migrations;
 
+/// Export migrations from a given path (`migrations` by default) as a public [MigrationSource]
+/// component with given [namespace](MigrationSource::namespace), so that library crates can ship
+/// their own migrations. Namespaced migrations are tracked in a separate migration table, so their
+/// versions don't conflict with application migrations.
+///
+/// ```no_run
+/// use springtime_migrate_refinery::migration::export_migrations;
+/// export_migrations!(pub LibraryMigrations, namespace = "library", path = "examples/migrations");
+/// ```
+pub use springtime_migrate_refinery_macros::export_migrations;
+
+/// Reverting counterpart of a versioned [Migration], used when rolling back.
+#[derive(Clone, Debug)]
+pub struct DownMigration {
+    version: u32,
+    name: String,
+    sql: String,
+}
+
+impl DownMigration {
+    /// Creates a down migration for a migration with given name, which must be in the same format
+    /// as for [Migration], e.g. `V01__init`.
+    pub fn new(input_name: &str, sql: &str) -> Result<Self, refinery_core::Error> {
+        let (_, version, name) = parse_migration_name(input_name)?;
+        Ok(Self {
+            version: version as u32,
+            name,
+            sql: sql.to_string(),
+        })
+    }
+
+    /// Version of the reverted migration.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Name of the reverted migration.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// SQL reverting the migration.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+}
+
 /// A source for [Migrations](Migration).
 #[injectable]
-#[cfg_attr(test, automock)]
 pub trait MigrationSource {
     /// Provides a migration from this source.
     fn migrations(&self) -> Result<Vec<Migration>, ErrorPtr>;
+
+    /// Provides down migrations from this source, used for rollbacks. Default is none.
+    fn down_migrations(&self) -> Result<Vec<DownMigration>, ErrorPtr> {
+        Ok(vec![])
+    }
+
+    /// Name of the database targeted by migrations from this source. Targeted migrations are only
+    /// run by [MigrationRunnerExecutors](crate::runner::MigrationRunnerExecutor) for the same
+    /// database, while untargeted ones are run by all executors. Default is none.
+    fn database(&self) -> Option<&str> {
+        None
+    }
+
+    /// Namespace of migrations from this source, e.g. the name of a library providing them.
+    /// Namespaced migrations are versioned independently and tracked in a separate migration table,
+    /// named `{namespace}_{migration_table_name}`. Default is none.
+    fn namespace(&self) -> Option<&str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::migration::DownMigration;
+
+    #[test]
+    fn should_parse_down_migration_name() {
+        let migration = DownMigration::new("V02__add_users", "DROP TABLE users").unwrap();
+        assert_eq!(migration.version(), 2);
+        assert_eq!(migration.name(), "add_users");
+        assert_eq!(migration.sql(), "DROP TABLE users");
+
+        assert!(DownMigration::new("invalid", "").is_err());
+    }
 }
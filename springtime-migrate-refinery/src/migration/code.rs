@@ -0,0 +1,183 @@
+//! Helpers for [MigrationSources](super::MigrationSource) generating migrations from code, rather
+//! than embedding static SQL.
+//!
+//! ```
+//! use springtime_migrate_refinery::migration::code::{build_down_migrations, build_migrations, CodeMigration};
+//!
+//! let migrations = [
+//!     CodeMigration::versioned(1, "create_users")
+//!         .create_table("users", &["id INTEGER PRIMARY KEY", "name TEXT NOT NULL"]),
+//!     CodeMigration::versioned(2, "index_users")
+//!         .create_index("users_name", "users", &["name"]),
+//! ];
+//!
+//! assert_eq!(build_migrations(&migrations).unwrap().len(), 2);
+//! assert_eq!(build_down_migrations(&migrations).unwrap().len(), 2);
+//! ```
+
+use crate::migration::DownMigration;
+use refinery_core::Migration;
+use springtime::runner::ErrorPtr;
+use std::sync::Arc;
+
+/// Builder for a single migration generated from code. SQL statements are executed in the order of
+/// adding them, while their reverting counterparts (if any) are executed in reverse order.
+#[derive(Clone, Debug)]
+pub struct CodeMigration {
+    prefix: &'static str,
+    version: u32,
+    name: String,
+    statements: Vec<String>,
+    down_statements: Vec<String>,
+}
+
+impl CodeMigration {
+    /// Creates a versioned migration, which is applied once in order of versions.
+    pub fn versioned(version: u32, name: impl Into<String>) -> Self {
+        Self::new("V", version, name.into())
+    }
+
+    /// Creates an unversioned migration, which can be applied out of order.
+    pub fn unversioned(version: u32, name: impl Into<String>) -> Self {
+        Self::new("U", version, name.into())
+    }
+
+    fn new(prefix: &'static str, version: u32, name: String) -> Self {
+        Self {
+            prefix,
+            version,
+            name,
+            statements: vec![],
+            down_statements: vec![],
+        }
+    }
+
+    /// Version of the migration.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Name of the migration.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Adds an arbitrary SQL statement.
+    pub fn sql(mut self, sql: impl Into<String>) -> Self {
+        self.statements.push(sql.into());
+        self
+    }
+
+    /// Adds an arbitrary SQL statement reverting this migration.
+    pub fn down_sql(mut self, sql: impl Into<String>) -> Self {
+        self.down_statements.push(sql.into());
+        self
+    }
+
+    /// Adds a `CREATE TABLE` statement with given column definitions, reverted by `DROP TABLE`.
+    pub fn create_table(self, table: &str, columns: &[&str]) -> Self {
+        self.sql(format!("CREATE TABLE {table} ({});", columns.join(", ")))
+            .down_sql(format!("DROP TABLE {table};"))
+    }
+
+    /// Adds a `DROP TABLE` statement. Note: dropped tables cannot be automatically restored, so no
+    /// reverting statement is added.
+    pub fn drop_table(self, table: &str) -> Self {
+        self.sql(format!("DROP TABLE {table};"))
+    }
+
+    /// Adds an `ALTER TABLE ... ADD COLUMN` statement with given column definition, reverted by
+    /// `ALTER TABLE ... DROP COLUMN`.
+    pub fn add_column(self, table: &str, column: &str, definition: &str) -> Self {
+        self.sql(format!(
+            "ALTER TABLE {table} ADD COLUMN {column} {definition};"
+        ))
+        .down_sql(format!("ALTER TABLE {table} DROP COLUMN {column};"))
+    }
+
+    /// Adds a `CREATE INDEX` statement for given columns, reverted by `DROP INDEX`.
+    pub fn create_index(self, index: &str, table: &str, columns: &[&str]) -> Self {
+        self.sql(format!(
+            "CREATE INDEX {index} ON {table} ({});",
+            columns.join(", ")
+        ))
+        .down_sql(format!("DROP INDEX {index};"))
+    }
+
+    fn input_name(&self) -> String {
+        format!("{}{}__{}", self.prefix, self.version, self.name)
+    }
+
+    /// Builds the resulting [Migration].
+    pub fn to_migration(&self) -> Result<Migration, ErrorPtr> {
+        Migration::unapplied(&self.input_name(), &self.statements.join("\n"))
+            .map_err(|error| Arc::new(error) as ErrorPtr)
+    }
+
+    /// Builds the resulting [DownMigration], if any reverting statements are present.
+    pub fn to_down_migration(&self) -> Result<Option<DownMigration>, ErrorPtr> {
+        if self.down_statements.is_empty() {
+            return Ok(None);
+        }
+
+        let sql = self
+            .down_statements
+            .iter()
+            .rev()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        DownMigration::new(&self.input_name(), &sql)
+            .map(Some)
+            .map_err(|error| Arc::new(error) as ErrorPtr)
+    }
+}
+
+/// Builds [Migrations](Migration) from given [CodeMigrations](CodeMigration), e.g. when
+/// implementing [MigrationSource::migrations](super::MigrationSource::migrations).
+pub fn build_migrations(migrations: &[CodeMigration]) -> Result<Vec<Migration>, ErrorPtr> {
+    migrations.iter().map(CodeMigration::to_migration).collect()
+}
+
+/// Builds [DownMigrations](DownMigration) from given [CodeMigrations](CodeMigration), e.g. when
+/// implementing [MigrationSource::down_migrations](super::MigrationSource::down_migrations).
+pub fn build_down_migrations(migrations: &[CodeMigration]) -> Result<Vec<DownMigration>, ErrorPtr> {
+    migrations
+        .iter()
+        .filter_map(|migration| migration.to_down_migration().transpose())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::migration::code::CodeMigration;
+
+    #[test]
+    fn should_build_migrations() {
+        let migration = CodeMigration::versioned(3, "users")
+            .create_table("users", &["id INTEGER"])
+            .add_column("users", "name", "TEXT");
+
+        let up = migration.to_migration().unwrap();
+        assert_eq!(up.version(), 3);
+        assert_eq!(up.name(), "users");
+        assert_eq!(
+            up.sql(),
+            Some("CREATE TABLE users (id INTEGER);\nALTER TABLE users ADD COLUMN name TEXT;")
+        );
+
+        let down = migration.to_down_migration().unwrap().unwrap();
+        assert_eq!(down.version(), 3);
+        assert_eq!(
+            down.sql(),
+            "ALTER TABLE users DROP COLUMN name;\nDROP TABLE users;"
+        );
+
+        assert!(CodeMigration::versioned(4, "drop")
+            .drop_table("users")
+            .to_down_migration()
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,152 @@
+//! Validation of local migration names and versions, performed on start before running
+//! migrations, so that mistakes don't surface as confusing failures when applying them.
+
+use crate::config::MigrationNamingConfig;
+use crate::refinery::Migration;
+use itertools::Itertools;
+use regex::Regex;
+use thiserror::Error;
+
+/// Problem with the naming of local migrations.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MigrationNamingProblem {
+    /// Multiple migrations, possibly from different sources, share a version.
+    #[error("duplicate version {version} in {first} and {second}")]
+    DuplicateVersion {
+        version: u32,
+        first: String,
+        second: String,
+    },
+    /// Migration prefix is not allowed by [MigrationNamingConfig::allowed_prefixes].
+    #[error("disallowed prefix in {0}")]
+    DisallowedPrefix(String),
+    /// Migration name doesn't match [MigrationNamingConfig::name_pattern].
+    #[error("name of {0} doesn't match the configured pattern")]
+    NamePatternMismatch(String),
+    /// Versioned migrations are not contiguous, when required by
+    /// [MigrationNamingConfig::contiguous_versions].
+    #[error("missing versions between {previous} and {next}")]
+    VersionGap { previous: u32, next: u32 },
+}
+
+/// Errors related to migration naming validation.
+#[derive(Error, Debug)]
+pub enum MigrationNamingError {
+    /// [MigrationNamingConfig::name_pattern] is not a valid regular expression.
+    #[error("Invalid migration name pattern: {0}")]
+    InvalidPattern(#[from] regex::Error),
+    /// Local migrations have naming problems.
+    #[error("Invalid migration naming for database {}: {}", .database.as_deref().unwrap_or("<default>"), .problems.iter().join("; "))]
+    InvalidNaming {
+        database: Option<String>,
+        problems: Vec<MigrationNamingProblem>,
+    },
+}
+
+/// Finds naming problems in given migrations, which are meant to be applied to a single database.
+pub fn naming_problems(
+    migrations: &[Migration],
+    config: &MigrationNamingConfig,
+) -> Result<Vec<MigrationNamingProblem>, MigrationNamingError> {
+    let name_pattern = config.name_pattern.as_deref().map(Regex::new).transpose()?;
+
+    let mut problems = vec![];
+    for migration in migrations {
+        // note: refinery doesn't export migration type, so its display value is used
+        let prefix = migration.prefix().to_string();
+        if !config.allowed_prefixes.contains(&prefix) {
+            problems.push(MigrationNamingProblem::DisallowedPrefix(
+                migration.to_string(),
+            ));
+        }
+
+        if let Some(name_pattern) = &name_pattern {
+            if !name_pattern.is_match(migration.name()) {
+                problems.push(MigrationNamingProblem::NamePatternMismatch(
+                    migration.to_string(),
+                ));
+            }
+        }
+    }
+
+    let sorted = migrations
+        .iter()
+        .sorted_by_key(|migration| migration.version())
+        .collect_vec();
+
+    for (previous, next) in sorted.iter().tuple_windows() {
+        if previous.version() == next.version() {
+            problems.push(MigrationNamingProblem::DuplicateVersion {
+                version: next.version(),
+                first: previous.to_string(),
+                second: next.to_string(),
+            });
+        }
+    }
+
+    if config.contiguous_versions {
+        let versions = sorted
+            .iter()
+            .filter(|migration| migration.prefix().to_string() == "V")
+            .map(|migration| migration.version())
+            .dedup();
+
+        for (previous, next) in versions.tuple_windows() {
+            if next - previous > 1 {
+                problems.push(MigrationNamingProblem::VersionGap { previous, next });
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::MigrationNamingConfig;
+    use crate::naming::{naming_problems, MigrationNamingProblem};
+    use crate::refinery::Migration;
+
+    #[test]
+    fn should_find_naming_problems() {
+        let migrations = vec![
+            Migration::unapplied("V01__first", "test").unwrap(),
+            Migration::unapplied("V01__duplicate", "test").unwrap(),
+            Migration::unapplied("U04__Invalid", "test").unwrap(),
+        ];
+
+        let config = MigrationNamingConfig {
+            allowed_prefixes: vec!["V".to_string()],
+            name_pattern: Some("^[a-z_]+$".to_string()),
+            contiguous_versions: true,
+            ..Default::default()
+        };
+
+        let problems = naming_problems(&migrations, &config).unwrap();
+        assert_eq!(
+            problems,
+            vec![
+                MigrationNamingProblem::DisallowedPrefix("U4__Invalid".to_string()),
+                MigrationNamingProblem::NamePatternMismatch("U4__Invalid".to_string()),
+                MigrationNamingProblem::DuplicateVersion {
+                    version: 1,
+                    first: "V1__first".to_string(),
+                    second: "V1__duplicate".to_string(),
+                },
+            ]
+        );
+
+        let migrations = vec![
+            Migration::unapplied("V01__first", "test").unwrap(),
+            Migration::unapplied("V03__third", "test").unwrap(),
+        ];
+        let problems = naming_problems(&migrations, &config).unwrap();
+        assert_eq!(
+            problems,
+            vec![MigrationNamingProblem::VersionGap {
+                previous: 1,
+                next: 3
+            }]
+        );
+    }
+}
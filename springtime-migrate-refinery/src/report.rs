@@ -0,0 +1,147 @@
+//! Machine-readable reports of migration runs, which can be archived and asserted on e.g. in CI/CD
+//! pipelines. Reports are written as JSON when
+//! [report_path](crate::config::MigrationConfig::report_path) is set.
+
+use crate::refinery::Migration;
+use serde::Serialize;
+use springtime_di::instance_provider::ErrorPtr;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Migration included in a [DatabaseMigrationReport].
+#[non_exhaustive]
+#[derive(Clone, Debug, Serialize)]
+pub struct ReportedMigration {
+    /// Migration version.
+    pub version: u32,
+    /// Migration name.
+    pub name: String,
+}
+
+impl From<&Migration> for ReportedMigration {
+    fn from(value: &Migration) -> Self {
+        Self {
+            version: value.version(),
+            name: value.name().to_string(),
+        }
+    }
+}
+
+/// Result of running migrations by a single
+/// [MigrationRunnerExecutor](crate::runner::MigrationRunnerExecutor). Migrations and the version
+/// are not reported if the executor cannot retrieve
+/// [applied_migrations](crate::runner::MigrationRunnerExecutor::applied_migrations).
+#[non_exhaustive]
+#[derive(Clone, Debug, Serialize)]
+pub struct DatabaseMigrationReport {
+    /// Index of the executor in the order of execution.
+    pub executor_index: usize,
+    /// Name of the database, if the executor specifies one.
+    pub database: Option<String>,
+    /// [Namespace](crate::migration::MigrationSource::namespace) of the migrations.
+    pub namespace: Option<String>,
+    /// Migrations applied by the run, or which would be applied in case of a dry run.
+    pub applied: Vec<ReportedMigration>,
+    /// Local migrations which are not applied after the run, e.g. due to the target version or an
+    /// error.
+    pub skipped: Vec<ReportedMigration>,
+    /// Time of running migrations, in milliseconds.
+    pub duration_ms: u64,
+    /// Version of the database after the run.
+    pub version: Option<u32>,
+    /// Error message, if running migrations failed.
+    pub error: Option<String>,
+}
+
+impl DatabaseMigrationReport {
+    pub(crate) fn new(
+        executor_index: usize,
+        database: Option<&str>,
+        migrations: &[Migration],
+        pending_migrations: &[Migration],
+        applied_migrations: &[Migration],
+        duration: Duration,
+        error: Option<&ErrorPtr>,
+    ) -> Self {
+        let is_applied = |migration: &Migration| {
+            applied_migrations
+                .iter()
+                .any(|applied| applied.version() == migration.version())
+        };
+
+        Self {
+            executor_index,
+            database: database.map(|database| database.to_string()),
+            namespace: None,
+            applied: pending_migrations
+                .iter()
+                .filter(|migration| is_applied(migration))
+                .map(ReportedMigration::from)
+                .collect(),
+            skipped: migrations
+                .iter()
+                .filter(|migration| !is_applied(migration))
+                .map(ReportedMigration::from)
+                .collect(),
+            duration_ms: duration.as_millis() as u64,
+            version: applied_migrations
+                .iter()
+                .map(|migration| migration.version())
+                .max(),
+            error: error.map(|error| error.to_string()),
+        }
+    }
+}
+
+/// Report of a whole migration run.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct MigrationRunReport {
+    /// Was this a dry run, in which case nothing was actually applied.
+    pub dry_run: bool,
+    /// Reports for all executors, in order of execution.
+    pub databases: Vec<DatabaseMigrationReport>,
+}
+
+pub(crate) fn write_report(path: &Path, report: &MigrationRunReport) -> Result<(), ErrorPtr> {
+    let file = File::create(path).map_err(|error| Arc::new(error) as ErrorPtr)?;
+    serde_json::to_writer_pretty(BufWriter::new(file), report)
+        .map_err(|error| Arc::new(error) as ErrorPtr)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::refinery::Migration;
+    use crate::report::DatabaseMigrationReport;
+    use std::time::Duration;
+
+    #[test]
+    fn should_report_applied_and_skipped_migrations() {
+        let migrations = vec![
+            Migration::unapplied("V00__first", "test").unwrap(),
+            Migration::unapplied("V01__second", "test").unwrap(),
+            Migration::unapplied("V02__third", "test").unwrap(),
+        ];
+
+        let report = DatabaseMigrationReport::new(
+            0,
+            Some("main"),
+            &migrations,
+            &migrations[1..],
+            &migrations[..2],
+            Duration::from_millis(5),
+            None,
+        );
+
+        assert_eq!(report.database.as_deref(), Some("main"));
+        assert_eq!(report.applied.len(), 1);
+        assert_eq!(report.applied[0].name, "second");
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].version, 2);
+        assert_eq!(report.version, Some(1));
+        assert_eq!(report.duration_ms, 5);
+    }
+}
@@ -0,0 +1,88 @@
+//! Retrying database connections with exponential backoff, e.g. when the application starts
+//! alongside a database, which doesn't accept connections yet.
+
+use crate::config::ConnectionRetryConfig;
+use springtime_di::instance_provider::ErrorPtr;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::warn;
+
+/// Calls given connection operation until it succeeds, the number of attempts is exhausted or
+/// the maximum wait time is exceeded. Returns the last error on failure.
+pub async fn retry_connection<T, F, Fut>(
+    config: &ConnectionRetryConfig,
+    mut operation: F,
+) -> Result<T, ErrorPtr>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ErrorPtr>>,
+{
+    let started = Instant::now();
+    let max_backoff = Duration::from_millis(config.max_backoff_ms);
+    let mut backoff = Duration::from_millis(config.initial_backoff_ms).min(max_backoff);
+    let mut attempt = 1;
+
+    loop {
+        let error = match operation().await {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+
+        let wait_exceeded = config
+            .max_wait_secs
+            .map(|max_wait_secs| started.elapsed() + backoff > Duration::from_secs(max_wait_secs))
+            .unwrap_or(false);
+
+        if attempt >= config.attempts || wait_exceeded {
+            return Err(error);
+        }
+
+        warn!(
+            attempt,
+            "Cannot connect to database, retrying in {}ms: {error}",
+            backoff.as_millis()
+        );
+
+        sleep(backoff).await;
+        backoff = (backoff * 2).min(max_backoff);
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::ConnectionRetryConfig;
+    use crate::retry::retry_connection;
+    use crate::runner::MigrationError;
+    use springtime_di::instance_provider::ErrorPtr;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn should_retry_connection() {
+        let config = ConnectionRetryConfig {
+            attempts: 3,
+            initial_backoff_ms: 1,
+            ..Default::default()
+        };
+
+        let calls = AtomicU32::new(0);
+        let connect = |succeed_on| {
+            let calls = &calls;
+            move || async move {
+                if calls.fetch_add(1, Ordering::SeqCst) + 1 == succeed_on {
+                    Ok(())
+                } else {
+                    Err(Arc::new(MigrationError::UnsupportedOperation("connect")) as ErrorPtr)
+                }
+            }
+        };
+
+        retry_connection(&config, connect(3)).await.unwrap();
+        assert_eq!(calls.swap(0, Ordering::SeqCst), 3);
+
+        assert!(retry_connection(&config, connect(4)).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,69 @@
+//! Support for reverting applied migrations with [DownMigrations](DownMigration).
+
+use crate::migration::DownMigration;
+use refinery_core::{AsyncMigrate, Migrate};
+use springtime_di::instance_provider::ErrorPtr;
+use std::sync::Arc;
+
+/// Migrations to revert by a [MigrationRunnerExecutor](crate::runner::MigrationRunnerExecutor).
+#[derive(Clone, Debug)]
+pub struct Rollback {
+    migration_table_name: String,
+    migrations: Vec<DownMigration>,
+}
+
+impl Rollback {
+    pub(crate) fn new(migration_table_name: String, migrations: Vec<DownMigration>) -> Self {
+        Self {
+            migration_table_name,
+            migrations,
+        }
+    }
+
+    /// Table name for migration data.
+    pub fn migration_table_name(&self) -> &str {
+        &self.migration_table_name
+    }
+
+    /// Migrations to revert, in order of reverting.
+    pub fn migrations(&self) -> &[DownMigration] {
+        &self.migrations
+    }
+
+    /// Reverts migrations using given connection. Each migration is reverted in a separate
+    /// transaction, which also removes it from the migration table.
+    pub async fn run_async<C: AsyncMigrate + Send>(
+        &self,
+        connection: &mut C,
+    ) -> Result<(), ErrorPtr> {
+        for migration in &self.migrations {
+            let delete_query = self.delete_query(migration);
+            connection
+                .execute(&[migration.sql(), &delete_query])
+                .await
+                .map_err(|error| Arc::new(error) as ErrorPtr)?;
+        }
+
+        Ok(())
+    }
+
+    /// Synchronous version of [run_async](Self::run_async) for blocking connections.
+    pub fn run<C: Migrate>(&self, connection: &mut C) -> Result<(), ErrorPtr> {
+        for migration in &self.migrations {
+            let delete_query = self.delete_query(migration);
+            connection
+                .execute(&[migration.sql(), &delete_query])
+                .map_err(|error| Arc::new(error) as ErrorPtr)?;
+        }
+
+        Ok(())
+    }
+
+    fn delete_query(&self, migration: &DownMigration) -> String {
+        format!(
+            "DELETE FROM {} WHERE version = {}",
+            self.migration_table_name,
+            migration.version()
+        )
+    }
+}
@@ -1,30 +1,1011 @@
 //! Module related to running migrations.
 
-use crate::config::MigrationConfigProvider;
-use crate::migration::MigrationSource;
-use crate::refinery::Runner;
+use crate::config::{MigrationConfig, MigrationConfigProvider, MigrationLockConfig, Target};
+use crate::history::MigrationHistory;
+use crate::hook::{ExecutorMigrationReport, MigrationHook, MigrationReport};
+use crate::lock::MigrationLockGuard;
+use crate::migration::{DownMigration, MigrationSource};
+use crate::naming::{naming_problems, MigrationNamingError};
+use crate::refinery::{Migration, Runner};
+use crate::report::{write_report, DatabaseMigrationReport, MigrationRunReport};
+use crate::rollback::Rollback;
+use crate::script::{render_script, MigrationScript};
+use crate::seed::Seed;
+use crate::status::ExecutorMigrationStatus;
+use crate::validation::{divergent_migrations, missing_migrations, ExecutorMigrationValidation};
 use itertools::Itertools;
 use springtime::future::{BoxFuture, FutureExt};
 use springtime::runner::ApplicationRunner;
 use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
 use springtime_di::{component_alias, injectable, Component};
-use tracing::{debug, info};
+use std::fs;
+use std::iter;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::runtime::Handle;
+use tokio::time::timeout;
+use tracing::{debug, info, warn};
+
+/// Errors related to running migrations.
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    /// Given [MigrationRunnerExecutor] doesn't support requested operation.
+    #[error("Operation not supported by migration executor: {0}")]
+    UnsupportedOperation(&'static str),
+    /// An applied migration has no corresponding [DownMigration].
+    #[error("Missing down migration for version: {0}")]
+    MissingDownMigration(u32),
+    /// Database lock could not be acquired in the configured time.
+    #[error("Cannot acquire migration lock: {0}")]
+    LockNotAcquired(String),
+    /// Running migrations took longer than the configured number of seconds.
+    #[error("Running migrations timed out after {0}s")]
+    RunTimeout(u64),
+    /// A run timeout is configured, but migrations don't run in a Tokio runtime, which is needed
+    /// for the timer.
+    #[error("Run timeout requires a Tokio runtime")]
+    RunTimeoutUnsupported,
+    /// Some executors failed to run migrations, when not stopping on first failure.
+    #[error("Running migrations failed: {}", .0.iter().join("; "))]
+    ExecutorsFailed(Vec<ErrorPtr>),
+    /// Validation found divergent or missing migrations.
+    #[error("Migration validation failed for executor: {executor_index}")]
+    ValidationFailed { executor_index: usize },
+    /// Applying a migration failed and so did an
+    /// [after_each](crate::hook::MigrationHook::after_each) hook called with the failure.
+    #[error("{migration_error} (after_each hook also failed: {hook_error})")]
+    AfterEachHookFailed {
+        #[source]
+        migration_error: ErrorPtr,
+        hook_error: ErrorPtr,
+    },
+}
 
 /// Since [Runner] requires a concrete DB client to execute migrations, an abstraction over all
 /// possible clients needs to exist, which will execute the actual run operation with a concrete
 /// client. This trait is such abstraction. By default, all MigrationRunnerExecutors will be called
-/// to run migrations in unspecified order.
+/// to run migrations in unspecified order, unless they declare their
+/// [order](MigrationRunnerExecutor::order). When multiple databases are used, executors can specify
+/// their [database](MigrationRunnerExecutor::database), to only receive migrations from
+/// [MigrationSources](MigrationSource) targeting the same database.
 #[injectable]
 pub trait MigrationRunnerExecutor {
     /// Runs migrations contained in the given [Runner] by passing a concrete DB client.
     fn run_migrations<'a>(&'a self, runner: &'a Runner) -> BoxFuture<'a, Result<(), ErrorPtr>>;
+
+    /// Returns migrations already applied to the database, e.g. by passing a concrete DB client to
+    /// [Runner::get_applied_migrations_async]. Used for operations which need to inspect database
+    /// state without running migrations, e.g. dry runs. Default implementation returns
+    /// [MigrationError::UnsupportedOperation].
+    fn applied_migrations<'a>(
+        &'a self,
+        _runner: &'a Runner,
+    ) -> BoxFuture<'a, Result<Vec<Migration>, ErrorPtr>> {
+        async {
+            Err(Arc::new(MigrationError::UnsupportedOperation("applied_migrations")) as ErrorPtr)
+        }
+        .boxed()
+    }
+
+    /// Reverts migrations contained in the given [Rollback], e.g. by passing a concrete DB client
+    /// to [Rollback::run_async]. Default implementation returns
+    /// [MigrationError::UnsupportedOperation].
+    fn rollback_migrations<'a>(
+        &'a self,
+        _rollback: &'a Rollback,
+    ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+        async {
+            Err(Arc::new(MigrationError::UnsupportedOperation("rollback_migrations")) as ErrorPtr)
+        }
+        .boxed()
+    }
+
+    /// Acquires a database-level lock, which prevents other application instances from running
+    /// migrations concurrently, until the returned guard is released. Default implementation
+    /// returns [MigrationError::UnsupportedOperation].
+    fn lock<'a>(
+        &'a self,
+        _config: &'a MigrationLockConfig,
+    ) -> BoxFuture<'a, Result<Box<dyn MigrationLockGuard>, ErrorPtr>> {
+        async { Err(Arc::new(MigrationError::UnsupportedOperation("lock")) as ErrorPtr) }.boxed()
+    }
+
+    /// Applies given [Seeds](Seed), e.g. by passing a concrete DB client to
+    /// [apply_seeds_async](crate::seed::apply_seeds_async). Default implementation returns
+    /// [MigrationError::UnsupportedOperation].
+    fn apply_seeds<'a>(&'a self, _seeds: &'a [Seed]) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+        async { Err(Arc::new(MigrationError::UnsupportedOperation("apply_seeds")) as ErrorPtr) }
+            .boxed()
+    }
+
+    /// Name identifying this executor in [MigrationConfig::executors]. Default is the
+    /// [database](MigrationRunnerExecutor::database) name.
+    fn name(&self) -> Option<&str> {
+        self.database()
+    }
+
+    /// Order of running migrations relative to other executors - lower values run first. Executors
+    /// with the same order run in unspecified order. Default is 0.
+    fn order(&self) -> i32 {
+        0
+    }
+
+    /// Name of the database this executor runs migrations for. Executors without a database only
+    /// receive migrations from sources which don't target a specific database. Default is none.
+    fn database(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Migrations which would be applied by a [MigrationRunnerExecutor].
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct PendingMigrations {
+    /// Index of the executor in the order of execution.
+    pub executor_index: usize,
+    /// [Namespace](MigrationSource::namespace) of the migrations.
+    pub namespace: Option<String>,
+    /// Migrations which are not yet applied, in order of application.
+    pub migrations: Vec<Migration>,
 }
 
+/// Application runner responsible for running migrations on start. Can also be injected to run
+/// additional operations at runtime.
 #[derive(Component)]
-struct MigrationRunner {
-    config_provider: ComponentInstancePtr<dyn MigrationConfigProvider + Send + Sync>,
-    migration_sources: Vec<ComponentInstancePtr<dyn MigrationSource + Send + Sync>>,
-    executors: Vec<ComponentInstancePtr<dyn MigrationRunnerExecutor + Send + Sync>>,
+pub struct MigrationRunner {
+    pub(crate) config_provider: ComponentInstancePtr<dyn MigrationConfigProvider + Send + Sync>,
+    pub(crate) migration_sources: Vec<ComponentInstancePtr<dyn MigrationSource + Send + Sync>>,
+    pub(crate) executors: Vec<ComponentInstancePtr<dyn MigrationRunnerExecutor + Send + Sync>>,
+    pub(crate) hooks: Vec<ComponentInstancePtr<dyn MigrationHook + Send + Sync>>,
+    #[component(default)]
+    pub(crate) failed: AtomicBool,
+}
+
+impl MigrationRunner {
+    /// Checks if running migrations on start has failed.
+    pub fn has_failed(&self) -> bool {
+        self.failed.load(Ordering::SeqCst)
+    }
+
+    /// Computes migrations which would be applied by each executor, without applying them.
+    pub fn dry_run(&self) -> BoxFuture<'_, Result<Vec<PendingMigrations>, ErrorPtr>> {
+        async {
+            Ok(self
+                .executor_statuses()
+                .await?
+                .into_iter()
+                .map(|status| PendingMigrations {
+                    executor_index: status.executor_index,
+                    namespace: status.namespace,
+                    migrations: status.pending_migrations,
+                })
+                .collect())
+        }
+        .boxed()
+    }
+
+    pub(crate) async fn executor_histories(&self) -> Result<Vec<MigrationHistory>, ErrorPtr> {
+        let config = self.config_provider.config().await?;
+
+        let targets = self.migration_targets(config);
+        let mut result = Vec::with_capacity(targets.len());
+        for target in targets {
+            // only the migration table name is needed to read the history
+            let runner = create_runner(&target.config, &[]);
+            result.push(MigrationHistory {
+                executor_index: target.executor_index,
+                database: target.database().map(|database| database.to_string()),
+                namespace: target.namespace.map(|namespace| namespace.to_string()),
+                applied_migrations: target
+                    .executor
+                    .applied_migrations(&runner)
+                    .await?
+                    .into_iter()
+                    .sorted()
+                    .collect(),
+            });
+        }
+
+        Ok(result)
+    }
+
+    pub(crate) async fn executor_statuses(&self) -> Result<Vec<ExecutorMigrationStatus>, ErrorPtr> {
+        let config = self.config_provider.config().await?;
+
+        let targets = self.migration_targets(config);
+        let mut result = Vec::with_capacity(targets.len());
+        for target in targets {
+            let migrations = self.collect_migrations(&target)?;
+            let runner = create_runner(&target.config, &migrations);
+            let applied_migrations = target.executor.applied_migrations(&runner).await?;
+            result.push(ExecutorMigrationStatus {
+                executor_index: target.executor_index,
+                namespace: target.namespace.map(|namespace| namespace.to_string()),
+                pending_migrations: pending_migrations(
+                    &migrations,
+                    &applied_migrations,
+                    target.config.target,
+                ),
+                applied_migrations,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Validates local migrations against the applied ones for each executor, without applying
+    /// anything. Requires executors to support
+    /// [applied_migrations](MigrationRunnerExecutor::applied_migrations).
+    pub fn validate(&self) -> BoxFuture<'_, Result<Vec<ExecutorMigrationValidation>, ErrorPtr>> {
+        async {
+            let config = self.config_provider.config().await?;
+
+            let targets = self.migration_targets(config);
+            let mut result = Vec::with_capacity(targets.len());
+            for target in targets {
+                let migrations = self.collect_migrations(&target)?;
+                let runner = create_runner(&target.config, &migrations);
+                let applied_migrations = target.executor.applied_migrations(&runner).await?;
+
+                result.push(ExecutorMigrationValidation {
+                    executor_index: target.executor_index,
+                    namespace: target.namespace.map(|namespace| namespace.to_string()),
+                    divergent_migrations: divergent_migrations(&migrations, &applied_migrations),
+                    missing_migrations: missing_migrations(&migrations, &applied_migrations),
+                });
+            }
+
+            Ok(result)
+        }
+        .boxed()
+    }
+
+    /// Renders migrations for each executor into [MigrationScripts](MigrationScript), without
+    /// connecting to databases. See [render_script] for details.
+    pub fn scripts(&self) -> BoxFuture<'_, Result<Vec<MigrationScript>, ErrorPtr>> {
+        async {
+            let config = self.config_provider.config().await?;
+
+            let targets = self.migration_targets(config);
+            let mut result = Vec::with_capacity(targets.len());
+            for target in targets {
+                let migrations = self.collect_migrations(&target)?;
+
+                result.push(MigrationScript {
+                    executor_index: target.executor_index,
+                    database: target.database().map(|database| database.to_string()),
+                    namespace: target.namespace.map(|namespace| namespace.to_string()),
+                    sql: render_script(
+                        &migrations,
+                        &target.config.migration_table_name,
+                        target.config.script_from_version,
+                        target.config.target,
+                    )?,
+                });
+            }
+
+            Ok(result)
+        }
+        .boxed()
+    }
+
+    /// Reverts all applied migrations with versions greater than `target_version`, using
+    /// [DownMigrations](DownMigration) provided by [MigrationSources](MigrationSource). Requires
+    /// executors to support
+    /// [applied_migrations](MigrationRunnerExecutor::applied_migrations) and
+    /// [rollback_migrations](MigrationRunnerExecutor::rollback_migrations).
+    pub fn rollback(&self, target_version: u32) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+        async move {
+            let config = self.config_provider.config().await?;
+
+            // migrations are reverted in the reverse order of application
+            for target in self.migration_targets(config).into_iter().rev() {
+                let migrations = self.collect_migrations(&target)?;
+                let down_migrations: Vec<DownMigration> = self
+                    .sources_for(target.database(), target.namespace)
+                    .map(|source| source.down_migrations())
+                    .flatten_ok()
+                    .try_collect()?;
+
+                let runner = create_runner(&target.config, &migrations);
+
+                let applied_migrations = target.executor.applied_migrations(&runner).await?;
+                let applied_migrations: Vec<_> = applied_migrations
+                    .into_iter()
+                    .filter(|migration| migration.version() > target_version)
+                    .sorted()
+                    .rev()
+                    .collect();
+
+                info!(
+                    namespace = target.namespace,
+                    "Reverting migrations to version {}: [{}]",
+                    target_version,
+                    applied_migrations.iter().join(", ")
+                );
+
+                let migrations_to_revert = applied_migrations
+                    .iter()
+                    .map(|migration| {
+                        down_migrations
+                            .iter()
+                            .find(|down_migration| down_migration.version() == migration.version())
+                            .cloned()
+                            .ok_or_else(|| {
+                                Arc::new(MigrationError::MissingDownMigration(migration.version()))
+                                    as ErrorPtr
+                            })
+                    })
+                    .try_collect()?;
+
+                target
+                    .executor
+                    .rollback_migrations(&Rollback::new(
+                        target.config.migration_table_name.clone(),
+                        migrations_to_revert,
+                    ))
+                    .await?;
+            }
+
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn sources_for<'a>(
+        &'a self,
+        database: Option<&'a str>,
+        namespace: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a ComponentInstancePtr<dyn MigrationSource + Send + Sync>> {
+        self.migration_sources.iter().filter(move |source| {
+            source.namespace() == namespace
+                && source
+                    .database()
+                    .map(|source_database| Some(source_database) == database)
+                    .unwrap_or(true)
+        })
+    }
+
+    fn migration_targets(&self, config: &MigrationConfig) -> Vec<MigrationTarget<'_>> {
+        active_executors(&self.executors, config)
+            .into_iter()
+            .flat_map(|(executor_index, executor)| {
+                let database = executor.database();
+                let config = config.for_database(database);
+
+                // the default namespace always exists, even without sources
+                let namespaces = self
+                    .migration_sources
+                    .iter()
+                    .filter_map(|source| source.namespace())
+                    .unique()
+                    .sorted()
+                    .map(Some);
+
+                iter::once(None)
+                    .chain(namespaces)
+                    .filter(move |namespace| {
+                        namespace.is_none()
+                            || self.sources_for(database, *namespace).next().is_some()
+                    })
+                    .map(move |namespace| MigrationTarget {
+                        executor_index,
+                        executor,
+                        namespace,
+                        config: config.for_namespace(namespace),
+                    })
+            })
+            .collect()
+    }
+
+    fn collect_migrations(&self, target: &MigrationTarget) -> Result<Vec<Migration>, ErrorPtr> {
+        let migrations: Vec<Migration> = self
+            .sources_for(target.database(), target.namespace)
+            .map(|source| source.migrations())
+            .flatten_ok()
+            .try_collect()?;
+
+        Ok(migrations
+            .into_iter()
+            .filter(|migration| {
+                let skipped = target
+                    .config
+                    .skip_migrations
+                    .iter()
+                    .any(|skipped| skipped.matches(migration));
+
+                if skipped {
+                    warn!(
+                        database = target.database(),
+                        namespace = target.namespace,
+                        "Skipping migration due to configuration: {migration}"
+                    );
+                }
+
+                !skipped
+            })
+            .collect())
+    }
+
+    async fn run_locked(&self, config: &MigrationConfig) -> Result<(), ErrorPtr> {
+        let guards = self.acquire_locks(config).await?;
+
+        // kept outside of the run, so a timed out run still has its report written
+        let mut run_report = config
+            .report_path
+            .as_ref()
+            .map(|_| MigrationRunReport::default());
+
+        let result = match config.run_timeout_secs {
+            // tokio timers panic outside a tokio runtime
+            Some(_) if Handle::try_current().is_err() => {
+                Err(Arc::new(MigrationError::RunTimeoutUnsupported) as ErrorPtr)
+            }
+            Some(timeout_secs) => timeout(
+                Duration::from_secs(timeout_secs),
+                self.run_executors(config, &mut run_report),
+            )
+            .await
+            .unwrap_or_else(|_| {
+                Err(Arc::new(MigrationError::RunTimeout(timeout_secs)) as ErrorPtr)
+            }),
+            None => self.run_executors(config, &mut run_report).await,
+        };
+
+        // reports are most useful when something went wrong, so they are written regardless,
+        // but the migration error is more important than a report error
+        let report_result = match (&config.report_path, &run_report) {
+            (Some(path), Some(run_report)) => write_report(path, run_report),
+            _ => Ok(()),
+        };
+
+        // locks need to be released regardless of the result
+        let release_result = release_locks(guards).await;
+        result.and(report_result).and(release_result)
+    }
+
+    async fn acquire_locks(
+        &self,
+        config: &MigrationConfig,
+    ) -> Result<Vec<Box<dyn MigrationLockGuard>>, ErrorPtr> {
+        if !config.lock.enabled {
+            return Ok(vec![]);
+        }
+
+        let mut guards = Vec::with_capacity(self.executors.len());
+        for (_, executor) in active_executors(&self.executors, config) {
+            match executor.lock(&config.lock).await {
+                Ok(guard) => guards.push(guard),
+                Err(error) => {
+                    // the error is more important than possible release errors
+                    let _ = release_locks(guards).await;
+                    return Err(error);
+                }
+            }
+        }
+
+        debug!("Acquired migration locks.");
+
+        Ok(guards)
+    }
+
+    async fn run_executors(
+        &self,
+        config: &MigrationConfig,
+        run_report: &mut Option<MigrationRunReport>,
+    ) -> Result<(), ErrorPtr> {
+        for hook in &self.hooks {
+            hook.before_all().await?;
+        }
+
+        let mut report = MigrationReport::default();
+        let mut failure = None;
+        let mut errors = vec![];
+
+        for target in self.migration_targets(config) {
+            let result = match run_report {
+                Some(run_report) => self.run_target_reported(&target, run_report).await,
+                None => self.run_target(&target).await,
+            };
+
+            match result {
+                Ok(Some(executor_report)) => report.executor_reports.push(executor_report),
+                Ok(None) => {}
+                Err(error) => {
+                    if target.config.stop_on_failure {
+                        failure = Some(error);
+                        break;
+                    }
+
+                    warn!(
+                        database = target.database(),
+                        namespace = target.namespace,
+                        "Error running migrations: {error}"
+                    );
+                    errors.push(error);
+                }
+            }
+        }
+
+        if let Some(error) = failure {
+            return Err(error);
+        }
+
+        into_result(errors)?;
+
+        for hook in &self.hooks {
+            hook.after_all(&report).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn run_target(
+        &self,
+        target: &MigrationTarget<'_>,
+    ) -> Result<Option<ExecutorMigrationReport>, ErrorPtr> {
+        if self.hooks.is_empty() {
+            self.run_target_without_hooks(target).await.map(|_| None)
+        } else {
+            self.run_target_with_hooks(target).await.map(Some)
+        }
+    }
+
+    async fn run_target_reported(
+        &self,
+        target: &MigrationTarget<'_>,
+        run_report: &mut MigrationRunReport,
+    ) -> Result<Option<ExecutorMigrationReport>, ErrorPtr> {
+        let migrations = self.collect_migrations(target)?;
+        let runner = create_runner(&target.config, &migrations);
+
+        // executors which cannot retrieve applied migrations are still run, but reported without
+        // migration details
+        let applied_migrations = self.reported_applied_migrations(target, &runner).await;
+        let pending = applied_migrations.as_ref().map(|applied_migrations| {
+            pending_migrations(&migrations, applied_migrations, target.config.target)
+        });
+
+        let started = Instant::now();
+        let result = self.run_target(target).await;
+        let duration = started.elapsed();
+
+        // failed runs might still apply some migrations, so the state needs to be refreshed
+        let applied_migrations = match applied_migrations {
+            Some(applied_migrations) => Some(
+                self.reported_applied_migrations(target, &runner)
+                    .await
+                    .unwrap_or(applied_migrations),
+            ),
+            None => None,
+        };
+
+        let mut database_report = match (&pending, &applied_migrations) {
+            (Some(pending), Some(applied_migrations)) => DatabaseMigrationReport::new(
+                target.executor_index,
+                target.database(),
+                &migrations,
+                pending,
+                applied_migrations,
+                duration,
+                result.as_ref().err(),
+            ),
+            _ => DatabaseMigrationReport::new(
+                target.executor_index,
+                target.database(),
+                &[],
+                &[],
+                &[],
+                duration,
+                result.as_ref().err(),
+            ),
+        };
+        database_report.namespace = target.namespace.map(|namespace| namespace.to_string());
+        run_report.databases.push(database_report);
+
+        result
+    }
+
+    async fn reported_applied_migrations(
+        &self,
+        target: &MigrationTarget<'_>,
+        runner: &Runner,
+    ) -> Option<Vec<Migration>> {
+        match target.executor.applied_migrations(runner).await {
+            Ok(applied_migrations) => Some(applied_migrations),
+            Err(error) => {
+                warn!(
+                    database = target.database(),
+                    namespace = target.namespace,
+                    "Cannot retrieve applied migrations for report: {error}"
+                );
+                None
+            }
+        }
+    }
+
+    async fn run_target_without_hooks(&self, target: &MigrationTarget<'_>) -> Result<(), ErrorPtr> {
+        let migrations = self.collect_migrations(target)?;
+
+        info!(
+            database = target.database(),
+            namespace = target.namespace,
+            "Running {} migrations...",
+            migrations.len()
+        );
+
+        let runner = create_runner(&target.config, &migrations);
+        target.executor.run_migrations(&runner).await
+    }
+
+    async fn run_target_with_hooks(
+        &self,
+        target: &MigrationTarget<'_>,
+    ) -> Result<ExecutorMigrationReport, ErrorPtr> {
+        let config = &target.config;
+        let migrations = self.collect_migrations(target)?;
+        let runner = create_runner(config, &migrations);
+        let applied_migrations = match target.executor.applied_migrations(&runner).await {
+            Ok(applied_migrations) => applied_migrations,
+            Err(error) if is_unsupported(&error) => {
+                // pending migrations are unknown, so they can't be reported to hooks one by one
+                warn!(
+                    database = target.database(),
+                    namespace = target.namespace,
+                    "Executor cannot retrieve applied migrations, so hooks won't be called for \
+                    individual migrations. Running {} migrations...",
+                    migrations.len()
+                );
+
+                target.executor.run_migrations(&runner).await?;
+
+                return Ok(ExecutorMigrationReport {
+                    executor_index: target.executor_index,
+                    namespace: target.namespace.map(|namespace| namespace.to_string()),
+                    applied_migrations: vec![],
+                });
+            }
+            Err(error) => return Err(error),
+        };
+        let pending = pending_migrations(&migrations, &applied_migrations, config.target);
+
+        info!(
+            database = target.database(),
+            namespace = target.namespace,
+            "Running {} migrations...",
+            pending.len()
+        );
+
+        if config.grouped {
+            // migrations need to be applied together, so hooks are called around the group
+            for migration in &pending {
+                self.before_each(migration).await?;
+            }
+
+            let result = target.executor.run_migrations(&runner).await;
+            let mut hook_result = Ok(());
+            for migration in &pending {
+                hook_result = self.after_each(migration, &result).await;
+                if hook_result.is_err() {
+                    break;
+                }
+            }
+
+            attach_hook_error(result, hook_result)?;
+        } else {
+            for migration in &pending {
+                self.before_each(migration).await?;
+
+                // pending migrations are sorted, so running up to given version applies only the
+                // current one
+                let runner = create_runner(config, &migrations)
+                    .set_target(step_target(config.target, migration.version()));
+
+                let result = target.executor.run_migrations(&runner).await;
+                let hook_result = self.after_each(migration, &result).await;
+
+                attach_hook_error(result, hook_result)?;
+            }
+        }
+
+        Ok(ExecutorMigrationReport {
+            executor_index: target.executor_index,
+            namespace: target.namespace.map(|namespace| namespace.to_string()),
+            applied_migrations: pending,
+        })
+    }
+
+    async fn before_each(&self, migration: &Migration) -> Result<(), ErrorPtr> {
+        for hook in &self.hooks {
+            hook.before_each(migration).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn after_each(
+        &self,
+        migration: &Migration,
+        result: &Result<(), ErrorPtr>,
+    ) -> Result<(), ErrorPtr> {
+        for hook in &self.hooks {
+            hook.after_each(migration, result).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn validate_on_start(&self, config: &MigrationConfig) -> Result<(), ErrorPtr> {
+        for validation in self.validate().await? {
+            if validation.is_valid() {
+                info!(
+                    executor = validation.executor_index,
+                    namespace = validation.namespace,
+                    "Migrations are valid."
+                );
+                continue;
+            }
+
+            warn!(
+                executor = validation.executor_index,
+                namespace = validation.namespace,
+                "Found divergent migrations: [{}], missing migrations: [{}]",
+                validation.divergent_migrations.iter().join(", "),
+                validation.missing_migrations.iter().join(", ")
+            );
+
+            let config = config.for_database(self.executors[validation.executor_index].database());
+            if (config.abort_divergent && !validation.divergent_migrations.is_empty())
+                || (config.abort_missing && !validation.missing_migrations.is_empty())
+            {
+                return Err(Arc::new(MigrationError::ValidationFailed {
+                    executor_index: validation.executor_index,
+                }) as ErrorPtr);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_naming(&self, config: &MigrationConfig) -> Result<(), ErrorPtr> {
+        if !config.naming.enabled {
+            return Ok(());
+        }
+
+        for target in self.migration_targets(config) {
+            let migrations = self.collect_migrations(&target)?;
+            let problems = naming_problems(&migrations, &config.naming)
+                .map_err(|error| Arc::new(error) as ErrorPtr)?;
+
+            if !problems.is_empty() {
+                return Err(Arc::new(MigrationNamingError::InvalidNaming {
+                    database: target.database().map(|database| database.to_string()),
+                    problems,
+                }) as ErrorPtr);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn write_scripts(&self, directory: &Path) -> Result<(), ErrorPtr> {
+        fs::create_dir_all(directory).map_err(|error| Arc::new(error) as ErrorPtr)?;
+
+        for script in self.scripts().await? {
+            let path = directory.join(script.file_name());
+            info!(
+                executor = script.executor_index,
+                "Writing migration script: {}",
+                path.display()
+            );
+
+            fs::write(path, script.sql).map_err(|error| Arc::new(error) as ErrorPtr)?;
+        }
+
+        Ok(())
+    }
+
+    async fn log_dry_run(&self, config: &MigrationConfig) -> Result<(), ErrorPtr> {
+        let statuses = self.executor_statuses().await?;
+        for status in &statuses {
+            info!(
+                executor = status.executor_index,
+                namespace = status.namespace,
+                "Dry run: {} pending migrations: [{}]",
+                status.pending_migrations.len(),
+                status.pending_migrations.iter().join(", ")
+            );
+        }
+
+        if let Some(path) = &config.report_path {
+            // statuses are created in the order of targets
+            let databases = self
+                .migration_targets(config)
+                .iter()
+                .zip(&statuses)
+                .map(|(target, status)| {
+                    let migrations = self.collect_migrations(target)?;
+
+                    // report the state as if pending migrations were applied
+                    let applied_migrations = status
+                        .applied_migrations
+                        .iter()
+                        .chain(&status.pending_migrations)
+                        .cloned()
+                        .collect_vec();
+
+                    let mut database_report = DatabaseMigrationReport::new(
+                        target.executor_index,
+                        target.database(),
+                        &migrations,
+                        &status.pending_migrations,
+                        &applied_migrations,
+                        Duration::ZERO,
+                        None,
+                    );
+                    database_report.namespace = status.namespace.clone();
+                    Ok(database_report)
+                })
+                .try_collect::<_, _, ErrorPtr>()?;
+
+            write_report(
+                path,
+                &MigrationRunReport {
+                    dry_run: true,
+                    databases,
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// executor running migrations from a single namespace, with effective config
+struct MigrationTarget<'a> {
+    executor_index: usize,
+    executor: &'a ComponentInstancePtr<dyn MigrationRunnerExecutor + Send + Sync>,
+    namespace: Option<&'a str>,
+    config: MigrationConfig,
+}
+
+impl MigrationTarget<'_> {
+    fn database(&self) -> Option<&str> {
+        self.executor.database()
+    }
+}
+
+/// Returns enabled executors with their indices, sorted by their
+/// [order](MigrationRunnerExecutor::order), which can be overridden in
+/// [MigrationConfig::executors].
+pub(crate) fn active_executors<'a>(
+    executors: &'a [ComponentInstancePtr<dyn MigrationRunnerExecutor + Send + Sync>],
+    config: &MigrationConfig,
+) -> Vec<(
+    usize,
+    &'a ComponentInstancePtr<dyn MigrationRunnerExecutor + Send + Sync>,
+)> {
+    executors
+        .iter()
+        .enumerate()
+        .filter(|(executor_index, executor)| {
+            let enabled = config.for_executor(executor.name()).enabled;
+            if !enabled {
+                debug!(executor = executor_index, "Executor disabled.");
+            }
+
+            enabled
+        })
+        .sorted_by_key(|(_, executor)| {
+            config
+                .for_executor(executor.name())
+                .order
+                .unwrap_or_else(|| executor.order())
+        })
+        .collect()
+}
+
+fn is_unsupported(error: &ErrorPtr) -> bool {
+    matches!(
+        error.downcast_ref::<MigrationError>(),
+        Some(MigrationError::UnsupportedOperation(_))
+    )
+}
+
+fn create_runner(config: &MigrationConfig, migrations: &[Migration]) -> Runner {
+    let mut runner = Runner::new(migrations)
+        .set_target(config.target.into())
+        .set_grouped(config.grouped)
+        .set_abort_divergent(config.abort_divergent)
+        .set_abort_missing(config.abort_missing);
+    runner.set_migration_table_name(&config.migration_table_name);
+    runner
+}
+
+fn into_result(errors: Vec<ErrorPtr>) -> Result<(), ErrorPtr> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Arc::new(MigrationError::ExecutorsFailed(errors)) as ErrorPtr)
+    }
+}
+
+// the migration failure is the primary one, so a hook error is only attached to it
+fn attach_hook_error(
+    result: Result<(), ErrorPtr>,
+    hook_result: Result<(), ErrorPtr>,
+) -> Result<(), ErrorPtr> {
+    match (result, hook_result) {
+        (Err(migration_error), Err(hook_error)) => {
+            Err(Arc::new(MigrationError::AfterEachHookFailed {
+                migration_error,
+                hook_error,
+            }) as ErrorPtr)
+        }
+        (Err(error), Ok(())) | (Ok(()), Err(error)) => Err(error),
+        (Ok(()), Ok(())) => Ok(()),
+    }
+}
+
+async fn release_locks(guards: Vec<Box<dyn MigrationLockGuard>>) -> Result<(), ErrorPtr> {
+    let mut result = Ok(());
+    for guard in guards.into_iter().rev() {
+        let release_result = guard.release().await;
+        if result.is_ok() {
+            result = release_result;
+        }
+    }
+
+    result
+}
+
+fn step_target(target: Target, version: u32) -> refinery_core::Target {
+    match target {
+        Target::Fake | Target::FakeVersion(_) => refinery_core::Target::FakeVersion(version),
+        Target::Latest | Target::Version(_) => refinery_core::Target::Version(version),
+    }
+}
+
+fn pending_migrations(
+    migrations: &[Migration],
+    applied_migrations: &[Migration],
+    target: Target,
+) -> Vec<Migration> {
+    let current_version = applied_migrations
+        .iter()
+        .map(|migration| migration.version())
+        .max();
+
+    let target_version = match target {
+        Target::Latest | Target::Fake => None,
+        Target::Version(version) | Target::FakeVersion(version) => Some(version),
+    };
+
+    migrations
+        .iter()
+        .filter(|migration| {
+            !applied_migrations
+                .iter()
+                .any(|applied| applied.version() == migration.version())
+        })
+        .filter(|migration| {
+            // note: refinery doesn't export migration type, so its display value is used
+            migration.prefix().to_string() != "V"
+                || current_version
+                    .map(|version| migration.version() > version)
+                    .unwrap_or(true)
+        })
+        .filter(|migration| {
+            target_version
+                .map(|version| migration.version() <= version)
+                .unwrap_or(true)
+        })
+        .sorted()
+        .cloned()
+        .collect()
 }
 
 #[component_alias]
@@ -42,28 +1023,32 @@ impl ApplicationRunner for MigrationRunner {
                 return Ok(());
             }
 
-            let migrations: Vec<_> = self
-                .migration_sources
-                .iter()
-                .map(|source| source.migrations())
-                .flatten_ok()
-                .try_collect()?;
+            self.validate_naming(config)?;
+
+            if config.dry_run {
+                return self.log_dry_run(config).await;
+            }
+
+            if config.validate {
+                return self.validate_on_start(config).await;
+            }
+
+            if let Some(target_version) = config.rollback_target {
+                return self.rollback(target_version).await;
+            }
+
+            if let Some(directory) = &config.script_directory {
+                return self.write_scripts(directory).await;
+            }
 
             info!(
-                "Running {} migrations by {} executors...",
-                migrations.len(),
-                self.executors.len()
+                "Running migrations by {} executors...",
+                active_executors(&self.executors, config).len()
             );
 
-            let mut runner = Runner::new(&migrations)
-                .set_target(config.target.into())
-                .set_grouped(config.grouped)
-                .set_abort_divergent(config.abort_divergent)
-                .set_abort_missing(config.abort_missing);
-            runner.set_migration_table_name(&config.migration_table_name);
-
-            for executor in &self.executors {
-                executor.run_migrations(&runner).await?;
+            if let Err(error) = self.run_locked(config).await {
+                self.failed.store(true, Ordering::SeqCst);
+                return Err(error);
             }
 
             debug!("Done running migrations.");
@@ -80,18 +1065,61 @@ impl ApplicationRunner for MigrationRunner {
 
 #[cfg(test)]
 mod tests {
-    use crate::config::{MigrationConfig, MigrationConfigProvider};
-    use crate::migration::MockMigrationSource;
-    use crate::runner::{MigrationRunner, MigrationRunnerExecutor};
+    use crate::config::Target;
+    use crate::config::{
+        DatabaseMigrationConfig, ExecutorConfig, MigrationConfig, MigrationConfigProvider,
+        MigrationLockConfig, SkippedMigration,
+    };
+    use crate::hook::{MigrationHook, MigrationReport};
+    use crate::lock::MigrationLockGuard;
+    use crate::migration::MigrationSource;
+    use crate::runner::{
+        pending_migrations, MigrationError, MigrationRunner, MigrationRunnerExecutor,
+        MigrationTarget,
+    };
     use mockall::automock;
     use refinery_core::{Migration, Runner};
     use springtime::future::{BoxFuture, FutureExt};
     use springtime::runner::ApplicationRunner;
     use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::{env, future};
 
     #[automock]
     pub trait TestMigrationRunnerExecutor {
         fn run_migrations(&self, runner: &Runner) -> BoxFuture<'_, Result<(), ErrorPtr>>;
+
+        fn applied_migrations(
+            &self,
+            runner: &Runner,
+        ) -> BoxFuture<'_, Result<Vec<Migration>, ErrorPtr>>;
+    }
+
+    #[automock]
+    pub trait TestMigrationProvider {
+        fn migrations(&self) -> Result<Vec<Migration>, ErrorPtr>;
+    }
+
+    // mocks can't return borrowed unsized values, such as the database name, so only migrations
+    // are mocked
+    struct MockMigrationSource {
+        inner: MockTestMigrationProvider,
+    }
+
+    impl MockMigrationSource {
+        fn new() -> Self {
+            Self {
+                inner: MockTestMigrationProvider::new(),
+            }
+        }
+    }
+
+    impl MigrationSource for MockMigrationSource {
+        fn migrations(&self) -> Result<Vec<Migration>, ErrorPtr> {
+            self.inner.migrations()
+        }
     }
 
     struct MockMigrationRunnerExecutor {
@@ -110,6 +1138,13 @@ mod tests {
         fn run_migrations<'a>(&'a self, runner: &'a Runner) -> BoxFuture<'a, Result<(), ErrorPtr>> {
             self.inner.run_migrations(runner)
         }
+
+        fn applied_migrations<'a>(
+            &'a self,
+            runner: &'a Runner,
+        ) -> BoxFuture<'a, Result<Vec<Migration>, ErrorPtr>> {
+            self.inner.applied_migrations(runner)
+        }
     }
 
     #[derive(Default)]
@@ -127,6 +1162,7 @@ mod tests {
     async fn should_execute_migrations() {
         let mut migration_source = MockMigrationSource::new();
         migration_source
+            .inner
             .expect_migrations()
             .times(1)
             .return_const(Ok(vec![Migration::unapplied("V00__test", "test").unwrap()]));
@@ -138,11 +1174,848 @@ mod tests {
             .times(1)
             .returning(|_| async { Ok(()) }.boxed());
 
+        // naming validation collects migrations on its own
+        let mut config = MigrationConfig::default();
+        config.naming.enabled = false;
+
         let runner = MigrationRunner {
-            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider::default()),
+            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider { config }),
             migration_sources: vec![ComponentInstancePtr::new(migration_source)],
             executors: vec![ComponentInstancePtr::new(executor)],
+            hooks: vec![],
+            failed: Default::default(),
         };
         runner.run().await.unwrap();
     }
+
+    struct TestMigrationSource {
+        migration: &'static str,
+        database: Option<&'static str>,
+    }
+
+    impl MigrationSource for TestMigrationSource {
+        fn migrations(&self) -> Result<Vec<Migration>, ErrorPtr> {
+            Ok(vec![Migration::unapplied(self.migration, "test").unwrap()])
+        }
+
+        fn database(&self) -> Option<&str> {
+            self.database
+        }
+    }
+
+    struct TestDatabaseMigrationRunnerExecutor {
+        database: Option<&'static str>,
+    }
+
+    impl MigrationRunnerExecutor for TestDatabaseMigrationRunnerExecutor {
+        fn run_migrations<'a>(
+            &'a self,
+            _runner: &'a Runner,
+        ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            async { Ok(()) }.boxed()
+        }
+
+        fn applied_migrations<'a>(
+            &'a self,
+            _runner: &'a Runner,
+        ) -> BoxFuture<'a, Result<Vec<Migration>, ErrorPtr>> {
+            async { Ok(vec![]) }.boxed()
+        }
+
+        fn database(&self) -> Option<&str> {
+            self.database
+        }
+    }
+
+    struct TestNamespacedMigrationSource;
+
+    impl MigrationSource for TestNamespacedMigrationSource {
+        fn migrations(&self) -> Result<Vec<Migration>, ErrorPtr> {
+            Ok(vec![Migration::unapplied("V00__library", "test").unwrap()])
+        }
+
+        fn namespace(&self) -> Option<&str> {
+            Some("library")
+        }
+    }
+
+    #[test]
+    fn should_separate_namespaces() {
+        let runner = MigrationRunner {
+            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider::default()),
+            migration_sources: vec![
+                ComponentInstancePtr::new(TestMigrationSource {
+                    migration: "V00__application",
+                    database: None,
+                }),
+                ComponentInstancePtr::new(TestNamespacedMigrationSource),
+            ],
+            executors: vec![ComponentInstancePtr::new(
+                TestDatabaseMigrationRunnerExecutor { database: None },
+            )],
+            hooks: vec![],
+            failed: Default::default(),
+        };
+
+        let config = MigrationConfig::default();
+        let targets = runner.migration_targets(&config);
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].namespace, None);
+        assert_eq!(
+            targets[0].config.migration_table_name,
+            config.migration_table_name
+        );
+        assert_eq!(targets[1].namespace, Some("library"));
+        assert_eq!(
+            targets[1].config.migration_table_name,
+            format!("library_{}", config.migration_table_name)
+        );
+
+        let names = |target| {
+            runner
+                .collect_migrations(target)
+                .unwrap()
+                .iter()
+                .map(|migration| migration.name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(&targets[0]), vec!["application"]);
+        assert_eq!(names(&targets[1]), vec!["library"]);
+    }
+
+    #[test]
+    fn should_route_migrations_to_databases() {
+        let source = |migration, database| {
+            ComponentInstancePtr::new(TestMigrationSource {
+                migration,
+                database,
+            }) as ComponentInstancePtr<dyn MigrationSource + Send + Sync>
+        };
+
+        let runner = MigrationRunner {
+            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider::default()),
+            migration_sources: vec![
+                source("V00__common", None),
+                source("V01__analytics", Some("analytics")),
+                source("V02__main", Some("main")),
+            ],
+            executors: vec![],
+            hooks: vec![],
+            failed: Default::default(),
+        };
+
+        let names = |database| {
+            let executor =
+                ComponentInstancePtr::new(TestDatabaseMigrationRunnerExecutor { database })
+                    as ComponentInstancePtr<dyn MigrationRunnerExecutor + Send + Sync>;
+
+            runner
+                .collect_migrations(&MigrationTarget {
+                    executor_index: 0,
+                    executor: &executor,
+                    namespace: None,
+                    config: MigrationConfig::default(),
+                })
+                .unwrap()
+                .iter()
+                .map(|migration| migration.name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(None), vec!["common"]);
+        assert_eq!(names(Some("analytics")), vec!["common", "analytics"]);
+    }
+
+    #[derive(Default)]
+    struct TestCountingMigrationRunnerExecutor {
+        runs: AtomicUsize,
+        released_locks: Arc<AtomicUsize>,
+    }
+
+    struct TestMigrationLockGuard {
+        released_locks: Arc<AtomicUsize>,
+    }
+
+    impl MigrationLockGuard for TestMigrationLockGuard {
+        fn release(self: Box<Self>) -> BoxFuture<'static, Result<(), ErrorPtr>> {
+            self.released_locks.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }.boxed()
+        }
+    }
+
+    impl MigrationRunnerExecutor for TestCountingMigrationRunnerExecutor {
+        fn run_migrations<'a>(
+            &'a self,
+            _runner: &'a Runner,
+        ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }.boxed()
+        }
+
+        fn applied_migrations<'a>(
+            &'a self,
+            _runner: &'a Runner,
+        ) -> BoxFuture<'a, Result<Vec<Migration>, ErrorPtr>> {
+            async { Ok(vec![]) }.boxed()
+        }
+
+        fn lock<'a>(
+            &'a self,
+            _config: &'a MigrationLockConfig,
+        ) -> BoxFuture<'a, Result<Box<dyn MigrationLockGuard>, ErrorPtr>> {
+            let guard = TestMigrationLockGuard {
+                released_locks: self.released_locks.clone(),
+            };
+            async { Ok(Box::new(guard) as Box<dyn MigrationLockGuard>) }.boxed()
+        }
+    }
+
+    #[derive(Default)]
+    struct TestMigrationHook {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl MigrationHook for TestMigrationHook {
+        fn before_all(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+            self.events.lock().unwrap().push("before_all".to_string());
+            async { Ok(()) }.boxed()
+        }
+
+        fn before_each<'a>(
+            &'a self,
+            migration: &'a Migration,
+        ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("before_{}", migration.name()));
+            async { Ok(()) }.boxed()
+        }
+
+        fn after_each<'a>(
+            &'a self,
+            migration: &'a Migration,
+            result: &'a Result<(), ErrorPtr>,
+        ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            assert!(result.is_ok());
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("after_{}", migration.name()));
+            async { Ok(()) }.boxed()
+        }
+
+        fn after_all<'a>(
+            &'a self,
+            report: &'a MigrationReport,
+        ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            self.events.lock().unwrap().push(format!(
+                "after_all_{}",
+                report.executor_reports[0].applied_migrations.len()
+            ));
+            async { Ok(()) }.boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_call_hooks() {
+        let hook = ComponentInstancePtr::new(TestMigrationHook::default());
+        let executor = ComponentInstancePtr::new(TestCountingMigrationRunnerExecutor::default());
+
+        let runner = MigrationRunner {
+            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider::default()),
+            migration_sources: vec![ComponentInstancePtr::new(TestMigrationSource {
+                migration: "V01__first",
+                database: None,
+            })],
+            executors: vec![executor.clone()],
+            hooks: vec![hook.clone()],
+            failed: Default::default(),
+        };
+        runner.run().await.unwrap();
+
+        assert_eq!(
+            *hook.events.lock().unwrap(),
+            vec!["before_all", "before_first", "after_first", "after_all_1"]
+        );
+        assert_eq!(executor.runs.load(Ordering::SeqCst), 1);
+    }
+
+    // implements only what's required
+    #[derive(Default)]
+    struct TestMinimalMigrationRunnerExecutor {
+        runs: AtomicUsize,
+    }
+
+    impl MigrationRunnerExecutor for TestMinimalMigrationRunnerExecutor {
+        fn run_migrations<'a>(
+            &'a self,
+            _runner: &'a Runner,
+        ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }.boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_call_hooks_without_applied_migrations() {
+        let hook = ComponentInstancePtr::new(TestMigrationHook::default());
+        let executor = ComponentInstancePtr::new(TestMinimalMigrationRunnerExecutor::default());
+
+        let runner = MigrationRunner {
+            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider::default()),
+            migration_sources: vec![ComponentInstancePtr::new(TestMigrationSource {
+                migration: "V01__first",
+                database: None,
+            })],
+            executors: vec![executor.clone()],
+            hooks: vec![hook.clone()],
+            failed: Default::default(),
+        };
+        runner.run().await.unwrap();
+
+        assert_eq!(
+            *hook.events.lock().unwrap(),
+            vec!["before_all", "after_all_0"]
+        );
+        assert_eq!(executor.runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn should_release_locks() {
+        let executor = ComponentInstancePtr::new(TestCountingMigrationRunnerExecutor::default());
+
+        let mut config = MigrationConfig::default();
+        config.lock.enabled = true;
+
+        let runner = MigrationRunner {
+            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider { config }),
+            migration_sources: vec![ComponentInstancePtr::new(TestMigrationSource {
+                migration: "V01__first",
+                database: None,
+            })],
+            executors: vec![executor.clone()],
+            hooks: vec![],
+            failed: Default::default(),
+        };
+        runner.run().await.unwrap();
+
+        assert_eq!(executor.runs.load(Ordering::SeqCst), 1);
+        assert_eq!(executor.released_locks.load(Ordering::SeqCst), 1);
+    }
+
+    fn create_timed_runner(
+        executor: ComponentInstancePtr<TestCountingMigrationRunnerExecutor>,
+    ) -> MigrationRunner {
+        let config = MigrationConfig {
+            run_timeout_secs: Some(u64::MAX),
+            ..Default::default()
+        };
+
+        MigrationRunner {
+            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider { config }),
+            migration_sources: vec![ComponentInstancePtr::new(TestMigrationSource {
+                migration: "V01__first",
+                database: None,
+            })],
+            executors: vec![executor],
+            hooks: vec![],
+            failed: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_run_with_timeout() {
+        let executor = ComponentInstancePtr::new(TestCountingMigrationRunnerExecutor::default());
+        create_timed_runner(executor.clone()).run().await.unwrap();
+
+        assert_eq!(executor.runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn should_reject_timeout_outside_runtime() {
+        let executor = ComponentInstancePtr::new(TestCountingMigrationRunnerExecutor::default());
+        let error = create_timed_runner(executor.clone())
+            .run()
+            .now_or_never()
+            .unwrap()
+            .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<MigrationError>(),
+            Some(MigrationError::RunTimeoutUnsupported)
+        ));
+        assert_eq!(executor.runs.load(Ordering::SeqCst), 0);
+    }
+
+    struct TestFailingMigrationRunnerExecutor;
+
+    impl MigrationRunnerExecutor for TestFailingMigrationRunnerExecutor {
+        fn run_migrations<'a>(
+            &'a self,
+            _runner: &'a Runner,
+        ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            async { Err(Arc::new(MigrationError::UnsupportedOperation("test")) as ErrorPtr) }
+                .boxed()
+        }
+
+        fn applied_migrations<'a>(
+            &'a self,
+            _runner: &'a Runner,
+        ) -> BoxFuture<'a, Result<Vec<Migration>, ErrorPtr>> {
+            async { Ok(vec![]) }.boxed()
+        }
+    }
+
+    struct TestFailingMigrationHook;
+
+    impl MigrationHook for TestFailingMigrationHook {
+        fn after_each<'a>(
+            &'a self,
+            _migration: &'a Migration,
+            result: &'a Result<(), ErrorPtr>,
+        ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            assert!(result.is_err());
+            async { Err(Arc::new(MigrationError::UnsupportedOperation("hook")) as ErrorPtr) }
+                .boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_report_migration_error_before_hook_error() {
+        for grouped in [false, true] {
+            let config = MigrationConfig {
+                grouped,
+                ..Default::default()
+            };
+
+            let runner = MigrationRunner {
+                config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider { config }),
+                migration_sources: vec![ComponentInstancePtr::new(TestMigrationSource {
+                    migration: "V01__first",
+                    database: None,
+                })],
+                executors: vec![ComponentInstancePtr::new(
+                    TestFailingMigrationRunnerExecutor,
+                )],
+                hooks: vec![ComponentInstancePtr::new(TestFailingMigrationHook)],
+                failed: Default::default(),
+            };
+
+            let error = runner.run().await.unwrap_err();
+            assert!(matches!(
+                error.downcast_ref::<MigrationError>().unwrap(),
+                MigrationError::AfterEachHookFailed {
+                    migration_error,
+                    hook_error,
+                } if migration_error.to_string().ends_with("test")
+                    && hook_error.to_string().ends_with("hook")
+            ));
+        }
+    }
+
+    struct TestHangingMigrationRunnerExecutor;
+
+    impl MigrationRunnerExecutor for TestHangingMigrationRunnerExecutor {
+        fn run_migrations<'a>(
+            &'a self,
+            _runner: &'a Runner,
+        ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            future::pending().boxed()
+        }
+
+        fn applied_migrations<'a>(
+            &'a self,
+            _runner: &'a Runner,
+        ) -> BoxFuture<'a, Result<Vec<Migration>, ErrorPtr>> {
+            async { Ok(vec![]) }.boxed()
+        }
+    }
+
+    fn create_reporting_runner(
+        config: MigrationConfig,
+        executor: ComponentInstancePtr<dyn MigrationRunnerExecutor + Send + Sync>,
+    ) -> MigrationRunner {
+        MigrationRunner {
+            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider { config }),
+            migration_sources: vec![ComponentInstancePtr::new(TestMigrationSource {
+                migration: "V01__first",
+                database: None,
+            })],
+            executors: vec![executor],
+            hooks: vec![],
+            failed: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_write_report_after_failure() {
+        let report_path = env::temp_dir().join("springtime-migrate-failure-report.json");
+        let _ = fs::remove_file(&report_path);
+
+        let config = MigrationConfig {
+            report_path: Some(report_path.clone()),
+            ..Default::default()
+        };
+
+        let error = create_reporting_runner(
+            config,
+            ComponentInstancePtr::new(TestFailingMigrationRunnerExecutor),
+        )
+        .run()
+        .await
+        .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<MigrationError>(),
+            Some(MigrationError::UnsupportedOperation(_))
+        ));
+        assert!(report_path.exists());
+
+        fs::remove_file(report_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_write_report_without_applied_migrations() {
+        let report_path = env::temp_dir().join("springtime-migrate-minimal-report.json");
+        let _ = fs::remove_file(&report_path);
+
+        let config = MigrationConfig {
+            report_path: Some(report_path.clone()),
+            ..Default::default()
+        };
+
+        let executor = ComponentInstancePtr::new(TestMinimalMigrationRunnerExecutor::default());
+        create_reporting_runner(config, executor.clone())
+            .run()
+            .await
+            .unwrap();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+        fs::remove_file(report_path).unwrap();
+
+        assert_eq!(executor.runs.load(Ordering::SeqCst), 1);
+        assert!(report["databases"][0]["applied"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+        assert!(report["databases"][0]["skipped"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+        assert!(report["databases"][0]["error"].is_null());
+    }
+
+    #[tokio::test]
+    async fn should_write_report_after_timeout() {
+        let report_path = env::temp_dir().join("springtime-migrate-timeout-report.json");
+        let _ = fs::remove_file(&report_path);
+
+        let config = MigrationConfig {
+            report_path: Some(report_path.clone()),
+            run_timeout_secs: Some(0),
+            ..Default::default()
+        };
+
+        let error = create_reporting_runner(
+            config,
+            ComponentInstancePtr::new(TestHangingMigrationRunnerExecutor),
+        )
+        .run()
+        .await
+        .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<MigrationError>(),
+            Some(MigrationError::RunTimeout(0))
+        ));
+        assert!(report_path.exists());
+
+        fs::remove_file(report_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_return_migration_error_before_report_error() {
+        let config = MigrationConfig {
+            report_path: Some(env::temp_dir().join("springtime-missing-dir/report.json")),
+            ..Default::default()
+        };
+
+        let error = create_reporting_runner(
+            config,
+            ComponentInstancePtr::new(TestFailingMigrationRunnerExecutor),
+        )
+        .run()
+        .await
+        .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<MigrationError>(),
+            Some(MigrationError::UnsupportedOperation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_skip_migrations_per_database_in_dry_run_report() {
+        let report_path = env::temp_dir().join("springtime-migrate-dry-run-report.json");
+        let _ = fs::remove_file(&report_path);
+
+        let config = MigrationConfig {
+            dry_run: true,
+            report_path: Some(report_path.clone()),
+            databases: [(
+                "skipping".to_string(),
+                DatabaseMigrationConfig {
+                    skip_migrations: vec![SkippedMigration::Version(1)],
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+
+        let executors: Vec<ComponentInstancePtr<dyn MigrationRunnerExecutor + Send + Sync>> = vec![
+            ComponentInstancePtr::new(TestDatabaseMigrationRunnerExecutor { database: None }),
+            ComponentInstancePtr::new(TestDatabaseMigrationRunnerExecutor {
+                database: Some("skipping"),
+            }),
+        ];
+
+        let runner = MigrationRunner {
+            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider { config }),
+            migration_sources: vec![ComponentInstancePtr::new(TestMigrationSource {
+                migration: "V01__first",
+                database: None,
+            })],
+            executors,
+            hooks: vec![],
+            failed: Default::default(),
+        };
+
+        runner.run().await.unwrap();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+        fs::remove_file(report_path).unwrap();
+
+        let applied_count = |index: usize| {
+            report["databases"][index]["applied"]
+                .as_array()
+                .unwrap()
+                .len()
+        };
+        assert_eq!(applied_count(0), 1);
+        assert_eq!(applied_count(1), 0);
+    }
+
+    #[tokio::test]
+    async fn should_continue_after_failure() {
+        let executor = ComponentInstancePtr::new(TestCountingMigrationRunnerExecutor::default());
+        let create_runner = |stop_on_failure| {
+            let config = MigrationConfig {
+                stop_on_failure,
+                ..Default::default()
+            };
+
+            MigrationRunner {
+                config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider { config }),
+                migration_sources: vec![ComponentInstancePtr::new(TestMigrationSource {
+                    migration: "V01__first",
+                    database: None,
+                })],
+                executors: vec![
+                    ComponentInstancePtr::new(TestFailingMigrationRunnerExecutor),
+                    executor.clone(),
+                ],
+                hooks: vec![],
+                failed: Default::default(),
+            }
+        };
+
+        assert!(create_runner(true).run().await.is_err());
+        assert_eq!(executor.runs.load(Ordering::SeqCst), 0);
+
+        let runner = create_runner(false);
+        assert!(runner.run().await.is_err());
+        assert!(runner.has_failed());
+        assert_eq!(executor.runs.load(Ordering::SeqCst), 1);
+    }
+
+    struct TestOrderedMigrationRunnerExecutor {
+        name: &'static str,
+        order: i32,
+        runs: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl MigrationRunnerExecutor for TestOrderedMigrationRunnerExecutor {
+        fn run_migrations<'a>(
+            &'a self,
+            _runner: &'a Runner,
+        ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            self.runs.lock().unwrap().push(self.name);
+            async { Ok(()) }.boxed()
+        }
+
+        fn name(&self) -> Option<&str> {
+            Some(self.name)
+        }
+
+        fn order(&self) -> i32 {
+            self.order
+        }
+    }
+
+    #[tokio::test]
+    async fn should_return_executor_statuses() {
+        let mut executor = MockMigrationRunnerExecutor::new();
+        executor
+            .inner
+            .expect_applied_migrations()
+            .times(2)
+            .returning(|runner| {
+                let applied_migrations = runner
+                    .get_migrations()
+                    .iter()
+                    .filter(|migration| migration.version() == 1)
+                    .cloned()
+                    .collect();
+                async { Ok(applied_migrations) }.boxed()
+            });
+
+        let runner = MigrationRunner {
+            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider::default()),
+            migration_sources: vec![
+                ComponentInstancePtr::new(TestMigrationSource {
+                    migration: "V01__first",
+                    database: None,
+                }),
+                ComponentInstancePtr::new(TestMigrationSource {
+                    migration: "V02__second",
+                    database: None,
+                }),
+                ComponentInstancePtr::new(TestNamespacedMigrationSource),
+            ],
+            executors: vec![ComponentInstancePtr::new(executor)],
+            hooks: vec![],
+            failed: Default::default(),
+        };
+
+        let names = |migrations: &[Migration]| {
+            migrations
+                .iter()
+                .map(|migration| migration.name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let statuses = runner.executor_statuses().await.unwrap();
+        assert_eq!(statuses.len(), 2);
+
+        assert_eq!(statuses[0].executor_index, 0);
+        assert_eq!(statuses[0].namespace, None);
+        assert_eq!(names(&statuses[0].applied_migrations), vec!["first"]);
+        assert_eq!(names(&statuses[0].pending_migrations), vec!["second"]);
+
+        assert_eq!(statuses[1].executor_index, 0);
+        assert_eq!(statuses[1].namespace.as_deref(), Some("library"));
+        assert!(statuses[1].applied_migrations.is_empty());
+        assert_eq!(names(&statuses[1].pending_migrations), vec!["library"]);
+    }
+
+    #[tokio::test]
+    async fn should_return_unsupported_executor_status_error() {
+        let runner = MigrationRunner {
+            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider::default()),
+            migration_sources: vec![ComponentInstancePtr::new(TestMigrationSource {
+                migration: "V01__first",
+                database: None,
+            })],
+            executors: vec![ComponentInstancePtr::new(
+                TestOrderedMigrationRunnerExecutor {
+                    name: "unsupported",
+                    order: 0,
+                    runs: Default::default(),
+                },
+            )],
+            hooks: vec![],
+            failed: Default::default(),
+        };
+
+        let error = runner.executor_statuses().await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<MigrationError>(),
+            Some(MigrationError::UnsupportedOperation("applied_migrations"))
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_order_and_disable_executors() {
+        let runs = Arc::new(Mutex::new(vec![]));
+        let executor = |name, order| {
+            ComponentInstancePtr::new(TestOrderedMigrationRunnerExecutor {
+                name,
+                order,
+                runs: runs.clone(),
+            }) as ComponentInstancePtr<dyn MigrationRunnerExecutor + Send + Sync>
+        };
+
+        let mut config = MigrationConfig::default();
+        config.executors.insert(
+            "disabled".to_string(),
+            ExecutorConfig {
+                enabled: false,
+                ..Default::default()
+            },
+        );
+        config.executors.insert(
+            "overridden".to_string(),
+            ExecutorConfig {
+                order: Some(-1),
+                ..Default::default()
+            },
+        );
+
+        let runner = MigrationRunner {
+            config_provider: ComponentInstancePtr::new(TestMigrationConfigProvider { config }),
+            migration_sources: vec![ComponentInstancePtr::new(TestMigrationSource {
+                migration: "V01__first",
+                database: None,
+            })],
+            executors: vec![
+                executor("last", 2),
+                executor("disabled", 0),
+                executor("first", 1),
+                executor("overridden", 3),
+            ],
+            hooks: vec![],
+            failed: Default::default(),
+        };
+        runner.run().await.unwrap();
+
+        assert_eq!(*runs.lock().unwrap(), vec!["overridden", "first", "last"]);
+    }
+
+    #[test]
+    fn should_compute_pending_migrations() {
+        let migrations = vec![
+            Migration::unapplied("V02__third", "test").unwrap(),
+            Migration::unapplied("V00__first", "test").unwrap(),
+            Migration::unapplied("V01__second", "test").unwrap(),
+        ];
+        let applied_migrations = vec![migrations[1].clone()];
+
+        let pending = pending_migrations(&migrations, &applied_migrations, Target::Latest);
+        assert_eq!(
+            pending
+                .iter()
+                .map(|migration| migration.version())
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        let pending = pending_migrations(&migrations, &applied_migrations, Target::Version(1));
+        assert_eq!(pending.len(), 1);
+    }
 }
@@ -0,0 +1,133 @@
+//! Offline rendering of migrations into SQL scripts, which can be reviewed and applied manually,
+//! e.g. in environments where applications cannot modify the schema.
+
+use crate::config::Target;
+use crate::refinery::Migration;
+use itertools::Itertools;
+use springtime_di::instance_provider::ErrorPtr;
+use std::fmt::Write;
+use std::sync::Arc;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// SQL script applying migrations for a single
+/// [MigrationRunnerExecutor](crate::runner::MigrationRunnerExecutor).
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct MigrationScript {
+    /// Index of the executor in the order of execution.
+    pub executor_index: usize,
+    /// Name of the database, if the executor specifies one.
+    pub database: Option<String>,
+    /// [Namespace](crate::migration::MigrationSource::namespace) of the migrations.
+    pub namespace: Option<String>,
+    /// Script contents.
+    pub sql: String,
+}
+
+impl MigrationScript {
+    /// Name of the file, which the script is written to: `<database>.sql` or `migrations.sql`
+    /// when the executor doesn't specify a database. Namespaced scripts are prefixed with
+    /// `<namespace>_`.
+    pub fn file_name(&self) -> String {
+        let database = self.database.as_deref().unwrap_or("migrations");
+        match &self.namespace {
+            Some(namespace) => format!("{namespace}_{database}.sql"),
+            None => format!("{database}.sql"),
+        }
+    }
+}
+
+/// Renders migrations with versions greater than `from_version` (all, if not given) up to the
+/// target into a single script, which also records them in the migration table the same way
+/// `refinery` does. Migrations are only recorded for fake targets.
+///
+/// Note: the migration table is created with a generic `CREATE TABLE IF NOT EXISTS` statement,
+/// which might need adjusting for databases not supporting it, e.g. SQL Server.
+pub fn render_script(
+    migrations: &[Migration],
+    migration_table_name: &str,
+    from_version: Option<u32>,
+    target: Target,
+) -> Result<String, ErrorPtr> {
+    let applied_on = OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .map_err(|error| Arc::new(error) as ErrorPtr)?;
+
+    let (target_version, fake) = match target {
+        Target::Latest => (None, false),
+        Target::Version(version) => (Some(version), false),
+        Target::Fake => (None, true),
+        Target::FakeVersion(version) => (Some(version), true),
+    };
+
+    let mut script = format!(
+        "-- Generated by springtime-migrate-refinery. Review before applying.\n\n\
+        CREATE TABLE IF NOT EXISTS {migration_table_name}(\n    \
+        version INT4 PRIMARY KEY,\n    \
+        name VARCHAR(255),\n    \
+        applied_on VARCHAR(255),\n    \
+        checksum VARCHAR(255));\n"
+    );
+
+    let migrations = migrations
+        .iter()
+        .filter(|migration| {
+            from_version
+                .map(|version| migration.version() > version)
+                .unwrap_or(true)
+                && target_version
+                    .map(|version| migration.version() <= version)
+                    .unwrap_or(true)
+        })
+        .sorted();
+
+    for migration in migrations {
+        // writing to a string cannot fail
+        let _ = writeln!(script, "\n-- {migration}");
+        if !fake {
+            if let Some(sql) = migration.sql() {
+                let _ = writeln!(script, "{};", sql.trim().trim_end_matches(';'));
+            }
+        }
+
+        let _ = writeln!(
+            script,
+            "INSERT INTO {} (version, name, applied_on, checksum) VALUES ({}, '{}', '{}', '{}');",
+            migration_table_name,
+            migration.version(),
+            migration.name().replace('\'', "''"),
+            applied_on,
+            migration.checksum()
+        );
+    }
+
+    Ok(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Target;
+    use crate::refinery::Migration;
+    use crate::script::render_script;
+
+    #[test]
+    fn should_render_script() {
+        let migrations = vec![
+            Migration::unapplied("V02__third", "CREATE TABLE third(id INT);").unwrap(),
+            Migration::unapplied("V00__first", "CREATE TABLE first(id INT)").unwrap(),
+            Migration::unapplied("V01__second", "CREATE TABLE second(id INT)").unwrap(),
+        ];
+
+        let script = render_script(&migrations, "history", Some(0), Target::Version(1)).unwrap();
+        assert!(script.contains("CREATE TABLE IF NOT EXISTS history("));
+        assert!(script.contains("CREATE TABLE second(id INT);"));
+        assert!(script.contains("VALUES (1, 'second'"));
+        assert!(!script.contains("first"));
+        assert!(!script.contains("third"));
+
+        let script = render_script(&migrations, "history", None, Target::Fake).unwrap();
+        assert!(!script.contains("CREATE TABLE second"));
+        assert!(script.find("'first'").unwrap() < script.find("'third'").unwrap());
+    }
+}
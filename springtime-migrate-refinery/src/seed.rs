@@ -0,0 +1,311 @@
+//! Seed data applied after successfully running migrations.
+
+use crate::config::MigrationConfigProvider;
+use crate::runner::{active_executors, MigrationRunner, MigrationRunnerExecutor};
+use refinery_core::{AsyncMigrate, Migrate};
+use springtime::future::{BoxFuture, FutureExt};
+use springtime::runner::ApplicationRunner;
+use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
+use springtime_di::{component_alias, injectable, Component};
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// Seed script, which inserts reference data. Since seeds are applied on every start, the script
+/// should be idempotent.
+#[derive(Clone, Debug)]
+pub struct Seed {
+    name: String,
+    sql: String,
+}
+
+impl Seed {
+    /// Creates a new seed with given name, used for diagnostics.
+    pub fn new(name: impl Into<String>, sql: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            sql: sql.into(),
+        }
+    }
+
+    /// Name of the seed.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// SQL inserting the data.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+}
+
+/// Applies given seeds using given connection. Each seed is applied in a separate transaction.
+pub async fn apply_seeds_async<C: AsyncMigrate + Send>(
+    seeds: &[Seed],
+    connection: &mut C,
+) -> Result<(), ErrorPtr> {
+    for seed in seeds {
+        connection
+            .execute(&[seed.sql()])
+            .await
+            .map_err(|error| Arc::new(error) as ErrorPtr)?;
+    }
+
+    Ok(())
+}
+
+/// Synchronous version of [apply_seeds_async] for blocking connections.
+pub fn apply_seeds<C: Migrate>(seeds: &[Seed], connection: &mut C) -> Result<(), ErrorPtr> {
+    for seed in seeds {
+        connection
+            .execute(&[seed.sql()])
+            .map_err(|error| Arc::new(error) as ErrorPtr)?;
+    }
+
+    Ok(())
+}
+
+/// A source for [Seeds](Seed).
+#[injectable]
+pub trait SeedSource {
+    /// Provides seeds from this source, in order of application.
+    fn seeds(&self) -> Result<Vec<Seed>, ErrorPtr>;
+
+    /// Name of the database targeted by seeds from this source, with the same semantics as
+    /// [MigrationSource::database](crate::migration::MigrationSource::database). Default is none.
+    fn database(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Application runner applying [Seeds](Seed) after [MigrationRunner] succeeds, hence it has a lower
+/// priority of 90. Seeds are applied by [MigrationRunnerExecutors](MigrationRunnerExecutor)
+/// supporting [apply_seeds](MigrationRunnerExecutor::apply_seeds), only when migrations run on
+/// start.
+#[derive(Component)]
+pub struct SeedRunner {
+    config_provider: ComponentInstancePtr<dyn MigrationConfigProvider + Send + Sync>,
+    migration_runner: ComponentInstancePtr<MigrationRunner>,
+    seed_sources: Vec<ComponentInstancePtr<dyn SeedSource + Send + Sync>>,
+    executors: Vec<ComponentInstancePtr<dyn MigrationRunnerExecutor + Send + Sync>>,
+}
+
+impl SeedRunner {
+    fn collect_seeds(&self, database: Option<&str>) -> Result<Vec<Seed>, ErrorPtr> {
+        let mut result = vec![];
+        for source in &self.seed_sources {
+            let targeted = source
+                .database()
+                .map(|source_database| Some(source_database) == database)
+                .unwrap_or(true);
+
+            if targeted {
+                result.extend(source.seeds()?);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[component_alias]
+impl ApplicationRunner for SeedRunner {
+    fn run(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+        async {
+            let config = self.config_provider.config().await?;
+            // seeds expect an up-to-date schema, which is only guaranteed by running migrations
+            if !config.run_seeds_on_start
+                || !config.run_migrations_on_start
+                || config.dry_run
+                || config.validate
+                || config.rollback_target.is_some()
+                || config.script_directory.is_some()
+            {
+                debug!("Seeds disabled.");
+                return Ok(());
+            }
+
+            if self.seed_sources.is_empty() {
+                return Ok(());
+            }
+
+            if self.migration_runner.has_failed() {
+                info!("Not applying seeds, since migrations failed.");
+                return Ok(());
+            }
+
+            for (_, executor) in active_executors(&self.executors, config) {
+                let seeds = self.collect_seeds(executor.database())?;
+                if seeds.is_empty() {
+                    continue;
+                }
+
+                info!(
+                    database = executor.database(),
+                    "Applying {} seeds...",
+                    seeds.len()
+                );
+
+                executor.apply_seeds(&seeds).await?;
+            }
+
+            debug!("Done applying seeds.");
+
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn priority(&self) -> i8 {
+        90
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{MigrationConfig, MigrationConfigProvider};
+    use crate::runner::{MigrationRunner, MigrationRunnerExecutor};
+    use crate::seed::{Seed, SeedRunner, SeedSource};
+    use refinery_core::Runner;
+    use springtime::future::{BoxFuture, FutureExt};
+    use springtime::runner::ApplicationRunner;
+    use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
+    use std::sync::atomic::Ordering;
+    use std::sync::Mutex;
+
+    struct TestMigrationConfigProvider {
+        config: MigrationConfig,
+    }
+
+    impl MigrationConfigProvider for TestMigrationConfigProvider {
+        fn config(&self) -> BoxFuture<'_, Result<&MigrationConfig, ErrorPtr>> {
+            async { Ok(&self.config) }.boxed()
+        }
+    }
+
+    struct TestSeedSource {
+        name: &'static str,
+        database: Option<&'static str>,
+    }
+
+    impl SeedSource for TestSeedSource {
+        fn seeds(&self) -> Result<Vec<Seed>, ErrorPtr> {
+            Ok(vec![Seed::new(self.name, "SELECT 1")])
+        }
+
+        fn database(&self) -> Option<&str> {
+            self.database
+        }
+    }
+
+    #[derive(Default)]
+    struct TestSeedingMigrationRunnerExecutor {
+        database: Option<&'static str>,
+        seeds: Mutex<Vec<String>>,
+    }
+
+    impl MigrationRunnerExecutor for TestSeedingMigrationRunnerExecutor {
+        fn run_migrations<'a>(
+            &'a self,
+            _runner: &'a Runner,
+        ) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            async { Ok(()) }.boxed()
+        }
+
+        fn database(&self) -> Option<&str> {
+            self.database
+        }
+
+        fn apply_seeds<'a>(&'a self, seeds: &'a [Seed]) -> BoxFuture<'a, Result<(), ErrorPtr>> {
+            self.seeds
+                .lock()
+                .unwrap()
+                .extend(seeds.iter().map(|seed| seed.name().to_string()));
+            async { Ok(()) }.boxed()
+        }
+    }
+
+    fn create_runner(
+        config: MigrationConfig,
+        executors: &[ComponentInstancePtr<TestSeedingMigrationRunnerExecutor>],
+    ) -> SeedRunner {
+        let config_provider = ComponentInstancePtr::new(TestMigrationConfigProvider { config });
+        let executors = executors
+            .iter()
+            .map(|executor| {
+                executor.clone() as ComponentInstancePtr<dyn MigrationRunnerExecutor + Send + Sync>
+            })
+            .collect::<Vec<_>>();
+
+        SeedRunner {
+            config_provider: config_provider.clone(),
+            migration_runner: ComponentInstancePtr::new(MigrationRunner {
+                config_provider,
+                migration_sources: vec![],
+                executors: executors.clone(),
+                hooks: vec![],
+                failed: Default::default(),
+            }),
+            seed_sources: vec![
+                ComponentInstancePtr::new(TestSeedSource {
+                    name: "common",
+                    database: None,
+                }),
+                ComponentInstancePtr::new(TestSeedSource {
+                    name: "analytics",
+                    database: Some("analytics"),
+                }),
+            ],
+            executors,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_apply_seeds_for_targeted_databases() {
+        let executors = [
+            ComponentInstancePtr::new(TestSeedingMigrationRunnerExecutor::default()),
+            ComponentInstancePtr::new(TestSeedingMigrationRunnerExecutor {
+                database: Some("analytics"),
+                ..Default::default()
+            }),
+        ];
+
+        create_runner(MigrationConfig::default(), &executors)
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(*executors[0].seeds.lock().unwrap(), vec!["common"]);
+        assert_eq!(
+            *executors[1].seeds.lock().unwrap(),
+            vec!["common", "analytics"]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_not_apply_seeds_when_migrations_are_disabled() {
+        let executors = [ComponentInstancePtr::new(
+            TestSeedingMigrationRunnerExecutor::default(),
+        )];
+        let config = MigrationConfig {
+            run_migrations_on_start: false,
+            ..Default::default()
+        };
+
+        create_runner(config, &executors).run().await.unwrap();
+
+        assert!(executors[0].seeds.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_not_apply_seeds_after_migration_failure() {
+        let executors = [ComponentInstancePtr::new(
+            TestSeedingMigrationRunnerExecutor::default(),
+        )];
+        let runner = create_runner(MigrationConfig::default(), &executors);
+        runner.migration_runner.failed.store(true, Ordering::SeqCst);
+
+        runner.run().await.unwrap();
+
+        assert!(executors[0].seeds.lock().unwrap().is_empty());
+    }
+}
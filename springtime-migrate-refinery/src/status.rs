@@ -0,0 +1,38 @@
+//! Inspection of migration state, e.g. for health checks or admin endpoints.
+
+use crate::refinery::Migration;
+use crate::runner::MigrationRunner;
+use springtime::future::{BoxFuture, FutureExt};
+use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
+use springtime_di::Component;
+
+/// State of migrations for a single
+/// [MigrationRunnerExecutor](crate::runner::MigrationRunnerExecutor). [Migrations](Migration)
+/// expose their name, version, checksum and application time.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct ExecutorMigrationStatus {
+    /// Index of the executor in the order of execution.
+    pub executor_index: usize,
+    /// [Namespace](crate::migration::MigrationSource::namespace) of the migrations.
+    pub namespace: Option<String>,
+    /// Migrations already applied to the database.
+    pub applied_migrations: Vec<Migration>,
+    /// Migrations which are not yet applied, in order of application.
+    pub pending_migrations: Vec<Migration>,
+}
+
+/// Queries applied and pending migrations through registered
+/// [MigrationRunnerExecutors](crate::runner::MigrationRunnerExecutor). Requires executors to
+/// support [applied_migrations](crate::runner::MigrationRunnerExecutor::applied_migrations).
+#[derive(Component)]
+pub struct MigrationStatus {
+    runner: ComponentInstancePtr<MigrationRunner>,
+}
+
+impl MigrationStatus {
+    /// Returns current migration state for each executor.
+    pub fn status(&self) -> BoxFuture<'_, Result<Vec<ExecutorMigrationStatus>, ErrorPtr>> {
+        self.runner.executor_statuses().boxed()
+    }
+}
@@ -0,0 +1,112 @@
+//! Validation of local migrations against the applied migration history.
+
+use crate::refinery::Migration;
+use itertools::Itertools;
+
+/// Result of validating migrations for a
+/// [MigrationRunnerExecutor](crate::runner::MigrationRunnerExecutor).
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct ExecutorMigrationValidation {
+    /// Index of the executor in the order of execution.
+    pub executor_index: usize,
+    /// [Namespace](crate::migration::MigrationSource::namespace) of the migrations.
+    pub namespace: Option<String>,
+    /// Applied migrations with the same version as local ones, but with a different name or
+    /// checksum.
+    pub divergent_migrations: Vec<Migration>,
+    /// Applied migrations which are not found locally, or local versioned migrations with a
+    /// version lower than the last applied one, which are not applied.
+    pub missing_migrations: Vec<Migration>,
+}
+
+impl ExecutorMigrationValidation {
+    /// Checks if no problems were found.
+    pub fn is_valid(&self) -> bool {
+        self.divergent_migrations.is_empty() && self.missing_migrations.is_empty()
+    }
+}
+
+pub(crate) fn divergent_migrations(
+    migrations: &[Migration],
+    applied_migrations: &[Migration],
+) -> Vec<Migration> {
+    applied_migrations
+        .iter()
+        .filter(|applied| {
+            migrations.iter().any(|migration| {
+                migration.version() == applied.version()
+                    && (migration.name() != applied.name()
+                        || migration.checksum() != applied.checksum())
+            })
+        })
+        .sorted()
+        .cloned()
+        .collect()
+}
+
+pub(crate) fn missing_migrations(
+    migrations: &[Migration],
+    applied_migrations: &[Migration],
+) -> Vec<Migration> {
+    let current_version = applied_migrations
+        .iter()
+        .map(|migration| migration.version())
+        .max();
+
+    let missing_locally = applied_migrations.iter().filter(|applied| {
+        !migrations
+            .iter()
+            .any(|migration| migration.version() == applied.version())
+    });
+
+    let missing_in_database = migrations.iter().filter(|migration| {
+        // note: refinery doesn't export migration type, so its display value is used
+        migration.prefix().to_string() == "V"
+            && current_version
+                .map(|version| migration.version() < version)
+                .unwrap_or(false)
+            && !applied_migrations
+                .iter()
+                .any(|applied| applied.version() == migration.version())
+    });
+
+    missing_locally
+        .chain(missing_in_database)
+        .sorted()
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::refinery::Migration;
+    use crate::validation::{divergent_migrations, missing_migrations};
+
+    #[test]
+    fn should_find_invalid_migrations() {
+        let migrations = vec![
+            Migration::unapplied("V00__first", "test").unwrap(),
+            Migration::unapplied("V01__second", "changed").unwrap(),
+            Migration::unapplied("V03__fourth", "test").unwrap(),
+        ];
+        let applied_migrations = vec![
+            Migration::unapplied("V01__second", "test").unwrap(),
+            Migration::unapplied("V02__third", "test").unwrap(),
+            Migration::unapplied("V03__fourth", "test").unwrap(),
+        ];
+
+        let divergent = divergent_migrations(&migrations, &applied_migrations);
+        assert_eq!(divergent.len(), 1);
+        assert_eq!(divergent[0].version(), 1);
+
+        let missing = missing_migrations(&migrations, &applied_migrations);
+        assert_eq!(
+            missing
+                .iter()
+                .map(|migration| migration.version())
+                .collect::<Vec<_>>(),
+            vec![0, 2]
+        );
+    }
+}
@@ -0,0 +1,53 @@
+// note: this example assumes you've analyzed the previous ones
+
+use axum::extract::Request;
+use axum::middleware::{from_fn, Next};
+use axum::response::Response;
+use axum::Router;
+use springtime::application;
+use springtime_di::instance_provider::ErrorPtr;
+use springtime_di::{component_alias, Component};
+use springtime_web_axum::controller;
+use springtime_web_axum::router::HttpLayerContribution;
+
+#[derive(Component)]
+struct ExampleController;
+
+#[controller]
+impl ExampleController {
+    #[get("/")]
+    async fn hello_world(&self) -> &'static str {
+        "Hello world!"
+    }
+}
+
+async fn add_header(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    response
+        .headers_mut()
+        .insert("x-powered-by", "springtime".parse().unwrap());
+    response
+}
+
+// any component implementing HttpLayerContribution will have its layer applied automatically - this
+// is how middleware crates can integrate with the framework
+#[derive(Component)]
+struct HeaderLayerContribution;
+
+#[component_alias]
+impl HttpLayerContribution for HeaderLayerContribution {
+    // contributions with higher order wrap the ones with lower order
+    fn order(&self) -> i32 {
+        10
+    }
+
+    fn apply(&self, router: Router) -> Result<Router, ErrorPtr> {
+        Ok(router.layer(from_fn(add_header)))
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let mut application = application::create_default().expect("unable to create application");
+    application.run().await.expect("error running application");
+}
@@ -2,17 +2,15 @@
 //! retrieve [WebConfig].
 //!
 //! By default, the config is created with opinionated default values, which can then be overwritten
-//! by values from `springtime.json` file under the `web` key.
+//! by values from [MergedConfig] (e.g. `springtime.json` file) under the `web` key.
 
-use config::{Config, File};
 use fxhash::FxHashMap;
 use serde::Deserialize;
-use springtime::config::CONFIG_FILE;
+use springtime::config::{ConfigSection, MergedConfig};
 use springtime::future::{BoxFuture, FutureExt};
 use springtime_di::component_registry::conditional::unregistered_component;
-use springtime_di::instance_provider::ErrorPtr;
+use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
 use springtime_di::{component_alias, injectable, Component};
-use std::sync::Arc;
 
 /// Name of the default server present in the default [WebConfig].
 pub const DEFAULT_SERVER_NAME: &str = "default";
@@ -55,15 +53,8 @@ impl Default for WebConfig {
     }
 }
 
-impl WebConfig {
-    fn init_from_config() -> Result<Self, ErrorPtr> {
-        Config::builder()
-            .add_source(File::with_name(CONFIG_FILE).required(false))
-            .build()
-            .and_then(|config| config.try_deserialize::<WebConfigWrapper>())
-            .map(|config| config.web)
-            .map_err(|error| Arc::new(error) as ErrorPtr)
-    }
+impl ConfigSection for WebConfig {
+    const KEY: &'static str = "web";
 }
 
 /// Provider for [WebConfig]. The primary instance of the provider will be used to retrieve web
@@ -75,7 +66,7 @@ pub trait WebConfigProvider {
 }
 
 #[derive(Component)]
-#[component(priority = -128, condition = "unregistered_component::<dyn WebConfigProvider + Send + Sync>", constructor = "DefaultWebConfigProvider::new")]
+#[component(priority = -128, condition = "unregistered_component::<dyn WebConfigProvider + Send + Sync>", constructor = "DefaultWebConfigProvider::new", constructor_parameters = "MergedConfig")]
 struct DefaultWebConfigProvider {
     // cached init result
     #[component(ignore)]
@@ -96,18 +87,14 @@ impl WebConfigProvider for DefaultWebConfigProvider {
 }
 
 impl DefaultWebConfigProvider {
-    fn new() -> BoxFuture<'static, Result<Self, ErrorPtr>> {
-        async {
+    fn new(
+        merged_config: ComponentInstancePtr<MergedConfig>,
+    ) -> BoxFuture<'static, Result<Self, ErrorPtr>> {
+        async move {
             Ok(Self {
-                config: WebConfig::init_from_config(),
+                config: merged_config.section(),
             })
         }
         .boxed()
     }
 }
-
-#[derive(Deserialize, Default)]
-#[serde(default)]
-struct WebConfigWrapper {
-    web: WebConfig,
-}
@@ -1,7 +1,7 @@
 //! Controller routing handling. By default, routing is based on gathering existing controllers and
 //! their request handlers.
 
-use crate::controller::Controller;
+use crate::controller::{Controller, ServerNameSet};
 use axum::Router;
 #[cfg(test)]
 use mockall::automock;
@@ -19,6 +19,29 @@ pub trait RouterConfigure {
     fn configure(&self, router: Router) -> Result<Router, ErrorPtr>;
 }
 
+/// Trait for components contributing a tower layer, which is applied to the [Router] created by
+/// [RouterBootstrap]. This allows middleware crates to integrate by simply registering a component.
+///
+/// Contributions are applied in ascending [order](HttpLayerContribution::order) after all
+/// [RouterConfigure] components, so the layer with the highest order is the outermost one and sees
+/// requests first.
+#[injectable]
+#[cfg_attr(test, automock)]
+pub trait HttpLayerContribution {
+    /// Ordering key for the contribution (default is 0).
+    fn order(&self) -> i32 {
+        0
+    }
+
+    /// Optional list of server names for which given layer should be applied.
+    fn server_names(&self) -> Option<ServerNameSet> {
+        None
+    }
+
+    /// Applies the layer to the [Router], typically by calling [Router::layer].
+    fn apply(&self, router: Router) -> Result<Router, ErrorPtr>;
+}
+
 /// Trait for creating a [Router], usually based on injected
 /// [Controller](crate::controller::Controller)s.
 #[injectable]
@@ -32,6 +55,7 @@ pub trait RouterBootstrap {
 struct ControllerRouterBootstrap {
     controllers: Vec<ComponentInstancePtr<dyn Controller + Send + Sync>>,
     configure_components: Vec<ComponentInstancePtr<dyn RouterConfigure + Send + Sync>>,
+    layer_contributions: Vec<ComponentInstancePtr<dyn HttpLayerContribution + Send + Sync>>,
 }
 
 #[component_alias]
@@ -61,15 +85,43 @@ impl RouterBootstrap for ControllerRouterBootstrap {
                     .iter()
                     .try_fold(router, |router, configure| configure.configure(router))
             })
+            .and_then(|router| self.apply_layers(router, server_name))
+    }
+}
+
+impl ControllerRouterBootstrap {
+    fn apply_layers(&self, router: Router, server_name: &str) -> Result<Router, ErrorPtr> {
+        let mut layer_contributions = self
+            .layer_contributions
+            .iter()
+            .filter(|contribution| {
+                contribution
+                    .server_names()
+                    .map(|server_names| server_names.contains(server_name))
+                    .unwrap_or(true)
+            })
+            .collect::<Vec<_>>();
+
+        layer_contributions.sort_by_key(|contribution| contribution.order());
+
+        layer_contributions
+            .into_iter()
+            .try_fold(router, |router, contribution| {
+                debug!(order = contribution.order(), "Applying layer contribution.");
+                contribution.apply(router)
+            })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::controller::MockController;
-    use crate::router::{ControllerRouterBootstrap, MockRouterConfigure, RouterBootstrap};
+    use crate::router::{
+        ControllerRouterBootstrap, MockHttpLayerContribution, MockRouterConfigure, RouterBootstrap,
+    };
     use axum::Router;
     use fxhash::FxHashSet;
+    use mockall::Sequence;
     use springtime_di::instance_provider::ComponentInstancePtr;
 
     #[test]
@@ -95,6 +147,7 @@ mod tests {
         let bootstrap = ControllerRouterBootstrap {
             controllers: vec![ComponentInstancePtr::new(controller)],
             configure_components: vec![],
+            layer_contributions: vec![],
         };
         assert!(bootstrap.bootstrap_router("1").is_ok());
     }
@@ -115,6 +168,7 @@ mod tests {
         let bootstrap = ControllerRouterBootstrap {
             controllers: vec![ComponentInstancePtr::new(controller)],
             configure_components: vec![],
+            layer_contributions: vec![],
         };
         assert!(bootstrap.bootstrap_router("3").is_ok());
     }
@@ -130,6 +184,48 @@ mod tests {
         let bootstrap = ControllerRouterBootstrap {
             controllers: vec![],
             configure_components: vec![ComponentInstancePtr::new(configure)],
+            layer_contributions: vec![],
+        };
+        assert!(bootstrap.bootstrap_router("1").is_ok());
+    }
+
+    #[test]
+    fn should_apply_layers_in_order_with_filtering() {
+        let mut seq = Sequence::new();
+
+        let mut first = MockHttpLayerContribution::new();
+        first.expect_order().return_const(-1);
+        first.expect_server_names().return_const(None);
+        first
+            .expect_apply()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|router| Ok(router));
+
+        let mut second = MockHttpLayerContribution::new();
+        second.expect_order().return_const(1);
+        second.expect_server_names().return_const(None);
+        second
+            .expect_apply()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|router| Ok(router));
+
+        let mut filtered = MockHttpLayerContribution::new();
+        filtered.expect_order().return_const(0);
+        filtered
+            .expect_server_names()
+            .return_const(Some(["2".to_string()].into_iter().collect()));
+        filtered.expect_apply().times(0);
+
+        let bootstrap = ControllerRouterBootstrap {
+            controllers: vec![],
+            configure_components: vec![],
+            layer_contributions: vec![
+                ComponentInstancePtr::new(second),
+                ComponentInstancePtr::new(filtered),
+                ComponentInstancePtr::new(first),
+            ],
         };
         assert!(bootstrap.bootstrap_router("1").is_ok());
     }
@@ -1,23 +1,40 @@
 //! Core application framework functionality.
 
-use crate::config::ApplicationConfigProvider;
+#[cfg(feature = "tokio-runtime")]
+use crate::config::configured_runtime;
+use crate::config::source::{
+    cast_override_source, ConfigMap, ConfigSourcePtr, OverrideConfigSource,
+};
+use crate::config::{
+    convert_error, ApplicationConfig, ApplicationConfigProviderPtr, ConfigContextFactory,
+};
+use crate::context::{ActiveProfiles, ApplicationContext};
+use crate::initializer::ApplicationInitializerPtr;
+use crate::logger::TracingConfigurerPtr;
 use crate::runner::ApplicationRunnerPtr;
-use derive_more::Constructor;
 #[cfg(feature = "async")]
-use futures::future::try_join_all;
-use springtime_di::component_registry::ComponentDefinitionRegistryError;
-use springtime_di::factory::{ComponentFactory, ComponentFactoryBuilder};
+use futures::future::{join_all, select, try_join_all, Either};
 #[cfg(feature = "async")]
-use springtime_di::instance_provider::ComponentInstancePtr;
+use futures::pin_mut;
+use springtime_di::component_registry::export::{
+    ComponentRegistryDiagnostics, ComponentRegistryExport,
+};
+use springtime_di::component_registry::internal::ComponentInstanceBounds;
+use springtime_di::component_registry::{
+    ComponentAliasMetadata, ComponentDefinitionRegistry, ComponentDefinitionRegistryError,
+};
+use springtime_di::factory::{ComponentFactory, ComponentFactoryBuilder};
 use springtime_di::instance_provider::{
-    ComponentInstanceProvider, ComponentInstanceProviderError, ErrorPtr,
+    ComponentInstanceProvider, ComponentInstanceProviderError, ComponentInstancePtr, ErrorPtr,
     TypedComponentInstanceProvider,
 };
+use std::any::{type_name, TypeId};
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
+use std::env;
+use std::io;
 use thiserror::Error;
 use tracing::{dispatcher, error, info};
-use tracing_subscriber::layer::SubscriberExt;
-use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{fmt, EnvFilter};
 
 #[derive(Clone, Error, Debug)]
 pub enum ApplicationError {
@@ -27,37 +44,125 @@ pub enum ApplicationError {
     /// A runner returned an error.
     #[error("Runner error: {0}")]
     RunnerError(ErrorPtr),
-    /// Cannot find any [ApplicationConfigProvider].
+    /// Runners returned errors, when
+    /// [aggregate_runner_errors](crate::config::ApplicationConfig::aggregate_runner_errors) is
+    /// enabled.
+    #[error("Runner errors: {}", join_errors(.0))]
+    AggregatedRunnerErrors(Vec<ErrorPtr>),
+    /// Cannot find any [ApplicationConfigProvider](crate::config::ApplicationConfigProvider).
     #[error("Cannot retrieve application config provider: {0}")]
     MissingApplicationConfigProvider(ComponentInstanceProviderError),
     /// An error occurred while creating the default [Application].
     #[error("Error creating default application: {0}")]
     DefaultInitializationError(ComponentDefinitionRegistryError),
-    /// [ApplicationConfigProvider] returned an error.
+    /// [ApplicationConfigProvider](crate::config::ApplicationConfigProvider) returned an error.
     #[error("Cannot retrieve application config: {0}")]
     CannotRetrieveApplicationConfig(ErrorPtr),
+    /// Cannot find any [TracingConfigurer](crate::logger::TracingConfigurer).
+    #[error("Cannot retrieve tracing configurer: {0}")]
+    MissingTracingConfigurer(ComponentInstanceProviderError),
+    /// [TracingConfigurer](crate::logger::TracingConfigurer) returned an error.
+    #[error("Cannot configure tracing: {0}")]
+    CannotConfigureTracing(ErrorPtr),
+    /// There was an error retrieving application initializers from the component instance factory.
+    #[error("Error retrieving initializers: {0}")]
+    InitializerInjectionError(ComponentInstanceProviderError),
+    /// An initializer returned an error.
+    #[error("Initializer error: {0}")]
+    InitializerError(ErrorPtr),
+    /// Cannot retrieve the [ApplicationContext].
+    #[error("Cannot retrieve application context: {0}")]
+    MissingApplicationContext(ComponentInstanceProviderError),
+    /// Cannot write the component registry export or diagnostics.
+    #[error("Cannot dump components: {0}")]
+    CannotDumpComponents(ErrorPtr),
+    /// Cannot create the Tokio runtime in
+    /// [run_blocking](Application::run_blocking).
+    #[error("Cannot create runtime: {0}")]
+    CannotCreateRuntime(ErrorPtr),
+    /// There was an error creating singletons up front, when
+    /// [eager singletons](ApplicationBuilder::with_eager_singletons) are enabled.
+    #[error("Error eagerly creating singletons: {0}")]
+    EagerInitializationError(ComponentInstanceProviderError),
 }
 
+/// Command line flag which makes the [Application] print the JSON
+/// [export](springtime_di::component_registry::export) of the component registry to the standard
+/// output and exit without running anything.
+pub const DUMP_COMPONENTS_ARGUMENT: &str = "--dump-components";
+
+/// Command line flag which makes the [Application] log JSON
+/// [diagnostics](springtime_di::component_registry::export::ComponentRegistryDiagnostics) of the
+/// component registry at startup, before running as usual.
+pub const LOG_COMPONENT_DIAGNOSTICS_ARGUMENT: &str = "--log-component-diagnostics";
+
 /// Main entrypoint for the application. Bootstraps the application and runs
 /// [ApplicationRunners](crate::runner::ApplicationRunner).
-#[derive(Constructor)]
 #[cfg(feature = "async")]
 pub struct Application<CIP: ComponentInstanceProvider + Send + Sync> {
     instance_provider: CIP,
+    config: Option<ApplicationConfig>,
+    logger_enabled: bool,
+    eager_singletons: bool,
+    component_export: Option<ComponentRegistryExport>,
+    component_diagnostics: Option<ComponentRegistryDiagnostics>,
 }
 
 /// Main entrypoint for the application. Bootstraps the application and runs
 /// [ApplicationRunners](crate::runner::ApplicationRunner).
-#[derive(Constructor)]
 #[cfg(not(feature = "async"))]
 pub struct Application<CIP: ComponentInstanceProvider> {
     instance_provider: CIP,
+    config: Option<ApplicationConfig>,
+    logger_enabled: bool,
+    eager_singletons: bool,
+    component_export: Option<ComponentRegistryExport>,
+    component_diagnostics: Option<ComponentRegistryDiagnostics>,
 }
 
 #[cfg(feature = "async")]
 impl<CIP: ComponentInstanceProvider + Send + Sync> Application<CIP> {
+    pub fn new(instance_provider: CIP) -> Self {
+        Self {
+            instance_provider,
+            config: None,
+            logger_enabled: true,
+            eager_singletons: false,
+            component_export: None,
+            component_diagnostics: None,
+        }
+    }
+
     pub async fn run(&mut self) -> Result<(), ApplicationError> {
-        let _logger = self.install_logger().await?;
+        if let Some(export) = &self.component_export {
+            return dump_components(export);
+        }
+
+        self.run_initializers().await?;
+
+        let config = self.resolve_config().await?;
+        let _logger = self.install_logger(&config).await?;
+
+        if let Some(diagnostics) = &self.component_diagnostics {
+            log_component_diagnostics(diagnostics)?;
+        }
+
+        if self.eager_singletons {
+            info!("Creating singletons...");
+
+            self.instance_provider
+                .instantiate_all_singletons()
+                .await
+                .map_err(ApplicationError::EagerInitializationError)?;
+        }
+
+        let context = self
+            .instance_provider
+            .primary_instance_typed::<ApplicationContext>()
+            .await
+            .map_err(ApplicationError::MissingApplicationContext)?;
+        let shutdown_handle = context.shutdown_handle();
+        let aggregate_errors = config.aggregate_runner_errors;
 
         info!("Searching for application runners...");
 
@@ -70,54 +175,177 @@ impl<CIP: ComponentInstanceProvider + Send + Sync> Application<CIP> {
                 ApplicationError::RunnerInjectionError(error)
             })?;
 
-        runners.sort_unstable_by_key(|runner| -runner.priority());
+        runners.sort_by(|first, second| compare_runners(first.as_ref(), second.as_ref()));
 
         info!("Running application runners...");
 
+        let mut errors = vec![];
         let mut current_runner_index = 0;
-        while current_runner_index < runners.len() {
-            current_runner_index += run_grouped_by_priority(&runners[current_runner_index..])
+        while current_runner_index < runners.len() && !shutdown_handle.is_shutdown_requested() {
+            let runner_group =
+                run_grouped_by_priority(&runners[current_runner_index..], aggregate_errors);
+            let shutdown = shutdown_handle.wait();
+            pin_mut!(runner_group);
+
+            match select(runner_group, shutdown).await {
+                Either::Left(((runner_count, group_errors), _)) => {
+                    current_runner_index += runner_count;
+
+                    for error in group_errors {
+                        error!(%error, "Error running application runner!");
+
+                        if !aggregate_errors {
+                            return Err(ApplicationError::RunnerError(error));
+                        }
+
+                        errors.push(error);
+                    }
+                }
+                Either::Right(_) => break,
+            }
+        }
+
+        if shutdown_handle.is_shutdown_requested() {
+            info!("Application shutdown requested.");
+        }
+
+        into_result(errors)
+    }
+
+    /// Runs the application on a Tokio runtime created from
+    /// [RuntimeConfig](crate::config::RuntimeConfig), blocking the current thread until
+    /// completion. This removes the need for manual runtime setup in `main()`. Initializers and
+    /// config resolution run on the created runtime, in the same order as in [run](Self::run).
+    #[cfg(feature = "tokio-runtime")]
+    pub fn run_blocking(&mut self) -> Result<(), ApplicationError> {
+        let runtime_config = match &self.config {
+            Some(config) => config.runtime.clone(),
+            None => {
+                configured_runtime().map_err(ApplicationError::CannotRetrieveApplicationConfig)?
+            }
+        };
+
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        builder.enable_all();
+
+        if let Some(worker_threads) = runtime_config.worker_threads {
+            builder.worker_threads(worker_threads);
+        }
+
+        if let Some(thread_name) = &runtime_config.thread_name {
+            builder.thread_name(thread_name);
+        }
+
+        if let Some(max_blocking_threads) = runtime_config.max_blocking_threads {
+            builder.max_blocking_threads(max_blocking_threads);
+        }
+
+        builder
+            .build()
+            .map_err(|error| ApplicationError::CannotCreateRuntime(convert_error(error)))?
+            .block_on(self.run())
+    }
+
+    async fn run_initializers(&mut self) -> Result<(), ApplicationError> {
+        let mut initializers = self
+            .instance_provider
+            .instances_typed::<ApplicationInitializerPtr>()
+            .await
+            .map_err(ApplicationError::InitializerInjectionError)?;
+
+        initializers.sort_by_key(|initializer| Reverse(initializer.priority()));
+
+        for initializer in &initializers {
+            initializer
+                .initialize()
                 .await
-                .map_err(|error| {
-                    error!(%error, "Error running application runner!");
-                    ApplicationError::RunnerError(error)
-                })?;
+                .map_err(ApplicationError::InitializerError)?;
         }
 
         Ok(())
     }
 
-    async fn install_logger(
-        &mut self,
-    ) -> Result<Option<dispatcher::DefaultGuard>, ApplicationError> {
+    async fn resolve_config(&mut self) -> Result<ApplicationConfig, ApplicationError> {
+        if let Some(config) = &self.config {
+            return Ok(config.clone());
+        }
+
         let config_provider = self
             .instance_provider
-            .primary_instance_typed::<dyn ApplicationConfigProvider + Send + Sync>()
+            .primary_instance_typed::<ApplicationConfigProviderPtr>()
             .await
             .map_err(ApplicationError::MissingApplicationConfigProvider)?;
 
-        let config = config_provider
+        config_provider
             .config()
             .await
-            .map_err(ApplicationError::CannotRetrieveApplicationConfig)?;
+            .cloned()
+            .map_err(ApplicationError::CannotRetrieveApplicationConfig)
+    }
 
-        if !config.install_tracing_logger {
+    async fn install_logger(
+        &mut self,
+        config: &ApplicationConfig,
+    ) -> Result<Option<dispatcher::DefaultGuard>, ApplicationError> {
+        if !self.logger_enabled || !config.install_tracing_logger {
             return Ok(None);
         }
 
-        Ok(Some(
-            tracing_subscriber::registry()
-                .with(EnvFilter::from_default_env())
-                .with(fmt::layer())
-                .set_default(),
-        ))
+        let tracing_configurer = self
+            .instance_provider
+            .primary_instance_typed::<TracingConfigurerPtr>()
+            .await
+            .map_err(ApplicationError::MissingTracingConfigurer)?;
+
+        tracing_configurer
+            .create_dispatch(config)
+            .map(|dispatch| Some(dispatcher::set_default(&dispatch)))
+            .map_err(ApplicationError::CannotConfigureTracing)
     }
 }
 
 #[cfg(not(feature = "async"))]
 impl<CIP: ComponentInstanceProvider> Application<CIP> {
+    pub fn new(instance_provider: CIP) -> Self {
+        Self {
+            instance_provider,
+            config: None,
+            logger_enabled: true,
+            eager_singletons: false,
+            component_export: None,
+            component_diagnostics: None,
+        }
+    }
+
     pub fn run(&mut self) -> Result<(), ApplicationError> {
-        let _logger = self.install_logger()?;
+        if let Some(export) = &self.component_export {
+            return dump_components(export);
+        }
+
+        self.run_initializers()?;
+
+        let config = self.resolve_config()?;
+        let _logger = self.install_logger(&config)?;
+
+        if let Some(diagnostics) = &self.component_diagnostics {
+            log_component_diagnostics(diagnostics)?;
+        }
+
+        if self.eager_singletons {
+            info!("Creating singletons...");
+
+            self.instance_provider
+                .instantiate_all_singletons()
+                .map_err(ApplicationError::EagerInitializationError)?;
+        }
+
+        let context = self
+            .instance_provider
+            .primary_instance_typed::<ApplicationContext>()
+            .map_err(ApplicationError::MissingApplicationContext)?;
+        let shutdown_handle = context.shutdown_handle();
+        let aggregate_errors = config.aggregate_runner_errors;
+        let runner_threads = config.runner_threads;
 
         info!("Searching for application runners...");
 
@@ -129,116 +357,519 @@ impl<CIP: ComponentInstanceProvider> Application<CIP> {
                 ApplicationError::RunnerInjectionError(error)
             })?;
 
-        runners.sort_unstable_by_key(|runner| -runner.priority());
+        runners.sort_by(|first, second| compare_runners(first.as_ref(), second.as_ref()));
 
         info!("Running application runners...");
 
-        for runner in &runners {
-            runner.run().map_err(|error| {
+        let mut errors = vec![];
+        let mut current_runner_index = 0;
+        while current_runner_index < runners.len() {
+            if shutdown_handle.is_shutdown_requested() {
+                info!("Application shutdown requested.");
+                break;
+            }
+
+            // runners are grouped only if they can be run in parallel
+            let group_size = if cfg!(feature = "threadsafe") && runner_threads > 1 {
+                priority_group_size(&runners[current_runner_index..])
+            } else {
+                1
+            };
+
+            let runner_group = &runners[current_runner_index..current_runner_index + group_size];
+            current_runner_index += group_size;
+
+            for error in run_runner_group(runner_group, runner_threads) {
                 error!(%error, "Error running application runner!");
-                ApplicationError::RunnerError(error)
-            })?;
+
+                if !aggregate_errors {
+                    return Err(ApplicationError::RunnerError(error));
+                }
+
+                errors.push(error);
+            }
         }
 
-        Ok(())
+        into_result(errors)
     }
 
-    fn install_logger(&mut self) -> Result<Option<dispatcher::DefaultGuard>, ApplicationError> {
-        #[cfg(feature = "threadsafe")]
-        type ProviderType = dyn ApplicationConfigProvider + Send + Sync;
+    fn run_initializers(&mut self) -> Result<(), ApplicationError> {
+        let mut initializers = self
+            .instance_provider
+            .instances_typed::<ApplicationInitializerPtr>()
+            .map_err(ApplicationError::InitializerInjectionError)?;
+
+        initializers.sort_by_key(|initializer| Reverse(initializer.priority()));
+
+        for initializer in &initializers {
+            initializer
+                .initialize()
+                .map_err(ApplicationError::InitializerError)?;
+        }
+
+        Ok(())
+    }
 
-        #[cfg(not(feature = "threadsafe"))]
-        type ProviderType = dyn ApplicationConfigProvider;
+    fn resolve_config(&mut self) -> Result<ApplicationConfig, ApplicationError> {
+        if let Some(config) = &self.config {
+            return Ok(config.clone());
+        }
 
         let config_provider = self
             .instance_provider
-            .primary_instance_typed::<ProviderType>()
+            .primary_instance_typed::<ApplicationConfigProviderPtr>()
             .map_err(ApplicationError::MissingApplicationConfigProvider)?;
 
-        let config = config_provider
+        config_provider
             .config()
-            .map_err(ApplicationError::CannotRetrieveApplicationConfig)?;
+            .cloned()
+            .map_err(ApplicationError::CannotRetrieveApplicationConfig)
+    }
 
-        if !config.install_tracing_logger {
+    fn install_logger(
+        &mut self,
+        config: &ApplicationConfig,
+    ) -> Result<Option<dispatcher::DefaultGuard>, ApplicationError> {
+        if !self.logger_enabled || !config.install_tracing_logger {
             return Ok(None);
         }
 
-        Ok(Some(
-            tracing_subscriber::registry()
-                .with(EnvFilter::from_default_env())
-                .with(fmt::layer())
-                .set_default(),
-        ))
+        let tracing_configurer = self
+            .instance_provider
+            .primary_instance_typed::<TracingConfigurerPtr>()
+            .map_err(ApplicationError::MissingTracingConfigurer)?;
+
+        tracing_configurer
+            .create_dispatch(config)
+            .map(|dispatch| Some(dispatcher::set_default(&dispatch)))
+            .map_err(ApplicationError::CannotConfigureTracing)
+    }
+}
+
+type InstanceSeeder = Box<
+    dyn FnOnce(
+        ComponentFactoryBuilder,
+    ) -> Result<ComponentFactoryBuilder, ComponentDefinitionRegistryError>,
+>;
+
+/// Builder for [Application], for cases when [create_default] is not enough, e.g. when embedding
+/// the framework into an existing binary.
+pub struct ApplicationBuilder {
+    component_factory: Option<ComponentFactory>,
+    config: Option<ApplicationConfig>,
+    profiles: Option<Vec<String>>,
+    config_overrides: Option<ConfigMap>,
+    instances: Vec<InstanceSeeder>,
+    logger_enabled: bool,
+    eager_singletons: bool,
+    dump_components: bool,
+    log_component_diagnostics: bool,
+}
+
+impl ApplicationBuilder {
+    /// Creates a new builder with a default configuration.
+    pub fn new() -> Self {
+        Self {
+            component_factory: None,
+            config: None,
+            profiles: None,
+            config_overrides: None,
+            instances: vec![],
+            logger_enabled: true,
+            eager_singletons: false,
+            dump_components: false,
+            log_component_diagnostics: false,
+        }
+    }
+
+    /// Sets the [ComponentFactory] to use, instead of one created with [ComponentFactoryBuilder].
+    /// Primary components chosen in the config are still applied to it.
+    pub fn with_component_factory(mut self, component_factory: ComponentFactory) -> Self {
+        self.component_factory = Some(component_factory);
+        self
+    }
+
+    /// Sets the config to use, instead of the one returned by the primary
+    /// [ApplicationConfigProvider](crate::config::ApplicationConfigProvider).
+    pub fn with_config(mut self, config: ApplicationConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Sets active profiles, instead of the ones read from the `profiles` config key. Profiles are
+    /// used when registering components, so they have no effect on a custom
+    /// [component factory](Self::with_component_factory).
+    pub fn with_profiles(mut self, profiles: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.profiles = Some(profiles.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Adds an already created instance as the [SINGLETON](springtime_di::scope::SINGLETON) for
+    /// `T`, e.g. a connection pool shared with the embedding binary. See
+    /// [ComponentFactoryBuilder::with_instance]. Instances are added to the component factory
+    /// created by this builder, so they have no effect on a custom
+    /// [component factory](Self::with_component_factory).
+    pub fn with_instance<T: ComponentInstanceBounds + 'static>(
+        mut self,
+        instance: impl Into<ComponentInstancePtr<T>>,
+    ) -> Self {
+        let instance = instance.into();
+        self.instances.push(Box::new(move |builder| {
+            builder.with_instance::<T>(instance)
+        }));
+        self
+    }
+
+    /// Overrides config values with a [ConfigSource](crate::config::source::ConfigSource) having
+    /// the highest priority. Overrides are also visible to registration conditions, e.g. the
+    /// `profiles` key selects active profiles. Like [instances](Self::with_instance), overrides
+    /// have no effect on a custom [component factory](Self::with_component_factory), apart from
+    /// selecting primary components.
+    pub(crate) fn with_config_overrides(mut self, config_overrides: ConfigMap) -> Self {
+        self.config_overrides = Some(config_overrides);
+        self
+    }
+
+    /// Disables installing the tracing logger, regardless of
+    /// [install_tracing_logger](ApplicationConfig::install_tracing_logger). Useful when the
+    /// embedding binary has its own logging set up.
+    pub fn without_logger(mut self) -> Self {
+        self.logger_enabled = false;
+        self
+    }
+
+    /// Makes the [Application] create all singletons before running, so configuration and
+    /// construction errors surface at startup instead of on first use.
+    pub fn with_eager_singletons(mut self) -> Self {
+        self.eager_singletons = true;
+        self
+    }
+
+    /// Makes the [Application] print the component registry export instead of running, as if
+    /// [DUMP_COMPONENTS_ARGUMENT] was passed on the command line.
+    pub fn with_component_dump(mut self) -> Self {
+        self.dump_components = true;
+        self
+    }
+
+    /// Makes the [Application] log component registry diagnostics at startup, as if
+    /// [LOG_COMPONENT_DIAGNOSTICS_ARGUMENT] was passed on the command line. Useful for verifying
+    /// which components were actually assembled in a given deployment.
+    pub fn with_component_diagnostics(mut self) -> Self {
+        self.log_component_diagnostics = true;
+        self
+    }
+
+    /// Builds the [Application].
+    pub fn build(self) -> Result<Application<ComponentFactory>, ApplicationError> {
+        let mut context_factory = match &self.config_overrides {
+            Some(config_overrides) => {
+                ConfigContextFactory::new_with_overrides(config_overrides.clone())
+            }
+            None => ConfigContextFactory::new(),
+        };
+        let mut component_factory = match self.component_factory {
+            Some(component_factory) => component_factory,
+            None => {
+                if let Some(profiles) = self.profiles {
+                    context_factory = context_factory.with_profiles(profiles);
+                }
+
+                let active_profiles = ActiveProfiles::new(context_factory.active_profiles());
+                let mut builder =
+                    ComponentFactoryBuilder::new_with_context_factory(&context_factory)
+                        .and_then(|builder| builder.with_instance(active_profiles))
+                        .map_err(ApplicationError::DefaultInitializationError)?;
+
+                for seed in self.instances {
+                    builder =
+                        seed(builder).map_err(ApplicationError::DefaultInitializationError)?;
+                }
+
+                match self.config_overrides {
+                    Some(config_overrides) => builder
+                        .with_instance(OverrideConfigSource::new(config_overrides))
+                        .map(|builder| builder.build())
+                        .and_then(|mut component_factory| {
+                            register_override_source(&mut component_factory)
+                                .map(|_| component_factory)
+                        })
+                        .map_err(ApplicationError::DefaultInitializationError)?,
+                    None => builder.build(),
+                }
+            }
+        };
+
+        // primary components come from config, so they apply to custom factories as well
+        let primary_components = context_factory
+            .primary_components()
+            .map_err(ApplicationError::CannotRetrieveApplicationConfig)?;
+        select_primary_components(&mut component_factory, primary_components)
+            .map_err(ApplicationError::DefaultInitializationError)?;
+
+        let component_export = (self.dump_components
+            || env::args_os().any(|argument| argument == DUMP_COMPONENTS_ARGUMENT))
+        .then(|| component_factory.export_definitions());
+        let component_diagnostics = (self.log_component_diagnostics
+            || env::args_os().any(|argument| argument == LOG_COMPONENT_DIAGNOSTICS_ARGUMENT))
+        .then(|| component_factory.export_definitions().diagnostics());
+
+        Ok(Application {
+            instance_provider: component_factory,
+            config: self.config,
+            logger_enabled: self.logger_enabled,
+            eager_singletons: self.eager_singletons,
+            component_export,
+            component_diagnostics,
+        })
+    }
+}
+
+impl Default for ApplicationBuilder {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 /// Creates an [Application] with a sensible default configuration.
 pub fn create_default() -> Result<Application<ComponentFactory>, ApplicationError> {
-    let component_factory = ComponentFactoryBuilder::new()
-        .map_err(ApplicationError::DefaultInitializationError)?
-        .build();
+    ApplicationBuilder::new().build()
+}
+
+fn select_primary_components(
+    component_factory: &mut ComponentFactory,
+    primary_components: HashMap<String, String>,
+) -> Result<(), ComponentDefinitionRegistryError> {
+    for (type_name, name) in primary_components {
+        let type_id = component_factory.type_id_by_name(&type_name)?;
+        component_factory.set_primary_component(type_id, &name)?;
+    }
 
-    Ok(Application::new(component_factory))
+    Ok(())
+}
+
+// the seeded instance is registered only as itself, so it needs to be exposed as a source
+fn register_override_source(
+    component_factory: &mut ComponentFactory,
+) -> Result<(), ComponentDefinitionRegistryError> {
+    component_factory.register_alias(
+        TypeId::of::<ConfigSourcePtr>(),
+        TypeId::of::<OverrideConfigSource>(),
+        type_name::<ConfigSourcePtr>(),
+        type_name::<OverrideConfigSource>(),
+        &ComponentAliasMetadata {
+            is_primary: false,
+            scope: None,
+            cast: cast_override_source,
+        },
+    )
+}
+
+fn compare_runners(first: &ApplicationRunnerPtr, second: &ApplicationRunnerPtr) -> Ordering {
+    second
+        .priority()
+        .cmp(&first.priority())
+        .then_with(|| first.order().cmp(&second.order()))
+        .then_with(|| first.name().cmp(second.name()))
+}
+
+fn dump_components(export: &ComponentRegistryExport) -> Result<(), ApplicationError> {
+    serde_json::to_writer_pretty(io::stdout().lock(), export)
+        .map_err(|error| ApplicationError::CannotDumpComponents(convert_error(error)))
+}
+
+fn log_component_diagnostics(
+    diagnostics: &ComponentRegistryDiagnostics,
+) -> Result<(), ApplicationError> {
+    let diagnostics = serde_json::to_string(diagnostics)
+        .map_err(|error| ApplicationError::CannotDumpComponents(convert_error(error)))?;
+
+    info!("Component registry diagnostics: {diagnostics}");
+    Ok(())
+}
+
+fn into_result(errors: Vec<ErrorPtr>) -> Result<(), ApplicationError> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ApplicationError::AggregatedRunnerErrors(errors))
+    }
+}
+
+fn join_errors(errors: &[ErrorPtr]) -> String {
+    errors
+        .iter()
+        .map(|error| error.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 // this could be replaced by group_by() from itertools, but it doesn't impl Send
-#[cfg(feature = "async")]
-async fn run_grouped_by_priority(
-    runners: &[ComponentInstancePtr<ApplicationRunnerPtr>],
-) -> Result<usize, ErrorPtr> {
+fn priority_group_size(runners: &[ComponentInstancePtr<ApplicationRunnerPtr>]) -> usize {
     // note: assuming runners are sorted by priority
     let current_priority = runners[0].priority();
-    let first_new_priority_index = runners
+    runners
         .iter()
         .enumerate()
         .find(|(_, entry)| entry.priority() != current_priority)
         .map(|(index, _)| index)
-        .unwrap_or(runners.len());
+        .unwrap_or(runners.len())
+}
 
-    try_join_all(
-        runners[..first_new_priority_index]
+#[cfg(all(not(feature = "async"), feature = "threadsafe"))]
+fn run_runner_group(
+    runners: &[ComponentInstancePtr<ApplicationRunnerPtr>],
+    runner_threads: usize,
+) -> Vec<ErrorPtr> {
+    use std::sync::atomic::{self, AtomicUsize};
+    use std::sync::Mutex;
+    use std::thread;
+
+    if runners.len() == 1 || runner_threads <= 1 {
+        return runners
             .iter()
-            .map(|runner| runner.run()),
-    )
-    .await
-    .map(move |_| first_new_priority_index)
+            .filter_map(|runner| runner.run().err())
+            .collect();
+    }
+
+    let next_runner_index = AtomicUsize::new(0);
+    let errors = Mutex::new(vec![]);
+
+    // the logger is installed only for the current thread
+    let dispatch = dispatcher::get_default(|dispatch| dispatch.clone());
+
+    thread::scope(|scope| {
+        for _ in 0..runner_threads.min(runners.len()) {
+            scope.spawn(|| {
+                dispatcher::with_default(&dispatch, || {
+                    while let Some(runner) =
+                        runners.get(next_runner_index.fetch_add(1, atomic::Ordering::Relaxed))
+                    {
+                        if let Err(error) = runner.run() {
+                            if let Ok(mut errors) = errors.lock() {
+                                errors.push(error);
+                            }
+                        }
+                    }
+                })
+            });
+        }
+    });
+
+    errors.into_inner().unwrap_or_default()
+}
+
+#[cfg(all(not(feature = "async"), not(feature = "threadsafe")))]
+fn run_runner_group(
+    runners: &[ComponentInstancePtr<ApplicationRunnerPtr>],
+    _runner_threads: usize,
+) -> Vec<ErrorPtr> {
+    runners
+        .iter()
+        .filter_map(|runner| runner.run().err())
+        .collect()
+}
+
+#[cfg(feature = "async")]
+async fn run_grouped_by_priority(
+    runners: &[ComponentInstancePtr<ApplicationRunnerPtr>],
+    aggregate_errors: bool,
+) -> (usize, Vec<ErrorPtr>) {
+    let first_new_priority_index = priority_group_size(runners);
+
+    let runners = runners[..first_new_priority_index]
+        .iter()
+        .map(|runner| runner.run());
+
+    let errors = if aggregate_errors {
+        join_all(runners)
+            .await
+            .into_iter()
+            .filter_map(Result::err)
+            .collect()
+    } else {
+        try_join_all(runners).await.err().into_iter().collect()
+    };
+
+    (first_new_priority_index, errors)
 }
 
-#[cfg(test)]
+// application tests rely on async runners
+#[cfg(all(test, feature = "async"))]
 mod tests {
-    use crate::application::{Application, ApplicationError};
-    use crate::config::{ApplicationConfig, ApplicationConfigProvider};
+    use crate::application::{compare_runners, Application, ApplicationBuilder, ApplicationError};
+    use crate::config::{ApplicationConfig, ApplicationConfigProvider, RuntimeConfig};
+    use crate::context::{ActiveProfiles, ApplicationContext, ShutdownHandle};
     use crate::future::BoxFuture;
-    use crate::runner::{ApplicationRunnerPtr, MockApplicationRunner};
+    use crate::initializer::{ApplicationInitializerPtr, MockApplicationInitializer};
+    use crate::logger::{MockTracingConfigurer, TracingConfigurerPtr};
+    use crate::runner::{ApplicationRunner, ApplicationRunnerPtr, MockApplicationRunner};
     use mockall::mock;
     use mockall::predicate::*;
+    #[cfg(feature = "tokio-runtime")]
+    use mockall::Sequence;
+    use springtime_di::event::EventPublisher;
+    use springtime_di::factory::ComponentFactoryBuilder;
     use springtime_di::future::FutureExt;
     use springtime_di::instance_provider::{
-        CastFunction, ComponentInstanceAnyPtr, ComponentInstanceProvider,
-        ComponentInstanceProviderError, ComponentInstancePtr, ErrorPtr,
+        store_cast_result, CastFunction, ComponentInstanceAnyPtr, ComponentInstanceProvider,
+        ComponentInstanceProviderError, ComponentInstancePtr, ErrorPtr, FactoryHandle,
+        TypedComponentInstanceProvider,
     };
     use std::any::{Any, TypeId};
+    use std::cmp::Ordering;
     use std::sync::Arc;
 
     fn mock_cast(
         instance: ComponentInstanceAnyPtr,
-    ) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr> {
+        target: &mut dyn Any,
+    ) -> Result<(), ComponentInstanceAnyPtr> {
         instance
             .downcast::<MockApplicationRunner>()
-            .map(|p| Box::new(p as ComponentInstancePtr<ApplicationRunnerPtr>) as Box<dyn Any>)
+            .map(|p| store_cast_result(p as ComponentInstancePtr<ApplicationRunnerPtr>, target))
     }
 
     fn config_cast(
         instance: ComponentInstanceAnyPtr,
-    ) -> Result<Box<dyn Any>, ComponentInstanceAnyPtr> {
+        target: &mut dyn Any,
+    ) -> Result<(), ComponentInstanceAnyPtr> {
         instance
             .downcast::<MockApplicationConfigProvider>()
             .map(|p| {
-                Box::new(p as ComponentInstancePtr<dyn ApplicationConfigProvider + Send + Sync>)
-                    as Box<dyn Any>
+                store_cast_result(
+                    p as ComponentInstancePtr<dyn ApplicationConfigProvider + Send + Sync>,
+                    target,
+                )
             })
     }
 
+    fn initializer_cast(
+        instance: ComponentInstanceAnyPtr,
+        target: &mut dyn Any,
+    ) -> Result<(), ComponentInstanceAnyPtr> {
+        instance.downcast::<MockApplicationInitializer>().map(|p| {
+            store_cast_result(p as ComponentInstancePtr<ApplicationInitializerPtr>, target)
+        })
+    }
+
+    fn tracing_configurer_cast(
+        instance: ComponentInstanceAnyPtr,
+        target: &mut dyn Any,
+    ) -> Result<(), ComponentInstanceAnyPtr> {
+        instance
+            .downcast::<MockTracingConfigurer>()
+            .map(|p| store_cast_result(p as ComponentInstancePtr<TracingConfigurerPtr>, target))
+    }
+
+    fn context_cast(
+        instance: ComponentInstanceAnyPtr,
+        target: &mut dyn Any,
+    ) -> Result<(), ComponentInstanceAnyPtr> {
+        instance
+            .downcast::<ApplicationContext>()
+            .map(|p| store_cast_result(p, target))
+    }
+
     mock! {
         ComponentInstanceProvider {}
 
@@ -267,11 +898,22 @@ mod tests {
                 '_,
                 Result<(ComponentInstanceAnyPtr, CastFunction), ComponentInstanceProviderError>,
             >;
+
+            fn instantiate_all_singletons(
+                &mut self,
+            ) -> BoxFuture<'_, Result<(), ComponentInstanceProviderError>>;
         }
     }
 
     const CONFIG: ApplicationConfig = ApplicationConfig {
         install_tracing_logger: false,
+        aggregate_runner_errors: false,
+        runner_threads: 1,
+        runtime: RuntimeConfig {
+            worker_threads: None,
+            thread_name: None,
+            max_blocking_threads: None,
+        },
     };
 
     #[derive(Default)]
@@ -284,6 +926,16 @@ mod tests {
     }
 
     fn create_instance_provider() -> MockComponentInstanceProvider {
+        let mut instance_provider = create_instance_provider_without_initializers();
+        instance_provider
+            .expect_instances()
+            .with(eq(TypeId::of::<ApplicationInitializerPtr>()))
+            .returning(|_| async { Ok(vec![]) }.boxed());
+
+        instance_provider
+    }
+
+    fn create_instance_provider_without_initializers() -> MockComponentInstanceProvider {
         let application_config_provider =
             ComponentInstancePtr::new(MockApplicationConfigProvider::default());
 
@@ -304,6 +956,31 @@ mod tests {
                 .boxed()
             });
 
+        let component_factory = ComponentFactoryBuilder::new().unwrap().build();
+        let application_context = ComponentInstancePtr::new(ApplicationContext::new(
+            ComponentInstancePtr::new(MockApplicationConfigProvider::default()),
+            FactoryHandle::new(&component_factory).unwrap(),
+            ComponentInstancePtr::new(EventPublisher::new(
+                FactoryHandle::new(&component_factory).unwrap(),
+            )),
+            ComponentInstancePtr::new(ActiveProfiles::new(Vec::<String>::new())),
+            ShutdownHandle::default(),
+        ));
+
+        instance_provider
+            .expect_primary_instance()
+            .with(eq(TypeId::of::<ApplicationContext>()))
+            .returning(move |_| {
+                let application_context = application_context.clone();
+                async move {
+                    Ok((
+                        application_context as ComponentInstanceAnyPtr,
+                        context_cast as CastFunction,
+                    ))
+                }
+                .boxed()
+            });
+
         instance_provider
     }
 
@@ -333,6 +1010,75 @@ mod tests {
         ));
     }
 
+    #[cfg(feature = "tokio-runtime")]
+    #[test]
+    fn should_run_blocking() {
+        let type_id = TypeId::of::<ApplicationRunnerPtr>();
+
+        let mut instance_provider = create_instance_provider();
+        instance_provider
+            .expect_instances()
+            .with(eq(type_id))
+            .times(1)
+            .returning(|_| async { Ok(vec![]) }.boxed());
+
+        let mut application = Application::new(instance_provider);
+        application.run_blocking().unwrap();
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    #[test]
+    fn should_run_initializers_before_resolving_config_when_blocking() {
+        let application_config_provider =
+            ComponentInstancePtr::new(MockApplicationConfigProvider::default());
+
+        let mut sequence = Sequence::new();
+        let mut instance_provider = MockComponentInstanceProvider::new();
+        instance_provider
+            .expect_instances()
+            .with(eq(TypeId::of::<ApplicationInitializerPtr>()))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_| async { Ok(vec![]) }.boxed());
+        instance_provider
+            .expect_primary_instance()
+            .with(eq(
+                TypeId::of::<dyn ApplicationConfigProvider + Send + Sync>(),
+            ))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(move |_| {
+                let application_config_provider = application_config_provider.clone();
+                async move {
+                    Ok((
+                        application_config_provider as ComponentInstanceAnyPtr,
+                        config_cast as CastFunction,
+                    ))
+                }
+                .boxed()
+            });
+        instance_provider
+            .expect_primary_instance()
+            .with(eq(TypeId::of::<ApplicationContext>()))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|type_id| {
+                async move {
+                    Err(ComponentInstanceProviderError::NoPrimaryInstance {
+                        type_id,
+                        type_name: None,
+                    })
+                }
+                .boxed()
+            });
+
+        let mut application = Application::new(instance_provider);
+        assert!(matches!(
+            application.run_blocking(),
+            Err(ApplicationError::MissingApplicationContext(_))
+        ));
+    }
+
     #[tokio::test]
     async fn should_return_runner_error() {
         let type_id = TypeId::of::<ApplicationRunnerPtr>();
@@ -370,4 +1116,204 @@ mod tests {
             ApplicationError::RunnerError(_)
         ));
     }
+
+    #[tokio::test]
+    async fn should_return_initializer_error() {
+        let mut instance_provider = create_instance_provider_without_initializers();
+        instance_provider
+            .expect_instances()
+            .with(eq(TypeId::of::<ApplicationInitializerPtr>()))
+            .times(1)
+            .returning(|_| {
+                let mut initializer = MockApplicationInitializer::new();
+                initializer.expect_initialize().returning(|| {
+                    async {
+                        Err(Arc::new(ComponentInstanceProviderError::NoPrimaryInstance {
+                            type_id: TypeId::of::<i8>(),
+                            type_name: None,
+                        }) as ErrorPtr)
+                    }
+                    .boxed()
+                });
+                initializer.expect_priority().return_const(0);
+
+                async {
+                    Ok(vec![(
+                        ComponentInstancePtr::new(initializer) as ComponentInstanceAnyPtr,
+                        initializer_cast as CastFunction,
+                    )])
+                }
+                .boxed()
+            });
+
+        let mut application = Application::new(instance_provider);
+        assert!(matches!(
+            application.run().await.unwrap_err(),
+            ApplicationError::InitializerError(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_return_tracing_configurer_error() {
+        let mut instance_provider = create_instance_provider();
+        instance_provider
+            .expect_primary_instance()
+            .with(eq(TypeId::of::<TracingConfigurerPtr>()))
+            .times(1)
+            .returning(|_| {
+                let mut tracing_configurer = MockTracingConfigurer::new();
+                tracing_configurer.expect_create_dispatch().returning(|_| {
+                    Err(Arc::new(ComponentInstanceProviderError::NoPrimaryInstance {
+                        type_id: TypeId::of::<i8>(),
+                        type_name: None,
+                    }) as ErrorPtr)
+                });
+
+                async {
+                    Ok((
+                        ComponentInstancePtr::new(tracing_configurer) as ComponentInstanceAnyPtr,
+                        tracing_configurer_cast as CastFunction,
+                    ))
+                }
+                .boxed()
+            });
+
+        let mut application = Application::new(instance_provider);
+        application.config = Some(ApplicationConfig {
+            install_tracing_logger: true,
+            ..CONFIG
+        });
+
+        assert!(matches!(
+            application.run().await.unwrap_err(),
+            ApplicationError::CannotConfigureTracing(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_return_missing_tracing_configurer_error() {
+        let type_id = TypeId::of::<TracingConfigurerPtr>();
+
+        let mut instance_provider = create_instance_provider();
+        instance_provider
+            .expect_primary_instance()
+            .with(eq(type_id))
+            .times(1)
+            .returning(move |_| {
+                async move {
+                    Err(ComponentInstanceProviderError::NoPrimaryInstance {
+                        type_id,
+                        type_name: None,
+                    })
+                }
+                .boxed()
+            });
+
+        let mut application = Application::new(instance_provider);
+        application.config = Some(ApplicationConfig {
+            install_tracing_logger: true,
+            ..CONFIG
+        });
+
+        assert!(matches!(
+            application.run().await.unwrap_err(),
+            ApplicationError::MissingTracingConfigurer(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_return_eager_initialization_error() {
+        let mut instance_provider = create_instance_provider();
+        instance_provider
+            .expect_instantiate_all_singletons()
+            .times(1)
+            .returning(|| {
+                async {
+                    Err(ComponentInstanceProviderError::NoPrimaryInstance {
+                        type_id: TypeId::of::<i8>(),
+                        type_name: None,
+                    })
+                }
+                .boxed()
+            });
+
+        let mut application = Application::new(instance_provider);
+        application.eager_singletons = true;
+
+        assert!(matches!(
+            application.run().await.unwrap_err(),
+            ApplicationError::EagerInitializationError(_)
+        ));
+    }
+
+    struct TestRunner {
+        priority: i8,
+        order: i32,
+        name: &'static str,
+    }
+
+    impl ApplicationRunner for TestRunner {
+        fn run(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+            async { Ok(()) }.boxed()
+        }
+
+        fn priority(&self) -> i8 {
+            self.priority
+        }
+
+        fn order(&self) -> i32 {
+            self.order
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+    }
+
+    struct EmbeddedState(i32);
+
+    #[tokio::test]
+    async fn should_build_with_profiles_and_instances() {
+        let mut application = ApplicationBuilder::new()
+            .with_profiles(["embedded"])
+            .with_instance(EmbeddedState(5))
+            .build()
+            .unwrap();
+
+        let profiles = application
+            .instance_provider
+            .primary_instance_typed::<ActiveProfiles>()
+            .await
+            .unwrap();
+        assert_eq!(profiles.profiles(), ["embedded"]);
+
+        let state = application
+            .instance_provider
+            .primary_instance_typed::<EmbeddedState>()
+            .await
+            .unwrap();
+        assert_eq!(state.0, 5);
+    }
+
+    #[test]
+    fn should_order_runners_deterministically() {
+        let runner = |priority, order, name| TestRunner {
+            priority,
+            order,
+            name,
+        };
+
+        assert_eq!(
+            compare_runners(&runner(1, 0, "b"), &runner(0, 0, "a")),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_runners(&runner(0, 1, "a"), &runner(0, 0, "b")),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_runners(&runner(0, 0, "a"), &runner(0, 0, "b")),
+            Ordering::Less
+        );
+    }
 }
@@ -3,30 +3,57 @@
 //! this config to configure itself, but it can also be injected into any other component.
 //!
 //! By default, the config is created with opinionated default values, which can then be overwritten
-//! by environment variables prefixed with `SPRINGTIME_` or `springtime.json` file.
+//! by `springtime.json` file, any registered [ConfigSource](source::ConfigSource), environment
+//! variables prefixed with `SPRINGTIME_` or `--springtime.key=value` command line arguments. Other
+//! crates can read their own [ConfigSection] from the same sources using [MergedConfig].
+//!
+//! Active [profiles](springtime_di::component_registry::conditional) for the default
+//! [Application](crate::application::Application) are read from the `profiles` key - either a list
+//! or a comma-separated string, e.g. `SPRINGTIME_PROFILES=dev,local`. Registration conditions can
+//! also read configuration properties with [config_property_equals] - see [ConfigContextFactory].
+//!
+//! Primary components can be chosen with the `primary` key, which maps type names to component
+//! names, e.g. `"primary": {"dyn Storage": "s3_storage"}`, overriding the `primary` attributes of
+//! aliases. Types can be named with their full or short names - see
+//! [type_id_by_name](springtime_di::component_registry::ComponentDefinitionRegistry::type_id_by_name).
+
+pub mod source;
 
-use config::{Config, ConfigError, Environment, File};
+use crate::config::source::{ConfigMap, ConfigSource, ConfigSourcePtr};
+use config::{Config, ConfigError, Environment, File, Map, Source, Value};
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use springtime_di::component_registry::conditional::unregistered_component;
+use springtime_di::component_registry::conditional::{
+    parse_profiles, unregistered_component, ComponentDefinitionRegistryFacade, Condition,
+    ConditionMetadata, Context, ContextFactory, ProfileContextFactory,
+};
 #[cfg(feature = "async")]
 use springtime_di::future::{BoxFuture, FutureExt};
-use springtime_di::instance_provider::ErrorPtr;
+use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
 use springtime_di::{component_alias, injectable, Component};
+use std::collections::HashMap;
+use std::env;
 use std::error::Error;
+use std::ffi::OsString;
 
 const CONFIG_ENV_PREFIX: &str = "SPRINGTIME";
+const CLI_ARGUMENT_PREFIX: &str = "--springtime.";
+const PROFILES_KEY: &str = "profiles";
+const PRIMARY_KEY: &str = "primary";
+#[cfg(feature = "tokio-runtime")]
+const RUNTIME_KEY: &str = "runtime";
 
 /// Name of the default config file.
 pub const CONFIG_FILE: &str = "springtime.json";
 
 #[cfg(feature = "threadsafe")]
-fn convert_error<E: Error + Send + Sync + 'static>(error: E) -> ErrorPtr {
+pub(crate) fn convert_error<E: Error + Send + Sync + 'static>(error: E) -> ErrorPtr {
     use std::sync::Arc;
     Arc::new(error) as ErrorPtr
 }
 
 #[cfg(not(feature = "threadsafe"))]
-fn convert_error<E: Error + 'static>(error: E) -> ErrorPtr {
+pub(crate) fn convert_error<E: Error + 'static>(error: E) -> ErrorPtr {
     use std::rc::Rc;
     Rc::new(error) as ErrorPtr
 }
@@ -38,26 +65,393 @@ fn convert_error<E: Error + 'static>(error: E) -> ErrorPtr {
 pub struct ApplicationConfig {
     /// Should a default tracing logger be installed in the scope of the application.
     pub install_tracing_logger: bool,
+    /// Should all [ApplicationRunners](crate::runner::ApplicationRunner) be run, even if some of
+    /// them fail. All encountered errors are then returned together.
+    pub aggregate_runner_errors: bool,
+    /// Number of threads used to run [ApplicationRunners](crate::runner::ApplicationRunner) with
+    /// the same priority, when the `async` feature is disabled and `threadsafe` is enabled. Default
+    /// 1, which means runners are run sequentially.
+    pub runner_threads: usize,
+    /// Configuration of the Tokio runtime created by
+    /// [run_blocking](crate::application::Application::run_blocking).
+    pub runtime: RuntimeConfig,
 }
 
 impl Default for ApplicationConfig {
     fn default() -> Self {
         Self {
             install_tracing_logger: true,
+            aggregate_runner_errors: false,
+            runner_threads: 1,
+            runtime: Default::default(),
+        }
+    }
+}
+
+/// Configuration of the Tokio runtime created by
+/// [run_blocking](crate::application::Application::run_blocking), available with the
+/// `tokio-runtime` feature. Unset values use Tokio defaults. Since the runtime is created before
+/// any component, this config is read from the config file, environment variables and command line
+/// arguments only, unless the whole config is set with
+/// [with_config](crate::application::ApplicationBuilder::with_config).
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RuntimeConfig {
+    /// Number of worker threads.
+    pub worker_threads: Option<usize>,
+    /// Name of the runtime threads.
+    pub thread_name: Option<String>,
+    /// Maximum number of threads in the blocking pool.
+    pub max_blocking_threads: Option<usize>,
+}
+
+/// Typed configuration section stored under a dedicated key in the merged config. Crates building
+/// on top of the framework should define their config as sections, in order to have it resolved
+/// from the same sources and with the same precedence as [ApplicationConfig].
+pub trait ConfigSection: DeserializeOwned + Default {
+    /// Top-level key of the section, e.g. `web`.
+    const KEY: &'static str;
+}
+
+/// Configuration merged from all sources, in order of increasing precedence: `springtime.json`
+/// file, registered [ConfigSources](source::ConfigSource), environment variables prefixed with
+/// `SPRINGTIME_` and command line arguments in the form of `--springtime.key=value`. Nested keys in
+/// environment variables are separated with `__`, e.g.
+/// `SPRINGTIME_WEB__SERVERS__DEFAULT__LISTEN_ADDRESS`, while in command line arguments with `.`,
+/// e.g. `--springtime.web.servers.default.listen_address=0.0.0.0:8080`.
+#[derive(Clone, Component)]
+#[cfg_attr(
+    feature = "threadsafe",
+    component(
+        constructor = "MergedConfig::new",
+        constructor_parameters = "Vec<dyn ConfigSource + Send + Sync>"
+    )
+)]
+#[cfg_attr(
+    not(feature = "threadsafe"),
+    component(
+        constructor = "MergedConfig::new",
+        constructor_parameters = "Vec<dyn ConfigSource>"
+    )
+)]
+pub struct MergedConfig {
+    // cached init result
+    #[component(ignore)]
+    config: Result<Config, ErrorPtr>,
+}
+
+impl MergedConfig {
+    #[cfg(feature = "async")]
+    fn new(
+        sources: Vec<ComponentInstancePtr<ConfigSourcePtr>>,
+    ) -> BoxFuture<'static, Result<Self, ErrorPtr>> {
+        async move {
+            let mut source_values = Vec::with_capacity(sources.len());
+            for source in sort_sources(sources) {
+                source_values.push(source.load().await?);
+            }
+
+            Ok(Self::from_source_values(source_values, env::args_os()))
+        }
+        .boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn new(sources: Vec<ComponentInstancePtr<ConfigSourcePtr>>) -> Result<Self, ErrorPtr> {
+        let source_values = sort_sources(sources)
+            .iter()
+            .map(|source| source.load())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::from_source_values(source_values, env::args_os()))
+    }
+
+    /// Creates a config from given [ConfigSource] values, in order of increasing precedence, and
+    /// command line arguments instead of the ones passed to the process. The config file and
+    /// environment variables are read as usual. Useful for checking how values from custom sources
+    /// are merged, e.g. in tests.
+    pub fn from_source_values(
+        source_values: Vec<ConfigMap>,
+        cli_arguments: impl IntoIterator<Item = OsString>,
+    ) -> Self {
+        let builder = source_values
+            .into_iter()
+            .fold(
+                Config::builder().add_source(File::with_name(CONFIG_FILE).required(false)),
+                |builder, values| builder.add_source(LoadedSource(values)),
+            )
+            .add_source(
+                Environment::with_prefix(CONFIG_ENV_PREFIX)
+                    .prefix_separator("_")
+                    .separator("__"),
+            );
+
+        let config = cli_overrides(cli_arguments)
+            .try_fold(builder, |builder, (key, value)| {
+                builder.set_override(key, value)
+            })
+            .and_then(|builder| builder.build())
+            .map_err(convert_error);
+
+        Self { config }
+    }
+
+    fn merged(&self) -> Result<&Config, ErrorPtr> {
+        match &self.config {
+            Ok(config) => Ok(config),
+            Err(error) => Err(error.clone()),
+        }
+    }
+
+    /// Returns given section or its default value, if not present.
+    pub fn section<T: ConfigSection>(&self) -> Result<T, ErrorPtr> {
+        self.value(T::KEY)
+            .map(|section| section.unwrap_or_default())
+    }
+
+    /// Returns a value for given key, if present. Nested keys are separated with `.`.
+    pub fn value<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, ErrorPtr> {
+        match self.merged()?.get::<T>(key) {
+            Ok(value) => Ok(Some(value)),
+            Err(ConfigError::NotFound(_)) => Ok(None),
+            Err(error) => Err(convert_error(error)),
         }
     }
+
+    // profiles can be either a list or a comma-separated string, e.g. from env variables
+    fn profiles(&self) -> Vec<String> {
+        if let Ok(Some(profiles)) = self.value::<Vec<String>>(PROFILES_KEY) {
+            return profiles;
+        }
+
+        self.value::<String>(PROFILES_KEY)
+            .ok()
+            .flatten()
+            .map(|profiles| parse_profiles(&profiles).into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    fn root<T: DeserializeOwned>(&self) -> Result<T, ErrorPtr> {
+        self.merged()?
+            .clone()
+            .try_deserialize()
+            .map_err(convert_error)
+    }
+}
+
+// runtime config needs to be known before any component is created, so it's read from the same
+// sources as the ones used by ConfigContextFactory
+#[cfg(feature = "tokio-runtime")]
+pub(crate) fn configured_runtime() -> Result<RuntimeConfig, ErrorPtr> {
+    MergedConfig::from_source_values(vec![], env::args_os())
+        .value(RUNTIME_KEY)
+        .map(|runtime| runtime.unwrap_or_default())
+}
+
+// profiles read from the same sources as the ones used by ConfigContextFactory
+pub(crate) fn configured_profiles() -> Vec<String> {
+    MergedConfig::from_source_values(vec![], env::args_os()).profiles()
+}
+
+/// Registration condition which passes if given [ConfigSection] is present in the configuration.
+/// Requires a [Context] with access to configuration, e.g. one created by [ConfigContextFactory],
+/// so sections are read from the same sources and with the same precedence as in
+/// [config_property_equals].
+pub fn config_section_present<T: ConfigSection>(
+    context: &dyn Context,
+    _metadata: ConditionMetadata,
+) -> bool {
+    context.memoize(&format!("config_section_present:{}", T::KEY), &|| {
+        context.property(T::KEY).is_some()
+    })
+}
+
+/// Registration condition helper which passes if the configuration property with given key has
+/// given value, e.g. `config_property_equals(context, "feature.enabled", "true")`. Requires a
+/// [Context] with access to configuration, e.g. one created by [ConfigContextFactory].
+pub fn config_property_equals(context: &dyn Context, key: &str, value: &str) -> bool {
+    context.property(key).as_deref() == Some(value)
 }
 
-impl ApplicationConfig {
-    fn init_from_environment() -> Result<Self, ConfigError> {
-        Config::builder()
-            .add_source(File::with_name(CONFIG_FILE).required(false))
-            .add_source(Environment::with_prefix(CONFIG_ENV_PREFIX))
-            .build()
-            .and_then(|config| config.try_deserialize::<ApplicationConfig>())
+/// Registration condition which passes if the configuration property with given key has given
+/// value, e.g. `condition = "ConfigPropertyEquals::new(\"feature.enabled\", \"true\")"`. See
+/// [config_property_equals].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConfigPropertyEquals {
+    key: String,
+    value: String,
+}
+
+impl ConfigPropertyEquals {
+    /// Creates a condition comparing the property with given key to given value.
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl Condition for ConfigPropertyEquals {
+    fn evaluate(&self, context: &dyn Context, _metadata: ConditionMetadata) -> bool {
+        config_property_equals(context, &self.key, &self.value)
+    }
+}
+
+/// [ContextFactory] exposing configuration from the config file, environment variables and command
+/// line arguments to registration conditions via [Context::property], with active profiles read
+/// from the `profiles` key. Sections and lists are exposed in their textual form. Used by default
+/// by [ApplicationBuilder](crate::application::ApplicationBuilder). Note:
+/// [ConfigSources](source::ConfigSource) are not taken into account, since they are components
+/// themselves and are not available when evaluating conditions.
+#[derive(Clone)]
+pub struct ConfigContextFactory {
+    config: MergedConfig,
+    profiles: Vec<String>,
+    profile_context_factory: ProfileContextFactory,
+}
+
+impl ConfigContextFactory {
+    pub fn new() -> Self {
+        Self::from_config(MergedConfig::from_source_values(vec![], env::args_os()))
+    }
+
+    // overrides take the place of the highest priority source, so they have the same precedence as
+    // in the MergedConfig component
+    pub(crate) fn new_with_overrides(overrides: ConfigMap) -> Self {
+        Self::from_config(MergedConfig::from_source_values(
+            vec![overrides],
+            env::args_os(),
+        ))
+    }
+
+    fn from_config(config: MergedConfig) -> Self {
+        let profiles = config.profiles();
+        Self {
+            profile_context_factory: ProfileContextFactory::new(profiles.clone()),
+            profiles,
+            config,
+        }
+    }
+
+    /// Replaces the profiles read from the `profiles` key with given ones.
+    pub fn with_profiles(mut self, profiles: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.profiles = profiles.into_iter().map(Into::into).collect();
+        self.profile_context_factory = ProfileContextFactory::new(self.profiles.clone());
+        self
+    }
+
+    /// Returns the profiles active when evaluating registration conditions.
+    pub fn active_profiles(&self) -> &[String] {
+        &self.profiles
+    }
+
+    /// Returns the names of primary components keyed by type names, read from the `primary` key.
+    pub fn primary_components(&self) -> Result<HashMap<String, String>, ErrorPtr> {
+        self.config
+            .value(PRIMARY_KEY)
+            .map(|primary| primary.unwrap_or_default())
     }
 }
 
+impl Default for ConfigContextFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContextFactory for ConfigContextFactory {
+    fn create_context<'a>(
+        &self,
+        registry: &'a dyn ComponentDefinitionRegistryFacade,
+    ) -> Box<dyn Context + 'a> {
+        Box::new(ConfigContext {
+            context: self.profile_context_factory.create_context(registry),
+            config: self.config.clone(),
+        })
+    }
+}
+
+struct ConfigContext<'a> {
+    context: Box<dyn Context + 'a>,
+    config: MergedConfig,
+}
+
+impl Context for ConfigContext<'_> {
+    fn registry(&self) -> &dyn ComponentDefinitionRegistryFacade {
+        self.context.registry()
+    }
+
+    fn memoize(&self, key: &str, evaluate: &dyn Fn() -> bool) -> bool {
+        self.context.memoize(key, evaluate)
+    }
+
+    fn is_profile_active(&self, profile: &str) -> bool {
+        self.context.is_profile_active(profile)
+    }
+
+    fn active_profiles(&self) -> Vec<String> {
+        self.context.active_profiles()
+    }
+
+    fn env_var(&self, name: &str) -> Option<String> {
+        self.context.env_var(name)
+    }
+
+    // sections and lists are exposed in their textual form, so they can still be checked for
+    // presence
+    fn property(&self, key: &str) -> Option<String> {
+        self.config.value::<Value>(key).ok().flatten().map(|value| {
+            value
+                .clone()
+                .into_string()
+                .unwrap_or_else(|_| value.to_string())
+        })
+    }
+}
+
+// parses "--springtime.key=value" arguments, skipping ones which are not valid UTF-8
+fn cli_overrides(
+    cli_arguments: impl IntoIterator<Item = OsString>,
+) -> impl Iterator<Item = (String, String)> {
+    cli_arguments.into_iter().filter_map(|argument| {
+        let (key, value) = argument
+            .to_str()?
+            .strip_prefix(CLI_ARGUMENT_PREFIX)?
+            .split_once('=')?;
+        Some((key.to_string(), value.to_string()))
+    })
+}
+
+// values already loaded from a ConfigSource
+#[derive(Clone, Debug)]
+struct LoadedSource(ConfigMap);
+
+impl Source for LoadedSource {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        Ok(self.0.clone())
+    }
+}
+
+fn sort_sources(
+    mut sources: Vec<ComponentInstancePtr<ConfigSourcePtr>>,
+) -> Vec<ComponentInstancePtr<ConfigSourcePtr>> {
+    // lower priorities first, so higher ones override their values
+    sources.sort_by_key(|source| source.priority());
+    sources
+}
+
+#[cfg(feature = "threadsafe")]
+pub type ApplicationConfigProviderPtr = dyn ApplicationConfigProvider + Send + Sync;
+
+#[cfg(not(feature = "threadsafe"))]
+pub type ApplicationConfigProviderPtr = dyn ApplicationConfigProvider;
+
 /// Provider for [ApplicationConfig]. The primary instance of the provider will be used to retrieve
 /// application configuration.
 #[injectable]
@@ -72,8 +466,8 @@ pub trait ApplicationConfigProvider {
 }
 
 #[derive(Component)]
-#[cfg_attr(feature = "threadsafe", component(priority = -128, condition = "unregistered_component::<dyn ApplicationConfigProvider + Send + Sync>", constructor = "DefaultApplicationConfigProvider::new"))]
-#[cfg_attr(not(feature = "threadsafe"), component(priority = -128, condition = "unregistered_component::<dyn ApplicationConfigProvider>", constructor = "DefaultApplicationConfigProvider::new"))]
+#[cfg_attr(feature = "threadsafe", component(priority = -128, condition = "unregistered_component::<dyn ApplicationConfigProvider + Send + Sync>", constructor = "DefaultApplicationConfigProvider::new", constructor_parameters = "MergedConfig"))]
+#[cfg_attr(not(feature = "threadsafe"), component(priority = -128, condition = "unregistered_component::<dyn ApplicationConfigProvider>", constructor = "DefaultApplicationConfigProvider::new", constructor_parameters = "MergedConfig"))]
 struct DefaultApplicationConfigProvider {
     // cached init result
     #[component(ignore)]
@@ -82,19 +476,21 @@ struct DefaultApplicationConfigProvider {
 
 impl DefaultApplicationConfigProvider {
     #[cfg(feature = "async")]
-    fn new() -> BoxFuture<'static, Result<Self, ErrorPtr>> {
-        async {
+    fn new(
+        merged_config: ComponentInstancePtr<MergedConfig>,
+    ) -> BoxFuture<'static, Result<Self, ErrorPtr>> {
+        async move {
             Ok(Self {
-                config: ApplicationConfig::init_from_environment().map_err(convert_error),
+                config: merged_config.root(),
             })
         }
         .boxed()
     }
 
     #[cfg(not(feature = "async"))]
-    fn new() -> Result<Self, ErrorPtr> {
+    fn new(merged_config: ComponentInstancePtr<MergedConfig>) -> Result<Self, ErrorPtr> {
         Ok(Self {
-            config: ApplicationConfig::init_from_environment().map_err(convert_error),
+            config: merged_config.root(),
         })
     }
 
@@ -118,3 +514,181 @@ impl ApplicationConfigProvider for DefaultApplicationConfigProvider {
         self.map_config()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{
+        config_property_equals, config_section_present, ConfigContextFactory, ConfigSection,
+        MergedConfig,
+    };
+    use config::{Map, Value};
+    use serde::Deserialize;
+    use springtime_di::component_registry::conditional::{
+        ComponentDefinitionRegistryFacade, ConditionMetadata, ContextFactory,
+    };
+    use springtime_di::component_registry::ComponentAliasMetadata;
+    use springtime_di::instance_provider::ComponentInstanceAnyPtr;
+    use std::any::{Any, TypeId};
+
+    #[derive(Default, Deserialize)]
+    #[serde(default)]
+    struct TestSection {
+        value: i32,
+    }
+
+    impl ConfigSection for TestSection {
+        const KEY: &'static str = "test_section";
+    }
+
+    #[test]
+    fn should_read_sections() {
+        let section_values: Map<String, Value> = [("value".to_string(), Value::from(5))]
+            .into_iter()
+            .collect();
+
+        let merged_config = MergedConfig::from_source_values(
+            vec![[("test_section".to_string(), Value::from(section_values))]
+                .into_iter()
+                .collect()],
+            vec![],
+        );
+
+        assert_eq!(merged_config.section::<TestSection>().unwrap().value, 5);
+        assert!(merged_config
+            .value::<i32>("missing_section.value")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn should_apply_cli_overrides() {
+        let merged_config = MergedConfig::from_source_values(
+            vec![],
+            vec![
+                "--springtime.test_section.value=7".into(),
+                "--other=1".into(),
+                "--springtime.invalid".into(),
+            ],
+        );
+
+        assert_eq!(merged_config.section::<TestSection>().unwrap().value, 7);
+    }
+
+    #[derive(Default, Deserialize)]
+    struct MissingSection;
+
+    impl ConfigSection for MissingSection {
+        const KEY: &'static str = "missing_section";
+    }
+
+    fn test_cast(
+        instance: ComponentInstanceAnyPtr,
+        _target: &mut dyn Any,
+    ) -> Result<(), ComponentInstanceAnyPtr> {
+        Err(instance)
+    }
+
+    struct EmptyRegistry;
+
+    impl ComponentDefinitionRegistryFacade for EmptyRegistry {
+        fn is_registered(&self, _target: TypeId) -> bool {
+            false
+        }
+
+        fn is_name_registered(&self, _name: &str) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn should_expose_properties_to_conditions() {
+        let context_factory = ConfigContextFactory::from_config(MergedConfig::from_source_values(
+            vec![],
+            vec![
+                "--springtime.feature.enabled=true".into(),
+                "--springtime.profiles=dev".into(),
+            ],
+        ));
+        let context = context_factory.create_context(&EmptyRegistry);
+
+        assert!(config_property_equals(
+            context.as_ref(),
+            "feature.enabled",
+            "true"
+        ));
+        assert!(!config_property_equals(
+            context.as_ref(),
+            "feature.missing",
+            "true"
+        ));
+        assert!(context.is_profile_active("dev"));
+    }
+
+    #[test]
+    fn should_check_section_presence_in_context() {
+        let context_factory = ConfigContextFactory::from_config(MergedConfig::from_source_values(
+            vec![],
+            vec!["--springtime.test_section.value=7".into()],
+        ));
+        let context = context_factory.create_context(&EmptyRegistry);
+        let metadata = ComponentAliasMetadata {
+            is_primary: false,
+            scope: None,
+            cast: test_cast,
+        };
+        let metadata = ConditionMetadata::Alias {
+            alias_type: TypeId::of::<i8>(),
+            target_type: TypeId::of::<i16>(),
+            metadata: &metadata,
+        };
+
+        assert!(config_section_present::<TestSection>(
+            context.as_ref(),
+            metadata
+        ));
+        assert!(!config_section_present::<MissingSection>(
+            context.as_ref(),
+            metadata
+        ));
+    }
+
+    #[test]
+    fn should_replace_configured_profiles() {
+        let context_factory = ConfigContextFactory::from_config(MergedConfig::from_source_values(
+            vec![],
+            vec!["--springtime.profiles=dev".into()],
+        ))
+        .with_profiles(["test"]);
+        let context = context_factory.create_context(&EmptyRegistry);
+
+        assert_eq!(context_factory.active_profiles(), ["test"]);
+        assert!(context.is_profile_active("test"));
+        assert!(!context.is_profile_active("dev"));
+    }
+
+    #[test]
+    fn should_read_primary_components() {
+        let context_factory = ConfigContextFactory::from_config(MergedConfig::from_source_values(
+            vec![],
+            vec!["--springtime.primary.TestTrait=test_component".into()],
+        ));
+
+        let primary = context_factory.primary_components().unwrap();
+        assert_eq!(
+            primary.get("TestTrait").map(String::as_str),
+            Some("test_component")
+        );
+    }
+
+    #[test]
+    fn should_read_comma_separated_profiles() {
+        let merged_config = MergedConfig::from_source_values(
+            vec![],
+            vec!["--springtime.profiles=dev, local".into()],
+        );
+
+        let mut profiles = merged_config.profiles();
+        profiles.sort();
+        assert_eq!(profiles, ["dev", "local"]);
+    }
+}
@@ -0,0 +1,455 @@
+//! Additional sources of configuration values. All registered [ConfigSource] components are loaded
+//! by the default [ApplicationConfigProvider](super::ApplicationConfigProvider) and merged into the
+//! standard config chain - values from sources override values from the config file, but can be
+//! overridden by environment variables. Sources with higher priority override the ones with lower.
+//!
+//! Remote configuration servers can be integrated by implementing a [RemoteConfigClient] and
+//! wrapping it in a [RemoteConfigSource], which takes care of caching and failure handling. If the
+//! `remote-config` feature is enabled, an HTTP source is automatically registered when the
+//! `SPRINGTIME_REMOTE_CONFIG_URL` environment variable is present. Such source expects a JSON
+//! document in the response body and can be further configured with
+//! `SPRINGTIME_REMOTE_CONFIG_FAILURE_POLICY` (`fail`, `use_cached` or `ignore`) and
+//! `SPRINGTIME_REMOTE_CONFIG_CACHE_TTL` (in seconds) environment variables.
+
+#[cfg(feature = "async")]
+use crate::future::{BoxFuture, FutureExt};
+use config::{Map, Value};
+use serde::Deserialize;
+use springtime_di::injectable;
+use springtime_di::instance_provider::{
+    store_cast_result, ComponentInstanceAnyPtr, ComponentInstancePtr, ErrorPtr,
+};
+use std::any::Any;
+#[cfg(feature = "async")]
+use std::sync::Mutex;
+#[cfg(feature = "async")]
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Configuration values provided by a [ConfigSource].
+pub type ConfigMap = Map<String, Value>;
+
+#[cfg(feature = "threadsafe")]
+pub type ConfigSourcePtr = dyn ConfigSource + Send + Sync;
+
+#[cfg(not(feature = "threadsafe"))]
+pub type ConfigSourcePtr = dyn ConfigSource;
+
+/// Source of configuration values, which are merged into the standard config chain.
+#[injectable]
+pub trait ConfigSource {
+    /// Loads configuration values.
+    #[cfg(feature = "async")]
+    fn load(&self) -> BoxFuture<'_, Result<ConfigMap, ErrorPtr>>;
+
+    /// Loads configuration values.
+    #[cfg(not(feature = "async"))]
+    fn load(&self) -> Result<ConfigMap, ErrorPtr>;
+
+    /// Returns the priority for this source. Values from sources with higher priorities override
+    /// values from sources with lower ones. Default 0.
+    fn priority(&self) -> i8 {
+        0
+    }
+}
+
+/// Errors related to remote configuration.
+#[derive(Error, Debug)]
+pub enum RemoteConfigError {
+    /// Remote config location is invalid.
+    #[error("Invalid remote config URL: {0}")]
+    InvalidUrl(String),
+    /// Remote server returned an unsuccessful status code.
+    #[error("Remote config server returned status: {0}")]
+    InvalidStatus(u16),
+    /// Communication with the remote server failed.
+    #[error("Error communicating with remote config server: {0}")]
+    CommunicationError(String),
+    /// Remote config cannot be parsed.
+    #[error("Error parsing remote config: {0}")]
+    ParseError(#[from] config::ConfigError),
+    /// An environment variable configuring the remote source has an invalid value.
+    #[error("Invalid value of {0}: {1}")]
+    InvalidEnvValue(&'static str, String),
+}
+
+/// What to do when a remote source cannot be loaded.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FailurePolicy {
+    /// Return the error, which prevents the config from being loaded.
+    #[default]
+    Fail,
+    /// Use previously fetched values, even if they are stale. Fails if there are no such values.
+    UseCached,
+    /// Ignore the error and contribute no values.
+    Ignore,
+}
+
+/// Client fetching configuration from a remote location, e.g. a config server or a key-value
+/// store. Used by [RemoteConfigSource].
+#[cfg(feature = "async")]
+pub trait RemoteConfigClient {
+    /// Fetches current configuration values.
+    fn fetch(&self) -> BoxFuture<'_, Result<ConfigMap, ErrorPtr>>;
+}
+
+/// [ConfigSource] using a [RemoteConfigClient] with result caching and a [FailurePolicy].
+#[cfg(feature = "async")]
+pub struct RemoteConfigSource<C: RemoteConfigClient> {
+    client: C,
+    failure_policy: FailurePolicy,
+    cache_ttl: Option<Duration>,
+    priority: i8,
+    cache: Mutex<Option<(Instant, ConfigMap)>>,
+}
+
+#[cfg(feature = "async")]
+impl<C: RemoteConfigClient> RemoteConfigSource<C> {
+    /// Creates a new source. Fetched values are cached for `cache_ttl` or indefinitely, if not
+    /// given.
+    pub fn new(
+        client: C,
+        failure_policy: FailurePolicy,
+        cache_ttl: Option<Duration>,
+        priority: i8,
+    ) -> Self {
+        Self {
+            client,
+            failure_policy,
+            cache_ttl,
+            priority,
+            cache: Default::default(),
+        }
+    }
+
+    fn cached(&self, allow_stale: bool) -> Option<ConfigMap> {
+        let cache = self.cache.lock().ok()?;
+        cache
+            .as_ref()
+            .filter(|(fetched_at, _)| {
+                allow_stale
+                    || self
+                        .cache_ttl
+                        .map(|ttl| fetched_at.elapsed() < ttl)
+                        .unwrap_or(true)
+            })
+            .map(|(_, values)| values.clone())
+    }
+
+    async fn load_remote(&self) -> Result<ConfigMap, ErrorPtr> {
+        if let Some(values) = self.cached(false) {
+            return Ok(values);
+        }
+
+        match self.client.fetch().await {
+            Ok(values) => {
+                if let Ok(mut cache) = self.cache.lock() {
+                    *cache = Some((Instant::now(), values.clone()));
+                }
+
+                Ok(values)
+            }
+            Err(error) => match self.failure_policy {
+                FailurePolicy::Fail => Err(error),
+                FailurePolicy::UseCached => self.cached(true).ok_or(error),
+                FailurePolicy::Ignore => Ok(Default::default()),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<C: RemoteConfigClient + Send + Sync> ConfigSource for RemoteConfigSource<C> {
+    fn load(&self) -> BoxFuture<'_, Result<ConfigMap, ErrorPtr>> {
+        self.load_remote().boxed()
+    }
+
+    fn priority(&self) -> i8 {
+        self.priority
+    }
+}
+
+// values overriding all other sources, seeded as an instance by
+// ApplicationBuilder::with_config_overrides()
+pub(crate) struct OverrideConfigSource {
+    values: ConfigMap,
+}
+
+impl OverrideConfigSource {
+    pub(crate) fn new(values: ConfigMap) -> Self {
+        Self { values }
+    }
+}
+
+impl ConfigSource for OverrideConfigSource {
+    #[cfg(feature = "async")]
+    fn load(&self) -> BoxFuture<'_, Result<ConfigMap, ErrorPtr>> {
+        let values = self.values.clone();
+        async move { Ok(values) }.boxed()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn load(&self) -> Result<ConfigMap, ErrorPtr> {
+        Ok(self.values.clone())
+    }
+
+    fn priority(&self) -> i8 {
+        i8::MAX
+    }
+}
+
+// the override source is not a component, so it needs a dedicated cast for its alias
+pub(crate) fn cast_override_source(
+    instance: ComponentInstanceAnyPtr,
+    target: &mut dyn Any,
+) -> Result<(), ComponentInstanceAnyPtr> {
+    instance
+        .downcast::<OverrideConfigSource>()
+        .map(|source| store_cast_result(source as ComponentInstancePtr<ConfigSourcePtr>, target))
+}
+
+#[cfg(feature = "remote-config")]
+pub use http::HttpConfigClient;
+
+#[cfg(feature = "remote-config")]
+mod http {
+    use super::{
+        ConfigMap, ConfigSource, FailurePolicy, RemoteConfigClient, RemoteConfigError,
+        RemoteConfigSource,
+    };
+    use crate::config::convert_error;
+    use crate::future::{BoxFuture, FutureExt};
+    use config::{File, FileFormat, Source};
+    use http_body_util::{BodyExt, Empty};
+    use hyper::body::Bytes;
+    use hyper::Uri;
+    use hyper_util::client::legacy::connect::HttpConnector;
+    use hyper_util::client::legacy::Client;
+    use hyper_util::rt::TokioExecutor;
+    use springtime_di::component_registry::conditional::{ConditionMetadata, Context};
+    use springtime_di::instance_provider::ErrorPtr;
+    use springtime_di::{component_alias, Component};
+    use std::env;
+    use std::time::Duration;
+
+    const URL_ENV: &str = "SPRINGTIME_REMOTE_CONFIG_URL";
+    const FAILURE_POLICY_ENV: &str = "SPRINGTIME_REMOTE_CONFIG_FAILURE_POLICY";
+    const CACHE_TTL_ENV: &str = "SPRINGTIME_REMOTE_CONFIG_CACHE_TTL";
+
+    /// [RemoteConfigClient] fetching a JSON document from a plain HTTP endpoint.
+    pub struct HttpConfigClient {
+        uri: Uri,
+        client: Client<HttpConnector, Empty<Bytes>>,
+    }
+
+    impl HttpConfigClient {
+        /// Creates a new client fetching the document from given URI.
+        pub fn new(uri: Uri) -> Self {
+            Self {
+                uri,
+                client: Client::builder(TokioExecutor::new()).build_http(),
+            }
+        }
+
+        async fn fetch_values(&self) -> Result<ConfigMap, RemoteConfigError> {
+            let response = self
+                .client
+                .get(self.uri.clone())
+                .await
+                .map_err(|error| RemoteConfigError::CommunicationError(error.to_string()))?;
+
+            if !response.status().is_success() {
+                return Err(RemoteConfigError::InvalidStatus(response.status().as_u16()));
+            }
+
+            let body = response
+                .into_body()
+                .collect()
+                .await
+                .map_err(|error| RemoteConfigError::CommunicationError(error.to_string()))?
+                .to_bytes();
+
+            File::from_str(&String::from_utf8_lossy(&body), FileFormat::Json)
+                .collect()
+                .map_err(RemoteConfigError::ParseError)
+        }
+    }
+
+    impl RemoteConfigClient for HttpConfigClient {
+        fn fetch(&self) -> BoxFuture<'_, Result<ConfigMap, ErrorPtr>> {
+            async { self.fetch_values().await.map_err(convert_error) }.boxed()
+        }
+    }
+
+    fn remote_config_url_present(_context: &dyn Context, _metadata: ConditionMetadata) -> bool {
+        env::var_os(URL_ENV).is_some()
+    }
+
+    #[derive(Component)]
+    #[component(
+        condition = "remote_config_url_present",
+        constructor = "DefaultHttpConfigSource::new"
+    )]
+    struct DefaultHttpConfigSource {
+        #[component(ignore)]
+        source: RemoteConfigSource<HttpConfigClient>,
+    }
+
+    impl DefaultHttpConfigSource {
+        fn new() -> BoxFuture<'static, Result<Self, ErrorPtr>> {
+            async {
+                let uri = env::var(URL_ENV)
+                    .unwrap_or_default()
+                    .parse::<Uri>()
+                    .map_err(|error| {
+                        convert_error(RemoteConfigError::InvalidUrl(error.to_string()))
+                    })?;
+
+                let failure_policy =
+                    env_value(FAILURE_POLICY_ENV, parse_failure_policy)?.unwrap_or_default();
+                let cache_ttl =
+                    env_value(CACHE_TTL_ENV, |ttl| ttl.parse().ok())?.map(Duration::from_secs);
+
+                Ok(Self {
+                    source: RemoteConfigSource::new(
+                        HttpConfigClient::new(uri),
+                        failure_policy,
+                        cache_ttl,
+                        0,
+                    ),
+                })
+            }
+            .boxed()
+        }
+    }
+
+    fn parse_failure_policy(policy: &str) -> Option<FailurePolicy> {
+        match policy {
+            "fail" => Some(FailurePolicy::Fail),
+            "use_cached" => Some(FailurePolicy::UseCached),
+            "ignore" => Some(FailurePolicy::Ignore),
+            _ => None,
+        }
+    }
+
+    // a missing variable means the default should be used, but an invalid one is an error
+    pub(super) fn env_value<T>(
+        name: &'static str,
+        parse: impl FnOnce(&str) -> Option<T>,
+    ) -> Result<Option<T>, ErrorPtr> {
+        match env::var_os(name) {
+            Some(value) => value.to_str().and_then(parse).map(Some).ok_or_else(|| {
+                convert_error(RemoteConfigError::InvalidEnvValue(
+                    name,
+                    value.to_string_lossy().into_owned(),
+                ))
+            }),
+            None => Ok(None),
+        }
+    }
+
+    #[component_alias]
+    impl ConfigSource for DefaultHttpConfigSource {
+        fn load(&self) -> BoxFuture<'_, Result<ConfigMap, ErrorPtr>> {
+            self.source.load()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use crate::config::source::{
+        ConfigMap, ConfigSource, FailurePolicy, RemoteConfigClient, RemoteConfigSource,
+    };
+    use crate::future::{BoxFuture, FutureExt};
+    use config::Value;
+    use springtime_di::instance_provider::ErrorPtr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    // succeeds only on the first fetch
+    #[derive(Default)]
+    struct TestClient {
+        fetch_count: AtomicUsize,
+    }
+
+    impl RemoteConfigClient for TestClient {
+        fn fetch(&self) -> BoxFuture<'_, Result<ConfigMap, ErrorPtr>> {
+            async {
+                if self.fetch_count.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Ok([("key".to_string(), Value::from("value"))]
+                        .into_iter()
+                        .collect())
+                } else {
+                    Err(Arc::new(std::fmt::Error) as ErrorPtr)
+                }
+            }
+            .boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_cache_values() {
+        let source = RemoteConfigSource::new(TestClient::default(), FailurePolicy::Fail, None, 0);
+
+        assert!(source.load().await.unwrap().contains_key("key"));
+        assert!(source.load().await.unwrap().contains_key("key"));
+    }
+
+    #[tokio::test]
+    async fn should_apply_failure_policy() {
+        let source = RemoteConfigSource::new(
+            TestClient::default(),
+            FailurePolicy::Fail,
+            Some(Duration::ZERO),
+            0,
+        );
+        source.load().await.unwrap();
+        assert!(source.load().await.is_err());
+
+        let source = RemoteConfigSource::new(
+            TestClient::default(),
+            FailurePolicy::UseCached,
+            Some(Duration::ZERO),
+            0,
+        );
+        source.load().await.unwrap();
+        assert!(source.load().await.unwrap().contains_key("key"));
+
+        let source = RemoteConfigSource::new(
+            TestClient::default(),
+            FailurePolicy::Ignore,
+            Some(Duration::ZERO),
+            0,
+        );
+        source.load().await.unwrap();
+        assert!(source.load().await.unwrap().is_empty());
+    }
+
+    #[cfg(feature = "remote-config")]
+    #[test]
+    fn should_reject_invalid_env_values() {
+        use crate::config::source::http::env_value;
+        use std::env;
+
+        const NAME: &str = "SPRINGTIME_TEST_REMOTE_CONFIG_ENV_VALUE";
+
+        env::remove_var(NAME);
+        assert!(env_value(NAME, |value| value.parse::<u64>().ok())
+            .unwrap()
+            .is_none());
+
+        env::set_var(NAME, "10");
+        assert_eq!(
+            env_value(NAME, |value| value.parse::<u64>().ok()).unwrap(),
+            Some(10)
+        );
+
+        env::set_var(NAME, "invalid");
+        assert!(env_value(NAME, |value| value.parse::<u64>().ok()).is_err());
+
+        env::remove_var(NAME);
+    }
+}
@@ -0,0 +1,277 @@
+//! Application-wide state available via dependency injection. [ApplicationContext] is a regular
+//! component, which can be injected anywhere to gain access to the running application - its
+//! components, [ActiveProfiles], config, events and shutdown - without the need to depend on the
+//! generic [Application](crate::application::Application) type.
+//!
+//! ```
+//! use springtime::context::ApplicationContext;
+//! use springtime_di::instance_provider::ComponentInstancePtr;
+//! use springtime_di::Component;
+//!
+//! #[derive(Component)]
+//! struct LibraryComponent {
+//!     context: ComponentInstancePtr<ApplicationContext>,
+//! }
+//!
+//! impl LibraryComponent {
+//!     fn stop_application(&self) {
+//!         self.context.shutdown_handle().request_shutdown();
+//!     }
+//! }
+//! ```
+
+use crate::config::{configured_profiles, ApplicationConfig, ApplicationConfigProviderPtr};
+#[cfg(feature = "async")]
+use futures::channel::oneshot::{channel, Receiver, Sender};
+#[cfg(feature = "async")]
+use futures::future::{BoxFuture, FutureExt, Shared};
+use springtime_di::event::EventPublisher;
+use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr, FactoryHandle};
+use springtime_di::Component;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "async")]
+use std::sync::Mutex;
+
+struct ShutdownState {
+    requested: AtomicBool,
+    #[cfg(feature = "async")]
+    sender: Mutex<Option<Sender<()>>>,
+    #[cfg(feature = "async")]
+    receiver: Shared<Receiver<()>>,
+}
+
+impl Default for ShutdownState {
+    #[cfg(feature = "async")]
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            requested: Default::default(),
+            sender: Mutex::new(Some(sender)),
+            receiver: receiver.shared(),
+        }
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn default() -> Self {
+        Self {
+            requested: Default::default(),
+        }
+    }
+}
+
+/// Handle which can be used to gracefully stop the application. When a shutdown is requested, the
+/// [Application](crate::application::Application) doesn't start any further runners and, if the
+/// `async` feature is enabled, stops waiting for currently running ones.
+#[derive(Clone, Default)]
+pub struct ShutdownHandle {
+    state: ComponentInstancePtr<ShutdownState>,
+}
+
+impl ShutdownHandle {
+    /// Requests application shutdown. Subsequent calls have no effect.
+    pub fn request_shutdown(&self) {
+        self.state.requested.store(true, Ordering::Release);
+
+        #[cfg(feature = "async")]
+        if let Some(sender) = self
+            .state
+            .sender
+            .lock()
+            .ok()
+            .and_then(|mut sender| sender.take())
+        {
+            let _ = sender.send(());
+        }
+    }
+
+    /// Checks if shutdown has been requested.
+    pub fn is_shutdown_requested(&self) -> bool {
+        self.state.requested.load(Ordering::Acquire)
+    }
+
+    /// Returns a future which resolves when shutdown is requested.
+    #[cfg(feature = "async")]
+    pub fn wait(&self) -> BoxFuture<'static, ()> {
+        self.state.receiver.clone().map(|_| ()).boxed()
+    }
+}
+
+/// Profiles active in the running application. By default, they are read from the `profiles` config
+/// key, the same way as for registration conditions, unless set with
+/// [with_profiles](crate::application::ApplicationBuilder::with_profiles).
+#[derive(Clone, Component, Debug)]
+pub struct ActiveProfiles {
+    #[component(default = "configured_profiles")]
+    profiles: Vec<String>,
+}
+
+impl ActiveProfiles {
+    pub fn new(profiles: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            profiles: profiles.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns all active profiles.
+    pub fn profiles(&self) -> &[String] {
+        &self.profiles
+    }
+
+    /// Checks if given profile is active.
+    pub fn is_active(&self, profile: &str) -> bool {
+        self.profiles
+            .iter()
+            .any(|active_profile| active_profile == profile)
+    }
+}
+
+/// Lightweight facade over the running application. Libraries can inject it to integrate with the
+/// application without taking [Application](crate::application::Application) generics into their
+/// API.
+#[derive(Component)]
+pub struct ApplicationContext {
+    config_provider: ComponentInstancePtr<ApplicationConfigProviderPtr>,
+    instance_provider: FactoryHandle,
+    event_publisher: ComponentInstancePtr<EventPublisher>,
+    active_profiles: ComponentInstancePtr<ActiveProfiles>,
+    #[component(default)]
+    shutdown_handle: ShutdownHandle,
+}
+
+impl ApplicationContext {
+    /// Creates a new context from its parts. Typically, the context is created by the dependency
+    /// injection framework instead.
+    pub fn new(
+        config_provider: ComponentInstancePtr<ApplicationConfigProviderPtr>,
+        instance_provider: FactoryHandle,
+        event_publisher: ComponentInstancePtr<EventPublisher>,
+        active_profiles: ComponentInstancePtr<ActiveProfiles>,
+        shutdown_handle: ShutdownHandle,
+    ) -> Self {
+        Self {
+            config_provider,
+            instance_provider,
+            event_publisher,
+            active_profiles,
+            shutdown_handle,
+        }
+    }
+
+    /// Returns the handle for retrieving other components at runtime.
+    pub fn instance_provider(&self) -> &FactoryHandle {
+        &self.instance_provider
+    }
+
+    /// Returns the publisher for application [events](springtime_di::event).
+    pub fn event_publisher(&self) -> &EventPublisher {
+        &self.event_publisher
+    }
+
+    /// Returns currently active profiles.
+    pub fn active_profiles(&self) -> &ActiveProfiles {
+        &self.active_profiles
+    }
+
+    /// Returns current application config.
+    #[cfg(feature = "async")]
+    pub fn config(&self) -> BoxFuture<'_, Result<&ApplicationConfig, ErrorPtr>> {
+        self.config_provider.config()
+    }
+
+    /// Returns current application config.
+    #[cfg(not(feature = "async"))]
+    pub fn config(&self) -> Result<&ApplicationConfig, ErrorPtr> {
+        self.config_provider.config()
+    }
+
+    /// Returns the handle for stopping the application.
+    pub fn shutdown_handle(&self) -> &ShutdownHandle {
+        &self.shutdown_handle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::context::{ActiveProfiles, ApplicationContext, ShutdownHandle};
+    use springtime_di::factory::ComponentFactoryBuilder;
+    use springtime_di::instance_provider::TypedComponentInstanceProvider;
+
+    #[test]
+    fn should_request_shutdown() {
+        let handle = ShutdownHandle::default();
+        let cloned_handle = handle.clone();
+        assert!(!handle.is_shutdown_requested());
+
+        cloned_handle.request_shutdown();
+        cloned_handle.request_shutdown();
+
+        assert!(handle.is_shutdown_requested());
+    }
+
+    #[test]
+    fn should_check_active_profiles() {
+        let profiles = ActiveProfiles::new(["dev", "local"]);
+
+        assert!(profiles.is_active("dev"));
+        assert!(!profiles.is_active("prod"));
+        assert_eq!(profiles.profiles(), ["dev", "local"]);
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_expose_application_state() {
+        let mut factory = ComponentFactoryBuilder::new()
+            .unwrap()
+            .with_instance(ActiveProfiles::new(["dev"]))
+            .unwrap()
+            .build();
+
+        let context = factory
+            .primary_instance_typed::<ApplicationContext>()
+            .unwrap();
+        assert!(context.active_profiles().is_active("dev"));
+
+        let profiles = context
+            .instance_provider()
+            .primary_instance::<ActiveProfiles>()
+            .unwrap();
+        assert_eq!(profiles.profiles(), ["dev"]);
+        assert!(context.event_publisher().publish(&()).is_ok());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_expose_application_state() {
+        let mut factory = ComponentFactoryBuilder::new()
+            .unwrap()
+            .with_instance(ActiveProfiles::new(["dev"]))
+            .unwrap()
+            .build();
+
+        let context = factory
+            .primary_instance_typed::<ApplicationContext>()
+            .await
+            .unwrap();
+        assert!(context.active_profiles().is_active("dev"));
+
+        let profiles = context
+            .instance_provider()
+            .primary_instance::<ActiveProfiles>()
+            .await
+            .unwrap();
+        assert_eq!(profiles.profiles(), ["dev"]);
+        assert!(context.event_publisher().publish(&()).await.is_ok());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_wake_shutdown_waiters() {
+        let handle = ShutdownHandle::default();
+        let wait = handle.wait();
+
+        handle.request_shutdown();
+
+        wait.await;
+        handle.wait().await;
+    }
+}
@@ -0,0 +1,37 @@
+//! Initializers executed before any other application infrastructure.
+
+#[cfg(feature = "async")]
+use crate::future::BoxFuture;
+#[cfg(test)]
+use mockall::automock;
+use springtime_di::injectable;
+pub use springtime_di::instance_provider::ErrorPtr;
+
+#[cfg(feature = "threadsafe")]
+pub type ApplicationInitializerPtr = dyn ApplicationInitializer + Send + Sync;
+
+#[cfg(not(feature = "threadsafe"))]
+pub type ApplicationInitializerPtr = dyn ApplicationInitializer;
+
+/// Initializes the environment before the [Application](crate::application::Application) installs
+/// the logger, reads config or resolves [ApplicationRunners](crate::runner::ApplicationRunner).
+/// Useful e.g. for mutating environment variables, selecting profiles or loading secrets.
+/// Initializers are discovered by the dependency injection framework and are run sequentially,
+/// ordered by priority. Note: initializers should not depend on components which need config, since
+/// it's not yet prepared at the time initializers are created.
+#[injectable]
+#[cfg_attr(test, automock)]
+pub trait ApplicationInitializer {
+    #[cfg(feature = "async")]
+    /// Runs initialization code.
+    fn initialize(&self) -> BoxFuture<'_, Result<(), ErrorPtr>>;
+
+    #[cfg(not(feature = "async"))]
+    /// Runs initialization code.
+    fn initialize(&self) -> Result<(), ErrorPtr>;
+
+    /// Returns the priority for this initializer. Higher priorities get run first. Default 0.
+    fn priority(&self) -> i8 {
+        0
+    }
+}
@@ -51,9 +51,16 @@
 //!
 //! * `threadsafe` - use threadsafe pointers and `Send + Sync` trait bounds
 //! * `async` - turn all run functions async
+//! * `remote-config` - HTTP [ConfigSource](config::source::ConfigSource) support
+//! * `tokio-runtime` - [run_blocking](application::Application::run_blocking) support with a
+//!   configurable Tokio runtime
 
 pub mod application;
 pub mod config;
+pub mod context;
 #[cfg(feature = "async")]
 pub mod future;
+pub mod initializer;
+pub mod logger;
 pub mod runner;
+pub mod test;
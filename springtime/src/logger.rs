@@ -0,0 +1,82 @@
+//! Logging is based on the [tracing] crate. By default, a registry with an [EnvFilter] and a
+//! [fmt::layer] is installed for the duration of the application run, but this can be changed by
+//! registering a custom [TracingConfigurer], e.g. for adding additional layers or using a custom
+//! formatter.
+
+use crate::config::ApplicationConfig;
+#[cfg(test)]
+use mockall::automock;
+use springtime_di::component_registry::conditional::unregistered_component;
+use springtime_di::instance_provider::ErrorPtr;
+use springtime_di::{component_alias, injectable, Component};
+use tracing::Dispatch;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+#[cfg(feature = "threadsafe")]
+pub type TracingConfigurerPtr = dyn TracingConfigurer + Send + Sync;
+
+#[cfg(not(feature = "threadsafe"))]
+pub type TracingConfigurerPtr = dyn TracingConfigurer;
+
+/// Builds the tracing subscriber used by the [Application](crate::application::Application). The
+/// primary instance of the configurer will be used, if
+/// [install_tracing_logger](ApplicationConfig::install_tracing_logger) is enabled.
+#[injectable]
+#[cfg_attr(test, automock)]
+pub trait TracingConfigurer {
+    /// Creates a [Dispatch] which will be set as the default for the duration of the application
+    /// run.
+    fn create_dispatch(&self, config: &ApplicationConfig) -> Result<Dispatch, ErrorPtr>;
+}
+
+#[derive(Component)]
+#[cfg_attr(
+    feature = "threadsafe",
+    component(
+        priority = -128,
+        condition = "unregistered_component::<dyn TracingConfigurer + Send + Sync>"
+    )
+)]
+#[cfg_attr(
+    not(feature = "threadsafe"),
+    component(
+        priority = -128,
+        condition = "unregistered_component::<dyn TracingConfigurer>"
+    )
+)]
+struct DefaultTracingConfigurer;
+
+#[component_alias]
+impl TracingConfigurer for DefaultTracingConfigurer {
+    fn create_dispatch(&self, _config: &ApplicationConfig) -> Result<Dispatch, ErrorPtr> {
+        Ok(tracing_subscriber::registry()
+            .with(EnvFilter::from_default_env())
+            .with(fmt::layer())
+            .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::ApplicationConfig;
+    use crate::logger::{DefaultTracingConfigurer, TracingConfigurer, TracingConfigurerPtr};
+    use springtime_di::component_registry::TypedComponentDefinitionRegistry;
+    use springtime_di::factory::ComponentFactoryBuilder;
+    use std::any::TypeId;
+
+    #[test]
+    fn should_register_default_tracing_configurer() {
+        let component_factory = ComponentFactoryBuilder::new().unwrap().build();
+
+        let configurers = component_factory.components_by_type_typed::<TracingConfigurerPtr>();
+        assert_eq!(configurers.len(), 1);
+        assert_eq!(
+            configurers[0].resolved_type_id,
+            TypeId::of::<DefaultTracingConfigurer>()
+        );
+        assert!(DefaultTracingConfigurer
+            .create_dispatch(&ApplicationConfig::default())
+            .is_ok());
+    }
+}
@@ -6,6 +6,7 @@ use crate::future::BoxFuture;
 use mockall::automock;
 use springtime_di::injectable;
 pub use springtime_di::instance_provider::ErrorPtr;
+use std::any::type_name;
 
 #[cfg(feature = "threadsafe")]
 pub type ApplicationRunnerPtr = dyn ApplicationRunner + Send + Sync;
@@ -16,6 +17,10 @@ pub type ApplicationRunnerPtr = dyn ApplicationRunner;
 /// Runs application logic. Runners are run by the [Application](crate::application::Application)
 /// and are discovered by the dependency injection framework. If the `async` feature is enabled,
 /// runners with the same priority are ran concurrently.
+///
+/// Runners are ordered by descending [priority](ApplicationRunner::priority), then by ascending
+/// [order](ApplicationRunner::order) and finally by [name](ApplicationRunner::name), which makes
+/// the execution (or start, in case of concurrent runners) order deterministic.
 #[injectable]
 #[cfg_attr(test, automock)]
 pub trait ApplicationRunner {
@@ -31,4 +36,15 @@ pub trait ApplicationRunner {
     fn priority(&self) -> i8 {
         0
     }
+
+    /// Returns the secondary ordering key for runners with the same priority. Lower values get run
+    /// first. Default 0.
+    fn order(&self) -> i32 {
+        0
+    }
+
+    /// Returns the name of this runner, used as the last ordering key. Defaults to the type name.
+    fn name(&self) -> &str {
+        type_name::<Self>()
+    }
 }
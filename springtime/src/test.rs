@@ -0,0 +1,513 @@
+//! In-process end-to-end testing of applications. [ApplicationTest] runs an
+//! [Application](crate::application::Application) with all discovered components, optional config
+//! overrides, and captures the tracing output, so that runner behavior can be asserted on without
+//! spawning separate processes.
+//!
+//! ```no_run
+//! use springtime::test::ApplicationTest;
+//!
+//! #[tokio::test]
+//! async fn should_run_application() {
+//!     let result = ApplicationTest::new()
+//!         .with_config_override("web.servers.default.listen_address", "127.0.0.1:0")
+//!         .run()
+//!         .await
+//!         .expect("unable to create application");
+//!
+//!     assert!(result.result.is_ok());
+//!     assert!(result.logs_contain("Running application runners..."));
+//! }
+//! ```
+//!
+//! *Note:* config overrides are provided by a [ConfigSource](crate::config::source::ConfigSource)
+//! with the highest priority, therefore they override values from the config file and other
+//! sources, but not environment variables and command line arguments. Unlike other sources, they
+//! are also visible to registration conditions reading the config, e.g.
+//! [config_property_equals](crate::config::config_property_equals),
+//! [config_section_present](crate::config::config_section_present) or active profiles, so
+//! components are registered the same way as in an application configured with these values.
+
+use crate::application::{ApplicationBuilder, ApplicationError};
+use crate::config::source::ConfigMap;
+use crate::config::ApplicationConfig;
+use config::Value;
+use std::io;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+#[cfg(not(feature = "async"))]
+use tracing::dispatcher;
+#[cfg(feature = "async")]
+use tracing::instrument::WithSubscriber;
+use tracing::{Dispatch, Level};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Builder and runner for in-process application tests.
+#[derive(Default)]
+pub struct ApplicationTest {
+    config: Option<ApplicationConfig>,
+    config_overrides: ConfigMap,
+}
+
+impl ApplicationTest {
+    /// Creates a new test with no overrides.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the [ApplicationConfig] to use, instead of the one resolved from config sources.
+    pub fn with_config(mut self, config: ApplicationConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Overrides a config value. Nested keys are separated with `.`, e.g. `web.servers.default`.
+    pub fn with_config_override<T: Into<Value>>(mut self, key: &str, value: T) -> Self {
+        self.config_overrides.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Runs the application and returns the outcome, along with captured logs. An error is only
+    /// returned if the application cannot be created.
+    #[cfg(feature = "async")]
+    pub async fn run(self) -> Result<ApplicationTestResult, ApplicationError> {
+        let logs = CapturedLogs::default();
+        let mut application = self.create_builder().build()?;
+
+        let result = application.run().with_subscriber(logs.dispatch()).await;
+
+        Ok(ApplicationTestResult {
+            result,
+            logs: logs.into_string(),
+        })
+    }
+
+    /// Runs the application and returns the outcome, along with captured logs. An error is only
+    /// returned if the application cannot be created.
+    ///
+    /// *Note:* logs from runners run on separate threads, when
+    /// [runner_threads](ApplicationConfig::runner_threads) is greater than 1, are not captured.
+    #[cfg(not(feature = "async"))]
+    pub fn run(self) -> Result<ApplicationTestResult, ApplicationError> {
+        let logs = CapturedLogs::default();
+        let mut application = self.create_builder().build()?;
+
+        let result = dispatcher::with_default(&logs.dispatch(), || application.run());
+
+        Ok(ApplicationTestResult {
+            result,
+            logs: logs.into_string(),
+        })
+    }
+
+    fn create_builder(self) -> ApplicationBuilder {
+        let builder = ApplicationBuilder::new()
+            .with_config_overrides(self.config_overrides)
+            .without_logger();
+
+        match self.config {
+            Some(config) => builder.with_config(config),
+            None => builder,
+        }
+    }
+}
+
+/// Outcome of an [ApplicationTest].
+#[derive(Debug)]
+pub struct ApplicationTestResult {
+    /// Result of running the application, including errors returned by runners.
+    pub result: Result<(), ApplicationError>,
+    /// Tracing output captured during the run, without ANSI colors.
+    pub logs: String,
+}
+
+impl ApplicationTestResult {
+    /// Checks if captured logs contain given text.
+    pub fn logs_contain(&self, text: &str) -> bool {
+        self.logs.contains(text)
+    }
+}
+
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl CapturedLogs {
+    fn dispatch(&self) -> Dispatch {
+        tracing_subscriber::fmt()
+            .with_max_level(Level::TRACE)
+            .with_ansi(false)
+            .with_writer(self.clone())
+            .finish()
+            .into()
+    }
+
+    fn into_string(self) -> String {
+        let logs = self.0.lock().map(|logs| logs.clone()).unwrap_or_default();
+        String::from_utf8_lossy(&logs).into_owned()
+    }
+}
+
+impl<'a> MakeWriter<'a> for CapturedLogs {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+impl Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .map_err(|_| io::Error::other("poisoned log buffer"))?
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::ApplicationError;
+    use crate::config::{convert_error, ApplicationConfig, ConfigPropertyEquals, MergedConfig};
+    #[cfg(feature = "async")]
+    use crate::future::{BoxFuture, FutureExt};
+    use crate::runner::ApplicationRunner;
+    use crate::test::ApplicationTest;
+    use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
+    use springtime_di::{component_alias, Component};
+    use std::io;
+    use tracing::info;
+
+    // runners fail only if the error is set, so they don't affect other tests
+    fn configured_error(config: &MergedConfig, key: &str) -> Result<(), ErrorPtr> {
+        match config.value::<String>(key)? {
+            Some(error) => Err(convert_error(io::Error::other(error))),
+            None => Ok(()),
+        }
+    }
+
+    #[derive(Component)]
+    struct TestMessageRunner {
+        config: ComponentInstancePtr<MergedConfig>,
+    }
+
+    impl TestMessageRunner {
+        fn log_message(&self) -> Result<(), ErrorPtr> {
+            let message = self
+                .config
+                .value::<String>("test_runner.message")?
+                .unwrap_or_default();
+
+            info!("Test message: {message}");
+            configured_error(&self.config, "test_runner.error")
+        }
+    }
+
+    #[component_alias]
+    impl ApplicationRunner for TestMessageRunner {
+        #[cfg(feature = "async")]
+        fn run(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+            async { self.log_message() }.boxed()
+        }
+
+        #[cfg(not(feature = "async"))]
+        fn run(&self) -> Result<(), ErrorPtr> {
+            self.log_message()
+        }
+    }
+
+    #[derive(Component)]
+    struct TestFailingRunner {
+        config: ComponentInstancePtr<MergedConfig>,
+    }
+
+    #[component_alias]
+    impl ApplicationRunner for TestFailingRunner {
+        #[cfg(feature = "async")]
+        fn run(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+            async { configured_error(&self.config, "failing_runner.error") }.boxed()
+        }
+
+        #[cfg(not(feature = "async"))]
+        fn run(&self) -> Result<(), ErrorPtr> {
+            configured_error(&self.config, "failing_runner.error")
+        }
+    }
+
+    #[derive(Component)]
+    #[component(condition = "ConfigPropertyEquals::new(\"conditional_runner.enabled\", \"true\")")]
+    struct TestConditionalRunner;
+
+    #[component_alias]
+    impl ApplicationRunner for TestConditionalRunner {
+        #[cfg(feature = "async")]
+        fn run(&self) -> BoxFuture<'_, Result<(), ErrorPtr>> {
+            async {
+                info!("Conditional runner registered.");
+                Ok(())
+            }
+            .boxed()
+        }
+
+        #[cfg(not(feature = "async"))]
+        fn run(&self) -> Result<(), ErrorPtr> {
+            info!("Conditional runner registered.");
+            Ok(())
+        }
+    }
+
+    fn create_failing_test(
+        aggregate_runner_errors: bool,
+        runner_threads: usize,
+    ) -> ApplicationTest {
+        ApplicationTest::new()
+            .with_config(ApplicationConfig {
+                aggregate_runner_errors,
+                runner_threads,
+                ..Default::default()
+            })
+            .with_config_override("test_runner.error", "first")
+            .with_config_override("failing_runner.error", "second")
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_run_application_with_overrides() {
+        let result = ApplicationTest::new()
+            .with_config_override("test_runner.message", "overridden")
+            .run()
+            .await
+            .unwrap();
+
+        assert!(result.result.is_ok());
+        assert!(result.logs_contain("Test message: overridden"));
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_run_application_with_overrides() {
+        let result = ApplicationTest::new()
+            .with_config_override("test_runner.message", "overridden")
+            .run()
+            .unwrap();
+
+        assert!(result.result.is_ok());
+        assert!(result.logs_contain("Test message: overridden"));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_register_conditional_components_with_overrides() {
+        let result = ApplicationTest::new()
+            .with_config_override("conditional_runner.enabled", "true")
+            .run()
+            .await
+            .unwrap();
+        assert!(result.logs_contain("Conditional runner registered."));
+
+        let result = ApplicationTest::new().run().await.unwrap();
+        assert!(!result.logs_contain("Conditional runner registered."));
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_register_conditional_components_with_overrides() {
+        let result = ApplicationTest::new()
+            .with_config_override("conditional_runner.enabled", "true")
+            .run()
+            .unwrap();
+        assert!(result.logs_contain("Conditional runner registered."));
+
+        let result = ApplicationTest::new().run().unwrap();
+        assert!(!result.logs_contain("Conditional runner registered."));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn should_aggregate_runner_errors() {
+        let result = create_failing_test(true, 1).run().await.unwrap();
+        assert!(matches!(
+            result.result,
+            Err(ApplicationError::AggregatedRunnerErrors(errors)) if errors.len() == 2
+        ));
+
+        let result = create_failing_test(false, 1).run().await.unwrap();
+        assert!(matches!(
+            result.result,
+            Err(ApplicationError::RunnerError(_))
+        ));
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_aggregate_runner_errors() {
+        let result = create_failing_test(true, 1).run().unwrap();
+        assert!(matches!(
+            result.result,
+            Err(ApplicationError::AggregatedRunnerErrors(errors)) if errors.len() == 2
+        ));
+
+        let result = create_failing_test(false, 1).run().unwrap();
+        assert!(matches!(
+            result.result,
+            Err(ApplicationError::RunnerError(_))
+        ));
+    }
+
+    #[cfg(not(feature = "async"))]
+    #[test]
+    fn should_run_runners_with_threads() {
+        let result = create_failing_test(true, 2).run().unwrap();
+        assert!(matches!(
+            result.result,
+            Err(ApplicationError::AggregatedRunnerErrors(errors)) if errors.len() == 2
+        ));
+    }
+
+    // runners are only run on separate threads in the sync version
+    #[cfg(all(not(feature = "async"), feature = "threadsafe"))]
+    mod threaded {
+        use crate::config::{convert_error, ApplicationConfig, MergedConfig};
+        use crate::runner::ApplicationRunner;
+        use crate::test::ApplicationTest;
+        use springtime_di::instance_provider::{ComponentInstancePtr, ErrorPtr};
+        use springtime_di::{component_alias, Component};
+        use std::io;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::{Condvar, Mutex};
+        use std::time::Duration;
+
+        const PARALLEL_RUNNER_COUNT: usize = 2;
+        const PARALLEL_RUNNER_PRIORITY: i8 = 10;
+
+        // runners are run in every test, so they do nothing unless explicitly enabled
+        fn parallel_runners_enabled(config: &MergedConfig) -> Result<bool, ErrorPtr> {
+            config
+                .value::<bool>("parallel_runners.enabled")
+                .map(|enabled| enabled.unwrap_or(false))
+        }
+
+        // sequentially run runners would never meet here, so waiting is limited
+        #[derive(Component)]
+        struct TestRunnerRendezvous {
+            #[component(default)]
+            arrived: Mutex<usize>,
+            #[component(default)]
+            all_arrived: Condvar,
+            #[component(default)]
+            finished: AtomicUsize,
+        }
+
+        impl TestRunnerRendezvous {
+            fn meet(&self) -> Result<(), ErrorPtr> {
+                let mut arrived = self
+                    .arrived
+                    .lock()
+                    .map_err(|_| convert_error(io::Error::other("poisoned rendezvous")))?;
+                *arrived += 1;
+                self.all_arrived.notify_all();
+
+                let (_arrived, wait_result) = self
+                    .all_arrived
+                    .wait_timeout_while(arrived, Duration::from_secs(5), |arrived| {
+                        *arrived < PARALLEL_RUNNER_COUNT
+                    })
+                    .map_err(|_| convert_error(io::Error::other("poisoned rendezvous")))?;
+
+                if wait_result.timed_out() {
+                    return Err(convert_error(io::Error::other(
+                        "runners with equal priority were not run in parallel",
+                    )));
+                }
+
+                self.finished.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        }
+
+        #[derive(Component)]
+        struct TestFirstParallelRunner {
+            config: ComponentInstancePtr<MergedConfig>,
+            rendezvous: ComponentInstancePtr<TestRunnerRendezvous>,
+        }
+
+        #[component_alias]
+        impl ApplicationRunner for TestFirstParallelRunner {
+            fn run(&self) -> Result<(), ErrorPtr> {
+                if parallel_runners_enabled(&self.config)? {
+                    self.rendezvous.meet()
+                } else {
+                    Ok(())
+                }
+            }
+
+            fn priority(&self) -> i8 {
+                PARALLEL_RUNNER_PRIORITY
+            }
+        }
+
+        #[derive(Component)]
+        struct TestSecondParallelRunner {
+            config: ComponentInstancePtr<MergedConfig>,
+            rendezvous: ComponentInstancePtr<TestRunnerRendezvous>,
+        }
+
+        #[component_alias]
+        impl ApplicationRunner for TestSecondParallelRunner {
+            fn run(&self) -> Result<(), ErrorPtr> {
+                if parallel_runners_enabled(&self.config)? {
+                    self.rendezvous.meet()
+                } else {
+                    Ok(())
+                }
+            }
+
+            fn priority(&self) -> i8 {
+                PARALLEL_RUNNER_PRIORITY
+            }
+        }
+
+        #[derive(Component)]
+        struct TestLowerPriorityRunner {
+            config: ComponentInstancePtr<MergedConfig>,
+            rendezvous: ComponentInstancePtr<TestRunnerRendezvous>,
+        }
+
+        #[component_alias]
+        impl ApplicationRunner for TestLowerPriorityRunner {
+            fn run(&self) -> Result<(), ErrorPtr> {
+                if parallel_runners_enabled(&self.config)?
+                    && self.rendezvous.finished.load(Ordering::SeqCst) != PARALLEL_RUNNER_COUNT
+                {
+                    return Err(convert_error(io::Error::other(
+                        "runner run before the higher priority group finished",
+                    )));
+                }
+
+                Ok(())
+            }
+
+            fn priority(&self) -> i8 {
+                PARALLEL_RUNNER_PRIORITY - 1
+            }
+        }
+
+        #[test]
+        fn should_run_priority_groups_in_parallel_and_in_order() {
+            let result = ApplicationTest::new()
+                .with_config(ApplicationConfig {
+                    aggregate_runner_errors: true,
+                    runner_threads: PARALLEL_RUNNER_COUNT,
+                    ..Default::default()
+                })
+                .with_config_override("parallel_runners.enabled", true)
+                .run()
+                .unwrap();
+
+            assert!(result.result.is_ok(), "{:?}", result.result);
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use springtime::config::ApplicationConfig;
+use springtime::logger::{TracingConfigurer, TracingConfigurerPtr};
+use springtime_di::component_registry::TypedComponentDefinitionRegistry;
+use springtime_di::factory::ComponentFactoryBuilder;
+use springtime_di::instance_provider::ErrorPtr;
+use springtime_di::{component_alias, Component};
+use std::any::TypeId;
+use tracing::Dispatch;
+
+#[derive(Component)]
+struct TestTracingConfigurer;
+
+#[component_alias]
+impl TracingConfigurer for TestTracingConfigurer {
+    fn create_dispatch(&self, _config: &ApplicationConfig) -> Result<Dispatch, ErrorPtr> {
+        Ok(Dispatch::none())
+    }
+}
+
+#[test]
+fn should_replace_default_tracing_configurer() {
+    let component_factory = ComponentFactoryBuilder::new().unwrap().build();
+
+    let configurers = component_factory.components_by_type_typed::<TracingConfigurerPtr>();
+    assert_eq!(configurers.len(), 1);
+    assert_eq!(
+        configurers[0].resolved_type_id,
+        TypeId::of::<TestTracingConfigurer>()
+    );
+}